@@ -0,0 +1,55 @@
+use super::{order::archive_alignment, NpzWriter, WriteNpzError};
+use std::io::{Read, Seek, Write};
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+impl<W: Read + Write + Seek> NpzWriter<W> {
+	/// Opens an existing `.npz` file without compression to add arrays to it.
+	///
+	/// The existing files are kept as is and the arrays are added after them, replacing the
+	/// central directory on [`Self::finish`], so long-running jobs can accumulate results
+	/// incrementally without rewriting the whole file. Like [`Self::new`], it ensures the added
+	/// `.npy` files are aligned for memory-mapping via [`NpzView`]/[`NpzViewMut`], i.e., to the
+	/// largest alignment recorded in the existing files or to 64 bytes otherwise.
+	///
+	/// [`NpzView`]: crate::NpzView
+	/// [`NpzViewMut`]: crate::NpzViewMut
+	///
+	/// # Example
+	///
+	/// ```
+	/// use ndarray::Array1;
+	/// use ndarray_npz::{NpzReader, NpzWriter};
+	/// use std::io::Cursor;
+	///
+	/// let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	/// npz.add_array("epoch_0", &Array1::<f64>::zeros(3))?;
+	/// let mut npz = NpzWriter::append(npz.finish()?)?;
+	/// npz.add_array("epoch_1", &Array1::<f64>::ones(3))?;
+	/// let mut npz = NpzReader::new(npz.finish()?)?;
+	/// assert_eq!(npz.names()?, ["epoch_0", "epoch_1"]);
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Fails with [`ZipError::InvalidArchive`] if the zip structures are malformed. Reading them
+	/// can fail with [`ZipError::Io`]. Adding an array whose name already exists fails with
+	/// [`WriteNpzError::DuplicateName`] unless configured otherwise.
+	///
+	/// [`ZipError::InvalidArchive`]: zip::result::ZipError::InvalidArchive
+	/// [`ZipError::Io`]: zip::result::ZipError::Io
+	pub fn append(mut readwriter: W) -> Result<NpzWriter<W>, WriteNpzError> {
+		let mut zip = ZipArchive::new(&mut readwriter)?;
+		let names = zip.file_names().map(From::from).collect();
+		let alignment = archive_alignment(&mut zip)?;
+		drop(zip);
+		Ok(NpzWriter {
+			names,
+			..NpzWriter::with_options(
+				ZipWriter::new_append(readwriter)?,
+				SimpleFileOptions::default().compression_method(CompressionMethod::Stored),
+			)
+			.with_alignment(alignment)
+		})
+	}
+}
@@ -0,0 +1,126 @@
+use super::{lenient, poison::Settle, NpyViewMut, NpzViewMut, ViewNpzError};
+use ndarray::{ArrayViewMut, Dimension};
+use ndarray_npy::ViewMutElement;
+use std::{collections::BTreeSet, num::NonZeroUsize, panic, thread};
+
+impl<'a> NpzViewMut<'a> {
+	/// Applies `f` to a mutable view of each `.npy` file of `names` in archive order.
+	///
+	/// The checksum of each `.npy` file is [updated](NpyViewMut::update) once right after `f`
+	/// returned or [poisoned](crate::ChecksumStatus::Poisoned) if `f` panicked. All `.npy` files
+	/// are viewed before `f` is applied to any of them, so nothing is modified if one of them
+	/// cannot be viewed. Duplicate names are applied once.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use aligned_vec::AVec;
+	/// use ndarray::{Array1, Ix1};
+	/// use ndarray_npz::{NpzViewMut, NpzWriter};
+	/// use std::io::Cursor;
+	///
+	/// let mut buffer = Vec::new();
+	/// let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+	/// npz.add_array("x.npy", &Array1::<f64>::ones(3))?;
+	/// npz.add_array("y.npy", &Array1::<f64>::ones(5))?;
+	/// npz.finish()?;
+	/// let mut buffer = AVec::<u8>::from_slice(64, &buffer);
+	/// let mut npz = NpzViewMut::new(&mut buffer)?;
+	/// npz.apply::<f64, Ix1, _, _>(&["x.npy", "y.npy"], |mut view| {
+	/// 	let norm = view.dot(&view).sqrt();
+	/// 	view /= norm;
+	/// })?;
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Fails like [`Self::by_name`] if a `.npy` file cannot be found, with
+	/// [`ViewNpzError::MovedNpyViewMut`] if it has already been moved out, or with
+	/// [`ViewNpzError::Npy`] if it cannot be viewed as an array of `A` and `D`.
+	pub fn apply<A, D, N, F>(&mut self, names: &[N], f: F) -> Result<(), ViewNpzError>
+	where
+		A: ViewMutElement,
+		D: Dimension,
+		N: AsRef<str>,
+		F: Fn(ArrayViewMut<'_, A, D>),
+	{
+		for file in self.files_mut::<A, D, N>(names)? {
+			let file = Settle(file);
+			f(file.0.view_mut()?);
+			file.0.update();
+		}
+		Ok(())
+	}
+
+	/// Like [`Self::apply`] but applies `f` and updates the checksums in parallel.
+	///
+	/// The `.npy` files are distributed evenly over as many scoped threads as there is
+	/// [available parallelism](thread::available_parallelism). Panics of `f` are propagated.
+	///
+	/// # Errors
+	///
+	/// Fails like [`Self::apply`].
+	pub fn apply_parallel<A, D, N, F>(&mut self, names: &[N], f: F) -> Result<(), ViewNpzError>
+	where
+		A: ViewMutElement,
+		D: Dimension,
+		N: AsRef<str>,
+		F: Fn(ArrayViewMut<'_, A, D>) + Sync,
+	{
+		let mut files = self.files_mut::<A, D, N>(names)?;
+		let threads = thread::available_parallelism()
+			.map_or(1, NonZeroUsize::get)
+			.min(files.len())
+			.max(1);
+		let chunk = files.len().div_ceil(threads).max(1);
+		let f = &f;
+		thread::scope(|scope| {
+			files
+				.chunks_mut(chunk)
+				.map(|files| {
+					scope.spawn(move || -> Result<(), ViewNpzError> {
+						for file in files {
+							let file = Settle(file);
+							f(file.0.view_mut()?);
+							file.0.update();
+						}
+						Ok(())
+					})
+				})
+				.collect::<Vec<_>>()
+				.into_iter()
+				.try_for_each(|thread| {
+					thread
+						.join()
+						.unwrap_or_else(|err| panic::resume_unwind(err))
+				})
+		})
+	}
+
+	/// Returns the `.npy` file views of `names` in archive order once all of them are viewable.
+	fn files_mut<A, D, N>(&mut self, names: &[N]) -> Result<Vec<&mut NpyViewMut<'a>>, ViewNpzError>
+	where
+		A: ViewMutElement,
+		D: Dimension,
+		N: AsRef<str>,
+	{
+		let indices = names
+			.iter()
+			.map(|name| self.index(name.as_ref()))
+			.collect::<Result<BTreeSet<usize>, _>>()?;
+		let mut files = self
+			.files
+			.iter_mut()
+			.filter(|(index, _file)| indices.contains(index))
+			.collect::<Vec<_>>();
+		if files.len() != indices.len() {
+			return Err(ViewNpzError::MovedNpyViewMut);
+		}
+		files.sort_unstable_by_key(|&(&index, _)| index);
+		for (_index, file) in &mut files {
+			lenient::view_mut_npy::<A, D>(&mut *file.data, file.lenient)?;
+		}
+		Ok(files.into_iter().map(|(_index, file)| file).collect())
+	}
+}
@@ -0,0 +1,167 @@
+use super::{NpzReader, NpzWriter, ReadNpzError, WriteNpzError};
+use std::{
+	fmt,
+	io::{Read, Seek, Write},
+	sync::{Arc, Mutex, PoisonError},
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use zip::result::ZipError;
+
+/// Reserved name of the member recording mutations of an `.npz` file.
+pub const MUTATION_LOG_NAME: &str = "__mutations__.log";
+
+/// Single mutation of an `.npy` file within an `.npz` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mutation {
+	/// Name of the mutated `.npy` file.
+	pub name: String,
+	/// Time of the mutation.
+	pub timestamp: SystemTime,
+	/// CRC-32 checksum of the `.npy` file after the mutation.
+	pub crc32: u32,
+}
+
+/// Audit log of mutations of `.npy` files within an `.npz` file.
+///
+/// Clones share the same records, so a log can be handed to an [`NpzViewMut`](crate::NpzViewMut)
+/// via [`with_mutation_log`](crate::NpzViewMut::with_mutation_log) while keeping a clone to
+/// persist it via [`NpzWriter::add_mutation_log`]. Every [`NpyViewMut::update`] records a
+/// [`Mutation`], including the automatic update on [`Drop::drop`], and so does every edit of an
+/// [`NpzEditor`](crate::NpzEditor) via [`with_mutation_log`](crate::NpzEditor::with_mutation_log).
+///
+/// The log is stored as [`MUTATION_LOG_NAME`] member of UTF-8 text with one mutation per line
+/// in the form of `name\tseconds.nanoseconds\tcrc32` where the timestamp is relative to the
+/// [`UNIX_EPOCH`] and the CRC-32 checksum is in lowercase hexadecimal, so other tools can
+/// read it as well.
+///
+/// [`NpyViewMut::update`]: crate::NpyViewMut::update
+#[derive(Debug, Clone, Default)]
+pub struct MutationLog {
+	mutations: Arc<Mutex<Vec<Mutation>>>,
+}
+
+impl MutationLog {
+	/// Creates a new empty log.
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+	/// Records a mutation of the `.npy` file `name` resulting in the `crc32` checksum.
+	pub fn record(&self, name: &str, crc32: u32) {
+		self.lock().push(Mutation {
+			name: name.to_string(),
+			timestamp: SystemTime::now(),
+			crc32,
+		});
+	}
+	/// Returns `true` iff no mutations have been recorded.
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.lock().is_empty()
+	}
+	/// Returns the number of recorded mutations.
+	#[must_use]
+	pub fn len(&self) -> usize {
+		self.lock().len()
+	}
+	/// Returns the recorded mutations in chronological order.
+	#[must_use]
+	pub fn mutations(&self) -> Vec<Mutation> {
+		self.lock().clone()
+	}
+	/// Parses a log from the content of a [`MUTATION_LOG_NAME`] member.
+	///
+	/// # Errors
+	///
+	/// Fails with [`ZipError::InvalidArchive`] if the log is malformed.
+	pub fn parse(log: &str) -> Result<Self, ZipError> {
+		let invalid = || ZipError::InvalidArchive("Invalid mutation log");
+		let mutations = log
+			.lines()
+			.filter(|line| !line.is_empty())
+			.map(|line| {
+				let mut fields = line.rsplitn(3, '\t');
+				let crc32 = fields.next().ok_or_else(invalid)?;
+				let timestamp = fields.next().ok_or_else(invalid)?;
+				let name = fields.next().ok_or_else(invalid)?;
+				let crc32 = u32::from_str_radix(crc32, 16).map_err(|_| invalid())?;
+				let (secs, nanos) = timestamp.split_once('.').ok_or_else(invalid)?;
+				let secs = secs.parse().map_err(|_| invalid())?;
+				let nanos = nanos.parse().map_err(|_| invalid())?;
+				let timestamp = UNIX_EPOCH
+					.checked_add(Duration::new(secs, nanos))
+					.ok_or_else(invalid)?;
+				Ok(Mutation {
+					name: name.to_string(),
+					timestamp,
+					crc32,
+				})
+			})
+			.collect::<Result<_, ZipError>>()?;
+		Ok(Self {
+			mutations: Arc::new(Mutex::new(mutations)),
+		})
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Mutation>> {
+		self.mutations
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner)
+	}
+}
+
+impl fmt::Display for MutationLog {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		for mutation in self.lock().iter() {
+			let timestamp = mutation
+				.timestamp
+				.duration_since(UNIX_EPOCH)
+				.unwrap_or_default();
+			writeln!(
+				f,
+				"{}\t{}.{:09}\t{:08x}",
+				mutation.name,
+				timestamp.as_secs(),
+				timestamp.subsec_nanos(),
+				mutation.crc32
+			)?;
+		}
+		Ok(())
+	}
+}
+
+impl<W: Write + Seek> NpzWriter<W> {
+	/// Adds the mutation `log` as [`MUTATION_LOG_NAME`] member to the `.npz` file.
+	///
+	/// To extend the log of an existing archive, read it via [`NpzReader::mutation_log`] and keep
+	/// recording into it before adding it to the rewritten archive.
+	///
+	/// # Errors
+	///
+	/// Adding the log can fail with [`ZipError`].
+	pub fn add_mutation_log(&mut self, log: &MutationLog) -> Result<(), WriteNpzError> {
+		self.zip.start_file(MUTATION_LOG_NAME, self.options)?;
+		self.zip
+			.write_all(log.to_string().as_bytes())
+			.map_err(ZipError::from)?;
+		Ok(())
+	}
+}
+
+impl<R: Read + Seek> NpzReader<R> {
+	/// Reads the mutation log of the [`MUTATION_LOG_NAME`] member if any.
+	///
+	/// # Errors
+	///
+	/// Reading the log can fail with [`ZipError`].
+	pub fn mutation_log(&mut self) -> Result<Option<MutationLog>, ReadNpzError> {
+		let mut file = match self.zip.by_name(MUTATION_LOG_NAME) {
+			Err(ZipError::FileNotFound) => return Ok(None),
+			Err(err) => return Err(err.into()),
+			Ok(file) => file,
+		};
+		let mut log = String::new();
+		file.read_to_string(&mut log).map_err(ZipError::from)?;
+		Ok(Some(MutationLog::parse(&log)?))
+	}
+}
@@ -0,0 +1,144 @@
+use super::{
+	json::{json_strings, parse_json_strings},
+	sibling_name, NpzReader, NpzWriter, ReadNpzError, WriteNpzError,
+};
+use ndarray::{prelude::*, Data};
+use ndarray_npy::{ReadableElement, WritableElement};
+use std::io::{Read, Seek, Write};
+use zip::result::ZipError;
+
+/// Array with named axes and optional coordinates, see [`NpzReader::by_name_labeled`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct LabeledArray<A, C, D: Dimension> {
+	/// Array data.
+	pub array: Array<A, D>,
+	/// Name of each axis, defaulting to `dim_0`, `dim_1`, and so on.
+	pub axes: Vec<String>,
+	/// Coordinates of each axis if any.
+	pub coords: Vec<Option<Array1<C>>>,
+}
+
+impl<A, C, D: Dimension> LabeledArray<A, C, D> {
+	/// Returns the index of the axis named `name`.
+	#[must_use]
+	pub fn axis(&self, name: &str) -> Option<Axis> {
+		self.axes.iter().position(|axis| axis == name).map(Axis)
+	}
+	/// Returns the coordinates of the axis named `name` if any.
+	#[must_use]
+	pub fn coords(&self, name: &str) -> Option<&Array1<C>> {
+		self.coords.get(self.axis(name)?.index())?.as_ref()
+	}
+}
+
+/// Returns the name of the member storing the axis names of the member `name`.
+fn axes_name(name: &str) -> String {
+	let stem = name.strip_suffix(".npy").unwrap_or(name);
+	format!("{stem}.axes.json")
+}
+
+/// Returns the name of the member storing the coordinates of `axis` of the member `name`.
+fn coord_name(name: &str, axis: usize) -> String {
+	sibling_name(name, &format!("coord{axis}"))
+}
+
+impl<W: Write + Seek> NpzWriter<W> {
+	/// Adds the names of the axes of the array `name`.
+	///
+	/// The names are stored as JSON array of strings in the member `x.axes.json` next to the array
+	/// `x.npy`, so other tools like Python's `json` module can read them as well.
+	///
+	/// # Errors
+	///
+	/// Adding the names can fail with [`ZipError`].
+	pub fn add_axis_names<T: AsRef<str>>(
+		&mut self,
+		name: &str,
+		axes: &[T],
+	) -> Result<(), WriteNpzError> {
+		let axes = axes.iter().map(AsRef::as_ref).collect::<Vec<_>>();
+		self.zip.start_file(axes_name(name), self.options)?;
+		self.zip
+			.write_all(json_strings(&axes).as_bytes())
+			.map_err(ZipError::from)?;
+		Ok(())
+	}
+
+	/// Adds the coordinates of the `axis` of the array `name`.
+	///
+	/// The coordinates are stored as one-dimensional array in the member `x.coord0.npy` for the
+	/// first axis of the array `x.npy` and so on.
+	///
+	/// # Errors
+	///
+	/// Adding an array can fail with [`WriteNpyError`](ndarray_npy::WriteNpyError).
+	pub fn add_axis_coords<S>(
+		&mut self,
+		name: &str,
+		axis: Axis,
+		coords: &ArrayBase<S, Ix1>,
+	) -> Result<(), WriteNpzError>
+	where
+		S::Elem: WritableElement,
+		S: Data,
+	{
+		self.add_array(coord_name(name, axis.index()), coords)
+	}
+}
+
+impl<R: Read + Seek> NpzReader<R> {
+	/// Reads an array by name together with the names and coordinates of its axes added via
+	/// [`NpzWriter::add_axis_names`] and [`NpzWriter::add_axis_coords`].
+	///
+	/// Missing names default to `dim_0`, `dim_1`, and so on, as in `xarray`.
+	///
+	/// # Errors
+	///
+	/// Reading an array from an archive can fail with [`ReadNpyError`](ndarray_npy::ReadNpyError)
+	/// or [`ZipError`]. Fails with [`ZipError::InvalidArchive`] if the number of names differs
+	/// from the number of axes or if the length of coordinates differs from the length of their
+	/// axis.
+	pub fn by_name_labeled<A, C, D>(
+		&mut self,
+		name: &str,
+	) -> Result<LabeledArray<A, C, D>, ReadNpzError>
+	where
+		A: ReadableElement,
+		C: ReadableElement,
+		D: Dimension,
+	{
+		let array: Array<A, D> = self.by_name(name)?;
+		let axes = match self.zip.by_name(&axes_name(name)) {
+			Err(ZipError::FileNotFound) => (0..array.ndim())
+				.map(|axis| format!("dim_{axis}"))
+				.collect(),
+			Err(err) => return Err(err.into()),
+			Ok(mut file) => {
+				let mut axes = String::new();
+				file.read_to_string(&mut axes).map_err(ZipError::from)?;
+				parse_json_strings(&axes).ok_or(ZipError::InvalidArchive("Invalid axis names"))?
+			}
+		};
+		if axes.len() != array.ndim() {
+			return Err(ZipError::InvalidArchive("Axis names differ from axes in number").into());
+		}
+		let mut coords = Vec::with_capacity(array.ndim());
+		for (axis, &len) in array.shape().iter().enumerate() {
+			coords.push(match self.by_name::<_, Ix1>(&coord_name(name, axis)) {
+				Err(ReadNpzError::Zip(ZipError::FileNotFound)) => None,
+				Err(err) => return Err(err),
+				Ok(axis_coords) if axis_coords.len() != len => {
+					return Err(
+						ZipError::InvalidArchive("Coordinates differ from axis in length").into(),
+					);
+				}
+				Ok(axis_coords) => Some(axis_coords),
+			});
+		}
+		Ok(LabeledArray {
+			array,
+			axes,
+			coords,
+		})
+	}
+}
@@ -0,0 +1,344 @@
+//! Throughput benchmarking of synthetic `.npz` files.
+//!
+//! Measures the throughput of writing via [`NpzWriter`], reading via [`NpzReader`], and viewing
+//! via [`NpzView`] with the exact code paths of this crate, so storage configurations can be
+//! evaluated before committing to them.
+//!
+//! # Example
+//!
+//! ```
+//! use ndarray_npz::{
+//! 	bench::{run, BenchSpec},
+//! 	Dtype,
+//! };
+//!
+//! let spec = BenchSpec::default()
+//! 	.with_entries(8)
+//! 	.with_elements(1024)
+//! 	.with_dtype(Dtype::F32);
+//! let report = run(&spec)?;
+//! println!("write: {:.0} B/s", report.write.bytes_per_sec());
+//! println!("read: {:.0} B/s", report.read.bytes_per_sec());
+//! if let Some(view) = report.view {
+//! 	println!("view: {:.0} B/s", view.bytes_per_sec());
+//! }
+//! # Ok::<(), ndarray_npz::bench::BenchError>(())
+//! ```
+
+use super::{
+	Dtype, ErrorCategory, ErrorCode, NpzReader, NpzView, NpzWriter, ReadNpzError, ViewNpzError,
+	WriteNpzError,
+};
+use ndarray::{Array1, Ix1, OwnedRepr};
+use ndarray_npy::{ReadableElement, ViewElement, WritableElement};
+use std::{
+	error::Error,
+	fmt,
+	hint::black_box,
+	io::Cursor,
+	time::{Duration, Instant},
+};
+
+/// Specification of a synthetic `.npz` file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchSpec {
+	entries: usize,
+	elements: usize,
+	dtype: Dtype,
+	compressed: bool,
+	iterations: u32,
+}
+
+impl Default for BenchSpec {
+	/// 16 uncompressed one-dimensional `f64` arrays of 65536 elements each measured once.
+	fn default() -> Self {
+		Self {
+			entries: 16,
+			elements: 65536,
+			dtype: Dtype::F64,
+			compressed: false,
+			iterations: 1,
+		}
+	}
+}
+
+impl BenchSpec {
+	/// Sets the number of arrays.
+	#[must_use]
+	pub fn with_entries(mut self, entries: usize) -> Self {
+		self.entries = entries;
+		self
+	}
+	/// Sets the number of elements per one-dimensional array.
+	#[must_use]
+	pub fn with_elements(mut self, elements: usize) -> Self {
+		self.elements = elements;
+		self
+	}
+	/// Sets the element type of the synthetic arrays.
+	#[must_use]
+	pub fn with_dtype(mut self, dtype: Dtype) -> Self {
+		self.dtype = dtype;
+		self
+	}
+	/// Sets whether to compress the arrays. Compressed arrays cannot be viewed.
+	#[cfg(feature = "compressed")]
+	#[must_use]
+	pub fn with_compressed(mut self, compressed: bool) -> Self {
+		self.compressed = compressed;
+		self
+	}
+	/// Sets the number of iterations, the measurements are averaged over. At least one.
+	#[must_use]
+	pub fn with_iterations(mut self, iterations: u32) -> Self {
+		self.iterations = iterations.max(1);
+		self
+	}
+	/// Returns the total payload size in bytes of the arrays excluding `.npy` headers.
+	#[must_use]
+	pub fn payload_size(&self) -> u64 {
+		(self.entries * self.elements * self.dtype.size()) as u64
+	}
+}
+
+/// Throughput of processing a payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Throughput {
+	/// Payload size in bytes.
+	pub bytes: u64,
+	/// Duration of processing the payload.
+	pub duration: Duration,
+}
+
+impl Throughput {
+	/// Returns the throughput in bytes per second.
+	#[must_use]
+	#[allow(clippy::cast_precision_loss)]
+	pub fn bytes_per_sec(&self) -> f64 {
+		self.bytes as f64 / self.duration.as_secs_f64().max(f64::MIN_POSITIVE)
+	}
+}
+
+/// Report of [`run`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchReport {
+	/// Size in bytes of the synthetic `.npz` file.
+	pub archive_size: u64,
+	/// Throughput of writing via [`NpzWriter::add_array`].
+	pub write: Throughput,
+	/// Throughput of reading via [`NpzReader::by_index`].
+	pub read: Throughput,
+	/// Throughput of viewing via [`NpzView`] including one pass over all elements.
+	///
+	/// `None` if compressed.
+	pub view: Option<Throughput>,
+}
+
+/// An error benchmarking a `.npz` file.
+#[derive(Debug)]
+pub enum BenchError {
+	/// An error writing the `.npz` file.
+	Write(WriteNpzError),
+	/// An error reading the `.npz` file.
+	Read(ReadNpzError),
+	/// An error viewing the `.npz` file.
+	View(ViewNpzError),
+}
+
+impl Error for BenchError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			BenchError::Write(err) => Some(err),
+			BenchError::Read(err) => Some(err),
+			BenchError::View(err) => Some(err),
+		}
+	}
+}
+
+impl fmt::Display for BenchError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			BenchError::Write(err) => write!(f, "error writing npz archive: {err}"),
+			BenchError::Read(err) => write!(f, "error reading npz archive: {err}"),
+			BenchError::View(err) => write!(f, "error viewing npz archive: {err}"),
+		}
+	}
+}
+
+impl From<WriteNpzError> for BenchError {
+	fn from(err: WriteNpzError) -> BenchError {
+		BenchError::Write(err)
+	}
+}
+
+impl From<ReadNpzError> for BenchError {
+	fn from(err: ReadNpzError) -> BenchError {
+		BenchError::Read(err)
+	}
+}
+
+impl From<ViewNpzError> for BenchError {
+	fn from(err: ViewNpzError) -> BenchError {
+		BenchError::View(err)
+	}
+}
+
+impl BenchError {
+	/// Returns the stable code of the error, see [`ErrorCode`].
+	#[must_use]
+	pub fn code(&self) -> ErrorCode {
+		match self {
+			BenchError::Write(err) => err.code(),
+			BenchError::Read(err) => err.code(),
+			BenchError::View(err) => err.code(),
+		}
+	}
+
+	/// Returns the stable category of the error, see [`ErrorCategory`].
+	#[must_use]
+	pub fn category(&self) -> ErrorCategory {
+		self.code().category()
+	}
+}
+
+/// Synthesizes a `.npz` file in memory according to `spec`.
+///
+/// The arrays are named `0.npy`, `1.npy`, and so on.
+///
+/// # Errors
+///
+/// Writing the arrays can fail with [`WriteNpzError`].
+pub fn synthesize(spec: &BenchSpec) -> Result<Vec<u8>, WriteNpzError> {
+	let mut buffer = Vec::new();
+	match spec.dtype {
+		Dtype::Bool => write(spec, &arrays(spec, |x| x & 1 != 0), &mut buffer)?,
+		Dtype::I8 => write(spec, &arrays(spec, |x| i8::from_le_bytes([x])), &mut buffer)?,
+		Dtype::U8 => write(spec, &arrays(spec, u8::from), &mut buffer)?,
+		Dtype::I16 => write(spec, &arrays(spec, i16::from), &mut buffer)?,
+		Dtype::U16 => write(spec, &arrays(spec, u16::from), &mut buffer)?,
+		Dtype::I32 => write(spec, &arrays(spec, i32::from), &mut buffer)?,
+		Dtype::U32 => write(spec, &arrays(spec, u32::from), &mut buffer)?,
+		Dtype::I64 => write(spec, &arrays(spec, i64::from), &mut buffer)?,
+		Dtype::U64 => write(spec, &arrays(spec, u64::from), &mut buffer)?,
+		Dtype::F32 => write(spec, &arrays(spec, f32::from), &mut buffer)?,
+		Dtype::F64 => write(spec, &arrays(spec, f64::from), &mut buffer)?,
+	};
+	Ok(buffer)
+}
+
+/// Measures the throughput of writing, reading, and viewing a `.npz` file according to `spec`.
+///
+/// # Errors
+///
+/// Fails with [`BenchError`] if the synthetic `.npz` file cannot be written, read, or viewed.
+pub fn run(spec: &BenchSpec) -> Result<BenchReport, BenchError> {
+	match spec.dtype {
+		Dtype::Bool => run_typed(spec, |x| x & 1 != 0),
+		Dtype::I8 => run_typed(spec, |x| i8::from_le_bytes([x])),
+		Dtype::U8 => run_typed(spec, u8::from),
+		Dtype::I16 => run_typed(spec, i16::from),
+		Dtype::U16 => run_typed(spec, u16::from),
+		Dtype::I32 => run_typed(spec, i32::from),
+		Dtype::U32 => run_typed(spec, u32::from),
+		Dtype::I64 => run_typed(spec, i64::from),
+		Dtype::U64 => run_typed(spec, u64::from),
+		Dtype::F32 => run_typed(spec, f32::from),
+		Dtype::F64 => run_typed(spec, f64::from),
+	}
+}
+
+fn run_typed<A>(spec: &BenchSpec, from: fn(u8) -> A) -> Result<BenchReport, BenchError>
+where
+	A: WritableElement + ReadableElement + ViewElement,
+{
+	let bytes = spec.payload_size() * u64::from(spec.iterations);
+	let arrays = arrays(spec, from);
+	let mut buffer = Vec::new();
+	let mut write_duration = Duration::ZERO;
+	for _ in 0..spec.iterations {
+		buffer.clear();
+		write_duration += write(spec, &arrays, &mut buffer)?;
+	}
+	let mut read_duration = Duration::ZERO;
+	for _ in 0..spec.iterations {
+		let start = Instant::now();
+		let mut npz = NpzReader::new(Cursor::new(&buffer))?;
+		for entry in 0..spec.entries {
+			black_box(npz.by_index::<OwnedRepr<A>, Ix1>(entry)?);
+		}
+		read_duration += start.elapsed();
+	}
+	let view = if spec.compressed {
+		None
+	} else {
+		// Copy into 64-byte aligned region.
+		let mut aligned = vec![0; buffer.len() + 64];
+		let offset = aligned.as_ptr().align_offset(64);
+		let aligned = &mut aligned[offset..offset + buffer.len()];
+		aligned.copy_from_slice(&buffer);
+		let mut view_duration = Duration::ZERO;
+		for _ in 0..spec.iterations {
+			let start = Instant::now();
+			let npz = NpzView::new(aligned)?;
+			for entry in 0..spec.entries {
+				for element in npz.by_index(entry)?.view::<A, Ix1>()? {
+					black_box(element);
+				}
+			}
+			view_duration += start.elapsed();
+		}
+		Some(Throughput {
+			bytes,
+			duration: view_duration,
+		})
+	};
+	Ok(BenchReport {
+		archive_size: buffer.len() as u64,
+		write: Throughput {
+			bytes,
+			duration: write_duration,
+		},
+		read: Throughput {
+			bytes,
+			duration: read_duration,
+		},
+		view,
+	})
+}
+
+fn arrays<A>(spec: &BenchSpec, from: fn(u8) -> A) -> Vec<Array1<A>> {
+	(0..spec.entries)
+		.map(|entry| {
+			// Deterministic pseudo-random pattern, so compression is neither trivial nor impossible.
+			#[allow(clippy::cast_possible_truncation)]
+			Array1::from_shape_fn(spec.elements, |index| {
+				from(((index ^ entry).wrapping_mul(0x9e37_79b9) >> 7) as u8)
+			})
+		})
+		.collect()
+}
+
+fn write<A: WritableElement>(
+	spec: &BenchSpec,
+	arrays: &[Array1<A>],
+	buffer: &mut Vec<u8>,
+) -> Result<Duration, WriteNpzError> {
+	let writer = Cursor::new(buffer);
+	#[cfg(feature = "compressed")]
+	let mut npz = if spec.compressed {
+		NpzWriter::new_compressed(writer)
+	} else {
+		NpzWriter::new(writer)
+	};
+	#[cfg(not(feature = "compressed"))]
+	let mut npz = {
+		debug_assert!(!spec.compressed);
+		NpzWriter::new(writer)
+	};
+	let start = Instant::now();
+	for (entry, array) in arrays.iter().enumerate() {
+		npz.add_array(format!("{entry}.npy"), array)?;
+	}
+	npz.finish()?;
+	Ok(start.elapsed())
+}
@@ -0,0 +1,203 @@
+use super::{
+	capability::is_supported_file,
+	chunking::Chunked,
+	header::{NpyHeader, MAGIC},
+	lenient::read_header,
+	packing::Packed,
+	NpzReader, NpzWriter, ReadNpzError, WriteNpzError,
+};
+use std::io::{self, Read, Seek, Write};
+use zip::{result::ZipError, ZipArchive};
+
+impl<W: Write + Seek> NpzWriter<W> {
+	/// Adds a blob, i.e., a file other than an `.npy` file, with the specified `name`.
+	///
+	/// Blobs store auxiliary data next to the arrays, e.g., configurations or license files, as is,
+	/// so Python reads them via [`zipfile`] without resorting to `numpy.load`. Unlike for arrays,
+	/// `.npy` is never appended to the `name`. The `bytes` must not start with the magic string of
+	/// `.npy` files as blobs are told apart by it, see [`NpzReader::blob_names`].
+	///
+	/// [`zipfile`]: https://docs.python.org/3/library/zipfile.html
+	///
+	/// # Example
+	///
+	/// ```
+	/// use ndarray::array;
+	/// use ndarray_npz::{NpzReader, NpzWriter};
+	/// use std::io::Cursor;
+	///
+	/// let mut buffer = Vec::new();
+	/// let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+	/// npz.add_array("x.npy", &array![1.0, 2.0])?;
+	/// npz.add_bytes("LICENSE", b"CC0-1.0")?;
+	/// npz.finish()?;
+	/// let mut npz = NpzReader::new(Cursor::new(&buffer))?;
+	/// assert_eq!(npz.npy_names()?, ["x.npy"]);
+	/// assert_eq!(npz.blob_names()?, ["LICENSE"]);
+	/// assert_eq!(npz.read_bytes("LICENSE")?, b"CC0-1.0");
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Fails with [`ZipError::InvalidArchive`] if the `bytes` start with the magic string of
+	/// `.npy` files, see [`Self::add_npy_bytes`] instead. Adding a blob can fail with
+	/// [`ZipError`] or with [`WriteNpzError::DuplicateName`] like [`NpzWriter::add_array`].
+	pub fn add_bytes<N>(&mut self, name: N, bytes: &[u8]) -> Result<(), WriteNpzError>
+	where
+		N: Into<String>,
+	{
+		if bytes.starts_with(MAGIC) {
+			return Err(ZipError::InvalidArchive("Blob starts with npy magic string").into());
+		}
+		let mut name = name.into();
+		if self.claim_name(&mut name)? || self.add_guarded(&name, bytes)? {
+			return Ok(());
+		}
+		let options = self.sized_options(u64::try_from(bytes.len()).unwrap_or(u64::MAX));
+		self.zip.start_file(name, options)?;
+		self.zip.write_all(bytes).map_err(ZipError::from)?;
+		Ok(())
+	}
+
+	/// Adds a pre-serialized `.npy` file read from `npy` with the specified `name`.
+	///
+	/// Assembles archives from `.npy` files serialized elsewhere, e.g., by another process,
+	/// without round-tripping through [`ArrayBase`](ndarray::ArrayBase). Like for arrays, `.npy`
+	/// is appended to the `name` if enabled, see [`NpzWriter::numpy_compat`]. The header is
+	/// validated but the data is copied as is, e.g., without converting its byte order.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use ndarray::{array, Array1};
+	/// use ndarray_npy::WriteNpyExt;
+	/// use ndarray_npz::{NpzReader, NpzWriter};
+	/// use std::io::Cursor;
+	///
+	/// let mut npy = Vec::new();
+	/// array![1.0, 2.0].write_npy(&mut npy)?;
+	/// let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	/// npz.add_npy_bytes("x.npy", npy.as_slice())?;
+	/// let mut npz = NpzReader::new(npz.finish()?)?;
+	/// let x: Array1<f64> = npz.by_name("x.npy")?;
+	/// assert_eq!(x, array![1.0, 2.0]);
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Fails with [`ZipError::InvalidArchive`] if the `.npy` header is invalid. Adding the file
+	/// can fail with [`ZipError`] or with [`WriteNpzError::DuplicateName`] like
+	/// [`NpzWriter::add_array`].
+	#[allow(clippy::case_sensitive_file_extension_comparisons)]
+	pub fn add_npy_bytes<N, R>(&mut self, name: N, mut npy: R) -> Result<(), WriteNpzError>
+	where
+		N: Into<String>,
+		R: Read,
+	{
+		let mut name = name.into();
+		if self.npy_extension && !name.ends_with(".npy") {
+			name.push_str(".npy");
+		}
+		let header = read_header(&mut npy).map_err(ZipError::from)?;
+		let Some(parsed) = NpyHeader::parse(&header) else {
+			return Err(ZipError::InvalidArchive("Invalid npy header").into());
+		};
+		if self.claim_name(&mut name)? {
+			return Ok(());
+		}
+		let len = parsed
+			.elements()
+			.zip(parsed.item_size())
+			.map_or(u64::MAX, |(elements, size)| elements.saturating_mul(size));
+		self.zip.start_file(name, self.sized_options(len))?;
+		self.zip.write_all(&header).map_err(ZipError::from)?;
+		io::copy(&mut npy, &mut self.zip).map_err(ZipError::from)?;
+		Ok(())
+	}
+}
+
+impl<R: Read + Seek> NpzReader<R> {
+	/// Returns the names of all of the `.npy` files in archive order.
+	///
+	/// Unlike [`Self::names`], this detects `.npy` files by their magic string regardless of
+	/// their names, e.g., it includes a `weights.bin` file storing an array but excludes an
+	/// `info.json` file. Directories and files listed by [`Self::unsupported_names`] are excluded.
+	/// Names of arrays packed via [`NpzWriter::with_packing`] or chunked via
+	/// [`NpzWriter::with_chunking`] follow the names of the other `.npy` files.
+	///
+	/// # Errors
+	///
+	/// Reading a zip archive can fail with [`ZipError`].
+	pub fn npy_names(&mut self) -> Result<Vec<String>, ReadNpzError> {
+		let mut names = classify_names(&mut self.zip)?.0;
+		if let Some(packed) = &self.packed {
+			names.extend_from_slice(packed.names());
+		}
+		if let Some(chunked) = &self.chunked {
+			names.retain(|name| !Chunked::is_reserved(name));
+			names.extend_from_slice(chunked.names());
+		}
+		Ok(names)
+	}
+
+	/// Returns the names of all of the blobs in archive order.
+	///
+	/// Blobs are files other than `.npy` files, e.g., `.json` files, see [`Self::npy_names`].
+	/// Their contents are accessible via [`Self::read_bytes`].
+	///
+	/// # Errors
+	///
+	/// Reading a zip archive can fail with [`ZipError`].
+	pub fn blob_names(&mut self) -> Result<Vec<String>, ReadNpzError> {
+		let mut names = classify_names(&mut self.zip)?.1;
+		if self.packed.is_some() {
+			names.retain(|name| !Packed::is_reserved(name));
+		}
+		if self.chunked.is_some() {
+			names.retain(|name| !Chunked::is_reserved(name));
+		}
+		Ok(names)
+	}
+
+	/// Reads the raw bytes of a file by name, e.g., of a blob.
+	///
+	/// # Errors
+	///
+	/// Reading a zip archive can fail with [`ZipError`].
+	pub fn read_bytes(&mut self, name: &str) -> Result<Vec<u8>, ReadNpzError> {
+		let mut file = self.zip.by_name(name)?;
+		let mut bytes = Vec::with_capacity(usize::try_from(file.size()).unwrap_or_default());
+		file.read_to_end(&mut bytes).map_err(ZipError::from)?;
+		Ok(bytes)
+	}
+}
+
+/// Returns the names of the `.npy` files and of the blobs by reading their magic strings.
+pub(crate) fn classify_names<R: Read + Seek>(
+	zip: &mut ZipArchive<R>,
+) -> Result<(Vec<String>, Vec<String>), ZipError> {
+	let mut npy_names = Vec::new();
+	let mut blob_names = Vec::new();
+	for index in 0..zip.len() {
+		// Files compressed by unsupported methods cannot be classified.
+		if !is_supported_file(&zip.by_index_raw(index)?) {
+			continue;
+		}
+		let mut file = zip.by_index(index)?;
+		if file.is_dir() {
+			continue;
+		}
+		let mut magic = Vec::with_capacity(MAGIC.len());
+		(&mut file)
+			.take(MAGIC.len() as u64)
+			.read_to_end(&mut magic)?;
+		if magic == MAGIC {
+			npy_names.push(file.name().to_owned());
+		} else {
+			blob_names.push(file.name().to_owned());
+		}
+	}
+	Ok((npy_names, blob_names))
+}
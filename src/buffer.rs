@@ -0,0 +1,215 @@
+use std::{
+	io::{self, Read, Seek, SeekFrom, Write},
+	mem,
+};
+
+/// Buffered writer keeping seeks within its buffer instead of flushing on each seek.
+///
+/// Writing a file to a zip archive ends with seeking back to its local header to patch in the
+/// checksum and sizes, and seeking forth again, whereas [`BufWriter`](io::BufWriter) flushes on
+/// each seek. For archives of many tiny arrays, e.g., tens of thousands of scalars, this results
+/// in several system calls per array dominating the time of writing. This writer buffers a
+/// contiguous window of the written bytes instead, patches them in place when seeking back into
+/// the window, and only writes the window to the inner writer once it is full or a write leaves
+/// it. Writes exceeding the capacity are passed through.
+///
+/// Like [`BufWriter`](io::BufWriter), it is flushed on [`Drop::drop`] ignoring errors, so it
+/// should be flushed explicitly, e.g., via [`NpzWriter::finish`](crate::NpzWriter::finish).
+///
+/// # Example
+///
+/// ```no_run
+/// use ndarray::arr0;
+/// use ndarray_npz::{BufSeekWriter, NpzWriter};
+/// use std::fs::File;
+///
+/// let mut npz = NpzWriter::new(BufSeekWriter::new(File::create("metrics.npz")?)?);
+/// for step in 0..80_000 {
+/// 	npz.add_array(format!("loss/{step}"), &arr0(0.5f32))?;
+/// }
+/// npz.finish()?;
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug)]
+pub struct BufSeekWriter<W: Write + Seek> {
+	/// Inner writer, only taken by [`Self::into_inner`].
+	inner: Option<W>,
+	/// Window of written bytes starting at `start`.
+	buf: Vec<u8>,
+	capacity: usize,
+	start: u64,
+	/// Logical position.
+	pos: u64,
+	/// Position of the inner writer if known.
+	inner_pos: Option<u64>,
+}
+
+impl<W: Write + Seek> BufSeekWriter<W> {
+	/// Creates a new writer with a buffer of 64 KiB starting at the current position of `inner`.
+	///
+	/// # Errors
+	///
+	/// Fails with [`io::Error`] if the position of `inner` cannot be queried.
+	pub fn new(inner: W) -> io::Result<Self> {
+		Self::with_capacity(64 * 1024, inner)
+	}
+
+	/// Like [`Self::new`] but with a buffer of `capacity` bytes.
+	///
+	/// # Errors
+	///
+	/// Fails with [`io::Error`] if the position of `inner` cannot be queried.
+	pub fn with_capacity(capacity: usize, mut inner: W) -> io::Result<Self> {
+		let pos = inner.stream_position()?;
+		Ok(Self {
+			inner: Some(inner),
+			buf: Vec::with_capacity(capacity),
+			capacity,
+			start: pos,
+			pos,
+			inner_pos: Some(pos),
+		})
+	}
+
+	/// Returns a reference to the inner writer.
+	///
+	/// Buffered bytes have not been written to it yet.
+	///
+	/// # Panics
+	///
+	/// Never panics, as the inner writer is only taken by [`Self::into_inner`] consuming the
+	/// writer.
+	#[must_use]
+	pub fn get_ref(&self) -> &W {
+		self.inner.as_ref().expect("inner writer")
+	}
+
+	/// Flushes the buffer and returns the inner writer positioned at the current position.
+	///
+	/// # Errors
+	///
+	/// Fails with [`io::Error`] if writing the buffer or seeking fails.
+	pub fn into_inner(mut self) -> io::Result<W> {
+		self.flush_buf()?;
+		self.seek_inner()?;
+		self.inner
+			.take()
+			.ok_or_else(|| io::Error::new(io::ErrorKind::Other, "inner writer taken"))
+	}
+
+	/// Returns a mutable reference to the inner writer, which must not be moved.
+	pub(crate) fn get_mut(&mut self) -> &mut W {
+		self.inner()
+	}
+
+	fn inner(&mut self) -> &mut W {
+		self.inner.as_mut().expect("inner writer")
+	}
+
+	/// Writes the window to the inner writer and starts an empty one at the current position.
+	fn flush_buf(&mut self) -> io::Result<()> {
+		if !self.buf.is_empty() {
+			let (start, pos) = (self.start, self.pos);
+			self.pos = start;
+			self.seek_inner()?;
+			self.pos = pos;
+			let buf = mem::take(&mut self.buf);
+			self.inner_pos = None;
+			let result = self.inner().write_all(&buf);
+			self.buf = buf;
+			result?;
+			self.inner_pos = Some(start + self.buf.len() as u64);
+			self.buf.clear();
+		}
+		self.start = self.pos;
+		Ok(())
+	}
+
+	/// Seeks the inner writer to the current position unless it is already there.
+	fn seek_inner(&mut self) -> io::Result<()> {
+		if self.inner_pos != Some(self.pos) {
+			self.inner_pos = None;
+			let pos = self.pos;
+			self.inner().seek(SeekFrom::Start(pos))?;
+			self.inner_pos = Some(pos);
+		}
+		Ok(())
+	}
+}
+
+impl<W: Write + Seek> Write for BufSeekWriter<W> {
+	fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+		let end = self.start + self.buf.len() as u64;
+		if self.pos < self.start || self.pos > end {
+			self.flush_buf()?;
+		}
+		let offset = usize::try_from(self.pos - self.start).expect("offset within buffer");
+		if offset + data.len() > self.capacity {
+			self.flush_buf()?;
+			if data.len() >= self.capacity {
+				self.seek_inner()?;
+				self.inner_pos = None;
+				let len = self.inner().write(data)?;
+				self.pos += len as u64;
+				self.inner_pos = Some(self.pos);
+				self.start = self.pos;
+				return Ok(len);
+			}
+		}
+		let offset = usize::try_from(self.pos - self.start).expect("offset within buffer");
+		let overlap = data.len().min(self.buf.len() - offset);
+		self.buf[offset..offset + overlap].copy_from_slice(&data[..overlap]);
+		self.buf.extend_from_slice(&data[overlap..]);
+		self.pos += data.len() as u64;
+		Ok(data.len())
+	}
+	fn flush(&mut self) -> io::Result<()> {
+		self.flush_buf()?;
+		self.inner().flush()
+	}
+}
+
+impl<W: Write + Seek> Seek for BufSeekWriter<W> {
+	fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+		match pos {
+			SeekFrom::Start(pos) => self.pos = pos,
+			SeekFrom::Current(offset) => {
+				self.pos = self.pos.checked_add_signed(offset).ok_or_else(|| {
+					io::Error::new(io::ErrorKind::InvalidInput, "seek before start")
+				})?;
+			}
+			SeekFrom::End(offset) => {
+				self.flush_buf()?;
+				self.inner_pos = None;
+				self.pos = self.inner().seek(SeekFrom::End(offset))?;
+				self.inner_pos = Some(self.pos);
+				self.start = self.pos;
+			}
+		}
+		Ok(self.pos)
+	}
+	fn stream_position(&mut self) -> io::Result<u64> {
+		Ok(self.pos)
+	}
+}
+
+impl<W: Read + Write + Seek> Read for BufSeekWriter<W> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		self.flush_buf()?;
+		self.seek_inner()?;
+		self.inner_pos = None;
+		let len = self.inner().read(buf)?;
+		self.pos += len as u64;
+		self.inner_pos = Some(self.pos);
+		self.start = self.pos;
+		Ok(len)
+	}
+}
+
+impl<W: Write + Seek> Drop for BufSeekWriter<W> {
+	fn drop(&mut self) {
+		if self.inner.is_some() {
+			let _ = self.flush_buf();
+		}
+	}
+}
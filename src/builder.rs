@@ -0,0 +1,738 @@
+use super::{
+	capability::{is_supported_file, scan},
+	convert::{swap, Dtype},
+	header::{NpyHeader, MAGIC},
+	lenient::{conforms, read_header},
+	limit::{self, Bounded, EXCEEDED},
+	HeaderStrictness, NpzReader, NpzView, NpzViewMut, NpzWriter, Provenance, ReadNpzError,
+	ViewNpzError, WriteNpzError,
+};
+use ndarray::{ArrayBase, Data, Dimension};
+use ndarray_npy::{WritableElement, WriteNpyExt};
+use py_literal::Value;
+use std::{
+	fmt::Write as _,
+	io::{self, Cursor, Read, Seek, SeekFrom, Write},
+	mem,
+};
+use zip::{
+	result::ZipError, write::SimpleFileOptions, CompressionMethod, DateTime, ZipArchive, ZipWriter,
+};
+
+/// Default capacity in bytes of the buffer between serializing arrays and the zip archive.
+pub(crate) const BUFFER_SIZE: usize = 8 * 1024;
+
+/// Byte order of written `.npy` files, see [`NpzWriterBuilder::endianness`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Endianness {
+	/// Byte order of the target, required for viewing via [`NpzView`](crate::NpzView).
+	#[default]
+	Native,
+	/// Little endian, e.g., matching NumPy's output on common platforms.
+	Little,
+	/// Big endian.
+	Big,
+}
+
+impl Endianness {
+	/// Whether it is big endian.
+	fn is_big(self) -> bool {
+		match self {
+			Endianness::Native => cfg!(target_endian = "big"),
+			Endianness::Little => false,
+			Endianness::Big => true,
+		}
+	}
+}
+
+/// Policy of files added under a name already taken, see [`NpzWriterBuilder::duplicates`].
+///
+/// Otherwise, the archive would hold several files of the same name of which NumPy only sees one.
+/// Replacing earlier files by later ones requires a readable writer, see
+/// [`NpzWriter::overwrite_duplicates`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Duplicates {
+	/// Fails with [`WriteNpzError::DuplicateName`].
+	#[default]
+	Reject,
+	/// Keeps the first array and silently skips later ones.
+	Skip,
+}
+
+/// Builder of an [`NpzWriter`] setting several options at once.
+///
+/// Defaults to the options of [`NpzWriter::new`], i.e., uncompressed and 64-byte aligned `.npy`
+/// files in native byte order for memory-mapping via [`NpzView`](crate::NpzView).
+///
+/// # Example
+///
+/// ```
+/// use ndarray::Array1;
+/// use ndarray_npz::{Duplicates, Endianness, NpzReader, NpzWriterBuilder};
+/// use std::io::Cursor;
+///
+/// let mut npz = NpzWriterBuilder::new()
+/// 	.deterministic(true)
+/// 	.endianness(Endianness::Big)
+/// 	.duplicates(Duplicates::Skip)
+/// 	.buffer_size(64 * 1024)
+/// 	.build(Cursor::new(Vec::new()));
+/// npz.add_array("x.npy", &Array1::<f64>::ones(3))?;
+/// npz.add_array("x.npy", &Array1::<f64>::zeros(3))?;
+/// let mut npz = NpzReader::new(npz.finish()?)?;
+/// let x: Array1<f64> = npz.by_name("x.npy")?;
+/// assert_eq!(x, Array1::<f64>::ones(3));
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct NpzWriterBuilder {
+	compression: CompressionMethod,
+	compression_level: Option<i64>,
+	alignment: u16,
+	last_modified_time: Option<DateTime>,
+	unix_permissions: Option<u32>,
+	deterministic: bool,
+	endianness: Endianness,
+	duplicates: Duplicates,
+	buffer_size: usize,
+	npy_extension: bool,
+	provenance: Option<Provenance>,
+}
+
+impl Default for NpzWriterBuilder {
+	fn default() -> Self {
+		Self {
+			compression: CompressionMethod::Stored,
+			compression_level: None,
+			alignment: 64,
+			last_modified_time: None,
+			unix_permissions: None,
+			deterministic: false,
+			endianness: Endianness::Native,
+			duplicates: Duplicates::Reject,
+			buffer_size: BUFFER_SIZE,
+			npy_extension: false,
+			provenance: None,
+		}
+	}
+}
+
+impl NpzWriterBuilder {
+	/// Creates a new builder with the options of [`NpzWriter::new`].
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets the compression `method`, e.g., [`CompressionMethod::Deflated`] with the
+	/// `compressed` feature. Defaults to [`CompressionMethod::Stored`].
+	///
+	/// Methods disabled by features fail on adding files with
+	/// [`ZipError::UnsupportedArchive`].
+	#[must_use]
+	pub fn compression(mut self, method: CompressionMethod) -> Self {
+		self.compression = method;
+		self
+	}
+
+	/// Sets the compression `level` or the default level of the method if `None`.
+	#[must_use]
+	pub fn compression_level(mut self, level: Option<i64>) -> Self {
+		self.compression_level = level;
+		self
+	}
+
+	/// Sets the `alignment` in bytes of the file data. Defaults to 64 bytes.
+	///
+	/// Only uncompressed `.npy` files aligned to their element size can be viewed, hence `0` or
+	/// `1` disables alignment, e.g., to save the padding of compressed files.
+	#[must_use]
+	pub fn alignment(mut self, alignment: u16) -> Self {
+		self.alignment = alignment;
+		self
+	}
+
+	/// Sets the modification time of the files, see [`NpzWriter::with_last_modified_time`].
+	#[must_use]
+	pub fn last_modified_time(mut self, time: DateTime) -> Self {
+		self.last_modified_time = Some(time);
+		self
+	}
+
+	/// Sets the Unix permissions of the files, see [`NpzWriter::with_unix_permissions`].
+	#[must_use]
+	pub fn unix_permissions(mut self, mode: u32) -> Self {
+		self.unix_permissions = Some(mode);
+		self
+	}
+
+	/// Whether to write byte-for-byte reproducible archives, see [`verify_deterministic`].
+	///
+	/// Fixes the modification time to the earliest time representable, 1980-01-01 00:00:00,
+	/// unless set via [`Self::last_modified_time`], as it otherwise defaults to the current time
+	/// with the `time` feature of the zip crate. Provenance records its own creation time and
+	/// must be left unset or created with a fixed [`Provenance::created`].
+	///
+	/// [`verify_deterministic`]: crate::verify_deterministic
+	#[must_use]
+	pub fn deterministic(mut self, deterministic: bool) -> Self {
+		self.deterministic = deterministic;
+		self
+	}
+
+	/// Sets the byte order of the added arrays. Defaults to [`Endianness::Native`].
+	///
+	/// See [`NpzWriter::with_endianness`].
+	#[must_use]
+	pub fn endianness(mut self, endianness: Endianness) -> Self {
+		self.endianness = endianness;
+		self
+	}
+
+	/// Sets the policy of files added under a name already taken. Defaults to
+	/// [`Duplicates::Reject`].
+	///
+	/// Applies to [`NpzWriter::add_array`], [`NpzWriter::add_npy_bytes`], and
+	/// [`NpzWriter::add_bytes`], whereas other files, e.g., sidecars of arrays, are always
+	/// rejected with [`ZipError::InvalidArchive`].
+	#[must_use]
+	pub fn duplicates(mut self, duplicates: Duplicates) -> Self {
+		self.duplicates = duplicates;
+		self
+	}
+
+	/// Sets the capacity in bytes of the buffer between serializing arrays and the zip archive.
+	/// Defaults to 8 KiB.
+	///
+	/// Larger buffers reduce the number of writes, e.g., to network file systems.
+	#[must_use]
+	pub fn buffer_size(mut self, size: usize) -> Self {
+		self.buffer_size = size;
+		self
+	}
+
+	/// Whether to append `.npy` to names of added arrays lacking it like
+	/// [`NpzWriter::numpy_compat`]. Defaults to `false`.
+	#[must_use]
+	pub fn npy_extension(mut self, npy_extension: bool) -> Self {
+		self.npy_extension = npy_extension;
+		self
+	}
+
+	/// Sets the provenance, see [`NpzWriter::with_provenance`].
+	#[must_use]
+	pub fn provenance(mut self, provenance: Provenance) -> Self {
+		self.provenance = Some(provenance);
+		self
+	}
+
+	/// Builds the [`NpzWriter`] of `writer`.
+	#[must_use]
+	pub fn build<W: Write + Seek>(self, writer: W) -> NpzWriter<W> {
+		let mut options = SimpleFileOptions::default()
+			.compression_method(self.compression)
+			.compression_level(self.compression_level)
+			.with_alignment(self.alignment);
+		if let Some(time) = self
+			.last_modified_time
+			.or_else(|| self.deterministic.then(DateTime::default))
+		{
+			options = options.last_modified_time(time);
+		}
+		if let Some(mode) = self.unix_permissions {
+			options = options.unix_permissions(mode);
+		}
+		NpzWriter {
+			alignment: self.alignment,
+			npy_extension: self.npy_extension,
+			provenance: self.provenance,
+			endianness: self.endianness,
+			duplicates: self.duplicates,
+			buffer_size: self.buffer_size,
+			..NpzWriter::with_options(ZipWriter::new(writer), options)
+		}
+	}
+}
+
+impl<W: Write + Seek> NpzWriter<W> {
+	/// Claims the `name` of a file to be added, returns whether it is a duplicate to be skipped.
+	///
+	/// If overwriting duplicates, the `name` of a duplicate is replaced by a unique placeholder
+	/// renamed on [`NpzWriter::finish`].
+	pub(crate) fn claim_name(&mut self, name: &mut String) -> Result<bool, WriteNpzError> {
+		if self.names.insert(name.clone()) {
+			return Ok(false);
+		}
+		if let Some(overwrites) = &mut self.overwrites {
+			if self.packing.is_none() && self.chunking.is_none() {
+				let mut placeholder = name.clone();
+				for count in overwrites.len().. {
+					placeholder.truncate(name.len());
+					let _ = write!(placeholder, "\0{count}");
+					if self.names.insert(placeholder.clone()) {
+						break;
+					}
+				}
+				overwrites.insert(placeholder.clone(), mem::replace(name, placeholder));
+				return Ok(false);
+			}
+		}
+		match self.duplicates {
+			Duplicates::Reject => Err(WriteNpzError::DuplicateName(mem::take(name))),
+			Duplicates::Skip => Ok(true),
+		}
+	}
+
+	/// Adds the array in foreign byte order if enabled, returns whether it has been added.
+	pub(crate) fn add_foreign_endian<S, D>(
+		&mut self,
+		name: &str,
+		array: &ArrayBase<S, D>,
+	) -> Result<bool, WriteNpzError>
+	where
+		S::Elem: WritableElement,
+		S: Data,
+		D: Dimension,
+	{
+		if self.endianness.is_big() == cfg!(target_endian = "big") {
+			return Ok(false);
+		}
+		let bytes = self.npy_bytes(array)?;
+		if !self.add_guarded(name, &bytes)? {
+			self.zip.start_file(name, self.options)?;
+			self.zip.write_all(&bytes).map_err(ZipError::from)?;
+		}
+		Ok(true)
+	}
+
+	/// Encodes the array as `.npy` file in the byte order of the writer.
+	pub(crate) fn npy_bytes<S, D>(&self, array: &ArrayBase<S, D>) -> Result<Vec<u8>, WriteNpzError>
+	where
+		S::Elem: WritableElement,
+		S: Data,
+		D: Dimension,
+	{
+		let mut bytes = Vec::new();
+		array.write_npy(&mut bytes)?;
+		swap_npy(&mut bytes, self.endianness)?;
+		Ok(bytes)
+	}
+}
+
+/// Swaps the encoded `.npy` file in native byte order to the given `endianness`.
+pub(crate) fn swap_npy(bytes: &mut [u8], endianness: Endianness) -> Result<(), WriteNpzError> {
+	let big_endian = endianness.is_big();
+	if big_endian != cfg!(target_endian = "big") {
+		let invalid = || ZipError::InvalidArchive("Invalid npy header");
+		let mut header = NpyHeader::parse(bytes).ok_or_else(invalid)?;
+		let foreign = match Dtype::parse(&header.descr) {
+			Some((dtype, _)) => (dtype.size() > 1).then(|| (dtype.descr(big_endian), dtype.size())),
+			None => complex_descr(&header.descr, big_endian),
+		};
+		if let Some((descr, size)) = foreign {
+			header.descr = descr;
+			let header_bytes = header.to_bytes_with_len(header.len).ok_or_else(invalid)?;
+			bytes[..header.len].copy_from_slice(&header_bytes);
+			swap(&mut bytes[header.len..], size);
+		}
+	}
+	Ok(())
+}
+
+/// Returns the type descriptor of a complex `descr` in the given byte order and the size of its
+/// real and imaginary parts, each swapped on its own.
+fn complex_descr(descr: &Value, big_endian: bool) -> Option<(Value, usize)> {
+	let Value::String(descr) = descr else {
+		return None;
+	};
+	let (order, code) = (descr.get(..1)?, descr.get(1..)?);
+	let size = match (order, code) {
+		("<" | ">" | "=", "c8") => 4,
+		("<" | ">" | "=", "c16") => 8,
+		_ => return None,
+	};
+	let order = if big_endian { ">" } else { "<" };
+	Some((Value::String(format!("{order}{code}")), size))
+}
+
+/// Resource limits of reading or viewing an archive, see [`NpzReaderBuilder::limits`].
+///
+/// Limits apply to the uncompressed sizes declared in the central directory and are checked on
+/// opening the archive. Unlimited by default.
+///
+/// As declared sizes can lie, [`Self::max_entry_size`] is enforced on reading each file via an
+/// [`NpzReader`] as well, i.e., reading fails with [`ZipError::UnsupportedArchive`] once a file
+/// decompresses to more bytes or its `.npy` header declares more bytes before they are allocated.
+/// The header of a chunked array declares the bytes of all of its chunks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Limits {
+	/// Maximum number of files, including directories and reserved members.
+	pub max_entries: Option<usize>,
+	/// Maximum size in bytes of each file.
+	pub max_entry_size: Option<u64>,
+	/// Maximum size in bytes of all files.
+	pub max_total_size: Option<u64>,
+}
+
+impl Limits {
+	/// Checks the limits against the files of `zip`.
+	fn check<R: Read + Seek>(&self, zip: &mut ZipArchive<R>) -> Result<(), ZipError> {
+		if self
+			.max_entries
+			.is_some_and(|max_entries| zip.len() > max_entries)
+		{
+			return Err(ZipError::UnsupportedArchive(
+				"Archive exceeds limit of files",
+			));
+		}
+		let mut total_size = 0u64;
+		for index in 0..zip.len() {
+			let size = zip.by_index_raw(index)?.size();
+			if self.max_entry_size.is_some_and(|max_size| size > max_size) {
+				return Err(ZipError::UnsupportedArchive(EXCEEDED));
+			}
+			total_size = total_size.saturating_add(size);
+		}
+		if self
+			.max_total_size
+			.is_some_and(|max_size| total_size > max_size)
+		{
+			return Err(ZipError::UnsupportedArchive(
+				"Archive exceeds limit of total size",
+			));
+		}
+		Ok(())
+	}
+}
+
+/// Ingestion policy shared by [`NpzReaderBuilder`] and [`NpzViewBuilder`].
+#[derive(Debug, Clone, Copy, Default)]
+struct Policy {
+	limits: Limits,
+	reject_duplicates: bool,
+	verify_checksums: bool,
+	strict: bool,
+	headers: HeaderStrictness,
+}
+
+impl Policy {
+	/// Checks the raw zip structures of `reader` and restores its position.
+	fn check_structures<R: Read + Seek>(&self, reader: &mut R) -> Result<(), ZipError> {
+		if !self.strict && !self.reject_duplicates {
+			return Ok(());
+		}
+		let position = reader.stream_position()?;
+		let capabilities = scan(reader)?;
+		reader.seek(SeekFrom::Start(position))?;
+		if self.strict {
+			capabilities.check()?;
+		}
+		if self.reject_duplicates && !capabilities.duplicates.is_empty() {
+			return Err(ZipError::UnsupportedArchive(
+				"Duplicate file names are not supported",
+			));
+		}
+		Ok(())
+	}
+	/// Checks the limits, the `.npy` headers, and verifies the checksums of the files of `zip` if
+	/// enabled.
+	fn check_files<R: Read + Seek>(&self, zip: &mut ZipArchive<R>) -> Result<(), ZipError> {
+		self.limits.check(zip)?;
+		let strict = self.headers == HeaderStrictness::Strict;
+		if !strict && !self.verify_checksums {
+			return Ok(());
+		}
+		for index in 0..zip.len() {
+			let file = zip.by_index_raw(index)?;
+			if file.is_dir() || file.encrypted() || !is_supported_file(&file) {
+				continue;
+			}
+			drop(file);
+			let file = zip.by_index(index)?;
+			let mut file =
+				Bounded::new(file, self.limits.max_entry_size).map_err(limit::zip_error)?;
+			if strict {
+				let header = read_header(&mut file).map_err(limit::zip_error)?;
+				if header.starts_with(MAGIC) && !conforms(&header) {
+					return Err(ZipError::UnsupportedArchive(
+						"Non-conforming npy header is not supported",
+					));
+				}
+			}
+			if self.verify_checksums {
+				// Reading to the end verifies the checksum.
+				io::copy(&mut file, &mut io::sink()).map_err(limit::zip_error)?;
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Builder of an [`NpzReader`] enforcing an ingestion policy on opening archives.
+///
+/// Services accepting archives from untrusted sources can configure their policy once, e.g.,
+/// resource limits against zip bombs, and open every archive through it. Defaults to the
+/// behavior of [`NpzReader::new`], which is lenient, i.e., it opens archives with files that
+/// cannot be read, e.g., encrypted files or files listed by [`NpzReader::unsupported_names`],
+/// failing on reading them only.
+///
+/// # Example
+///
+/// ```
+/// use ndarray::Array1;
+/// use ndarray_npz::{Limits, NpzReaderBuilder, NpzWriter};
+/// use std::io::Cursor;
+///
+/// let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+/// npz.add_array("x.npy", &Array1::<f64>::zeros(1000))?;
+/// let buffer = npz.finish()?;
+/// let builder = NpzReaderBuilder::new()
+/// 	.limits(Limits {
+/// 		max_entry_size: Some(1024),
+/// 		..Limits::default()
+/// 	})
+/// 	.reject_duplicates(true)
+/// 	.verify_checksums(true)
+/// 	.strict(true);
+/// assert!(builder.clone().build(buffer.clone()).is_err());
+/// let mut npz = builder
+/// 	.limits(Limits {
+/// 		max_entry_size: Some(1 << 20),
+/// 		..Limits::default()
+/// 	})
+/// 	.build(buffer)?;
+/// let x: Array1<f64> = npz.by_name("x.npy")?;
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct NpzReaderBuilder {
+	policy: Policy,
+	normalize_names: bool,
+	cache: Option<usize>,
+}
+
+impl Default for NpzReaderBuilder {
+	fn default() -> Self {
+		Self {
+			policy: Policy::default(),
+			normalize_names: true,
+			cache: None,
+		}
+	}
+}
+
+impl NpzReaderBuilder {
+	/// Creates a new builder with the behavior of [`NpzReader::new`].
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets the resource `limits`.
+	#[must_use]
+	pub fn limits(mut self, limits: Limits) -> Self {
+		self.policy.limits = limits;
+		self
+	}
+
+	/// Whether to find files by names differing in their Unicode normalization form, see
+	/// [`NpzReader::by_name`]. Defaults to `true` but requires the `unicode` feature.
+	#[must_use]
+	pub fn normalize_names(mut self, normalize_names: bool) -> Self {
+		self.normalize_names = normalize_names;
+		self
+	}
+
+	/// Whether to reject archives with duplicate names, see [`Capabilities::duplicates`].
+	/// Defaults to `false`, in which case only the last of the files of the same name can be
+	/// read.
+	///
+	/// [`Capabilities::duplicates`]: crate::Capabilities::duplicates
+	#[must_use]
+	pub fn reject_duplicates(mut self, reject_duplicates: bool) -> Self {
+		self.policy.reject_duplicates = reject_duplicates;
+		self
+	}
+
+	/// Whether to verify the CRC-32 checksums of all files on opening the archive. Defaults to
+	/// `false`, in which case the checksum of a file is verified once it has been read completely.
+	///
+	/// Encrypted files and files compressed by unsupported methods are skipped unless
+	/// [`Self::strict`] rejects them in the first place.
+	#[must_use]
+	pub fn verify_checksums(mut self, verify_checksums: bool) -> Self {
+		self.policy.verify_checksums = verify_checksums;
+		self
+	}
+
+	/// Whether to reject archives with files which cannot be read, see [`Capabilities::check`].
+	/// Defaults to `false`.
+	///
+	/// [`Capabilities::check`]: crate::Capabilities::check
+	#[must_use]
+	pub fn strict(mut self, strict: bool) -> Self {
+		self.policy.strict = strict;
+		self
+	}
+
+	/// Sets the `strictness` of parsing `.npy` headers. Defaults to
+	/// [`HeaderStrictness::Standard`].
+	///
+	/// With [`HeaderStrictness::Lenient`], arrays with headers padded differently or missing the
+	/// trailing newline are read by normalizing their headers. With [`HeaderStrictness::Strict`],
+	/// archives with such headers are rejected on opening them, e.g., in validation pipelines.
+	#[must_use]
+	pub fn header_strictness(mut self, strictness: HeaderStrictness) -> Self {
+		self.policy.headers = strictness;
+		self
+	}
+
+	/// Sets the `budget` of the cache, see [`NpzReader::with_cache`].
+	#[must_use]
+	pub fn cache(mut self, budget: usize) -> Self {
+		self.cache = Some(budget);
+		self
+	}
+
+	/// Builds the [`NpzReader`] of `reader` enforcing the policy.
+	///
+	/// # Errors
+	///
+	/// Fails like [`NpzReader::new`] or with [`ZipError::UnsupportedArchive`] if the archive
+	/// violates the policy. Verifying the checksums can fail with [`ZipError::Io`].
+	pub fn build<R: Read + Seek>(self, mut reader: R) -> Result<NpzReader<R>, ReadNpzError> {
+		self.policy.check_structures(&mut reader)?;
+		let mut npz = NpzReader::new(reader)?;
+		self.policy.check_files(&mut npz.zip)?;
+		npz.normalize = self.normalize_names;
+		npz.lenient = self.policy.headers == HeaderStrictness::Lenient;
+		npz.max_entry_size = self.policy.limits.max_entry_size;
+		if let Some(budget) = self.cache {
+			npz = npz.with_cache(budget);
+		}
+		Ok(npz)
+	}
+}
+
+/// Builder of an [`NpzView`] or an [`NpzViewMut`] enforcing an ingestion policy on viewing
+/// archives.
+///
+/// Enforces the same policy as [`NpzReaderBuilder`], whereas views never normalize names.
+///
+/// # Example
+///
+/// ```
+/// use aligned_vec::AVec;
+/// use ndarray::Array1;
+/// use ndarray_npz::{Limits, NpzViewBuilder, NpzWriter};
+/// use std::io::Cursor;
+///
+/// let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+/// npz.add_array("x.npy", &Array1::<f64>::zeros(10))?;
+/// let mut buffer = AVec::<u8>::from_slice(64, &npz.finish()?.into_inner());
+/// let builder = NpzViewBuilder::new()
+/// 	.limits(Limits {
+/// 		max_entries: Some(100),
+/// 		..Limits::default()
+/// 	})
+/// 	.verify_checksums(true);
+/// assert_eq!(builder.view(&buffer)?.len(), 1);
+/// assert_eq!(builder.view_mut(&mut buffer)?.len(), 1);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct NpzViewBuilder {
+	policy: Policy,
+}
+
+impl NpzViewBuilder {
+	/// Creates a new builder with the behavior of [`NpzView::new`] and [`NpzViewMut::new`].
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets the resource `limits`, see [`NpzReaderBuilder::limits`].
+	#[must_use]
+	pub fn limits(mut self, limits: Limits) -> Self {
+		self.policy.limits = limits;
+		self
+	}
+
+	/// Whether to reject archives with duplicate names, see
+	/// [`NpzReaderBuilder::reject_duplicates`].
+	#[must_use]
+	pub fn reject_duplicates(mut self, reject_duplicates: bool) -> Self {
+		self.policy.reject_duplicates = reject_duplicates;
+		self
+	}
+
+	/// Whether to verify the CRC-32 checksums of all files on viewing the archive, see
+	/// [`NpzReaderBuilder::verify_checksums`]. Defaults to `false`, in which case the checksums
+	/// can be verified via [`NpyView::verify`](crate::NpyView::verify).
+	#[must_use]
+	pub fn verify_checksums(mut self, verify_checksums: bool) -> Self {
+		self.policy.verify_checksums = verify_checksums;
+		self
+	}
+
+	/// Whether to reject archives with files which cannot be read, see
+	/// [`NpzReaderBuilder::strict`].
+	#[must_use]
+	pub fn strict(mut self, strict: bool) -> Self {
+		self.policy.strict = strict;
+		self
+	}
+
+	/// Sets the `strictness` of parsing `.npy` headers, see
+	/// [`NpzReaderBuilder::header_strictness`].
+	///
+	/// With [`HeaderStrictness::Lenient`], arrays with headers padded differently or missing the
+	/// trailing newline are viewed by parsing their headers in place instead of normalizing them.
+	#[must_use]
+	pub fn header_strictness(mut self, strictness: HeaderStrictness) -> Self {
+		self.policy.headers = strictness;
+		self
+	}
+
+	/// Creates an immutable view of `bytes` enforcing the policy.
+	///
+	/// # Errors
+	///
+	/// Fails like [`NpzView::new`] or with [`ZipError::UnsupportedArchive`] if the archive
+	/// violates the policy.
+	pub fn view<'a>(&self, bytes: &'a [u8]) -> Result<NpzView<'a>, ViewNpzError> {
+		self.check(bytes)?;
+		let mut npz = NpzView::new(bytes)?;
+		if self.policy.headers == HeaderStrictness::Lenient {
+			for file in npz.files.values_mut() {
+				file.lenient = true;
+			}
+		}
+		Ok(npz)
+	}
+
+	/// Creates a mutable view of `bytes` enforcing the policy.
+	///
+	/// # Errors
+	///
+	/// Fails like [`NpzViewMut::new`] or with [`ZipError::UnsupportedArchive`] if the archive
+	/// violates the policy.
+	pub fn view_mut<'a>(&self, bytes: &'a mut [u8]) -> Result<NpzViewMut<'a>, ViewNpzError> {
+		self.check(bytes)?;
+		let mut npz = NpzViewMut::new(bytes)?;
+		if self.policy.headers == HeaderStrictness::Lenient {
+			for file in npz.files.values_mut() {
+				file.lenient = true;
+			}
+		}
+		Ok(npz)
+	}
+
+	/// Checks the archive of `bytes` against the policy.
+	fn check(&self, bytes: &[u8]) -> Result<(), ZipError> {
+		let mut reader = Cursor::new(bytes);
+		self.policy.check_structures(&mut reader)?;
+		self.policy.check_files(&mut ZipArchive::new(reader)?)
+	}
+}
@@ -0,0 +1,164 @@
+use super::{blob::classify_names, header::NpyHeader, EditNpzError, NpzReader, NpzWriter};
+use py_literal::Value;
+use std::io::{self, Read, Seek, Write};
+
+/// Packs the `.npy` files of `reader` into a single `.npy` file written to `writer`.
+///
+/// The bundle is a zero-dimensional array of a structured data type with one field per `.npy`
+/// file in archive order. Each field is named after its file without the `.npy` extension and is
+/// a subarray of the file's element type and shape, e.g., a bundle of `a.npy` and `b.npy` has the
+/// header `{'descr': [('a', '<f8', (2, 3)), ('b', '<i4', (5,))], 'fortran_order': False,
+/// 'shape': ()}`. The fields are packed without padding, hence NumPy reads it via
+/// `numpy.load("bundle.npy")["a"]`. Blobs are skipped, see [`NpzReader::npy_names`].
+///
+/// # Example
+///
+/// ```no_run
+/// use ndarray_npz::{pack_bundle, NpzReader};
+/// use std::fs::File;
+///
+/// let mut npz = NpzReader::new(File::open("arrays.npz")?)?;
+/// pack_bundle(&mut npz, File::create("bundle.npy")?)?;
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// # Errors
+///
+/// Fails with [`EditNpzError::FortranOrder`] if a multidimensional array is in Fortran order, with
+/// [`EditNpzError::UnsupportedDtype`] if an element type has no fixed size, with
+/// [`EditNpzError::InvalidHeader`] if an `.npy` header cannot be parsed, or with
+/// [`ZipError`](zip::result::ZipError) if reading the zip archive or writing the bundle fails.
+pub fn pack_bundle<R, W>(reader: &mut NpzReader<R>, mut writer: W) -> Result<(), EditNpzError>
+where
+	R: Read + Seek,
+	W: Write,
+{
+	let mut members = Vec::new();
+	let mut fields = Vec::new();
+	for name in classify_names(&mut reader.zip)?.0 {
+		let mut file = reader.zip.by_name(&name)?;
+		let header = NpyHeader::read(&mut file).map_err(|_| EditNpzError::InvalidHeader)?;
+		if header.fortran_order && header.shape.len() > 1 {
+			return Err(EditNpzError::FortranOrder);
+		}
+		header.item_size().ok_or(EditNpzError::UnsupportedDtype)?;
+		let field_name = name.strip_suffix(".npy").unwrap_or(&name).to_owned();
+		let mut field = vec![Value::String(field_name), header.descr];
+		if !header.shape.is_empty() {
+			field.push(Value::Tuple(
+				header
+					.shape
+					.iter()
+					.map(|&axis| Value::Integer(axis.into()))
+					.collect(),
+			));
+		}
+		fields.push(Value::Tuple(field));
+		members.push(name);
+	}
+	let mut header = NpyHeader {
+		descr: Value::List(fields),
+		fortran_order: false,
+		shape: Vec::new(),
+		version: (1, 0),
+		len: 0,
+	};
+	let bytes = if let Some(bytes) = header.to_bytes() {
+		bytes
+	} else {
+		header.version = (2, 0);
+		header.to_bytes().ok_or(EditNpzError::HeaderOverflow)?
+	};
+	writer.write_all(&bytes)?;
+	for name in members {
+		let mut file = reader.zip.by_name(&name)?;
+		NpyHeader::read(&mut file)?;
+		io::copy(&mut file, &mut writer)?;
+	}
+	writer.flush()?;
+	Ok(())
+}
+
+/// Unpacks the bundle of [`pack_bundle`] read from `reader` into `writer` with one `.npy` file per
+/// field.
+///
+/// Accepts zero-dimensional bundles as well as one-dimensional bundles of a single record.
+///
+/// # Example
+///
+/// ```no_run
+/// use ndarray_npz::{unpack_bundle, NpzWriter};
+/// use std::fs::File;
+///
+/// let mut npz = NpzWriter::new(File::create("arrays.npz")?);
+/// unpack_bundle(File::open("bundle.npy")?, &mut npz)?;
+/// npz.finish()?;
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// # Errors
+///
+/// Fails with [`EditNpzError::InvalidHeader`] if the header is not the one of a bundle, with
+/// [`EditNpzError::UnsupportedDtype`] if a field's element type has no fixed size, or with
+/// [`ZipError`](zip::result::ZipError) if reading the bundle or writing the zip archive fails.
+pub fn unpack_bundle<R, W>(mut reader: R, writer: &mut NpzWriter<W>) -> Result<(), EditNpzError>
+where
+	R: Read,
+	W: Write + Seek,
+{
+	let header = NpyHeader::read(&mut reader).map_err(|_| EditNpzError::InvalidHeader)?;
+	if !matches!(header.shape.as_slice(), [] | [1]) {
+		return Err(EditNpzError::InvalidHeader);
+	}
+	let Value::List(fields) = header.descr else {
+		return Err(EditNpzError::InvalidHeader);
+	};
+	for field in fields {
+		let Value::Tuple(field) = field else {
+			return Err(EditNpzError::InvalidHeader);
+		};
+		let mut field = field.into_iter();
+		let (Some(Value::String(name)), Some(descr)) = (field.next(), field.next()) else {
+			return Err(EditNpzError::InvalidHeader);
+		};
+		let shape = match field.next() {
+			None => Vec::new(),
+			Some(Value::Tuple(shape)) => shape
+				.into_iter()
+				.map(|axis| match axis {
+					Value::Integer(axis) => u64::try_from(axis).ok(),
+					_ => None,
+				})
+				.collect::<Option<Vec<u64>>>()
+				.ok_or(EditNpzError::InvalidHeader)?,
+			Some(Value::Integer(axis)) => {
+				vec![u64::try_from(axis).map_err(|_| EditNpzError::InvalidHeader)?]
+			}
+			Some(_) => return Err(EditNpzError::InvalidHeader),
+		};
+		if field.next().is_some() {
+			return Err(EditNpzError::InvalidHeader);
+		}
+		let header = NpyHeader {
+			descr,
+			fortran_order: false,
+			shape,
+			version: (1, 0),
+			len: 0,
+		};
+		let item_size = header.item_size().ok_or(EditNpzError::UnsupportedDtype)?;
+		let len = header
+			.elements()
+			.and_then(|elements| elements.checked_mul(item_size))
+			.ok_or(EditNpzError::InvalidHeader)?;
+		let bytes = header.to_bytes().ok_or(EditNpzError::HeaderOverflow)?;
+		writer
+			.zip
+			.start_file(format!("{name}.npy"), writer.options)?;
+		writer.zip.write_all(&bytes)?;
+		if io::copy(&mut (&mut reader).take(len), &mut writer.zip)? != len {
+			return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+		}
+	}
+	Ok(())
+}
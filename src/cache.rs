@@ -0,0 +1,147 @@
+use super::{
+	limit::{self, Bounded},
+	timeout::Deadline,
+	NpzReader, ReadNpzError,
+};
+use std::{
+	collections::{BTreeMap, HashMap},
+	io::{Read, Seek},
+	sync::Arc,
+};
+use zip::CompressionMethod;
+
+/// Instrumentation of the decompressed-file cache, see [`NpzReader::with_cache`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct CacheStats {
+	/// Number of reads served from the cache.
+	pub hits: u64,
+	/// Number of reads which had to decompress.
+	pub misses: u64,
+	/// Number of files evicted to stay within the budget.
+	pub evictions: u64,
+	/// Number of decompressed bytes currently cached.
+	pub bytes: usize,
+}
+
+impl CacheStats {
+	/// Ratio of hits to all reads, `NaN` if there were no reads.
+	#[must_use]
+	pub fn hit_rate(&self) -> f64 {
+		#[allow(clippy::cast_precision_loss)]
+		let rate = self.hits as f64 / (self.hits + self.misses) as f64;
+		rate
+	}
+}
+
+/// Least-recently used cache of decompressed files within a byte budget.
+#[derive(Debug)]
+pub(crate) struct EntryCache {
+	budget: usize,
+	tick: u64,
+	entries: HashMap<String, (Arc<[u8]>, u64)>,
+	order: BTreeMap<u64, String>,
+	stats: CacheStats,
+}
+
+impl EntryCache {
+	/// Creates an empty cache of `budget` bytes.
+	fn new(budget: usize) -> Self {
+		Self {
+			budget,
+			tick: 0,
+			entries: HashMap::new(),
+			order: BTreeMap::new(),
+			stats: CacheStats::default(),
+		}
+	}
+	/// Returns the file `name` marking it as most recently used.
+	fn get(&mut self, name: &str) -> Option<Arc<[u8]>> {
+		let (bytes, tick) = self.entries.get_mut(name)?;
+		let name = self.order.remove(tick)?;
+		self.tick += 1;
+		*tick = self.tick;
+		self.order.insert(self.tick, name);
+		Some(bytes.clone())
+	}
+	/// Inserts the file `name` evicting least recently used ones to stay within the budget.
+	///
+	/// Files larger than the budget are not inserted.
+	fn insert(&mut self, name: &str, bytes: Arc<[u8]>) {
+		if bytes.len() > self.budget {
+			return;
+		}
+		while self.stats.bytes + bytes.len() > self.budget {
+			let Some((_tick, name)) = self.order.pop_first() else {
+				break;
+			};
+			if let Some((bytes, _tick)) = self.entries.remove(&name) {
+				self.stats.bytes -= bytes.len();
+				self.stats.evictions += 1;
+			}
+		}
+		self.tick += 1;
+		self.stats.bytes += bytes.len();
+		self.order.insert(self.tick, name.to_owned());
+		self.entries.insert(name.to_owned(), (bytes, self.tick));
+	}
+}
+
+impl<R: Read + Seek> NpzReader<R> {
+	/// Caches decompressed `.npy` files up to a `budget` in bytes.
+	///
+	/// Repeatedly reading the same compressed file via [`Self::by_name`] or [`Self::by_index`]
+	/// decompresses it once as long as it has not been evicted. The least recently used files are
+	/// evicted to stay within the `budget`. Files larger than the `budget` and uncompressed files
+	/// are never cached. See [`Self::cache_stats`] for the hit rate.
+	///
+	/// # Example
+	///
+	/// ```no_run
+	/// use ndarray::Array2;
+	/// use ndarray_npz::NpzReader;
+	/// use std::fs::File;
+	///
+	/// let npz = NpzReader::new(File::open("compressed.npz")?)?;
+	/// let mut npz = npz.with_cache(512 << 20);
+	/// for _ in 0..10 {
+	/// 	let a: Array2<f64> = npz.by_name("a.npy")?;
+	/// }
+	/// assert_eq!(npz.cache_stats().unwrap().hits, 9);
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	#[must_use]
+	pub fn with_cache(mut self, budget: usize) -> Self {
+		self.cache = Some(EntryCache::new(budget));
+		self
+	}
+
+	/// Returns the instrumentation of the cache if enabled via [`Self::with_cache`].
+	#[must_use]
+	pub fn cache_stats(&self) -> Option<CacheStats> {
+		self.cache.as_ref().map(|cache| cache.stats)
+	}
+
+	/// Returns the decompressed file `name` via the cache if enabled and the file is compressed.
+	pub(crate) fn cached(&mut self, name: &str) -> Result<Option<Arc<[u8]>>, ReadNpzError> {
+		let deadline = self.deadline();
+		let Some(cache) = &mut self.cache else {
+			return Ok(None);
+		};
+		if let Some(bytes) = cache.get(name) {
+			cache.stats.hits += 1;
+			return Ok(Some(bytes));
+		}
+		let file = self.zip.by_name(name)?;
+		if file.compression() == CompressionMethod::Stored {
+			return Ok(None);
+		}
+		let mut bytes = Vec::with_capacity(usize::try_from(file.size()).unwrap_or_default());
+		Bounded::new(Deadline::new(file, deadline), self.max_entry_size)
+			.and_then(|mut file| file.read_to_end(&mut bytes))
+			.map_err(limit::zip_error)?;
+		let bytes = Arc::<[u8]>::from(bytes);
+		cache.stats.misses += 1;
+		cache.insert(name, bytes.clone());
+		Ok(Some(bytes))
+	}
+}
@@ -0,0 +1,290 @@
+use super::{NpzReader, ReadNpzError};
+use std::{
+	collections::HashSet,
+	io::{Read, Seek, SeekFrom},
+};
+use zip::{read::ZipFile, result::ZipError, CompressionMethod, ZipArchive};
+
+/// Detail of [`ZipError::UnsupportedArchive`] for files compressed by unsupported methods.
+pub(crate) const UNSUPPORTED_COMPRESSION: &str = "Compression method not supported by this build";
+
+/// Zip features used by an archive, see [`NpzReader::capabilities`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Capabilities {
+	/// Whether Zip64 end of central directory records or extra fields are used.
+	pub zip64: bool,
+	/// Whether the archive spans multiple disks, i.e., is split into volumes.
+	pub multi_disk: bool,
+	/// Names of files followed by data descriptors.
+	pub data_descriptors: Vec<String>,
+	/// Names of encrypted files.
+	pub encrypted: Vec<String>,
+	/// Names and compression methods of files compressed by methods not supported by this build.
+	pub unsupported_compression: Vec<(String, u16)>,
+	/// Names occurring more than once, of which only the last file can be read.
+	pub duplicates: Vec<String>,
+}
+
+impl Capabilities {
+	/// Checks whether the archive can be read.
+	///
+	/// Zip64 and data descriptors are supported, hence not checked.
+	///
+	/// # Errors
+	///
+	/// Fails with [`ZipError::UnsupportedArchive`] explaining the first unsupported feature.
+	pub fn check(&self) -> Result<(), ZipError> {
+		if self.multi_disk {
+			Err(ZipError::UnsupportedArchive(
+				"Multi-disk archives are not supported",
+			))
+		} else if !self.encrypted.is_empty() {
+			Err(ZipError::UnsupportedArchive(
+				"Encrypted files are not supported",
+			))
+		} else if !self.unsupported_compression.is_empty() {
+			Err(ZipError::UnsupportedArchive(UNSUPPORTED_COMPRESSION))
+		} else {
+			Ok(())
+		}
+	}
+}
+
+impl<R: Read + Seek> NpzReader<R> {
+	/// Reports the zip features used by the archive of `reader` without reading it as such.
+	///
+	/// Unlike [`Self::new`], this parses the raw end of central directory record and the central
+	/// directory only, so tools can refuse unsupported archives early with a clear message via
+	/// [`Capabilities::check`], including multi-disk archives which [`Self::new`] cannot open.
+	///
+	/// # Example
+	///
+	/// ```no_run
+	/// use ndarray_npz::NpzReader;
+	/// use std::fs::File;
+	///
+	/// let mut file = File::open("arrays.npz")?;
+	/// let capabilities = NpzReader::capabilities(&mut file)?;
+	/// capabilities.check()?;
+	/// let mut npz = NpzReader::new(file)?;
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Fails with [`ZipError::InvalidArchive`] if the zip structures are malformed. Reading them
+	/// can fail with [`ZipError::Io`].
+	pub fn capabilities(reader: &mut R) -> Result<Capabilities, ReadNpzError> {
+		Ok(scan(reader)?)
+	}
+
+	/// Returns the names and compression methods of files compressed by methods not supported by
+	/// this build in archive order, e.g., *bzip2* (12), *LZMA* (14), or *XZ* (95).
+	///
+	/// The methods are detected on opening the archive, so these files can be skipped, whereas
+	/// reading them fails with [`ZipError::UnsupportedArchive`]. Enable the `bzip2` or `lzma`
+	/// features to read them. Files encrypted via AES (99) are not listed.
+	///
+	/// # Example
+	///
+	/// ```no_run
+	/// use ndarray_npz::NpzReader;
+	/// use std::fs::File;
+	///
+	/// let npz = NpzReader::new(File::open("arrays.npz")?)?;
+	/// for (name, method) in npz.unsupported_names() {
+	/// 	eprintln!("Skipping {name} compressed by method {method}");
+	/// }
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	#[must_use]
+	pub fn unsupported_names(&self) -> &[(String, u16)] {
+		&self.unsupported
+	}
+}
+
+/// Signature of the end of central directory record.
+pub(crate) const EOCD_SIGNATURE: u32 = 0x0605_4b50;
+/// Signature of the Zip64 end of central directory locator.
+pub(crate) const EOCD64_LOCATOR_SIGNATURE: u32 = 0x0706_4b50;
+/// Signature of the Zip64 end of central directory record.
+pub(crate) const EOCD64_SIGNATURE: u32 = 0x0606_4b50;
+/// Signature of a central header.
+pub(crate) const CENTRAL_SIGNATURE: u32 = 0x0201_4b50;
+
+/// Scans the end of central directory records and the central directory.
+pub(crate) fn scan<R: Read + Seek>(reader: &mut R) -> Result<Capabilities, ZipError> {
+	let invalid = ZipError::InvalidArchive;
+	let truncated = || invalid("Truncated zip structure");
+	let len = reader.seek(SeekFrom::End(0))?;
+	let tail_len = len.min(22 + 0xffff);
+	let mut tail = vec![0; usize::try_from(tail_len).unwrap_or_default()];
+	reader.seek(SeekFrom::Start(len - tail_len))?;
+	reader.read_exact(&mut tail)?;
+	let eocd = (0..tail.len().saturating_sub(21))
+		.rev()
+		.find(|&pos| u32_at(&tail, pos) == Some(EOCD_SIGNATURE))
+		.ok_or_else(|| invalid("No end of central directory record"))?;
+	let eocd_start = len - tail_len + eocd as u64;
+	let eocd = &tail[eocd..];
+	let mut capabilities = Capabilities {
+		multi_disk: u16_at(eocd, 4).ok_or_else(truncated)? != 0
+			|| u16_at(eocd, 6).ok_or_else(truncated)? != 0,
+		..Capabilities::default()
+	};
+	let mut entries = u64::from(u16_at(eocd, 10).ok_or_else(truncated)?);
+	let mut cd_size = u64::from(u32_at(eocd, 12).ok_or_else(truncated)?);
+	let mut cd_offset = u64::from(u32_at(eocd, 16).ok_or_else(truncated)?);
+	let mut locator = [0; 20];
+	if eocd_start >= 20 {
+		reader.seek(SeekFrom::Start(eocd_start - 20))?;
+		reader.read_exact(&mut locator)?;
+	}
+	let archive_offset = if u32_at(&locator, 0) == Some(EOCD64_LOCATOR_SIGNATURE) {
+		capabilities.zip64 = true;
+		capabilities.multi_disk |= u32_at(&locator, 4).ok_or_else(truncated)? != 0
+			|| u32_at(&locator, 16).ok_or_else(truncated)? > 1;
+		let mut eocd64 = [0; 56];
+		reader.seek(SeekFrom::Start(u64_at(&locator, 8).ok_or_else(truncated)?))?;
+		reader.read_exact(&mut eocd64)?;
+		if u32_at(&eocd64, 0) != Some(EOCD64_SIGNATURE) {
+			return Err(invalid("Invalid Zip64 end of central directory record"));
+		}
+		capabilities.multi_disk |= u32_at(&eocd64, 16).ok_or_else(truncated)? != 0
+			|| u32_at(&eocd64, 20).ok_or_else(truncated)? != 0;
+		entries = u64_at(&eocd64, 32).ok_or_else(truncated)?;
+		cd_size = u64_at(&eocd64, 40).ok_or_else(truncated)?;
+		cd_offset = u64_at(&eocd64, 48).ok_or_else(truncated)?;
+		0
+	} else {
+		// Data may be prepended to the archive.
+		eocd_start
+			.checked_sub(cd_offset + cd_size)
+			.ok_or_else(|| invalid("Invalid central directory size or offset"))?
+	};
+	if capabilities.multi_disk {
+		return Ok(capabilities);
+	}
+	let cd_start = archive_offset
+		.checked_add(cd_offset)
+		.filter(|cd_start| {
+			cd_start
+				.checked_add(cd_size)
+				.is_some_and(|cd_end| cd_end <= len)
+		})
+		.ok_or_else(|| invalid("Invalid central directory size or offset"))?;
+	let mut cd = vec![0; usize::try_from(cd_size).unwrap_or_default()];
+	reader.seek(SeekFrom::Start(cd_start))?;
+	reader.read_exact(&mut cd)?;
+	let invalid_header = || invalid("Invalid central header");
+	let mut names = HashSet::new();
+	let mut pos = 0;
+	for _entry in 0..entries {
+		if u32_at(&cd, pos) != Some(CENTRAL_SIGNATURE) {
+			return Err(invalid_header());
+		}
+		let flags = u16_at(&cd, pos + 8).ok_or_else(invalid_header)?;
+		let method = u16_at(&cd, pos + 10).ok_or_else(invalid_header)?;
+		let name_len = usize::from(u16_at(&cd, pos + 28).ok_or_else(invalid_header)?);
+		let extra_len = usize::from(u16_at(&cd, pos + 30).ok_or_else(invalid_header)?);
+		let comment_len = usize::from(u16_at(&cd, pos + 32).ok_or_else(invalid_header)?);
+		let name_start = pos + 46;
+		let extra_start = name_start + name_len;
+		let end = extra_start + extra_len + comment_len;
+		let (Some(name), Some(extra)) = (
+			cd.get(name_start..extra_start),
+			cd.get(extra_start..extra_start + extra_len),
+		) else {
+			return Err(invalid_header());
+		};
+		let name = String::from_utf8_lossy(name).into_owned();
+		capabilities.multi_disk |= u16_at(&cd, pos + 34).ok_or_else(invalid_header)? != 0;
+		capabilities.zip64 |= has_zip64_field(extra);
+		if flags & (1 << 3) != 0 {
+			capabilities.data_descriptors.push(name.clone());
+		}
+		// Traditional, strong, or AES encryption.
+		if flags & (1 | (1 << 6)) != 0 || method == 99 {
+			capabilities.encrypted.push(name.clone());
+		}
+		if !names.insert(name.clone()) && !capabilities.duplicates.contains(&name) {
+			capabilities.duplicates.push(name.clone());
+		}
+		if method != 99 && !is_supported(method) {
+			capabilities.unsupported_compression.push((name, method));
+		}
+		pos = end;
+	}
+	Ok(capabilities)
+}
+
+/// Whether the compression `method` is supported by this build, see the `bzip2` and `lzma`
+/// features.
+#[allow(deprecated)]
+pub(crate) fn is_supported(method: u16) -> bool {
+	!matches!(
+		CompressionMethod::from_u16(method),
+		CompressionMethod::Unsupported(_)
+	)
+}
+
+/// Whether the compression method of `file` is supported by this build.
+///
+/// Encrypted files are considered supported as they are classified as encrypted instead.
+#[allow(deprecated)]
+pub(crate) fn is_supported_file(file: &ZipFile) -> bool {
+	file.encrypted() || !matches!(file.compression(), CompressionMethod::Unsupported(_))
+}
+
+/// Returns the names and compression methods of files of `zip` compressed by methods not
+/// supported by this build, see [`NpzReader::unsupported_names`].
+#[allow(deprecated)]
+pub(crate) fn unsupported_names<R: Read + Seek>(
+	zip: &mut ZipArchive<R>,
+) -> Result<Vec<(String, u16)>, ZipError> {
+	let mut unsupported = Vec::new();
+	for index in 0..zip.len() {
+		let file = zip.by_index_raw(index)?;
+		if let CompressionMethod::Unsupported(method) = file.compression() {
+			if !file.encrypted() {
+				unsupported.push((file.name().to_owned(), method));
+			}
+		}
+	}
+	Ok(unsupported)
+}
+
+/// Whether the `extra` field contains a Zip64 extended information extra field.
+fn has_zip64_field(extra: &[u8]) -> bool {
+	let mut pos = 0;
+	while let (Some(id), Some(len)) = (u16_at(extra, pos), u16_at(extra, pos + 2)) {
+		if id == 0x0001 {
+			return true;
+		}
+		pos += 4 + usize::from(len);
+	}
+	false
+}
+
+/// Reads a little-endian `u16` at `pos` or `None` if out of bounds.
+///
+/// Like zip structures, the members and sidecars of this crate, e.g., the [`INDEX_NAME`] member,
+/// are little endian on every target, so they must never be decoded in native byte order.
+///
+/// [`INDEX_NAME`]: crate::INDEX_NAME
+pub(crate) fn u16_at(bytes: &[u8], pos: usize) -> Option<u16> {
+	let bytes = bytes.get(pos..pos.checked_add(2)?)?;
+	bytes.try_into().ok().map(u16::from_le_bytes)
+}
+
+/// Reads a little-endian `u32` at `pos` or `None` if out of bounds.
+pub(crate) fn u32_at(bytes: &[u8], pos: usize) -> Option<u32> {
+	let bytes = bytes.get(pos..pos.checked_add(4)?)?;
+	bytes.try_into().ok().map(u32::from_le_bytes)
+}
+
+/// Reads a little-endian `u64` at `pos` or `None` if out of bounds.
+pub(crate) fn u64_at(bytes: &[u8], pos: usize) -> Option<u64> {
+	let bytes = bytes.get(pos..pos.checked_add(8)?)?;
+	bytes.try_into().ok().map(u64::from_le_bytes)
+}
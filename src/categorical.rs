@@ -0,0 +1,119 @@
+use super::{
+	json::{json_strings, parse_json_strings},
+	NpzReader, NpzWriter, ReadNpzError, WriteNpzError,
+};
+use ndarray::{prelude::*, Data};
+use std::{
+	collections::HashMap,
+	io::{Read, Seek, Write},
+};
+use zip::result::ZipError;
+
+/// Categorical array of integer codes indexing into a list of category labels.
+///
+/// Corresponds to `pandas.Categorical`, see [`NpzWriter::add_categorical`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Categorical<D: Dimension> {
+	/// Index into [`Self::categories`] of each element.
+	pub codes: Array<u32, D>,
+	/// Label of each category.
+	pub categories: Vec<String>,
+}
+
+impl<D: Dimension> Categorical<D> {
+	/// Encodes `labels` assigning codes to categories in order of first appearance.
+	///
+	/// # Panics
+	///
+	/// Panics if there are more than [`u32::MAX`] categories.
+	#[must_use]
+	pub fn from_labels<S>(labels: &ArrayBase<S, D>) -> Self
+	where
+		S::Elem: AsRef<str>,
+		S: Data,
+	{
+		let mut categories = Vec::new();
+		let mut codes = HashMap::new();
+		let codes = labels.map(|label| {
+			let label = label.as_ref();
+			*codes.entry(label.to_string()).or_insert_with(|| {
+				categories.push(label.to_string());
+				u32::try_from(categories.len() - 1).expect("too many categories")
+			})
+		});
+		Self { codes, categories }
+	}
+	/// Returns the label of each element.
+	///
+	/// # Panics
+	///
+	/// Panics if a code is out of bounds.
+	#[must_use]
+	pub fn labels(&self) -> Array<&str, D> {
+		self.codes
+			.map(|&code| self.categories[code as usize].as_str())
+	}
+}
+
+/// Returns the name of the member storing the categories of the member `name`.
+fn categories_name(name: &str) -> String {
+	let stem = name.strip_suffix(".npy").unwrap_or(name);
+	format!("{stem}.categories.json")
+}
+
+impl<W: Write + Seek> NpzWriter<W> {
+	/// Adds a categorical array with the specified `name`.
+	///
+	/// The codes are stored as `u32` array `x.npy` and the categories as JSON array of strings in
+	/// the member `x.categories.json` next to it. In Python, it can be restored via
+	/// `pandas.Categorical.from_codes(codes, json.loads(categories))`.
+	///
+	/// # Errors
+	///
+	/// Adding an array can fail with [`WriteNpyError`](ndarray_npy::WriteNpyError) or
+	/// [`ZipError`].
+	pub fn add_categorical<D: Dimension>(
+		&mut self,
+		name: &str,
+		categorical: &Categorical<D>,
+	) -> Result<(), WriteNpzError> {
+		self.add_array(name, &categorical.codes)?;
+		let categories = categorical
+			.categories
+			.iter()
+			.map(String::as_str)
+			.collect::<Vec<_>>();
+		self.zip.start_file(categories_name(name), self.options)?;
+		self.zip
+			.write_all(json_strings(&categories).as_bytes())
+			.map_err(ZipError::from)?;
+		Ok(())
+	}
+}
+
+impl<R: Read + Seek> NpzReader<R> {
+	/// Reads a categorical array added via [`NpzWriter::add_categorical`].
+	///
+	/// # Errors
+	///
+	/// Reading an array from an archive can fail with [`ReadNpyError`](ndarray_npy::ReadNpyError)
+	/// or [`ZipError`]. Fails with [`ZipError::InvalidArchive`] if the categories are malformed or
+	/// if a code is out of bounds.
+	pub fn by_name_categorical<D: Dimension>(
+		&mut self,
+		name: &str,
+	) -> Result<Categorical<D>, ReadNpzError> {
+		let codes: Array<u32, D> = self.by_name(name)?;
+		let mut categories = String::new();
+		self.zip
+			.by_name(&categories_name(name))?
+			.read_to_string(&mut categories)
+			.map_err(ZipError::from)?;
+		let categories = parse_json_strings(&categories)
+			.ok_or(ZipError::InvalidArchive("Invalid categories"))?;
+		if codes.iter().any(|&code| code as usize >= categories.len()) {
+			return Err(ZipError::InvalidArchive("Category code out of bounds").into());
+		}
+		Ok(Categorical { codes, categories })
+	}
+}
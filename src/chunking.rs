@@ -0,0 +1,328 @@
+use super::{
+	crc32_update,
+	json::{json_string, parse_json_object, parse_json_string},
+	limit::{self, Bounded},
+	timeout::Deadline,
+	NpzReader, NpzWriter, ReadNpzError, WriteNpzError,
+};
+use ndarray::{ArrayBase, Data, DataOwned, Dimension};
+use ndarray_npy::{ReadNpyExt, ReadableElement, WritableElement};
+use std::{
+	collections::HashMap,
+	io::{Read, Seek, Write},
+	ops::Range,
+};
+use zip::{result::ZipError, CompressionMethod, ZipArchive};
+
+/// Reserved name of the member mapping chunked `.npy` files to their chunks.
+pub const CHUNKS_NAME: &str = "__chunks__.json";
+/// Reserved name prefix of the members storing chunks of `.npy` files.
+pub const CHUNK_PREFIX: &str = "__chunks__/";
+
+/// Arrays chunked on [`NpzWriter::add_array`] to be indexed on [`NpzWriter::finish`].
+#[derive(Debug)]
+pub(crate) struct Chunking {
+	bits: u32,
+	manifest: Vec<(String, Vec<String>)>,
+	chunks: HashMap<String, u64>,
+}
+
+/// Chunked `.npy` files read on [`NpzReader::new`].
+#[derive(Debug)]
+pub(crate) struct Chunked {
+	names: Vec<String>,
+	manifest: HashMap<String, Vec<String>>,
+	reserved: usize,
+}
+
+impl<W: Write + Seek> NpzWriter<W> {
+	/// Splits arrays encoded to more than four times `avg_bytes` into chunks at content-defined
+	/// boundaries of about `avg_bytes` on average.
+	///
+	/// Chunk boundaries are found by a rolling hash of the encoded bytes, so they move along
+	/// with inserted or removed bytes, e.g., appended rows, whereas the other chunks stay the
+	/// same. Delta-synchronization tools, e.g., rsync, and [`quick_compare`](crate::quick_compare)
+	/// exploit this between versions of an archive by transferring changed chunks only. Each
+	/// chunk is stored without compression as a member named after its CRC-32 checksum and
+	/// length in hexadecimal, e.g., `__chunks__/1c291ca3-7f3e.bin`, whereas equal chunks are
+	/// stored once. The [`CHUNKS_NAME`] member of UTF-8 JSON in the form of
+	/// `{"x.npy": ["__chunks__/1c291ca3-7f3e.bin", ...]}` maps names to their chunks in order.
+	/// Reading chunked arrays via [`NpzReader`] is transparent, whereas Python reads them via
+	///
+	/// ```python
+	/// import io, json, numpy as np, zipfile
+	///
+	/// with zipfile.ZipFile("arrays.npz") as npz:
+	///     manifest = json.loads(npz.read("__chunks__.json"))
+	///     arrays = {
+	///         name: np.load(io.BytesIO(b"".join(npz.read(chunk) for chunk in chunks)))
+	///         for name, chunks in manifest.items()
+	///     }
+	/// ```
+	///
+	/// The average `avg_bytes` is rounded up to a power of two between 64 bytes and 256 MiB.
+	/// Chunks are at least a quarter and at most four times the average. Chunked arrays are
+	/// encoded in memory before being split.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use ndarray::Array1;
+	/// use ndarray_npz::{NpzReader, NpzWriter};
+	/// use std::io::Cursor;
+	///
+	/// let x = Array1::from_shape_fn(100_000, |index| (index as f64).sin());
+	/// let mut npz = NpzWriter::new(Cursor::new(Vec::new())).with_chunking(16 * 1024);
+	/// npz.add_array("x.npy", &x)?;
+	/// let mut npz = NpzReader::new(npz.finish()?)?;
+	/// assert_eq!(npz.names()?, ["x.npy"]);
+	/// let y: Array1<f64> = npz.by_name("x.npy")?;
+	/// assert_eq!(x, y);
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	#[must_use]
+	pub fn with_chunking(mut self, avg_bytes: usize) -> Self {
+		self.chunking = Some(Chunking {
+			bits: avg_bytes
+				.clamp(64, 1 << 28)
+				.next_power_of_two()
+				.trailing_zeros(),
+			manifest: Vec::new(),
+			chunks: HashMap::new(),
+		});
+		self
+	}
+
+	/// Chunks the array if enabled and large enough, returns whether it has been added.
+	pub(crate) fn add_chunkable<S, D>(
+		&mut self,
+		name: &str,
+		array: &ArrayBase<S, D>,
+	) -> Result<bool, WriteNpzError>
+	where
+		S::Elem: WritableElement,
+		S: Data,
+		D: Dimension,
+	{
+		if self.chunking.is_none() {
+			return Ok(false);
+		}
+		let bytes = self.npy_bytes(array)?;
+		self.add_chunkable_bytes(name, &bytes)
+	}
+
+	/// Chunks the encoded array if enabled and large enough, returns whether it has been added.
+	pub(crate) fn add_chunkable_bytes(
+		&mut self,
+		name: &str,
+		bytes: &[u8],
+	) -> Result<bool, WriteNpzError> {
+		let Some(chunking) = &mut self.chunking else {
+			return Ok(false);
+		};
+		if bytes.len() <= 4 << chunking.bits {
+			self.zip.start_file(name, self.options)?;
+			self.zip.write_all(bytes).map_err(ZipError::from)?;
+			return Ok(true);
+		}
+		let options = self.options.compression_method(CompressionMethod::Stored);
+		let mut chunks = Vec::new();
+		for range in boundaries(bytes, chunking.bits) {
+			let chunk = &bytes[range];
+			let hash = fnv1a(chunk);
+			let name = format!(
+				"{CHUNK_PREFIX}{:08x}-{:x}",
+				crc32_update(chunk),
+				chunk.len()
+			);
+			let mut suffix = 0;
+			let name = loop {
+				let unique = match suffix {
+					0 => format!("{name}.bin"),
+					suffix => format!("{name}-{suffix}.bin"),
+				};
+				match chunking.chunks.get(&unique) {
+					Some(&other) if other == hash => break unique,
+					Some(_) => suffix += 1,
+					None => {
+						self.zip.start_file(unique.as_str(), options)?;
+						self.zip.write_all(chunk).map_err(ZipError::from)?;
+						chunking.chunks.insert(unique.clone(), hash);
+						break unique;
+					}
+				}
+			};
+			chunks.push(name);
+		}
+		chunking.manifest.push((name.to_owned(), chunks));
+		Ok(true)
+	}
+
+	/// Adds the manifest of the chunked arrays if any.
+	pub(crate) fn add_chunked(&mut self) -> Result<(), WriteNpzError> {
+		let Some(chunking) = self.chunking.take() else {
+			return Ok(());
+		};
+		if chunking.manifest.is_empty() {
+			return Ok(());
+		}
+		let manifest = chunking
+			.manifest
+			.iter()
+			.map(|(name, chunks)| {
+				let chunks = chunks
+					.iter()
+					.map(|chunk| json_string(chunk))
+					.collect::<Vec<_>>();
+				format!("{}: [{}]", json_string(name), chunks.join(", "))
+			})
+			.collect::<Vec<_>>();
+		self.zip.start_file(CHUNKS_NAME, self.options)?;
+		self.zip
+			.write_all(format!("{{{}}}\n", manifest.join(", ")).as_bytes())
+			.map_err(ZipError::from)?;
+		Ok(())
+	}
+}
+
+impl Chunked {
+	/// Reads the manifest of the chunked `.npy` files of `zip` if any.
+	pub(crate) fn read<R: Read + Seek>(zip: &mut ZipArchive<R>) -> Result<Option<Self>, ZipError> {
+		let invalid = || ZipError::InvalidArchive("Invalid manifest of chunked files");
+		if zip.index_for_name(CHUNKS_NAME).is_none() {
+			return Ok(None);
+		}
+		let mut json = String::new();
+		zip.by_name(CHUNKS_NAME)?.read_to_string(&mut json)?;
+		let (members, rest) = parse_json_object(&json, parse_chunks).ok_or_else(invalid)?;
+		if !rest.trim().is_empty() {
+			return Err(invalid());
+		}
+		if members
+			.iter()
+			.flat_map(|(_name, chunks)| chunks)
+			.any(|chunk| !chunk.starts_with(CHUNK_PREFIX) || zip.index_for_name(chunk).is_none())
+		{
+			return Err(invalid());
+		}
+		Ok(Some(Self {
+			names: members.iter().map(|(name, _chunks)| name.clone()).collect(),
+			manifest: members.into_iter().collect(),
+			reserved: zip
+				.file_names()
+				.filter(|name| Self::is_reserved(name))
+				.count(),
+		}))
+	}
+	/// Whether `name` is one of the reserved members.
+	pub(crate) fn is_reserved(name: &str) -> bool {
+		name == CHUNKS_NAME || name.starts_with(CHUNK_PREFIX)
+	}
+	/// Names of the chunked `.npy` files in writing order.
+	pub(crate) fn names(&self) -> &[String] {
+		&self.names
+	}
+	/// Number of reserved members.
+	pub(crate) fn reserved(&self) -> usize {
+		self.reserved
+	}
+}
+
+impl<R: Read + Seek> NpzReader<R> {
+	/// Reads a chunked array by name if any, see [`NpzWriter::with_chunking`].
+	pub(crate) fn by_name_chunked<S, D>(
+		&mut self,
+		name: &str,
+	) -> Result<Option<ArrayBase<S, D>>, ReadNpzError>
+	where
+		S::Elem: ReadableElement,
+		S: DataOwned,
+		D: Dimension,
+	{
+		let Some(chunks) = self
+			.chunked
+			.as_ref()
+			.and_then(|chunked| chunked.manifest.get(name))
+		else {
+			return Ok(None);
+		};
+		let deadline = self.deadline();
+		let mut bytes = Vec::new();
+		for chunk in chunks {
+			let chunk = Deadline::new(self.zip.by_name(chunk)?, deadline);
+			Bounded::new(chunk, self.max_entry_size)
+				.and_then(|mut chunk| chunk.read_to_end(&mut bytes))
+				.map_err(limit::zip_error)?;
+		}
+		Ok(Some(ArrayBase::<S, D>::read_npy(&*bytes)?))
+	}
+}
+
+/// Returns the chunks of `bytes` at content-defined boundaries of `1 << bits` bytes on average.
+///
+/// The boundaries are found by a gear hash over the last 64 bytes, i.e., a boundary follows a
+/// byte whose hash has its `bits` most significant bits cleared.
+fn boundaries(bytes: &[u8], bits: u32) -> Vec<Range<usize>> {
+	let (min, max) = (1 << (bits - 2), 4 << bits);
+	let mut chunks = Vec::new();
+	let mut start = 0;
+	while start < bytes.len() {
+		let end = bytes.len().min(start + max);
+		let mut hash = 0u64;
+		let mut len = end - start;
+		for (index, &byte) in bytes[start..end].iter().enumerate() {
+			hash = (hash << 1).wrapping_add(GEAR[usize::from(byte)]);
+			if index + 1 >= min && hash >> (64 - bits) == 0 {
+				len = index + 1;
+				break;
+			}
+		}
+		chunks.push(start..start + len);
+		start += len;
+	}
+	chunks
+}
+
+/// Random values of the gear hash, one per byte value.
+const GEAR: [u64; 256] = gear();
+
+/// Generates the [`GEAR`] values via `SplitMix64`, so they are fixed across versions.
+const fn gear() -> [u64; 256] {
+	let mut gear = [0; 256];
+	let mut state = 0u64;
+	let mut index = 0;
+	while index < gear.len() {
+		state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+		let mut value = state;
+		value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+		value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+		gear[index] = value ^ (value >> 31);
+		index += 1;
+	}
+	gear
+}
+
+/// Returns the 64-bit FNV-1a hash of `bytes` telling apart chunks of equal CRC-32 and length.
+fn fnv1a(bytes: &[u8]) -> u64 {
+	bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+		(hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+	})
+}
+
+/// Parses a JSON array of chunk names at the start of `json`.
+fn parse_chunks(json: &str) -> Option<(Vec<String>, &str)> {
+	let mut json = json.strip_prefix('[')?.trim_start();
+	let mut chunks = Vec::new();
+	if let Some(rest) = json.strip_prefix(']') {
+		return Some((chunks, rest));
+	}
+	loop {
+		let (chunk, rest) = parse_json_string(json)?;
+		chunks.push(chunk);
+		let rest = rest.trim_start();
+		match rest.strip_prefix(',') {
+			Some(rest) => json = rest.trim_start(),
+			None => return Some((chunks, rest.strip_prefix(']')?)),
+		}
+	}
+}
@@ -0,0 +1,274 @@
+use super::{ReadNpzError, ViewNpzError, WriteNpzError};
+use ndarray_npy::{ReadNpyError, ViewNpyError, WriteNpyError};
+use zip::result::ZipError;
+
+/// Stable code of an error, see [`ReadNpzError::code`].
+///
+/// Codes are stable across releases, allowing non-Rust callers and structured logs to branch on
+/// failures without matching the messages. The hundreds digit of a code is its category, see
+/// [`ErrorCategory`]. Errors of dependencies are mapped to the code of their cause, e.g., an
+/// [`io::Error`](std::io::Error) nested in a [`ZipError`] or in an error of an inner `.npy` file
+/// maps to [`ErrorCode::Io`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
+#[repr(u16)]
+pub enum ErrorCode {
+	/// An I/O error of the underlying reader or writer.
+	Io = 100,
+	/// Reading a file has exceeded its deadline.
+	TimedOut = 101,
+	/// The zip archive is invalid.
+	InvalidArchive = 200,
+	/// The `.npy` header is invalid.
+	InvalidHeader = 201,
+	/// The data of the `.npy` file is invalid or cannot be serialized.
+	InvalidData = 202,
+	/// The `.npy` file misses data.
+	MissingData = 203,
+	/// The `.npy` file has extra bytes after its data.
+	ExtraBytes = 204,
+	/// The length of the data overflows `usize` or `isize`.
+	LengthOverflow = 205,
+	/// A JSON member is invalid or cannot be serialized.
+	Json = 206,
+	/// The zip archive uses an unsupported feature, e.g., an unsupported compression method.
+	UnsupportedArchive = 300,
+	/// Compressed files cannot be viewed or edited.
+	CompressedFile = 301,
+	/// Encrypted files cannot be read without password, viewed, or edited.
+	EncryptedFile = 302,
+	/// Directories cannot be viewed or edited.
+	Directory = 303,
+	/// Blobs cannot be viewed as arrays.
+	Blob = 304,
+	/// Arrays in Fortran order cannot be truncated along their first axis nor be bundled.
+	FortranOrder = 305,
+	/// The element type has no fixed size.
+	UnsupportedDtype = 306,
+	/// The `.npy` file cannot be viewed as its endianness is not native.
+	NonNativeEndian = 307,
+	/// The `.npy` file cannot be viewed as its data is misaligned.
+	MisalignedData = 308,
+	/// The file is not found in the zip archive.
+	FileNotFound = 400,
+	/// The array has no unit.
+	MissingUnit = 401,
+	/// The element type differs.
+	DtypeMismatch = 500,
+	/// The shape or the number of dimensions differs.
+	ShapeMismatch = 501,
+	/// The number of elements differs.
+	ElementCountMismatch = 502,
+	/// The element types differ in size.
+	ItemSizeMismatch = 503,
+	/// The unit differs.
+	UnitMismatch = 504,
+	/// The password is incorrect.
+	InvalidPassword = 600,
+	/// A mutable `.npy` file view has already been moved out of its `.npz` file view.
+	MovedNpyViewMut = 601,
+	/// The new `.npy` header exceeds the space of the old one.
+	HeaderOverflow = 602,
+	/// The new length exceeds the old one.
+	LengthExceeded = 603,
+	/// The checksum is stale as a thread panicked while mutably viewing the `.npy` file.
+	Poisoned = 604,
+	/// An argument is invalid, e.g., a null pointer passed via [`ffi`](crate::ffi).
+	InvalidArgument = 605,
+	/// The slice is out of bounds or has a zero step.
+	InvalidSlice = 606,
+	/// The writer has already been finished or has failed.
+	WriterFinished = 607,
+	/// A file has already been added under the name.
+	DuplicateName = 608,
+	/// An error of a dependency unknown to this release.
+	Other = 900,
+}
+
+impl ErrorCode {
+	/// Returns the numeric value of the code.
+	#[must_use]
+	pub const fn value(self) -> u16 {
+		self as u16
+	}
+
+	/// Returns the category of the code.
+	#[must_use]
+	pub const fn category(self) -> ErrorCategory {
+		match self.value() / 100 {
+			1 => ErrorCategory::Io,
+			2 => ErrorCategory::Format,
+			3 => ErrorCategory::Unsupported,
+			4 => ErrorCategory::NotFound,
+			5 => ErrorCategory::Mismatch,
+			6 => ErrorCategory::Usage,
+			_ => ErrorCategory::Other,
+		}
+	}
+}
+
+/// Stable category of an error, see [`ErrorCode::category`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
+#[repr(u8)]
+pub enum ErrorCategory {
+	/// An I/O error, possibly transient.
+	Io = 1,
+	/// The zip archive or an inner file is malformed.
+	Format = 2,
+	/// The zip archive or an inner file uses an unsupported feature.
+	Unsupported = 3,
+	/// A file or an attribute of it is missing.
+	NotFound = 4,
+	/// The requested and the stored element type, shape, or unit differ.
+	Mismatch = 5,
+	/// An operation is invalid in the current state or with the given arguments.
+	Usage = 6,
+	/// An error of a dependency unknown to this release.
+	Other = 9,
+}
+
+impl ErrorCategory {
+	/// Returns the numeric value of the category, i.e., the hundreds digit of its codes.
+	#[must_use]
+	pub const fn value(self) -> u8 {
+		self as u8
+	}
+}
+
+/// Returns the code of a zip error.
+pub(crate) fn zip_code(err: &ZipError) -> ErrorCode {
+	match err {
+		ZipError::Io(_) => ErrorCode::Io,
+		ZipError::InvalidArchive(_) => ErrorCode::InvalidArchive,
+		ZipError::UnsupportedArchive(ZipError::PASSWORD_REQUIRED) => ErrorCode::EncryptedFile,
+		ZipError::UnsupportedArchive(_) => ErrorCode::UnsupportedArchive,
+		ZipError::FileNotFound => ErrorCode::FileNotFound,
+		ZipError::InvalidPassword => ErrorCode::InvalidPassword,
+		_ => ErrorCode::Other,
+	}
+}
+
+/// Returns the code of an error reading a `.npy` file.
+fn read_npy_code(err: &ReadNpyError) -> ErrorCode {
+	match err {
+		ReadNpyError::Io(_) => ErrorCode::Io,
+		ReadNpyError::ParseHeader(_) => ErrorCode::InvalidHeader,
+		ReadNpyError::ParseData(_) => ErrorCode::InvalidData,
+		ReadNpyError::LengthOverflow => ErrorCode::LengthOverflow,
+		ReadNpyError::WrongNdim(..) => ErrorCode::ShapeMismatch,
+		ReadNpyError::WrongDescriptor(_) => ErrorCode::DtypeMismatch,
+		ReadNpyError::MissingData => ErrorCode::MissingData,
+		ReadNpyError::ExtraBytes(_) => ErrorCode::ExtraBytes,
+	}
+}
+
+/// Returns the code of an error writing a `.npy` file.
+fn write_npy_code(err: &WriteNpyError) -> ErrorCode {
+	match err {
+		WriteNpyError::Io(_) => ErrorCode::Io,
+		WriteNpyError::FormatHeader(_) => ErrorCode::InvalidHeader,
+		WriteNpyError::FormatData(_) => ErrorCode::InvalidData,
+	}
+}
+
+/// Returns the code of an error viewing a `.npy` file.
+fn view_npy_code(err: &ViewNpyError) -> ErrorCode {
+	match err {
+		ViewNpyError::Io(_) => ErrorCode::Io,
+		ViewNpyError::ParseHeader(_) => ErrorCode::InvalidHeader,
+		ViewNpyError::InvalidData(_) => ErrorCode::InvalidData,
+		ViewNpyError::LengthOverflow => ErrorCode::LengthOverflow,
+		ViewNpyError::WrongNdim(..) => ErrorCode::ShapeMismatch,
+		ViewNpyError::WrongDescriptor(_) => ErrorCode::DtypeMismatch,
+		ViewNpyError::NonNativeEndian => ErrorCode::NonNativeEndian,
+		ViewNpyError::MisalignedData => ErrorCode::MisalignedData,
+		ViewNpyError::MissingBytes(_) => ErrorCode::MissingData,
+		ViewNpyError::ExtraBytes(_) => ErrorCode::ExtraBytes,
+		_ => ErrorCode::Other,
+	}
+}
+
+impl WriteNpzError {
+	/// Returns the stable code of the error, see [`ErrorCode`].
+	#[must_use]
+	pub fn code(&self) -> ErrorCode {
+		match self {
+			WriteNpzError::Zip(err) => zip_code(err),
+			WriteNpzError::Npy(err) => write_npy_code(err),
+			WriteNpzError::Finished => ErrorCode::WriterFinished,
+			WriteNpzError::DuplicateName(_) => ErrorCode::DuplicateName,
+		}
+	}
+
+	/// Returns the stable category of the error, see [`ErrorCategory`].
+	#[must_use]
+	pub fn category(&self) -> ErrorCategory {
+		self.code().category()
+	}
+}
+
+impl ReadNpzError {
+	/// Returns the stable code of the error, see [`ErrorCode`].
+	///
+	/// # Example
+	///
+	/// ```
+	/// use ndarray::Array1;
+	/// use ndarray_npz::{ErrorCategory, ErrorCode, NpzReader, NpzWriter};
+	/// use std::io::Cursor;
+	///
+	/// let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	/// npz.add_array("x.npy", &Array1::<f64>::zeros(3))?;
+	/// let mut npz = NpzReader::new(npz.finish()?)?;
+	/// let y: Result<Array1<f64>, _> = npz.by_name("y.npy");
+	/// let err = y.unwrap_err();
+	/// assert_eq!(err.code(), ErrorCode::FileNotFound);
+	/// assert_eq!(err.code().value(), 400);
+	/// assert_eq!(err.category(), ErrorCategory::NotFound);
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	#[must_use]
+	pub fn code(&self) -> ErrorCode {
+		match self {
+			ReadNpzError::Zip(err) => zip_code(err),
+			ReadNpzError::Npy(err) => read_npy_code(err),
+			ReadNpzError::TimedOut { .. } => ErrorCode::TimedOut,
+		}
+	}
+
+	/// Returns the stable category of the error, see [`ErrorCategory`].
+	#[must_use]
+	pub fn category(&self) -> ErrorCategory {
+		self.code().category()
+	}
+}
+
+impl ViewNpzError {
+	/// Returns the stable code of the error, see [`ErrorCode`].
+	#[must_use]
+	pub fn code(&self) -> ErrorCode {
+		match self {
+			ViewNpzError::Zip(err) => zip_code(err),
+			ViewNpzError::Npy(err) => view_npy_code(err),
+			ViewNpzError::MovedNpyViewMut => ErrorCode::MovedNpyViewMut,
+			ViewNpzError::Directory => ErrorCode::Directory,
+			ViewNpzError::CompressedFile => ErrorCode::CompressedFile,
+			ViewNpzError::EncryptedFile => ErrorCode::EncryptedFile,
+			ViewNpzError::Blob => ErrorCode::Blob,
+			ViewNpzError::WriteNpy(err) => write_npy_code(err),
+			ViewNpzError::DtypeMismatch => ErrorCode::DtypeMismatch,
+			ViewNpzError::ShapeMismatch => ErrorCode::ShapeMismatch,
+			ViewNpzError::Poisoned => ErrorCode::Poisoned,
+			ViewNpzError::ReadNpy(err) => read_npy_code(err),
+			ViewNpzError::InvalidSlice => ErrorCode::InvalidSlice,
+			ViewNpzError::Layout(err) => err.code(),
+		}
+	}
+
+	/// Returns the stable category of the error, see [`ErrorCategory`].
+	#[must_use]
+	pub fn category(&self) -> ErrorCategory {
+		self.code().category()
+	}
+}
@@ -0,0 +1,87 @@
+use super::{blob::classify_names, convert::Dtype, header::NpyHeader, NpzReader, ReadNpzError};
+use std::io::{Read, Seek};
+use zip::result::ZipError;
+
+/// Coercion required to consume an `.npy` file as native array, see [`coercion_report`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Coercion {
+	/// Name of the file.
+	pub name: String,
+	/// Element type of the file or `None` if it is not a [`Dtype`], e.g., complex or strings.
+	pub dtype: Option<Dtype>,
+	/// Whether the elements are in foreign byte order, hence must be byte-swapped.
+	pub byte_swap: bool,
+	/// Whether the elements must be cast, i.e., are neither `f32`, `f64`, nor `usize`.
+	pub cast: bool,
+	/// Whether the array is multi-dimensional in column-major order, hence must be transposed to
+	/// get standard layout.
+	pub layout: bool,
+}
+
+impl Coercion {
+	/// Returns `true` iff the array can be consumed without any coercion.
+	#[must_use]
+	pub fn is_native(&self) -> bool {
+		!self.byte_swap && !self.cast && !self.layout
+	}
+}
+
+/// Reports the coercions required to consume the `.npy` files of `reader` as native `f32`, `f64`,
+/// or `usize` arrays of standard layout, in archive order.
+///
+/// Only the `.npy` headers are read, so data engineers can decide whether to normalize archives
+/// offline, e.g., via [`convert_entry_dtype`](crate::convert_entry_dtype), or to pay the
+/// conversion at load time. Files which need no coercion are reported as well, see
+/// [`Coercion::is_native`]. Files packed via
+/// [`NpzWriter::with_packing`](crate::NpzWriter::with_packing) are reported as a whole.
+///
+/// # Example
+///
+/// ```no_run
+/// use ndarray_npz::{coercion_report, NpzReader};
+/// use std::fs::File;
+///
+/// let mut npz = NpzReader::new(File::open("arrays.npz")?)?;
+/// for coercion in coercion_report(&mut npz)? {
+/// 	if !coercion.is_native() {
+/// 		println!("{} needs coercion: {coercion:?}", coercion.name);
+/// 	}
+/// }
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// # Errors
+///
+/// Reading the zip archive or an invalid `.npy` header fails with [`ZipError`].
+pub fn coercion_report<R: Read + Seek>(
+	reader: &mut NpzReader<R>,
+) -> Result<Vec<Coercion>, ReadNpzError> {
+	let index = if cfg!(target_pointer_width = "64") {
+		Dtype::U64
+	} else {
+		Dtype::U32
+	};
+	let names = classify_names(&mut reader.zip)?.0;
+	let mut coercions = Vec::with_capacity(names.len());
+	for name in names {
+		let mut file = reader.zip.by_name(&name)?;
+		let header = NpyHeader::read(&mut file).map_err(ZipError::from)?;
+		let parsed = Dtype::parse(&header.descr);
+		let dtype = parsed.map(|(dtype, _big_endian)| dtype);
+		let byte_swap = parsed.is_some_and(|(dtype, big_endian)| {
+			dtype.size() > 1 && big_endian != cfg!(target_endian = "big")
+		});
+		let cast =
+			!matches!(dtype, Some(dtype) if [Dtype::F32, Dtype::F64, index].contains(&dtype));
+		let layout = header.fortran_order && header.shape.len() > 1;
+		drop(file);
+		coercions.push(Coercion {
+			name,
+			dtype,
+			byte_swap,
+			cast,
+			layout,
+		});
+	}
+	Ok(coercions)
+}
@@ -0,0 +1,192 @@
+use super::{order::archive_alignment, EditNpzError};
+use std::{
+	io::{self, Read, Seek, SeekFrom, Write},
+	iter,
+};
+use zip::{
+	result::ZipError, write::FullFileOptions, CompressionMethod, DateTime, ZipArchive, ZipWriter,
+};
+
+/// Report of [`compact`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Compaction {
+	/// Size in bytes of the original archive.
+	pub original_size: u64,
+	/// Size in bytes of the compacted archive.
+	pub compacted_size: u64,
+}
+
+impl Compaction {
+	/// Returns the number of bytes reclaimed by compaction.
+	#[must_use]
+	pub fn reclaimed(&self) -> u64 {
+		self.original_size.saturating_sub(self.compacted_size)
+	}
+}
+
+/// Rewrites the `.npz` file of `reader` tightly into `writer` and reports the reclaimed bytes.
+///
+/// Removes unused space between files, e.g., left behind by
+/// [`NpzEditor::truncate_entry`](crate::NpzEditor::truncate_entry), and superfluous padding.
+/// Uncompressed files are rewritten aligned to the largest alignment recorded in the archive or to
+/// 64 bytes otherwise for memory-mapping via
+/// [`NpzView`](crate::NpzView)/[`NpzViewMut`](crate::NpzViewMut) while verifying their CRC-32
+/// checksums. Compressed and encrypted files are copied as is. Names, modification times, and
+/// permissions are preserved, and so are the extra fields of uncompressed files and directories,
+/// e.g., extended timestamps, and the archive comment, see [`strip_metadata`] for scrubbing them
+/// instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use ndarray_npz::compact;
+/// use std::fs::{rename, File};
+///
+/// let compaction = compact(File::open("arrays.npz")?, File::create("arrays.npz.tmp")?)?;
+/// rename("arrays.npz.tmp", "arrays.npz")?;
+/// println!("Reclaimed {} bytes", compaction.reclaimed());
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// # Errors
+///
+/// Reading or writing the zip archives can fail with [`ZipError`](zip::result::ZipError).
+pub fn compact<R, W>(reader: R, writer: W) -> Result<Compaction, EditNpzError>
+where
+	R: Read + Seek,
+	W: Write + Seek,
+{
+	rewrite(reader, writer, Metadata::Preserve)
+}
+
+/// Rewrites the `.npz` file of `reader` tightly into `writer` like [`compact`] but strips the
+/// metadata of its files.
+///
+/// Scrubs datasets before publishing them by resetting the modification times to the earliest
+/// representable time and the permissions to their defaults, e.g., `0o644` for files, and by
+/// dropping the extra fields, e.g., extended timestamps or user and group identifiers, and the
+/// archive comment. The names and data of the files are kept.
+///
+/// # Example
+///
+/// ```no_run
+/// use ndarray_npz::strip_metadata;
+/// use std::fs::File;
+///
+/// strip_metadata(File::open("private.npz")?, File::create("public.npz")?)?;
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// # Errors
+///
+/// Reading or writing the zip archives can fail with [`ZipError`](zip::result::ZipError).
+pub fn strip_metadata<R, W>(reader: R, writer: W) -> Result<Compaction, EditNpzError>
+where
+	R: Read + Seek,
+	W: Write + Seek,
+{
+	rewrite(reader, writer, Metadata::Strip)
+}
+
+/// Handling of the metadata of files by [`copy_entry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Metadata {
+	/// Preserves modification times, permissions, extra fields, and the archive comment.
+	Preserve,
+	/// Resets modification times and permissions and drops extra fields and the archive comment.
+	Strip,
+}
+
+/// Rewrites the `.npz` file of `reader` tightly into `writer`, see [`compact`].
+fn rewrite<R, W>(mut reader: R, writer: W, metadata: Metadata) -> Result<Compaction, EditNpzError>
+where
+	R: Read + Seek,
+	W: Write + Seek,
+{
+	let original_size = reader.seek(SeekFrom::End(0))?;
+	let mut zip = ZipArchive::new(reader)?;
+	let mut compacted = ZipWriter::new(writer);
+	if metadata == Metadata::Preserve {
+		compacted.set_raw_comment(zip.comment().into());
+	}
+	let alignment = archive_alignment(&mut zip)?;
+	for index in 0..zip.len() {
+		copy_entry(&mut zip, index, &mut compacted, metadata, alignment)?;
+	}
+	let mut writer = compacted.finish()?;
+	let compacted_size = writer.seek(SeekFrom::End(0))?;
+	writer.flush()?;
+	Ok(Compaction {
+		original_size,
+		compacted_size,
+	})
+}
+
+/// Copies the file with `index` of `zip` to `writer` tightly, see [`compact`].
+///
+/// Uncompressed files are aligned to `alignment`. Extra fields of compressed and encrypted files are dropped as they are copied raw.
+pub(crate) fn copy_entry<R, W>(
+	zip: &mut ZipArchive<R>,
+	index: usize,
+	writer: &mut ZipWriter<W>,
+	metadata: Metadata,
+	alignment: u16,
+) -> Result<(), ZipError>
+where
+	R: Read + Seek,
+	W: Write + Seek,
+{
+	let file = zip.by_index_raw(index)?;
+	let mut options = FullFileOptions::default()
+		.compression_method(CompressionMethod::Stored)
+		.large_file(file.size() >= u64::from(u32::MAX));
+	match metadata {
+		Metadata::Preserve => {
+			if let Some(time) = file.last_modified() {
+				options = options.last_modified_time(time);
+			}
+			if let Some(mode) = file.unix_mode() {
+				options = options.unix_permissions(mode);
+			}
+			for (id, data) in extra_fields(file.extra_data().unwrap_or_default()) {
+				// Fields failing validation are dropped as if they were unknown.
+				let mut extended = options.clone();
+				if extended.add_extra_data(id, data.into(), false).is_ok() {
+					options = extended;
+				}
+			}
+		}
+		Metadata::Strip => options = options.last_modified_time(DateTime::default()),
+	}
+	if file.is_dir() {
+		let name = file.name().to_string();
+		drop(file);
+		writer.add_directory(name, options)?;
+	} else if file.compression() == CompressionMethod::Stored && !file.encrypted() {
+		let name = file.name().to_string();
+		drop(file);
+		writer.start_file(name, options.with_alignment(alignment))?;
+		io::copy(&mut zip.by_index(index)?, writer)?;
+	} else if metadata == Metadata::Strip {
+		writer.raw_copy_file_touch(file, DateTime::default(), Some(0o644))?;
+	} else {
+		writer.raw_copy_file(file)?;
+	}
+	Ok(())
+}
+
+/// Iterates the IDs and data of the extra fields in `extra` which are not rewritten anyway, i.e.,
+/// other than Zip64, AES, and alignment padding fields.
+fn extra_fields(extra: &[u8]) -> impl Iterator<Item = (u16, &[u8])> {
+	let mut pos = 0;
+	iter::from_fn(move || {
+		let id = u16::from_le_bytes(extra.get(pos..pos + 2)?.try_into().ok()?);
+		let len = usize::from(u16::from_le_bytes(
+			extra.get(pos + 2..pos + 4)?.try_into().ok()?,
+		));
+		let data = extra.get(pos + 4..pos + 4 + len)?;
+		pos += 4 + len;
+		Some((id, data))
+	})
+	.filter(|&(id, _data)| !matches!(id, 0x0001 | 0x9901 | 0xa11e))
+}
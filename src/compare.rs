@@ -0,0 +1,198 @@
+use super::{NpzReader, ReadNpzError};
+use std::{
+	collections::BTreeMap,
+	io::{self, Read, Seek},
+	ops::Range,
+};
+use zip::result::ZipError;
+
+/// Summary of [`quick_compare`] classifying the files of two `.npz` files by name.
+///
+/// The names of each classification are sorted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ComparisonSummary {
+	/// Names of files in both archives with equal CRC-32 checksums and sizes.
+	pub identical: Vec<String>,
+	/// Names of files in both archives with different CRC-32 checksums or sizes.
+	pub different: Vec<String>,
+	/// Names of files only in the first archive.
+	pub missing_in_b: Vec<String>,
+	/// Names of files only in the second archive.
+	pub missing_in_a: Vec<String>,
+}
+
+impl ComparisonSummary {
+	/// Returns `true` iff all files are [`identical`](Self::identical).
+	#[must_use]
+	pub fn is_identical(&self) -> bool {
+		self.different.is_empty() && self.missing_in_b.is_empty() && self.missing_in_a.is_empty()
+	}
+}
+
+/// Compares the files of the `.npz` files `a` and `b` by their CRC-32 checksums and uncompressed
+/// sizes as recorded in the central directories.
+///
+/// Only the file headers are read, no file data, so comparing is cheap even for huge archives,
+/// e.g., to decide which files need to be synchronized before comparing their arrays. Files of
+/// equal checksums and sizes are considered identical regardless of compression, alignment, or
+/// order, whereas collisions of CRC-32 checksums are possible but unlikely. Files packed via
+/// [`NpzWriter::with_packing`](crate::NpzWriter::with_packing) are compared as a whole.
+///
+/// # Example
+///
+/// ```no_run
+/// use ndarray_npz::{quick_compare, NpzReader};
+/// use std::fs::File;
+///
+/// let mut local = NpzReader::new(File::open("local.npz")?)?;
+/// let mut remote = NpzReader::new(File::open("remote.npz")?)?;
+/// let summary = quick_compare(&mut local, &mut remote)?;
+/// for name in summary.different.iter().chain(&summary.missing_in_a) {
+/// 	println!("Fetch {name}");
+/// }
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// # Errors
+///
+/// Reading the file headers can fail with [`ZipError`](zip::result::ZipError).
+pub fn quick_compare<A, B>(
+	a: &mut NpzReader<A>,
+	b: &mut NpzReader<B>,
+) -> Result<ComparisonSummary, ReadNpzError>
+where
+	A: Read + Seek,
+	B: Read + Seek,
+{
+	let a = a.manifest()?;
+	let mut b = b.manifest()?;
+	let mut summary = ComparisonSummary::default();
+	for (name, a) in a {
+		match b.remove(&name) {
+			Some(b) if a == b => summary.identical.push(name),
+			Some(_) => summary.different.push(name),
+			None => summary.missing_in_b.push(name),
+		}
+	}
+	summary.missing_in_a.extend(b.into_keys());
+	Ok(summary)
+}
+
+/// Differing byte ranges of a file within two `.npz` files, see [`block_diff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlockDiff {
+	/// Byte ranges of differing blocks in ascending order, merging adjacent blocks.
+	///
+	/// Offsets refer to the uncompressed file including its `.npy` header. Bytes beyond the end
+	/// of the shorter file differ.
+	pub ranges: Vec<Range<u64>>,
+	/// Whether comparing stopped early as further ranges differ.
+	pub truncated: bool,
+}
+
+impl BlockDiff {
+	/// Returns `true` iff no bytes differ.
+	#[must_use]
+	pub fn is_identical(&self) -> bool {
+		self.ranges.is_empty()
+	}
+}
+
+/// Compares the file `name` of the `.npz` files `a` and `b` by streaming it in blocks of
+/// `block_size` bytes.
+///
+/// Unlike comparing arrays element-wise, memory stays flat at two blocks regardless of the file
+/// size, e.g., for files of several gigabytes, and differing blocks are reported as byte ranges
+/// rather than element indices. Comparing stops after `max_ranges` differing ranges if any, e.g.,
+/// to only check whether there is a difference at all. Files are decompressed on the fly, so their
+/// compression may differ. A `block_size` of zero is treated as one.
+///
+/// # Example
+///
+/// ```no_run
+/// use ndarray_npz::{block_diff, NpzReader};
+/// use std::fs::File;
+///
+/// let mut old = NpzReader::new(File::open("old.npz")?)?;
+/// let mut new = NpzReader::new(File::open("new.npz")?)?;
+/// let diff = block_diff(&mut old, &mut new, "weights.npy", 1 << 20, Some(10))?;
+/// for range in &diff.ranges {
+/// 	println!("Bytes {range:?} differ");
+/// }
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// # Errors
+///
+/// Fails with [`ZipError::FileNotFound`] if either archive has no file `name`. Reading the files
+/// can fail with [`ZipError`].
+pub fn block_diff<A, B>(
+	a: &mut NpzReader<A>,
+	b: &mut NpzReader<B>,
+	name: &str,
+	block_size: usize,
+	max_ranges: Option<usize>,
+) -> Result<BlockDiff, ReadNpzError>
+where
+	A: Read + Seek,
+	B: Read + Seek,
+{
+	let mut a = a.zip.by_name(name)?;
+	let mut b = b.zip.by_name(name)?;
+	let block_size = block_size.max(1);
+	let mut block_a = vec![0; block_size];
+	let mut block_b = vec![0; block_size];
+	let mut diff = BlockDiff::default();
+	let mut pos = 0;
+	loop {
+		let len_a = fill(&mut a, &mut block_a).map_err(ZipError::from)?;
+		let len_b = fill(&mut b, &mut block_b).map_err(ZipError::from)?;
+		let len = len_a.max(len_b);
+		if len == 0 {
+			break;
+		}
+		if block_a[..len_a] != block_b[..len_b] {
+			let block = pos..pos + len as u64;
+			if let Some(last) = diff
+				.ranges
+				.last_mut()
+				.filter(|last| last.end == block.start)
+			{
+				last.end = block.end;
+			} else if max_ranges.is_some_and(|max| diff.ranges.len() >= max) {
+				diff.truncated = true;
+				break;
+			} else {
+				diff.ranges.push(block);
+			}
+		}
+		pos += len as u64;
+	}
+	Ok(diff)
+}
+
+/// Reads into `block` until it is full or the end of `reader` is reached, returns the length read.
+fn fill<R: Read>(reader: &mut R, block: &mut [u8]) -> io::Result<usize> {
+	let mut len = 0;
+	while len < block.len() {
+		match reader.read(&mut block[len..]) {
+			Ok(0) => break,
+			Ok(read) => len += read,
+			Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+			Err(err) => return Err(err),
+		}
+	}
+	Ok(len)
+}
+
+impl<R: Read + Seek> NpzReader<R> {
+	/// Returns the CRC-32 checksums and uncompressed sizes of all files by name.
+	fn manifest(&mut self) -> Result<BTreeMap<String, (u32, u64)>, ReadNpzError> {
+		(0..self.zip.len())
+			.map(|index| {
+				let file = self.zip.by_index_raw(index)?;
+				Ok((file.name().to_owned(), (file.crc32(), file.size())))
+			})
+			.collect()
+	}
+}
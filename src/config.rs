@@ -0,0 +1,127 @@
+use super::{code::zip_code, ErrorCategory, ErrorCode, NpzReader, NpzWriter};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+	error::Error,
+	fmt,
+	io::{BufReader, BufWriter, Read, Seek, Write},
+};
+use zip::result::ZipError;
+
+/// An error adding or reading a JSON member, see [`NpzWriter::add_json`].
+#[derive(Debug)]
+pub enum JsonNpzError {
+	/// An error caused by the zip archive.
+	Zip(ZipError),
+	/// An error caused by serializing or deserializing the JSON member.
+	Json(serde_json::Error),
+}
+
+impl Error for JsonNpzError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			JsonNpzError::Zip(err) => Some(err),
+			JsonNpzError::Json(err) => Some(err),
+		}
+	}
+}
+
+impl fmt::Display for JsonNpzError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			JsonNpzError::Zip(err) => write!(f, "zip file error: {err}"),
+			JsonNpzError::Json(err) => write!(f, "json error: {err}"),
+		}
+	}
+}
+
+impl From<ZipError> for JsonNpzError {
+	fn from(err: ZipError) -> JsonNpzError {
+		JsonNpzError::Zip(err)
+	}
+}
+
+impl From<serde_json::Error> for JsonNpzError {
+	fn from(err: serde_json::Error) -> JsonNpzError {
+		JsonNpzError::Json(err)
+	}
+}
+
+impl JsonNpzError {
+	/// Returns the stable code of the error, see [`ErrorCode`].
+	#[must_use]
+	pub fn code(&self) -> ErrorCode {
+		match self {
+			JsonNpzError::Zip(err) => zip_code(err),
+			JsonNpzError::Json(err) if err.is_io() => ErrorCode::Io,
+			JsonNpzError::Json(_) => ErrorCode::Json,
+		}
+	}
+
+	/// Returns the stable category of the error, see [`ErrorCategory`].
+	#[must_use]
+	pub fn category(&self) -> ErrorCategory {
+		self.code().category()
+	}
+}
+
+/// Returns the name of the JSON member `name` by appending `.json` unless present.
+#[allow(clippy::case_sensitive_file_extension_comparisons)]
+fn json_name(name: &str) -> String {
+	if name.ends_with(".json") {
+		name.to_owned()
+	} else {
+		format!("{name}.json")
+	}
+}
+
+impl<W: Write + Seek> NpzWriter<W> {
+	/// Adds `value` serialized as pretty-printed UTF-8 JSON member `config.json` given the `name`
+	/// `config` or `config.json`.
+	///
+	/// The member is a [blob](Self::add_bytes), so Python reads it via
+	/// `json.load(zipfile.ZipFile("arrays.npz").open("config.json"))`.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use ndarray_npz::{NpzReader, NpzWriter};
+	/// use std::{collections::BTreeMap, io::Cursor};
+	///
+	/// let config = BTreeMap::from([("learning_rate", 0.001), ("momentum", 0.9)]);
+	/// let mut buffer = Vec::new();
+	/// let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+	/// npz.add_json("config", &config)?;
+	/// npz.finish()?;
+	/// let mut npz = NpzReader::new(Cursor::new(&buffer))?;
+	/// let read: BTreeMap<String, f64> = npz.read_json("config")?;
+	/// assert_eq!(read["momentum"], 0.9);
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Adding the member can fail with [`ZipError`] or [`serde_json::Error`].
+	pub fn add_json<T>(&mut self, name: &str, value: &T) -> Result<(), JsonNpzError>
+	where
+		T: Serialize + ?Sized,
+	{
+		self.zip.start_file(json_name(name), self.options)?;
+		let mut writer = BufWriter::with_capacity(self.buffer_size, &mut self.zip);
+		serde_json::to_writer_pretty(&mut writer, value)?;
+		writer.flush().map_err(ZipError::from)?;
+		Ok(())
+	}
+}
+
+impl<R: Read + Seek> NpzReader<R> {
+	/// Reads and deserializes the JSON member `config.json` given the `name` `config` or
+	/// `config.json`, see [`NpzWriter::add_json`].
+	///
+	/// # Errors
+	///
+	/// Reading the member can fail with [`ZipError`] or [`serde_json::Error`].
+	pub fn read_json<T: DeserializeOwned>(&mut self, name: &str) -> Result<T, JsonNpzError> {
+		let file = self.zip.by_name(&json_name(name))?;
+		Ok(serde_json::from_reader(BufReader::new(file))?)
+	}
+}
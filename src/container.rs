@@ -0,0 +1,258 @@
+use super::{portable::long_path, NpzReader, ReadNpzError};
+use std::{
+	env,
+	fs::{self, File, OpenOptions},
+	io::{self, Cursor, Read, Seek, SeekFrom},
+	path::{Path, PathBuf},
+	process,
+	sync::atomic::{AtomicU64, Ordering},
+};
+use zip::result::ZipError;
+
+/// Outer container of a `.npz` file, see [`NpzReader::open`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Container {
+	/// Plain `.npz` file, e.g., `arrays.npz`.
+	Plain,
+	/// `.npz` file compressed as a whole via *gzip*, e.g., `arrays.npz.gz`.
+	Gzip,
+	/// `.npz` file compressed as a whole via *zstd*, e.g., `arrays.npz.zst`.
+	Zstd,
+}
+
+impl Container {
+	/// Detects the container by the magic bytes at the start of a file.
+	#[must_use]
+	pub fn detect(magic: &[u8]) -> Self {
+		if magic.starts_with(&[0x1f, 0x8b]) {
+			Self::Gzip
+		} else if magic.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+			Self::Zstd
+		} else {
+			Self::Plain
+		}
+	}
+}
+
+/// Buffering of a decompressed `.npz` file, see [`NpzReader::open_with`].
+///
+/// Zip archives are read by seeking to their central directory at the end and from there to their
+/// files, whereas decompressing streams cannot seek. Hence, a `.npz` file compressed as a whole is
+/// decompressed into a seekable buffer first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Buffering {
+	/// Decompresses into memory.
+	#[default]
+	Memory,
+	/// Decompresses into a temporary file which is deleted when dropped.
+	TempFile,
+	/// Rejects compressed containers, see [`NpzReader::open_with`].
+	Reject,
+}
+
+/// Seekable reader of a `.npz` file within its outer [`Container`], see [`NpzReader::open`].
+#[derive(Debug)]
+pub struct ContainerReader {
+	container: Container,
+	inner: Inner,
+}
+
+#[derive(Debug)]
+enum Inner {
+	File(File),
+	Memory(Cursor<Vec<u8>>),
+	TempFile(TempFile),
+}
+
+impl ContainerReader {
+	/// Detects the [`Container`] of the `.npz` file at `path` and opens it.
+	///
+	/// Plain `.npz` files are read as is. Compressed ones are decompressed according to
+	/// `buffering`.
+	///
+	/// # Errors
+	///
+	/// Fails with [`io::ErrorKind::Unsupported`] if the container is compressed but its
+	/// decompression is disabled, either via [`Buffering::Reject`] or by the `gzip` or `zstd`
+	/// feature. Reading or buffering the file can fail with [`io::Error`].
+	pub fn open<P: AsRef<Path>>(path: P, buffering: Buffering) -> io::Result<Self> {
+		let mut file = File::open(long_path(path.as_ref()))?;
+		let container = Container::detect(&magic(&mut file)?);
+		file.seek(SeekFrom::Start(0))?;
+		match container {
+			Container::Plain => Ok(Self {
+				container,
+				inner: Inner::File(file),
+			}),
+			container => Self::decompress(file, container, buffering),
+		}
+	}
+	/// Detects the [`Container`] of the `.npz` file streamed by `reader` and buffers it.
+	///
+	/// Unlike [`Self::open`], plain `.npz` files are buffered as well.
+	///
+	/// # Errors
+	///
+	/// Fails like [`Self::open`], including for plain `.npz` files with [`Buffering::Reject`].
+	pub fn new<R: Read>(mut reader: R, buffering: Buffering) -> io::Result<Self> {
+		let magic = magic(&mut reader)?;
+		let container = Container::detect(&magic);
+		Self::decompress(Cursor::new(magic).chain(reader), container, buffering)
+	}
+	/// Returns the detected container.
+	#[must_use]
+	pub fn container(&self) -> Container {
+		self.container
+	}
+	/// Decompresses the `reader` of `container` into the `buffering`.
+	fn decompress<R: Read>(
+		reader: R,
+		container: Container,
+		buffering: Buffering,
+	) -> io::Result<Self> {
+		let inner = match container {
+			Container::Plain => buffer(reader, buffering)?,
+			#[cfg(feature = "gzip")]
+			Container::Gzip => buffer(flate2::read::MultiGzDecoder::new(reader), buffering)?,
+			#[cfg(not(feature = "gzip"))]
+			Container::Gzip => return Err(unsupported("gzip container requires feature `gzip`")),
+			#[cfg(feature = "zstd")]
+			Container::Zstd => buffer(zstd::stream::read::Decoder::new(reader)?, buffering)?,
+			#[cfg(not(feature = "zstd"))]
+			Container::Zstd => return Err(unsupported("zstd container requires feature `zstd`")),
+		};
+		Ok(Self { container, inner })
+	}
+}
+
+impl Read for ContainerReader {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		match &mut self.inner {
+			Inner::File(file) => file.read(buf),
+			Inner::Memory(cursor) => cursor.read(buf),
+			Inner::TempFile(temp) => temp.file.read(buf),
+		}
+	}
+}
+
+impl Seek for ContainerReader {
+	fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+		match &mut self.inner {
+			Inner::File(file) => file.seek(pos),
+			Inner::Memory(cursor) => cursor.seek(pos),
+			Inner::TempFile(temp) => temp.file.seek(pos),
+		}
+	}
+}
+
+impl NpzReader<ContainerReader> {
+	/// Opens a `.npz` file for reading, decompressing it into memory if it is compressed as a
+	/// whole, e.g., `arrays.npz.gz` or `arrays.npz.zst`.
+	///
+	/// The outer [`Container`] is detected by its magic bytes regardless of the file extension.
+	/// See [`Self::open_with`] for decompressing into a temporary file instead.
+	///
+	/// # Example
+	///
+	/// ```no_run
+	/// use ndarray_npz::{ndarray::Array2, NpzReader};
+	///
+	/// let mut npz = NpzReader::open("arrays.npz.zst")?;
+	/// let a: Array2<f64> = npz.by_name("a.npy")?;
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Opening or decompressing the file can fail with [`ZipError::Io`], reading the zip archive
+	/// with [`ZipError`].
+	pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, ReadNpzError> {
+		Self::open_with(path, Buffering::default())
+	}
+
+	/// Like [`Self::open`] but decompresses according to `buffering`.
+	///
+	/// # Errors
+	///
+	/// Fails with [`ZipError::UnsupportedArchive`] if the container is compressed but its
+	/// decompression is disabled, see [`ContainerReader::open`], otherwise like [`Self::open`].
+	pub fn open_with<P: AsRef<Path>>(path: P, buffering: Buffering) -> Result<Self, ReadNpzError> {
+		let reader = ContainerReader::open(path, buffering).map_err(|err| {
+			if err.kind() == io::ErrorKind::Unsupported {
+				ZipError::UnsupportedArchive("Compressed container not supported")
+			} else {
+				ZipError::from(err)
+			}
+		})?;
+		Self::new(reader)
+	}
+}
+
+/// Temporary file deleted when dropped.
+#[derive(Debug)]
+pub(crate) struct TempFile {
+	/// Temporary file opened for reading and writing.
+	pub file: File,
+	/// Path of the temporary file if it could not be deleted while open.
+	path: Option<PathBuf>,
+}
+
+impl TempFile {
+	/// Creates a new temporary file in [`env::temp_dir`].
+	///
+	/// The file is deleted right away where open files can be deleted, leaving it anonymous.
+	pub fn new() -> io::Result<Self> {
+		static COUNTER: AtomicU64 = AtomicU64::new(0);
+		let name = format!(
+			"ndarray-npz-{}-{}.tmp",
+			process::id(),
+			COUNTER.fetch_add(1, Ordering::Relaxed)
+		);
+		let path = env::temp_dir().join(name);
+		let file = OpenOptions::new()
+			.read(true)
+			.write(true)
+			.create_new(true)
+			.open(&path)?;
+		let path = fs::remove_file(&path).is_err().then_some(path);
+		Ok(Self { file, path })
+	}
+}
+
+impl Drop for TempFile {
+	fn drop(&mut self) {
+		if let Some(path) = &self.path {
+			let _ = fs::remove_file(path);
+		}
+	}
+}
+
+/// Reads up to the first four bytes.
+fn magic<R: Read>(reader: &mut R) -> io::Result<Vec<u8>> {
+	let mut magic = Vec::with_capacity(4);
+	reader.take(4).read_to_end(&mut magic)?;
+	Ok(magic)
+}
+
+/// Buffers the `reader` according to `buffering`.
+fn buffer<R: Read>(mut reader: R, buffering: Buffering) -> io::Result<Inner> {
+	match buffering {
+		Buffering::Memory => {
+			let mut bytes = Vec::new();
+			reader.read_to_end(&mut bytes)?;
+			Ok(Inner::Memory(Cursor::new(bytes)))
+		}
+		Buffering::TempFile => {
+			let mut temp = TempFile::new()?;
+			io::copy(&mut reader, &mut temp.file)?;
+			temp.file.seek(SeekFrom::Start(0))?;
+			Ok(Inner::TempFile(temp))
+		}
+		Buffering::Reject => Err(unsupported("container requires buffering")),
+	}
+}
+
+/// Returns an [`io::ErrorKind::Unsupported`] error.
+fn unsupported(msg: &'static str) -> io::Error {
+	io::Error::new(io::ErrorKind::Unsupported, msg)
+}
@@ -0,0 +1,354 @@
+use super::{header::NpyHeader, EditNpzError, NpzReader, NpzWriter};
+use py_literal::Value;
+use std::{
+	any::TypeId,
+	io::{Read, Seek, Write},
+};
+
+/// Number of elements converted at once.
+pub(crate) const CHUNK_LEN: usize = 1 << 14;
+
+/// Element type of `.npy` files supported by [`convert_entry_dtype`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dtype {
+	/// Boolean `|b1`.
+	Bool,
+	/// Signed 8-bit integer `|i1`.
+	I8,
+	/// Unsigned 8-bit integer `|u1`.
+	U8,
+	/// Signed 16-bit integer `i2`.
+	I16,
+	/// Unsigned 16-bit integer `u2`.
+	U16,
+	/// Signed 32-bit integer `i4`.
+	I32,
+	/// Unsigned 32-bit integer `u4`.
+	U32,
+	/// Signed 64-bit integer `i8`.
+	I64,
+	/// Unsigned 64-bit integer `u8`.
+	U64,
+	/// Single-precision floating point `f4`.
+	F32,
+	/// Double-precision floating point `f8`.
+	F64,
+}
+
+impl Dtype {
+	/// Returns the size in bytes of an element.
+	#[must_use]
+	pub fn size(self) -> usize {
+		match self {
+			Dtype::Bool | Dtype::I8 | Dtype::U8 => 1,
+			Dtype::I16 | Dtype::U16 => 2,
+			Dtype::I32 | Dtype::U32 | Dtype::F32 => 4,
+			Dtype::I64 | Dtype::U64 | Dtype::F64 => 8,
+		}
+	}
+	/// Returns the type code without byte order, e.g., `f4`.
+	fn code(self) -> &'static str {
+		match self {
+			Dtype::Bool => "b1",
+			Dtype::I8 => "i1",
+			Dtype::U8 => "u1",
+			Dtype::I16 => "i2",
+			Dtype::U16 => "u2",
+			Dtype::I32 => "i4",
+			Dtype::U32 => "u4",
+			Dtype::I64 => "i8",
+			Dtype::U64 => "u8",
+			Dtype::F32 => "f4",
+			Dtype::F64 => "f8",
+		}
+	}
+	/// Returns the element type of `A` if any.
+	pub(crate) fn of<A: 'static>() -> Option<Self> {
+		let id = TypeId::of::<A>();
+		[
+			(TypeId::of::<bool>(), Dtype::Bool),
+			(TypeId::of::<i8>(), Dtype::I8),
+			(TypeId::of::<u8>(), Dtype::U8),
+			(TypeId::of::<i16>(), Dtype::I16),
+			(TypeId::of::<u16>(), Dtype::U16),
+			(TypeId::of::<i32>(), Dtype::I32),
+			(TypeId::of::<u32>(), Dtype::U32),
+			(TypeId::of::<i64>(), Dtype::I64),
+			(TypeId::of::<u64>(), Dtype::U64),
+			(TypeId::of::<f32>(), Dtype::F32),
+			(TypeId::of::<f64>(), Dtype::F64),
+		]
+		.into_iter()
+		.find_map(|(other, dtype)| (other == id).then_some(dtype))
+	}
+	/// Parses the type descriptor of an `.npy` header and returns whether it is big endian.
+	pub(crate) fn parse(descr: &Value) -> Option<(Self, bool)> {
+		let Value::String(descr) = descr else {
+			return None;
+		};
+		let split = descr.len().checked_sub(2)?;
+		let (order, code) = (descr.get(..split)?, descr.get(split..)?);
+		let dtype = [
+			Dtype::Bool,
+			Dtype::I8,
+			Dtype::U8,
+			Dtype::I16,
+			Dtype::U16,
+			Dtype::I32,
+			Dtype::U32,
+			Dtype::I64,
+			Dtype::U64,
+			Dtype::F32,
+			Dtype::F64,
+		]
+		.into_iter()
+		.find(|dtype| dtype.code() == code)?;
+		let big_endian = match order {
+			"<" => false,
+			">" => true,
+			"=" | "" => cfg!(target_endian = "big"),
+			"|" if dtype.size() == 1 => false,
+			_ => return None,
+		};
+		Some((dtype, big_endian))
+	}
+	/// Returns the type descriptor of an `.npy` header in the given byte order.
+	pub(crate) fn descr(self, big_endian: bool) -> Value {
+		let order = if self.size() == 1 {
+			"|"
+		} else if big_endian {
+			">"
+		} else {
+			"<"
+		};
+		Value::String(format!("{order}{}", self.code()))
+	}
+}
+
+/// Conversion of the element type of an `.npy` file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Conversion {
+	/// Casts the value of each element like the `as` operator, e.g., `1.5f32` to `1i32`.
+	///
+	/// Booleans cast to `0` or `1` and numbers cast to `true` unless zero.
+	Cast(Dtype),
+	/// Reinterprets the bytes of each element, e.g., `1.0f32` to `1_065_353_216i32`.
+	///
+	/// Requires the element types to be of the same size. Reinterpreting as [`Dtype::Bool`]
+	/// requires every byte to be either `0` or `1`.
+	Reinterpret(Dtype),
+}
+
+impl Conversion {
+	/// Returns the target element type.
+	#[must_use]
+	pub fn dtype(self) -> Dtype {
+		match self {
+			Conversion::Cast(dtype) | Conversion::Reinterpret(dtype) => dtype,
+		}
+	}
+	/// Parses the `.npy` header `bytes` and returns the converted header and the source element
+	/// type.
+	pub(crate) fn header(self, bytes: &[u8]) -> Result<(NpyHeader, Dtype, bool), EditNpzError> {
+		let mut header = NpyHeader::parse(bytes).ok_or(EditNpzError::InvalidHeader)?;
+		let (source, big_endian) =
+			Dtype::parse(&header.descr).ok_or(EditNpzError::UnsupportedDtype)?;
+		if let Conversion::Reinterpret(target) = self {
+			if target.size() != source.size() {
+				return Err(EditNpzError::ItemSizeMismatch);
+			}
+		}
+		header.descr = self.dtype().descr(big_endian);
+		Ok((header, source, big_endian))
+	}
+	/// Converts the elements of `source` type in `input` and appends them to `output`.
+	pub(crate) fn convert(
+		self,
+		source: Dtype,
+		big_endian: bool,
+		input: &[u8],
+		output: &mut Vec<u8>,
+	) {
+		match self {
+			Conversion::Cast(target) => {
+				for bytes in input.chunks_exact(source.size()) {
+					cast(
+						target,
+						decode(source, bytes, big_endian),
+						big_endian,
+						output,
+					);
+				}
+			}
+			Conversion::Reinterpret(_) => output.extend_from_slice(input),
+		}
+	}
+}
+
+/// Converts the element type of the array of the file `name` from `reader` and adds it to
+/// `writer` under the same name.
+///
+/// The array is streamed in chunks, so it never has to fit into memory as a whole. The byte order
+/// and memory layout are kept. Entries of the same element size can be converted in place via
+/// [`NpzEditor::convert_entry_dtype`](crate::NpzEditor::convert_entry_dtype) instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use ndarray_npz::{convert_entry_dtype, Conversion, Dtype, NpzReader, NpzWriter};
+/// use std::fs::File;
+///
+/// let mut reader = NpzReader::new(File::open("f64.npz")?)?;
+/// let mut writer = NpzWriter::new(File::create("f32.npz")?);
+/// for name in reader.names()? {
+/// 	convert_entry_dtype(&mut reader, &mut writer, &name, Conversion::Cast(Dtype::F32))?;
+/// }
+/// writer.finish()?;
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// # Errors
+///
+/// Fails with [`EditNpzError::UnsupportedDtype`] if the source element type is not a [`Dtype`],
+/// with [`EditNpzError::ItemSizeMismatch`] if the element types of a
+/// [`Conversion::Reinterpret`] differ in size, with [`EditNpzError::InvalidHeader`] if the
+/// `.npy` header cannot be parsed, or with [`ZipError`](zip::result::ZipError) if reading or
+/// writing the zip archives fails.
+pub fn convert_entry_dtype<R, W>(
+	reader: &mut NpzReader<R>,
+	writer: &mut NpzWriter<W>,
+	name: &str,
+	conversion: Conversion,
+) -> Result<(), EditNpzError>
+where
+	R: Read + Seek,
+	W: Write + Seek,
+{
+	let mut file = reader.zip.by_name(name)?;
+	let mut bytes = vec![0; 12];
+	file.read_exact(&mut bytes)?;
+	let len = NpyHeader::len(&bytes).ok_or(EditNpzError::InvalidHeader)?;
+	if len < 12 {
+		return Err(EditNpzError::InvalidHeader);
+	}
+	bytes.resize(len, 0);
+	file.read_exact(&mut bytes[12..])?;
+	let (header, source, big_endian) = conversion.header(&bytes)?;
+	let elements = header.elements().ok_or(EditNpzError::InvalidHeader)?;
+	let bytes = header.to_bytes().ok_or(EditNpzError::HeaderOverflow)?;
+	let len = elements.saturating_mul(conversion.dtype().size() as u64);
+	writer.zip.start_file(name, writer.sized_options(len))?;
+	writer.zip.write_all(&bytes)?;
+	let mut input = vec![0; CHUNK_LEN * source.size()];
+	let mut output = Vec::with_capacity(CHUNK_LEN * conversion.dtype().size());
+	let mut remaining = elements;
+	while remaining > 0 {
+		let chunk = usize::try_from(remaining.min(CHUNK_LEN as u64)).unwrap_or(CHUNK_LEN);
+		let input = &mut input[..chunk * source.size()];
+		file.read_exact(input)?;
+		output.clear();
+		conversion.convert(source, big_endian, input, &mut output);
+		writer.zip.write_all(&output)?;
+		remaining -= chunk as u64;
+	}
+	Ok(())
+}
+
+/// Element value decoded from an `.npy` file.
+#[derive(Clone, Copy)]
+pub(crate) enum Scalar {
+	Bool(bool),
+	Int(i64),
+	UInt(u64),
+	Float(f64),
+}
+
+impl Scalar {
+	/// Returns the value as `f64`, possibly losing precision.
+	#[allow(clippy::cast_precision_loss)]
+	pub(crate) fn to_f64(self) -> f64 {
+		match self {
+			Scalar::Bool(value) => f64::from(u8::from(value)),
+			Scalar::Int(value) => value as f64,
+			Scalar::UInt(value) => value as f64,
+			Scalar::Float(value) => value,
+		}
+	}
+}
+
+/// Reverses the bytes of each element of `size` bytes.
+pub(crate) fn swap(data: &mut [u8], size: usize) {
+	for element in data.chunks_exact_mut(size) {
+		element.reverse();
+	}
+}
+
+pub(crate) fn decode(dtype: Dtype, bytes: &[u8], big_endian: bool) -> Scalar {
+	macro_rules! decode {
+		($ty:ty) => {{
+			let bytes = bytes.try_into().unwrap();
+			if big_endian {
+				<$ty>::from_be_bytes(bytes)
+			} else {
+				<$ty>::from_le_bytes(bytes)
+			}
+		}};
+	}
+	match dtype {
+		Dtype::Bool => Scalar::Bool(bytes[0] != 0),
+		Dtype::I8 => Scalar::Int(i64::from(decode!(i8))),
+		Dtype::U8 => Scalar::UInt(u64::from(decode!(u8))),
+		Dtype::I16 => Scalar::Int(i64::from(decode!(i16))),
+		Dtype::U16 => Scalar::UInt(u64::from(decode!(u16))),
+		Dtype::I32 => Scalar::Int(i64::from(decode!(i32))),
+		Dtype::U32 => Scalar::UInt(u64::from(decode!(u32))),
+		Dtype::I64 => Scalar::Int(decode!(i64)),
+		Dtype::U64 => Scalar::UInt(decode!(u64)),
+		Dtype::F32 => Scalar::Float(f64::from(decode!(f32))),
+		Dtype::F64 => Scalar::Float(decode!(f64)),
+	}
+}
+
+#[allow(
+	clippy::cast_possible_truncation,
+	clippy::cast_possible_wrap,
+	clippy::cast_precision_loss,
+	clippy::cast_sign_loss,
+	clippy::cast_lossless,
+	clippy::unnecessary_cast
+)]
+fn cast(dtype: Dtype, scalar: Scalar, big_endian: bool, output: &mut Vec<u8>) {
+	macro_rules! cast {
+		($ty:ty) => {{
+			let value = match scalar {
+				Scalar::Bool(value) => u8::from(value) as $ty,
+				Scalar::Int(value) => value as $ty,
+				Scalar::UInt(value) => value as $ty,
+				Scalar::Float(value) => value as $ty,
+			};
+			if big_endian {
+				output.extend(value.to_be_bytes());
+			} else {
+				output.extend(value.to_le_bytes());
+			}
+		}};
+	}
+	match dtype {
+		Dtype::Bool => output.push(u8::from(match scalar {
+			Scalar::Bool(value) => value,
+			Scalar::Int(value) => value != 0,
+			Scalar::UInt(value) => value != 0,
+			Scalar::Float(value) => value != 0.0,
+		})),
+		Dtype::I8 => cast!(i8),
+		Dtype::U8 => cast!(u8),
+		Dtype::I16 => cast!(i16),
+		Dtype::U16 => cast!(u16),
+		Dtype::I32 => cast!(i32),
+		Dtype::U32 => cast!(u32),
+		Dtype::I64 => cast!(i64),
+		Dtype::U64 => cast!(u64),
+		Dtype::F32 => cast!(f32),
+		Dtype::F64 => cast!(f64),
+	}
+}
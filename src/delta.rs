@@ -0,0 +1,197 @@
+use super::{sibling_name, NpzReader, NpzWriter, ReadNpzError, WriteNpzError};
+use ndarray::{prelude::*, Data, Zip};
+use ndarray_npy::{ReadableElement, WritableElement};
+use std::io::{Read, Seek, Write};
+use zip::result::ZipError;
+
+/// Encoding of an array as delta against a base array, e.g., of a previous snapshot.
+///
+/// The encoding is stored as `u8` scalar member next to the delta, i.e., `x.npy` is accompanied
+/// by `x.delta.npy` containing the encoding's discriminant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum DeltaEncoding {
+	/// Bitwise XOR stored as unsigned integers of the same size, reconstructing arrays exactly.
+	///
+	/// Leading bits of slowly changing values cancel out, which suits floating point arrays.
+	Xor = 1,
+	/// Numeric difference stored in the element type, wrapping around for integers.
+	///
+	/// Reconstructs integer arrays exactly but floating point arrays only up to rounding errors.
+	Difference = 2,
+}
+
+impl DeltaEncoding {
+	fn from_u8(encoding: u8) -> Option<Self> {
+		match encoding {
+			1 => Some(DeltaEncoding::Xor),
+			2 => Some(DeltaEncoding::Difference),
+			_ => None,
+		}
+	}
+}
+
+/// Element type of arrays stored as delta via [`NpzWriter::add_array_delta`].
+pub trait DeltaElement: ReadableElement + WritableElement + Copy {
+	/// Unsigned integer of the same size storing [`DeltaEncoding::Xor`] deltas.
+	type Bits: ReadableElement + WritableElement + Copy;
+	/// Returns the bitwise XOR of `self` and `base`.
+	fn xor(self, base: Self) -> Self::Bits;
+	/// Reconstructs the value from its bitwise XOR `delta` with `base`.
+	fn from_xor(delta: Self::Bits, base: Self) -> Self;
+	/// Returns the numeric difference of `self` and `base`.
+	#[must_use]
+	fn difference(self, base: Self) -> Self;
+	/// Reconstructs the value from its numeric difference `delta` with `base`.
+	fn from_difference(delta: Self, base: Self) -> Self;
+}
+
+macro_rules! impl_delta_element_int {
+	($($ty:ty => $bits:ty),*) => {$(
+		#[allow(
+			clippy::cast_possible_wrap,
+			clippy::cast_sign_loss,
+			clippy::unnecessary_cast
+		)]
+		impl DeltaElement for $ty {
+			type Bits = $bits;
+			fn xor(self, base: Self) -> Self::Bits {
+				(self ^ base) as $bits
+			}
+			fn from_xor(delta: Self::Bits, base: Self) -> Self {
+				delta as $ty ^ base
+			}
+			fn difference(self, base: Self) -> Self {
+				self.wrapping_sub(base)
+			}
+			fn from_difference(delta: Self, base: Self) -> Self {
+				delta.wrapping_add(base)
+			}
+		}
+	)*};
+}
+
+macro_rules! impl_delta_element_float {
+	($($ty:ty => $bits:ty),*) => {$(
+		impl DeltaElement for $ty {
+			type Bits = $bits;
+			fn xor(self, base: Self) -> Self::Bits {
+				self.to_bits() ^ base.to_bits()
+			}
+			fn from_xor(delta: Self::Bits, base: Self) -> Self {
+				<$ty>::from_bits(delta ^ base.to_bits())
+			}
+			fn difference(self, base: Self) -> Self {
+				self - base
+			}
+			fn from_difference(delta: Self, base: Self) -> Self {
+				delta + base
+			}
+		}
+	)*};
+}
+
+impl_delta_element_int!(
+	i8 => u8, u8 => u8, i16 => u16, u16 => u16, i32 => u32, u32 => u32, i64 => u64, u64 => u64
+);
+impl_delta_element_float!(f32 => u32, f64 => u64);
+
+impl<W: Write + Seek> NpzWriter<W> {
+	/// Adds an array with the specified `name` encoded as delta against the `base` array, e.g.,
+	/// the same-named array of the previous snapshot.
+	///
+	/// The delta is stored under `name` and the `encoding` as scalar member next to it, see
+	/// [`DeltaEncoding`]. Read it back via [`NpzReader::by_name_delta`].
+	///
+	/// # Errors
+	///
+	/// Adding an array can fail with [`WriteNpyError`](ndarray_npy::WriteNpyError).
+	///
+	/// # Panics
+	///
+	/// Panics if the shapes of `array` and `base` differ.
+	pub fn add_array_delta<N, S, T, D>(
+		&mut self,
+		name: N,
+		array: &ArrayBase<S, D>,
+		base: &ArrayBase<T, D>,
+		encoding: DeltaEncoding,
+	) -> Result<(), WriteNpzError>
+	where
+		N: Into<String>,
+		S::Elem: DeltaElement,
+		S: Data,
+		T: Data<Elem = S::Elem>,
+		D: Dimension,
+	{
+		assert_eq!(
+			array.shape(),
+			base.shape(),
+			"shapes of array and base differ"
+		);
+		let name = name.into();
+		self.add_array(sibling_name(&name, "delta"), &arr0(encoding as u8))?;
+		let delta = Zip::from(array).and(base);
+		match encoding {
+			DeltaEncoding::Xor => {
+				self.add_array(name, &delta.map_collect(|&value, &base| value.xor(base)))
+			}
+			DeltaEncoding::Difference => self.add_array(
+				name,
+				&delta.map_collect(|&value, &base| value.difference(base)),
+			),
+		}
+	}
+}
+
+impl<R: Read + Seek> NpzReader<R> {
+	/// Reads an array by name reconstructing it from its delta against the `base` array if it has
+	/// been added via [`NpzWriter::add_array_delta`].
+	///
+	/// Arrays without delta encoding are read as is, so every array of a snapshot can be read
+	/// transparently given the arrays of the previous snapshot.
+	///
+	/// # Errors
+	///
+	/// Reading an array from an archive can fail with [`ReadNpyError`](ndarray_npy::ReadNpyError)
+	/// or [`ZipError`]. Fails with [`ZipError::InvalidArchive`] if the encoding is unknown or if
+	/// the shapes of the delta and `base` differ.
+	pub fn by_name_delta<A, T, D>(
+		&mut self,
+		name: &str,
+		base: &ArrayBase<T, D>,
+	) -> Result<Array<A, D>, ReadNpzError>
+	where
+		A: DeltaElement,
+		T: Data<Elem = A>,
+		D: Dimension,
+	{
+		let encoding: Array0<u8> = match self.by_name(&sibling_name(name, "delta")) {
+			Err(ReadNpzError::Zip(ZipError::FileNotFound)) => return self.by_name(name),
+			encoding => encoding?,
+		};
+		let encoding = DeltaEncoding::from_u8(encoding.into_scalar())
+			.ok_or(ZipError::InvalidArchive("Unknown delta encoding"))?;
+		let shape_mismatch = ZipError::InvalidArchive("Delta and base differ in shape");
+		Ok(match encoding {
+			DeltaEncoding::Xor => {
+				let delta: Array<A::Bits, D> = self.by_name(name)?;
+				if delta.shape() != base.shape() {
+					return Err(shape_mismatch.into());
+				}
+				Zip::from(&delta)
+					.and(base)
+					.map_collect(|&delta, &base| A::from_xor(delta, base))
+			}
+			DeltaEncoding::Difference => {
+				let delta: Array<A, D> = self.by_name(name)?;
+				if delta.shape() != base.shape() {
+					return Err(shape_mismatch.into());
+				}
+				Zip::from(&delta)
+					.and(base)
+					.map_collect(|&delta, &base| A::from_difference(delta, base))
+			}
+		})
+	}
+}
@@ -0,0 +1,199 @@
+use super::{
+	header::{NpyHeader, MAGIC},
+	NpzWriter, ReadNpzError,
+};
+use std::{
+	fmt,
+	io::{self, Read, Seek, SeekFrom, Write},
+};
+use zip::{result::ZipError, ZipArchive};
+
+/// Checks a finished archive on [`NpzWriter::finish`].
+pub(crate) type SelfCheck<W> = fn(&mut W) -> Result<(), ZipError>;
+
+/// Region of an archive, see [`Divergence`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Region {
+	/// Local header of the named file.
+	LocalHeader(String),
+	/// Data of the named file.
+	Data(String),
+	/// Central directory including the end of central directory records.
+	CentralDirectory,
+	/// Region outside of any file, e.g., padding, or of an archive which cannot be read.
+	Unknown,
+}
+
+impl fmt::Display for Region {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Region::LocalHeader(name) => write!(f, "local header of {name}"),
+			Region::Data(name) => write!(f, "data of {name}"),
+			Region::CentralDirectory => write!(f, "central directory"),
+			Region::Unknown => write!(f, "unknown region"),
+		}
+	}
+}
+
+/// First difference of two archives found by [`verify_deterministic`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Divergence {
+	/// Offset in bytes of the first differing byte or the length of the shorter archive.
+	pub offset: u64,
+	/// Region of the first archive at the offset.
+	pub region: Region,
+}
+
+impl fmt::Display for Divergence {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			f,
+			"archives diverge at byte {} in {}",
+			self.offset, self.region
+		)
+	}
+}
+
+/// Verifies that the archives `a` and `b` are byte-for-byte identical and otherwise locates
+/// their first [`Divergence`].
+///
+/// Archives written from the same arrays with the same options are expected to be identical,
+/// e.g., when they are addressed by their content. A divergence points at the changed file or at
+/// structures of the zip archive, e.g., after updating the zip crate. The modification time
+/// defaults to the current time if the `time` feature of the zip crate is enabled, in which case
+/// it has to be pinned via [`NpzWriter::with_last_modified_time`].
+///
+/// # Example
+///
+/// ```
+/// use ndarray::Array1;
+/// use ndarray_npz::{verify_deterministic, NpzWriter};
+/// use std::io::Cursor;
+///
+/// let write = || -> Result<_, Box<dyn std::error::Error>> {
+/// 	let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+/// 	npz.add_array("x.npy", &Array1::<f64>::linspace(0.0, 1.0, 100))?;
+/// 	Ok(npz.finish()?)
+/// };
+/// assert_eq!(verify_deterministic(write()?, write()?)?, None);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// # Errors
+///
+/// Reading the archives can fail with [`ZipError::Io`].
+pub fn verify_deterministic<A, B>(mut a: A, mut b: B) -> Result<Option<Divergence>, ReadNpzError>
+where
+	A: Read + Seek,
+	B: Read + Seek,
+{
+	a.rewind().map_err(ZipError::from)?;
+	b.rewind().map_err(ZipError::from)?;
+	let (mut a_chunk, mut b_chunk) = (vec![0; 1 << 16], vec![0; 1 << 16]);
+	let mut offset = 0u64;
+	let offset = loop {
+		let a_len = read_full(&mut a, &mut a_chunk)?;
+		let b_len = read_full(&mut b, &mut b_chunk)?;
+		let len = a_len.min(b_len);
+		if let Some(index) = (0..len).find(|&index| a_chunk[index] != b_chunk[index]) {
+			break offset + index as u64;
+		}
+		if a_len != b_len {
+			break offset + len as u64;
+		}
+		if len == 0 {
+			return Ok(None);
+		}
+		offset += len as u64;
+	};
+	let region = ZipArchive::new(a)
+		.ok()
+		.and_then(|mut zip| region(&mut zip, offset))
+		.unwrap_or(Region::Unknown);
+	Ok(Some(Divergence { offset, region }))
+}
+
+/// Reads into `buf` until it is full or the end of `reader` is reached.
+fn read_full<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<usize, ZipError> {
+	let mut len = 0;
+	while len < buf.len() {
+		match reader.read(&mut buf[len..]) {
+			Ok(0) => break,
+			Ok(read) => len += read,
+			Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+			Err(err) => return Err(err.into()),
+		}
+	}
+	Ok(len)
+}
+
+/// Returns the region of `zip` at `offset` if known.
+fn region<R: Read + Seek>(zip: &mut ZipArchive<R>, offset: u64) -> Option<Region> {
+	if offset >= zip.central_directory_start() {
+		return Some(Region::CentralDirectory);
+	}
+	for index in 0..zip.len() {
+		let file = zip.by_index_raw(index).ok()?;
+		if (file.header_start()..file.data_start()).contains(&offset) {
+			return Some(Region::LocalHeader(file.name().to_owned()));
+		}
+		if (file.data_start()..file.data_start() + file.compressed_size()).contains(&offset) {
+			return Some(Region::Data(file.name().to_owned()));
+		}
+	}
+	None
+}
+
+impl<W: Read + Write + Seek> NpzWriter<W> {
+	/// Reads the archive back on [`Self::finish`] to check what has been written.
+	///
+	/// Each file is read again, including the local headers, the `.npy` headers, and the data to
+	/// verify the CRC-32 checksums, so regressions of the writer or of the zip crate are caught
+	/// right away instead of silently breaking content addressing downstream. Finishing then
+	/// fails with [`ZipError`]. The check reads the whole archive, hence the writer must be
+	/// readable.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use ndarray::Array1;
+	/// use ndarray_npz::NpzWriter;
+	/// use std::io::Cursor;
+	///
+	/// let mut npz = NpzWriter::new(Cursor::new(Vec::new())).with_self_check();
+	/// npz.add_array("x.npy", &Array1::<f64>::zeros(100))?;
+	/// npz.finish()?;
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	#[must_use]
+	pub fn with_self_check(mut self) -> Self {
+		self.check = Some(self_check::<W>);
+		self
+	}
+}
+
+/// Reads all files of the finished archive of `writer` and restores its position.
+fn self_check<W: Read + Write + Seek>(writer: &mut W) -> Result<(), ZipError> {
+	let position = writer.stream_position()?;
+	let mut zip = ZipArchive::new(&mut *writer)?;
+	for index in 0..zip.len() {
+		let mut file = zip.by_index(index)?;
+		if file.is_dir() {
+			continue;
+		}
+		let mut magic = Vec::with_capacity(MAGIC.len());
+		(&mut file)
+			.take(MAGIC.len() as u64)
+			.read_to_end(&mut magic)?;
+		if magic == MAGIC {
+			NpyHeader::read(&mut magic.as_slice().chain(&mut file))
+				.map_err(|_| ZipError::InvalidArchive("Invalid npy header"))?;
+		}
+		// Reading to the end verifies the checksum.
+		io::copy(&mut file, &mut io::sink())?;
+	}
+	drop(zip);
+	writer.seek(SeekFrom::Start(position))?;
+	Ok(())
+}
@@ -0,0 +1,141 @@
+use super::{NpzView, NpzViewMut};
+use std::{collections::HashSet, fmt};
+
+/// Alignment of `.npy` files for memory-mapping, as written by [`NpzWriter`](crate::NpzWriter).
+const ALIGNMENT: usize = 64;
+
+/// Issue of a file within a `.npz` file hindering memory-mapping, see [`NpzView::diagnostics`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Issue {
+	/// The file is compressed, hence it cannot be viewed.
+	Compressed,
+	/// The `.npy` file is not 64-byte aligned, hence viewing it fails for element types of
+	/// greater alignment than its offset.
+	Misaligned {
+		/// Offset in bytes of the `.npy` file from the preceding 64-byte boundary.
+		offset: usize,
+	},
+}
+
+impl fmt::Display for Issue {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Issue::Compressed => write!(f, "compressed"),
+			Issue::Misaligned { offset } => write!(f, "misaligned by {offset} bytes"),
+		}
+	}
+}
+
+/// Suggested fix of an [`Issue`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Fix {
+	/// Rewrite the file without compression, e.g., via [`NpzWriter::new`](crate::NpzWriter::new).
+	Store,
+	/// Rewrite the archive with 64-byte aligned files, e.g., via [`compact`](crate::compact).
+	Realign,
+}
+
+impl fmt::Display for Fix {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Fix::Store => write!(f, "rewrite it without compression"),
+			Fix::Realign => write!(f, "rewrite the archive 64-byte aligned"),
+		}
+	}
+}
+
+/// Diagnostic of a file within a `.npz` file, see [`NpzView::diagnostics`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Diagnostic {
+	/// Name of the file.
+	pub name: String,
+	/// Issue of the file.
+	pub issue: Issue,
+	/// Suggested fix of the issue.
+	pub fix: Fix,
+}
+
+impl fmt::Display for Diagnostic {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}: {}, {}", self.name, self.issue, self.fix)
+	}
+}
+
+impl NpzView<'_> {
+	/// Returns diagnostics of the files which are compressed or not 64-byte aligned in memory,
+	/// sorted by name.
+	///
+	/// Viewing such files fails, either right away via [`ViewNpzError::CompressedFile`] or later
+	/// via [`ViewNpyError`](ndarray_npy::ViewNpyError) depending on the element type, whereas the
+	/// diagnostics suggest how to rewrite the archive, so tools can guide users. Alignment is
+	/// checked against the memory address, so the bytes should be memory-mapped or otherwise
+	/// 64-byte aligned themselves.
+	///
+	/// # Example
+	///
+	/// ```no_run
+	/// use memmap2::Mmap;
+	/// use ndarray_npz::NpzView;
+	/// use std::fs::File;
+	///
+	/// let file = File::open("arrays.npz")?;
+	/// let mmap = unsafe { Mmap::map(&file)? };
+	/// let npz = NpzView::new(&mmap)?;
+	/// for diagnostic in npz.diagnostics() {
+	/// 	eprintln!("{diagnostic}");
+	/// }
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	///
+	/// [`ViewNpzError::CompressedFile`]: crate::ViewNpzError::CompressedFile
+	#[must_use]
+	pub fn diagnostics(&self) -> Vec<Diagnostic> {
+		let files = self.names.iter().filter_map(|(name, index)| {
+			let file = self.files.get(index)?;
+			Some((name, file.data.as_ptr() as usize))
+		});
+		diagnose(&self.compressed_names, files)
+	}
+}
+
+impl NpzViewMut<'_> {
+	/// Returns diagnostics of the files which are compressed or not 64-byte aligned in memory,
+	/// sorted by name.
+	///
+	/// See [`NpzView::diagnostics`]. Views moved out via [`Self::by_name`] or [`Self::by_index`]
+	/// are skipped.
+	#[must_use]
+	pub fn diagnostics(&self) -> Vec<Diagnostic> {
+		let files = self.names.iter().filter_map(|(name, index)| {
+			let file = self.files.get(index)?;
+			Some((name, file.data.as_ptr() as usize))
+		});
+		diagnose(&self.compressed_names, files)
+	}
+}
+
+/// Diagnoses the compressed files and the `.npy` files by their memory addresses.
+fn diagnose<'n, I>(compressed_names: &HashSet<String>, files: I) -> Vec<Diagnostic>
+where
+	I: Iterator<Item = (&'n String, usize)>,
+{
+	let compressed = compressed_names.iter().map(|name| Diagnostic {
+		name: name.clone(),
+		issue: Issue::Compressed,
+		fix: Fix::Store,
+	});
+	let misaligned = files
+		.filter(|&(_name, address)| address % ALIGNMENT != 0)
+		.map(|(name, address)| Diagnostic {
+			name: name.clone(),
+			issue: Issue::Misaligned {
+				offset: address % ALIGNMENT,
+			},
+			fix: Fix::Realign,
+		});
+	let mut diagnostics = compressed.chain(misaligned).collect::<Vec<_>>();
+	diagnostics.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+	diagnostics
+}
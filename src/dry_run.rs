@@ -0,0 +1,262 @@
+use super::{capability::u16_at, IndexEntry, NpzWriter, WriteNpzError};
+use std::{
+	collections::BTreeMap,
+	io::{self, Read, Seek, SeekFrom, Write},
+};
+use zip::{result::ZipError, CompressionMethod, ZipArchive};
+
+/// Writer discarding the written bytes while counting them, see [`NpzWriter::finish_dry_run`].
+///
+/// All encoding, compression, and bookkeeping is performed as usual, but only the zip structures
+/// surrounding the data, i.e., local and central file headers and the end of central directory
+/// records, are retained. This suffices to report the exact layout of the archive without touching
+/// storage, whereas memory stays proportional to the number of files instead of their size. As
+/// the data cannot be read back, methods reading the archive on finishing, e.g.,
+/// [`NpzWriter::with_index`], are not available.
+#[derive(Debug, Default)]
+pub struct DryRunWriter {
+	/// Logical position.
+	pos: u64,
+	/// Length of the archive.
+	len: u64,
+	/// Retained zip structures by offset.
+	retained: BTreeMap<u64, Vec<u8>>,
+	/// Starts and ends of the zip structures being retained.
+	pending: Vec<(u64, u64)>,
+}
+
+/// Layout of an archive written to a [`DryRunWriter`], see [`NpzWriter::finish_dry_run`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DryRunReport {
+	/// Size in bytes of the archive.
+	pub size: u64,
+	/// Files in archive order whose [`IndexEntry::npy`] is `None` as the data has been discarded.
+	pub entries: Vec<IndexEntry>,
+}
+
+impl DryRunWriter {
+	/// Creates an empty writer.
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Returns the number of bytes written so far, i.e., the length of the archive.
+	#[must_use]
+	pub fn len(&self) -> u64 {
+		self.len
+	}
+
+	/// Returns `true` iff nothing has been written.
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	/// Reports the layout of the finished archive.
+	///
+	/// # Errors
+	///
+	/// Fails with [`ZipError`] if the archive has not been finished.
+	pub fn report(&self) -> Result<DryRunReport, ZipError> {
+		let mut zip = ZipArchive::new(Retained {
+			writer: self,
+			pos: 0,
+		})?;
+		let mut entries = Vec::with_capacity(zip.len());
+		for index in 0..zip.len() {
+			let file = zip.by_index_raw(index)?;
+			entries.push(IndexEntry {
+				name: file.name().to_owned(),
+				header_start: file.header_start(),
+				data_start: file.data_start(),
+				compressed_size: file.compressed_size(),
+				size: file.size(),
+				crc32: file.crc32(),
+				directory: file.is_dir(),
+				compressed: file.compression() != CompressionMethod::Stored,
+				encrypted: file.encrypted(),
+				npy: None,
+			});
+		}
+		Ok(DryRunReport {
+			size: self.len,
+			entries,
+		})
+	}
+
+	/// Updates the retained bytes overlapping `buf` written at the current position.
+	fn patch(&mut self, buf: &[u8]) {
+		let pos = self.pos;
+		let end = pos + buf.len() as u64;
+		for (&start, bytes) in self.retained.range_mut(pos.saturating_sub(MAX_LEN)..end) {
+			let stop = start + bytes.len() as u64;
+			if stop > pos {
+				let (from, to) = (pos.max(start), end.min(stop));
+				bytes[offset(from - start)..offset(to - start)]
+					.copy_from_slice(&buf[offset(from - pos)..offset(to - pos)]);
+			}
+		}
+	}
+
+	/// Retains `buf` written at the current position if it continues or starts zip structures.
+	///
+	/// The data may contain signatures of zip structures as well, so structures starting within
+	/// the data are retained too, which costs memory but never hides structures following them.
+	fn retain(&mut self, buf: &[u8]) {
+		let pos = self.pos;
+		let end = pos + buf.len() as u64;
+		let mut pending = Vec::with_capacity(self.pending.len() + 1);
+		for (start, stop) in self.pending.drain(..) {
+			let bytes = self.retained.get_mut(&start).expect("pending structure");
+			if start + bytes.len() as u64 == pos {
+				bytes.extend_from_slice(&buf[..offset(stop - pos).min(buf.len())]);
+				pending.push((start, stop));
+			}
+		}
+		if let Some(len) = structure_len(buf) {
+			let len = offset(len);
+			let bytes = self.retained.entry(pos).or_default();
+			if bytes.len() < len.min(buf.len()) {
+				bytes.resize(len.min(buf.len()), 0);
+			}
+			let retained = bytes.len().min(buf.len());
+			bytes[..retained].copy_from_slice(&buf[..retained]);
+			pending.push((pos, pos + len as u64));
+		}
+		pending.retain(|&(_start, stop)| end < stop);
+		self.pending = pending;
+	}
+}
+
+/// Maximum length of a zip structure, i.e., of a central file header with name, extra field, and
+/// comment of maximum length.
+const MAX_LEN: u64 = 46 + 3 * 0xffff;
+
+/// Returns the length of the zip structure starting with `buf` or `None` if it starts none.
+fn structure_len(buf: &[u8]) -> Option<u64> {
+	let len_at = |pos: usize| u16_at(buf, pos).map(u64::from);
+	match buf.get(..4)? {
+		// Local file header.
+		b"PK\x03\x04" => Some(30 + len_at(26).unwrap_or(0) + len_at(28).unwrap_or(0)),
+		// Central file header.
+		b"PK\x01\x02" => {
+			Some(46 + len_at(28).unwrap_or(0) + len_at(30).unwrap_or(0) + len_at(32).unwrap_or(0))
+		}
+		// Zip64 end of central directory record.
+		b"PK\x06\x06" => Some(
+			12 + buf
+				.get(4..12)
+				.map_or(0, |b| u64::from_le_bytes(b.try_into().expect("8 bytes"))),
+		),
+		// Zip64 end of central directory locator.
+		b"PK\x06\x07" => Some(20),
+		// End of central directory record.
+		b"PK\x05\x06" => Some(22 + len_at(20).unwrap_or(0)),
+		_ => None,
+	}
+	.map(|len| len.min(MAX_LEN))
+}
+
+/// Converts the offset within a retained structure, which fits into memory.
+fn offset(offset: u64) -> usize {
+	usize::try_from(offset).expect("offset within memory")
+}
+
+impl Write for DryRunWriter {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.patch(buf);
+		self.retain(buf);
+		self.pos += buf.len() as u64;
+		self.len = self.len.max(self.pos);
+		Ok(buf.len())
+	}
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+impl Seek for DryRunWriter {
+	fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+		self.pos = seek(self.pos, self.len, pos)?;
+		Ok(self.pos)
+	}
+}
+
+/// Returns the position after seeking to `to` from `pos` within `len` bytes.
+fn seek(pos: u64, len: u64, to: SeekFrom) -> io::Result<u64> {
+	let (base, offset) = match to {
+		SeekFrom::Start(pos) => (pos, 0),
+		SeekFrom::Current(offset) => (pos, offset),
+		SeekFrom::End(offset) => (len, offset),
+	};
+	base.checked_add_signed(offset)
+		.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "seek before start"))
+}
+
+/// Reader of the retained bytes, reading zeros in place of the discarded ones.
+struct Retained<'a> {
+	writer: &'a DryRunWriter,
+	pos: u64,
+}
+
+impl Read for Retained<'_> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		let len = offset(self.writer.len.saturating_sub(self.pos)).min(buf.len());
+		let buf = &mut buf[..len];
+		buf.fill(0);
+		let end = self.pos + len as u64;
+		let retained = self
+			.writer
+			.retained
+			.range(self.pos.saturating_sub(MAX_LEN)..end);
+		for (&start, bytes) in retained {
+			let stop = start + bytes.len() as u64;
+			if stop > self.pos {
+				let (from, to) = (self.pos.max(start), end.min(stop));
+				buf[offset(from - self.pos)..offset(to - self.pos)]
+					.copy_from_slice(&bytes[offset(from - start)..offset(to - start)]);
+			}
+		}
+		self.pos = end;
+		Ok(len)
+	}
+}
+
+impl Seek for Retained<'_> {
+	fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+		self.pos = seek(self.pos, self.writer.len, pos)?;
+		Ok(self.pos)
+	}
+}
+
+impl NpzWriter<DryRunWriter> {
+	/// Finishes the archive like [`Self::finish`] and reports its exact layout without having
+	/// touched storage.
+	///
+	/// Writing to a [`DryRunWriter`] performs all encoding, compression, and bookkeeping, e.g.,
+	/// validating huge export jobs and predicting their storage needs before running them for
+	/// real.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use ndarray::Array2;
+	/// use ndarray_npz::{DryRunWriter, NpzWriter};
+	///
+	/// let mut npz = NpzWriter::new(DryRunWriter::new());
+	/// npz.add_array("x", &Array2::<f64>::zeros((1000, 1000)))?;
+	/// let report = npz.finish_dry_run()?;
+	/// assert!(report.size > 8_000_000);
+	/// assert_eq!(report.entries[0].name, "x");
+	/// assert_eq!(report.entries[0].size, 8_000_128);
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Fails like [`Self::finish`].
+	pub fn finish_dry_run(self) -> Result<DryRunReport, WriteNpzError> {
+		Ok(self.finish()?.report()?)
+	}
+}
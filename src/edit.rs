@@ -0,0 +1,647 @@
+use super::{
+	code::zip_code,
+	convert::{Conversion, CHUNK_LEN},
+	crc32_patch, crc32_replace,
+	header::NpyHeader,
+	ErrorCategory, ErrorCode, MutationLog,
+};
+use ndarray::arr0;
+use ndarray_npy::{WritableElement, WriteNpyExt};
+use std::{
+	collections::HashMap,
+	error::Error,
+	fmt,
+	io::{self, Read, Seek, SeekFrom, Write},
+};
+use zip::{result::ZipError, CompressionMethod, ZipArchive};
+
+/// An error editing a `.npz` file.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum EditNpzError {
+	/// An error caused by the zip archive.
+	Zip(ZipError),
+	/// Directories cannot be edited.
+	Directory,
+	/// Compressed files cannot be edited.
+	CompressedFile,
+	/// Encrypted files cannot be edited.
+	EncryptedFile,
+	/// The `.npy` header is invalid.
+	InvalidHeader,
+	/// The number of elements of the new shape differs.
+	ElementCountMismatch,
+	/// The new `.npy` header exceeds the space of the old one.
+	HeaderOverflow,
+	/// Arrays in Fortran order cannot be truncated along their first axis nor be bundled.
+	FortranOrder,
+	/// The element type has no fixed size.
+	UnsupportedDtype,
+	/// The new length exceeds the old one.
+	LengthOverflow,
+	/// The element types differ in size.
+	ItemSizeMismatch,
+	/// The element type differs.
+	DtypeMismatch,
+}
+
+impl Error for EditNpzError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			EditNpzError::Zip(err) => Some(err),
+			EditNpzError::Directory
+			| EditNpzError::CompressedFile
+			| EditNpzError::EncryptedFile
+			| EditNpzError::InvalidHeader
+			| EditNpzError::ElementCountMismatch
+			| EditNpzError::HeaderOverflow
+			| EditNpzError::FortranOrder
+			| EditNpzError::UnsupportedDtype
+			| EditNpzError::LengthOverflow
+			| EditNpzError::ItemSizeMismatch
+			| EditNpzError::DtypeMismatch => None,
+		}
+	}
+}
+
+impl fmt::Display for EditNpzError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			EditNpzError::Zip(err) => write!(f, "zip file error: {err}"),
+			EditNpzError::Directory => write!(f, "directories cannot be edited"),
+			EditNpzError::CompressedFile => write!(f, "compressed files cannot be edited"),
+			EditNpzError::EncryptedFile => write!(f, "encrypted files cannot be edited"),
+			EditNpzError::InvalidHeader => write!(f, "invalid npy header"),
+			EditNpzError::ElementCountMismatch => write!(f, "number of elements differs"),
+			EditNpzError::HeaderOverflow => write!(f, "npy header exceeds available space"),
+			EditNpzError::FortranOrder => write!(f, "fortran order cannot be truncated or bundled"),
+			EditNpzError::UnsupportedDtype => write!(f, "element type has no fixed size"),
+			EditNpzError::LengthOverflow => write!(f, "new length exceeds old length"),
+			EditNpzError::ItemSizeMismatch => write!(f, "element types differ in size"),
+			EditNpzError::DtypeMismatch => write!(f, "element type differs"),
+		}
+	}
+}
+
+impl From<ZipError> for EditNpzError {
+	fn from(err: ZipError) -> EditNpzError {
+		EditNpzError::Zip(err)
+	}
+}
+
+impl EditNpzError {
+	/// Returns the stable code of the error, see [`ErrorCode`].
+	#[must_use]
+	pub fn code(&self) -> ErrorCode {
+		match self {
+			EditNpzError::Zip(err) => zip_code(err),
+			EditNpzError::Directory => ErrorCode::Directory,
+			EditNpzError::CompressedFile => ErrorCode::CompressedFile,
+			EditNpzError::EncryptedFile => ErrorCode::EncryptedFile,
+			EditNpzError::InvalidHeader => ErrorCode::InvalidHeader,
+			EditNpzError::ElementCountMismatch => ErrorCode::ElementCountMismatch,
+			EditNpzError::HeaderOverflow => ErrorCode::HeaderOverflow,
+			EditNpzError::FortranOrder => ErrorCode::FortranOrder,
+			EditNpzError::UnsupportedDtype => ErrorCode::UnsupportedDtype,
+			EditNpzError::LengthOverflow => ErrorCode::LengthExceeded,
+			EditNpzError::ItemSizeMismatch => ErrorCode::ItemSizeMismatch,
+			EditNpzError::DtypeMismatch => ErrorCode::DtypeMismatch,
+		}
+	}
+
+	/// Returns the stable category of the error, see [`ErrorCategory`].
+	#[must_use]
+	pub fn category(&self) -> ErrorCategory {
+		self.code().category()
+	}
+}
+
+impl From<io::Error> for EditNpzError {
+	fn from(err: io::Error) -> EditNpzError {
+		EditNpzError::Zip(err.into())
+	}
+}
+
+/// Location of a file within the zip archive.
+#[derive(Debug, Clone)]
+pub(crate) struct Entry {
+	/// Offset of the local header.
+	pub header_start: u64,
+	/// Offset of the central header.
+	pub central_header_start: u64,
+	/// Offset of the data.
+	pub data_start: u64,
+	/// Whether the data is followed by a data descriptor with or without signature.
+	pub data_descriptor: Option<bool>,
+	/// Size of the uncompressed data.
+	pub size: u64,
+	/// CRC-32 checksum of the data.
+	pub crc32: u32,
+	/// Offsets of the local and central CRC-32 checksums.
+	pub crc32_starts: [u64; 2],
+	pub is_dir: bool,
+	pub encrypted: bool,
+	pub compression: CompressionMethod,
+}
+
+/// Editor of `.npz` files modifying `.npy` files in place.
+///
+/// Unlike [`NpzViewMut`](crate::NpzViewMut), it operates on files via [`Read`], [`Write`], and
+/// [`Seek`] instead of memory-mapped bytes and patches `.npy` headers and zip structures
+/// without rewriting the array data, which makes it suitable for fixing metadata of huge archives.
+///
+/// # Example
+///
+/// ```no_run
+/// use ndarray_npz::NpzEditor;
+/// use std::fs::OpenOptions;
+///
+/// let file = OpenOptions::new().read(true).write(true).open("arrays.npz")?;
+/// let mut npz = NpzEditor::new(file)?;
+/// // Fix `(N,)` to `(N, 1)`.
+/// npz.reshape_entry("a.npy", &[1000, 1])?;
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug)]
+pub struct NpzEditor<F: Read + Write + Seek> {
+	file: F,
+	entries: HashMap<String, Entry>,
+	log: Option<MutationLog>,
+}
+
+impl<F: Read + Write + Seek> NpzEditor<F> {
+	/// Creates a new editor of a `.npz` file.
+	///
+	/// # Errors
+	///
+	/// Reading the zip archive can fail with [`ZipError`].
+	pub fn new(mut file: F) -> Result<Self, EditNpzError> {
+		let mut zip = ZipArchive::new(&mut file)?;
+		let mut files = Vec::with_capacity(zip.len());
+		for index in 0..zip.len() {
+			let file = zip.by_index_raw(index)?;
+			files.push((
+				file.name().to_string(),
+				Entry {
+					header_start: file.header_start(),
+					central_header_start: file.central_header_start(),
+					data_start: file.data_start(),
+					data_descriptor: None,
+					size: file.size(),
+					crc32: file.crc32(),
+					crc32_starts: [0; 2],
+					is_dir: file.is_dir(),
+					encrypted: file.encrypted(),
+					compression: file.compression(),
+				},
+			));
+		}
+		drop(zip);
+		let mut entries = HashMap::with_capacity(files.len());
+		for (name, mut entry) in files {
+			// Parse central general purpose bit flag.
+			let mut flag = [0; 2];
+			file.seek(SeekFrom::Start(entry.central_header_start + 8))?;
+			file.read_exact(&mut flag)?;
+			// Whether local CRC-32 is located in header or data descriptor.
+			let crc32_start = if u16::from_le_bytes(flag) & (1 << 3) != 0 {
+				let data_end = entry.data_start + entry.size;
+				let mut crc32 = [0; 4];
+				file.seek(SeekFrom::Start(data_end))?;
+				file.read_exact(&mut crc32)?;
+				// Whether local CRC-32 equals optional data descriptor signature.
+				if u32::from_le_bytes(crc32) == 0x0807_4b50 {
+					if entry.crc32 == 0x0807_4b50 {
+						return Err(ZipError::InvalidArchive(
+							"Ambiguous CRC-32 location in data descriptor",
+						)
+						.into());
+					}
+					entry.data_descriptor = Some(true);
+					data_end + 4
+				} else {
+					entry.data_descriptor = Some(false);
+					data_end
+				}
+			} else {
+				entry.header_start + 14
+			};
+			entry.crc32_starts = [crc32_start, entry.central_header_start + 16];
+			entries.insert(name, entry);
+		}
+		Ok(Self {
+			file,
+			entries,
+			log: None,
+		})
+	}
+
+	/// Records every edit of a file into the mutation `log` with its new CRC-32 checksum.
+	#[must_use]
+	pub fn with_mutation_log(mut self, log: &MutationLog) -> Self {
+		self.log = Some(log.clone());
+		self
+	}
+
+	/// Returns the names of all of the files in the `.npz` file.
+	pub fn names(&self) -> impl Iterator<Item = &str> {
+		self.entries.keys().map(String::as_str)
+	}
+
+	/// Returns the inner file.
+	#[must_use]
+	pub fn into_inner(self) -> F {
+		self.file
+	}
+
+	/// Rewrites the shape in the `.npy` header of the file `name` and returns its new CRC-32
+	/// checksum.
+	///
+	/// Only the `.npy` header and the CRC-32 checksums are rewritten. The checksum is updated
+	/// without reading the array data. The new shape must have the same number of elements and the
+	/// new header must fit into the space of the old header including its padding.
+	///
+	/// # Errors
+	///
+	/// Fails with [`EditNpzError::ElementCountMismatch`] or [`EditNpzError::HeaderOverflow`] if
+	/// the requirements are not met, with [`EditNpzError::InvalidHeader`] if the `.npy` header
+	/// cannot be parsed, or with [`ZipError::FileNotFound`] if the `name` is not found. Trying to
+	/// edit a directory, compressed file, or encrypted file, fails with
+	/// [`EditNpzError::Directory`], [`EditNpzError::CompressedFile`], or
+	/// [`EditNpzError::EncryptedFile`].
+	pub fn reshape_entry(&mut self, name: &str, shape: &[usize]) -> Result<u32, EditNpzError> {
+		let entry = self.entry(name)?.clone();
+		let old_bytes = self.read_header(&entry)?;
+		let old_header = NpyHeader::parse(&old_bytes).ok_or(EditNpzError::InvalidHeader)?;
+		let mut new_header = old_header.clone();
+		new_header.shape = shape.iter().map(|&axis| axis as u64).collect();
+		if new_header.elements() != old_header.elements() {
+			return Err(EditNpzError::ElementCountMismatch);
+		}
+		let new_bytes = new_header
+			.to_bytes_with_len(old_header.len)
+			.ok_or(EditNpzError::HeaderOverflow)?;
+		self.write_header(name, &entry, &old_bytes, &new_bytes)
+	}
+
+	/// Truncates the first axis of the array of the file `name` to `len` and returns its new CRC-32
+	/// checksum.
+	///
+	/// Patches the `.npy` header, the sizes in the local and central header, and the CRC-32
+	/// checksums. Computing the checksum reads the retained array data but no data is moved. The
+	/// freed space remains as unused gap between files which is reclaimed by rewriting the
+	/// archive.
+	///
+	/// # Errors
+	///
+	/// Fails with [`EditNpzError::FortranOrder`] if the array is in Fortran order, with
+	/// [`EditNpzError::LengthOverflow`] if `len` exceeds the current length, or with
+	/// [`EditNpzError::UnsupportedDtype`] if the element type has no fixed size. Fails like
+	/// [`Self::reshape_entry`] otherwise.
+	pub fn truncate_entry(&mut self, name: &str, len: usize) -> Result<u32, EditNpzError> {
+		let entry = self.entry(name)?.clone();
+		let old_bytes = self.read_header(&entry)?;
+		let old_header = NpyHeader::parse(&old_bytes).ok_or(EditNpzError::InvalidHeader)?;
+		if old_header.fortran_order {
+			return Err(EditNpzError::FortranOrder);
+		}
+		let mut new_header = old_header.clone();
+		match new_header.shape.first_mut() {
+			Some(axis) if *axis >= len as u64 => *axis = len as u64,
+			Some(_) => return Err(EditNpzError::LengthOverflow),
+			None => return Err(EditNpzError::ElementCountMismatch),
+		}
+		let item_size = old_header
+			.item_size()
+			.ok_or(EditNpzError::UnsupportedDtype)?;
+		let data_size = new_header
+			.elements()
+			.and_then(|elements| elements.checked_mul(item_size))
+			.ok_or(EditNpzError::InvalidHeader)?;
+		let size = old_header.len as u64 + data_size;
+		if size > entry.size {
+			return Err(EditNpzError::InvalidHeader);
+		}
+		let new_bytes = new_header
+			.to_bytes_with_len(old_header.len)
+			.ok_or(EditNpzError::HeaderOverflow)?;
+		// Compute CRC-32 of retained data.
+		let mut hasher = crc32fast::Hasher::new();
+		hasher.update(&new_bytes);
+		self.file
+			.seek(SeekFrom::Start(entry.data_start + old_header.len as u64))?;
+		let mut remaining = data_size;
+		let mut buffer = vec![0; 1 << 16];
+		while remaining > 0 {
+			let chunk = usize::try_from(remaining.min(buffer.len() as u64)).unwrap_or(buffer.len());
+			self.file.read_exact(&mut buffer[..chunk])?;
+			hasher.update(&buffer[..chunk]);
+			remaining -= chunk as u64;
+		}
+		let crc32 = hasher.finalize();
+		self.file.seek(SeekFrom::Start(entry.data_start))?;
+		self.file.write_all(&new_bytes)?;
+		self.write_size(name, size)?;
+		self.write_crc32(name, crc32)?;
+		Ok(crc32)
+	}
+
+	/// Converts the element type of the array of the file `name` in place and returns its new CRC-32
+	/// checksum.
+	///
+	/// Both element types must be of the same size, e.g., `f32` and `i32`. A
+	/// [`Conversion::Reinterpret`] only rewrites the `.npy` header and updates the checksum without
+	/// reading the array data whereas a [`Conversion::Cast`] rewrites the array data as well. See
+	/// [`convert_entry_dtype`](crate::convert_entry_dtype) for converting between element types of
+	/// different sizes.
+	///
+	/// # Errors
+	///
+	/// Fails with [`EditNpzError::ItemSizeMismatch`] if the element types differ in size or with
+	/// [`EditNpzError::UnsupportedDtype`] if the element type is not a [`Dtype`](crate::Dtype).
+	/// Fails like [`Self::reshape_entry`] otherwise.
+	pub fn convert_entry_dtype(
+		&mut self,
+		name: &str,
+		conversion: Conversion,
+	) -> Result<u32, EditNpzError> {
+		let entry = self.entry(name)?.clone();
+		let old_bytes = self.read_header(&entry)?;
+		let (new_header, source, big_endian) = conversion.header(&old_bytes)?;
+		if source.size() != conversion.dtype().size() {
+			return Err(EditNpzError::ItemSizeMismatch);
+		}
+		let new_bytes = new_header
+			.to_bytes_with_len(old_bytes.len())
+			.ok_or(EditNpzError::HeaderOverflow)?;
+		if let Conversion::Reinterpret(_) = conversion {
+			return self.write_header(name, &entry, &old_bytes, &new_bytes);
+		}
+		let data_size = new_header
+			.elements()
+			.and_then(|elements| elements.checked_mul(source.size() as u64))
+			.ok_or(EditNpzError::InvalidHeader)?;
+		if old_bytes.len() as u64 + data_size > entry.size {
+			return Err(EditNpzError::InvalidHeader);
+		}
+		// Cast array data chunk by chunk and compute its CRC-32.
+		let mut hasher = crc32fast::Hasher::new();
+		hasher.update(&new_bytes);
+		let mut offset = entry.data_start + old_bytes.len() as u64;
+		let mut remaining = data_size;
+		let mut input = vec![0; CHUNK_LEN * source.size()];
+		let mut output = Vec::with_capacity(input.len());
+		while remaining > 0 {
+			let chunk = usize::try_from(remaining.min(input.len() as u64)).unwrap_or(input.len());
+			self.file.seek(SeekFrom::Start(offset))?;
+			self.file.read_exact(&mut input[..chunk])?;
+			output.clear();
+			conversion.convert(source, big_endian, &input[..chunk], &mut output);
+			hasher.update(&output);
+			self.file.seek(SeekFrom::Start(offset))?;
+			self.file.write_all(&output)?;
+			offset += chunk as u64;
+			remaining -= chunk as u64;
+		}
+		// Include trailing bytes beyond the array data if any.
+		let mut trailing = Vec::new();
+		(&mut self.file)
+			.take(entry.data_start + entry.size - offset)
+			.read_to_end(&mut trailing)?;
+		hasher.update(&trailing);
+		let crc32 = hasher.finalize();
+		self.file.seek(SeekFrom::Start(entry.data_start))?;
+		self.file.write_all(&new_bytes)?;
+		self.write_crc32(name, crc32)?;
+		Ok(crc32)
+	}
+
+	/// Overwrites the stored bytes of the file `name` at `offset` with `bytes` and returns its new
+	/// CRC-32 checksum.
+	///
+	/// The `offset` is relative to the start of the file, i.e., of its `.npy` header. Only the
+	/// overwritten bytes are read to update the checksum, the remaining ones are not.
+	///
+	/// # Errors
+	///
+	/// Fails with [`EditNpzError::LengthOverflow`] if `bytes` exceed the end of the file. Fails
+	/// like [`Self::reshape_entry`] otherwise.
+	pub fn patch_bytes(
+		&mut self,
+		name: &str,
+		offset: u64,
+		bytes: &[u8],
+	) -> Result<u32, EditNpzError> {
+		let entry = self.entry(name)?.clone();
+		let end = offset
+			.checked_add(bytes.len() as u64)
+			.filter(|&end| end <= entry.size)
+			.ok_or(EditNpzError::LengthOverflow)?;
+		let mut old_bytes = vec![0; bytes.len()];
+		self.file.seek(SeekFrom::Start(entry.data_start + offset))?;
+		self.file.read_exact(&mut old_bytes)?;
+		let crc32 = crc32_patch(entry.crc32, offset, &old_bytes, bytes, entry.size - end);
+		self.file.seek(SeekFrom::Start(entry.data_start + offset))?;
+		self.file.write_all(bytes)?;
+		self.write_crc32(name, crc32)?;
+		Ok(crc32)
+	}
+
+	/// Overwrites the single element of the array of the file `name` with `value` and returns its
+	/// new CRC-32 checksum.
+	///
+	/// The array can be of any shape with exactly one element, e.g., a scalar of zero dimensions.
+	///
+	/// # Errors
+	///
+	/// Fails with [`EditNpzError::ElementCountMismatch`] if the array has not exactly one element
+	/// or with [`EditNpzError::DtypeMismatch`] if the element type differs including its byte
+	/// order. Fails like [`Self::reshape_entry`] otherwise.
+	pub fn patch_scalar<A: WritableElement>(
+		&mut self,
+		name: &str,
+		value: A,
+	) -> Result<u32, EditNpzError> {
+		let entry = self.entry(name)?.clone();
+		let old_bytes = self.read_header(&entry)?;
+		let old_header = NpyHeader::parse(&old_bytes).ok_or(EditNpzError::InvalidHeader)?;
+		if old_header.elements() != Some(1) {
+			return Err(EditNpzError::ElementCountMismatch);
+		}
+		let mut bytes = Vec::new();
+		arr0(value)
+			.write_npy(&mut bytes)
+			.map_err(|_| EditNpzError::UnsupportedDtype)?;
+		let new_header = NpyHeader::parse(&bytes).ok_or(EditNpzError::InvalidHeader)?;
+		if new_header.descr != old_header.descr {
+			return Err(EditNpzError::DtypeMismatch);
+		}
+		self.patch_bytes(name, old_header.len as u64, &bytes[new_header.len..])
+	}
+
+	pub(crate) fn entry(&self, name: &str) -> Result<&Entry, EditNpzError> {
+		let entry = self.entries.get(name).ok_or(ZipError::FileNotFound)?;
+		if entry.is_dir {
+			Err(EditNpzError::Directory)
+		} else if entry.encrypted {
+			Err(EditNpzError::EncryptedFile)
+		} else if entry.compression != CompressionMethod::Stored {
+			Err(EditNpzError::CompressedFile)
+		} else {
+			Ok(entry)
+		}
+	}
+
+	/// Reads the whole `.npy` header of `entry`.
+	pub(crate) fn read_header(&mut self, entry: &Entry) -> Result<Vec<u8>, EditNpzError> {
+		let mut bytes = vec![0; 12.min(usize::try_from(entry.size).unwrap_or(usize::MAX))];
+		self.file.seek(SeekFrom::Start(entry.data_start))?;
+		self.file.read_exact(&mut bytes)?;
+		let len = NpyHeader::len(&bytes).ok_or(EditNpzError::InvalidHeader)?;
+		if len < 12 || len as u64 > entry.size {
+			return Err(EditNpzError::InvalidHeader);
+		}
+		bytes.resize(len, 0);
+		self.file.read_exact(&mut bytes[12..])?;
+		Ok(bytes)
+	}
+
+	/// Overwrites the `.npy` header of `entry` of same length and updates its CRC-32 checksum.
+	pub(crate) fn write_header(
+		&mut self,
+		name: &str,
+		entry: &Entry,
+		old_bytes: &[u8],
+		new_bytes: &[u8],
+	) -> Result<u32, EditNpzError> {
+		let suffix = entry.size - old_bytes.len() as u64;
+		let crc32 = crc32_replace(entry.crc32, old_bytes, new_bytes, suffix);
+		self.file.seek(SeekFrom::Start(entry.data_start))?;
+		self.file.write_all(new_bytes)?;
+		self.write_crc32(name, crc32)?;
+		Ok(crc32)
+	}
+
+	/// Writes the local and central sizes of the uncompressed file `name`.
+	///
+	/// Moves an existing data descriptor to the new end of data.
+	pub(crate) fn write_size(&mut self, name: &str, size: u64) -> Result<(), EditNpzError> {
+		let entry = self
+			.entries
+			.get(name)
+			.ok_or(ZipError::FileNotFound)?
+			.clone();
+		let local_zip64 = self.write_header_size(entry.header_start, 18, 26, 30, size, false)?;
+		self.write_header_size(entry.central_header_start, 20, 28, 46, size, true)?;
+		if let Some(signature) = entry.data_descriptor {
+			let mut descriptor = Vec::with_capacity(24);
+			if signature {
+				descriptor.extend(0x0807_4b50u32.to_le_bytes());
+			}
+			let crc32_start = entry.data_start + size + descriptor.len() as u64;
+			descriptor.extend(entry.crc32.to_le_bytes());
+			if local_zip64 {
+				descriptor.extend(size.to_le_bytes());
+				descriptor.extend(size.to_le_bytes());
+			} else {
+				let size =
+					u32::try_from(size).map_err(|_| ZipError::InvalidArchive("Size overflow"))?;
+				descriptor.extend(size.to_le_bytes());
+				descriptor.extend(size.to_le_bytes());
+			}
+			self.file.seek(SeekFrom::Start(entry.data_start + size))?;
+			self.file.write_all(&descriptor)?;
+			let entry = self.entries.get_mut(name).ok_or(ZipError::FileNotFound)?;
+			entry.crc32_starts[0] = crc32_start;
+		}
+		let entry = self.entries.get_mut(name).ok_or(ZipError::FileNotFound)?;
+		entry.size = size;
+		Ok(())
+	}
+
+	/// Writes the compressed and uncompressed size of a local or central header and returns
+	/// whether a Zip64 extra field is used.
+	///
+	/// Sizes of zero as in local headers followed by data descriptors are kept.
+	fn write_header_size(
+		&mut self,
+		header_start: u64,
+		size_offset: u64,
+		name_len_offset: u64,
+		fixed_len: u64,
+		size: u64,
+		central: bool,
+	) -> Result<bool, EditNpzError> {
+		let mut sizes = [0; 8];
+		self.file
+			.seek(SeekFrom::Start(header_start + size_offset))?;
+		self.file.read_exact(&mut sizes)?;
+		let compressed_size = u32::from_le_bytes(sizes[..4].try_into().unwrap());
+		let uncompressed_size = u32::from_le_bytes(sizes[4..].try_into().unwrap());
+		let mut lens = [0; 4];
+		self.file
+			.seek(SeekFrom::Start(header_start + name_len_offset))?;
+		self.file.read_exact(&mut lens)?;
+		let name_len = u64::from(u16::from_le_bytes([lens[0], lens[1]]));
+		let extra_len = usize::from(u16::from_le_bytes([lens[2], lens[3]]));
+		let mut extra = vec![0; extra_len];
+		let extra_start = header_start + fixed_len + name_len;
+		self.file.seek(SeekFrom::Start(extra_start))?;
+		self.file.read_exact(&mut extra)?;
+		// Find Zip64 extra field.
+		let mut offset = 0;
+		let mut zip64 = None;
+		while offset + 4 <= extra.len() {
+			let id = u16::from_le_bytes([extra[offset], extra[offset + 1]]);
+			let len = usize::from(u16::from_le_bytes([extra[offset + 2], extra[offset + 3]]));
+			if id == 0x0001 {
+				zip64 = Some((offset + 4, len));
+				break;
+			}
+			offset += 4 + len;
+		}
+		if let Some((start, len)) = zip64 {
+			// Central headers only contain fields set to their maximum.
+			let mut field = start;
+			for present in [
+				uncompressed_size == u32::MAX || !central,
+				compressed_size == u32::MAX || !central,
+			] {
+				if present && field + 8 <= start + len {
+					self.file
+						.seek(SeekFrom::Start(extra_start + field as u64))?;
+					if u64::from_le_bytes(extra[field..field + 8].try_into().unwrap()) != 0
+						|| central
+					{
+						self.file.write_all(&size.to_le_bytes())?;
+					}
+					field += 8;
+				}
+			}
+		}
+		if compressed_size != u32::MAX && (compressed_size != 0 || central) {
+			let size =
+				u32::try_from(size).map_err(|_| ZipError::InvalidArchive("Size overflow"))?;
+			self.file
+				.seek(SeekFrom::Start(header_start + size_offset))?;
+			self.file.write_all(&size.to_le_bytes())?;
+			self.file.write_all(&size.to_le_bytes())?;
+		}
+		Ok(zip64.is_some())
+	}
+
+	/// Writes the local and central CRC-32 checksum of the file `name`.
+	pub(crate) fn write_crc32(&mut self, name: &str, crc32: u32) -> Result<(), EditNpzError> {
+		let entry = self.entries.get_mut(name).ok_or(ZipError::FileNotFound)?;
+		for start in entry.crc32_starts {
+			self.file.seek(SeekFrom::Start(start))?;
+			self.file.write_all(&crc32.to_le_bytes())?;
+		}
+		entry.crc32 = crc32;
+		self.file.flush()?;
+		if let Some(log) = &self.log {
+			log.record(name, crc32);
+		}
+		Ok(())
+	}
+}
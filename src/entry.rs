@@ -0,0 +1,114 @@
+use super::{NpzReader, NpzWriter, ReadNpzError, WriteNpzError};
+use std::io::{self, BufReader, BufWriter, Read, Seek, Write};
+use zip::result::ZipError;
+
+/// Custom encoder of a file, see [`NpzWriter::add_entry`].
+///
+/// Implementors only encode the content of the file, e.g., of exotic element types like bitfields,
+/// whereas the zip framing, the alignment, the compression, and the checksum are handled by the
+/// writer.
+pub trait EntrySink {
+	/// Encodes the content of the file into the buffered `writer`.
+	///
+	/// # Errors
+	///
+	/// Fails if encoding fails or with the errors of `writer`.
+	fn write_entry<W: Write>(&self, writer: W) -> io::Result<()>;
+}
+
+/// Custom decoder of a file, see [`NpzReader::read_entry`].
+///
+/// Implementors only decode the content of the file, whereas the zip framing, the decompression,
+/// and the checksum are handled by the reader.
+pub trait EntrySource: Sized {
+	/// Decodes the content of the file from the buffered `reader`.
+	///
+	/// The content need not be read to its end.
+	///
+	/// # Errors
+	///
+	/// Fails with [`io::ErrorKind::InvalidData`] if the content is malformed or with the errors of
+	/// `reader`.
+	fn read_entry<R: Read>(reader: R) -> io::Result<Self>;
+}
+
+impl<W: Write + Seek> NpzWriter<W> {
+	/// Adds a file with the specified `name` encoded by the custom `entry`.
+	///
+	/// The file is aligned and compressed like arrays added via [`Self::add_array`]. Unlike for
+	/// arrays, `.npy` is never appended to the `name`.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use ndarray_npz::{EntrySink, EntrySource, NpzReader, NpzWriter};
+	/// use std::io::{self, Cursor, Read, Write};
+	///
+	/// /// Bitfield packing eight flags per byte.
+	/// #[derive(Debug, PartialEq)]
+	/// struct Bits(Vec<bool>);
+	///
+	/// impl EntrySink for Bits {
+	/// 	fn write_entry<W: Write>(&self, mut writer: W) -> io::Result<()> {
+	/// 		writer.write_all(&(self.0.len() as u64).to_le_bytes())?;
+	/// 		for bits in self.0.chunks(8) {
+	/// 			let byte = bits.iter().rev().fold(0, |byte, &bit| byte << 1 | u8::from(bit));
+	/// 			writer.write_all(&[byte])?;
+	/// 		}
+	/// 		Ok(())
+	/// 	}
+	/// }
+	///
+	/// impl EntrySource for Bits {
+	/// 	fn read_entry<R: Read>(mut reader: R) -> io::Result<Self> {
+	/// 		let mut len = [0; 8];
+	/// 		reader.read_exact(&mut len)?;
+	/// 		let len = u64::from_le_bytes(len) as usize;
+	/// 		let mut bytes = vec![0; len.div_ceil(8)];
+	/// 		reader.read_exact(&mut bytes)?;
+	/// 		Ok(Bits((0..len).map(|bit| bytes[bit / 8] >> (bit % 8) & 1 == 1).collect()))
+	/// 	}
+	/// }
+	///
+	/// let bits = Bits(vec![true, false, true, true, false, false, false, true, true]);
+	/// let mut buffer = Vec::new();
+	/// let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+	/// npz.add_entry("flags.bits", &bits)?;
+	/// npz.finish()?;
+	/// let mut npz = NpzReader::new(Cursor::new(&buffer))?;
+	/// assert_eq!(npz.read_entry::<Bits>("flags.bits")?, bits);
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Adding a file can fail with [`ZipError`], including the errors of `entry`.
+	pub fn add_entry<N, E>(&mut self, name: N, entry: &E) -> Result<(), WriteNpzError>
+	where
+		N: Into<String>,
+		E: EntrySink + ?Sized,
+	{
+		self.zip.start_file(name.into(), self.options)?;
+		let mut writer = BufWriter::with_capacity(self.buffer_size, &mut self.zip);
+		entry.write_entry(&mut writer).map_err(ZipError::from)?;
+		writer.flush().map_err(ZipError::from)?;
+		Ok(())
+	}
+}
+
+impl<R: Read + Seek> NpzReader<R> {
+	/// Reads a file by name decoded by the custom [`EntrySource`], see [`NpzWriter::add_entry`].
+	///
+	/// The remaining content not read by the decoder is skipped, so the checksum of the file is
+	/// verified in any case.
+	///
+	/// # Errors
+	///
+	/// Reading a file can fail with [`ZipError`], including the errors of the decoder.
+	pub fn read_entry<E: EntrySource>(&mut self, name: &str) -> Result<E, ReadNpzError> {
+		let mut reader = BufReader::new(self.zip.by_name(name)?);
+		let entry = E::read_entry(&mut reader).map_err(ZipError::from)?;
+		io::copy(&mut reader, &mut io::sink()).map_err(ZipError::from)?;
+		Ok(entry)
+	}
+}
@@ -0,0 +1,460 @@
+//! Synthetic example `.npz` files for testing edge cases.
+//!
+//! Generates archives with chosen zip and `.npy` features, so downstream crates can test their
+//! handling without shipping binary fixtures. The archives are written byte by byte instead of via
+//! the `zip` crate as the latter cannot produce every feature, e.g., data descriptors or
+//! deliberately misaligned entries.
+//!
+//! # Example
+//!
+//! ```
+//! use ndarray_npz::{
+//! 	example::{generate_example_npz, ExampleSpec},
+//! 	NpzReader,
+//! };
+//! use std::io::Cursor;
+//!
+//! let spec = ExampleSpec::default()
+//! 	.with_data_descriptor(true)
+//! 	.with_zip64(true);
+//! let mut npz = NpzReader::new(Cursor::new(generate_example_npz(&spec)))?;
+//! assert_eq!(npz.len(), 11);
+//! # Ok::<(), ndarray_npz::ReadNpzError>(())
+//! ```
+//!
+//! The binary fixtures of this crate's test suite are generated via [`generate_fixture`] or
+//! written into a directory at once via [`write_fixtures`].
+
+use std::{fs, io, path::Path};
+
+/// Specification of a synthetic example `.npz` file.
+///
+/// The archive comprises uncompressed `.npy` files named `b8.npy`, `i8.npy`, `u8.npy`,
+/// `i16.npy`, `u16.npy`, `i32.npy`, `u32.npy`, `i64.npy`, `u64.npy`, `f32.npy`, and `f64.npy`
+/// containing `arange(10)` of the respective element type, whereas `b8.npy` alternates between
+/// `true` and `false`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct ExampleSpec {
+	data_descriptor: bool,
+	zip64: bool,
+	fortran_order: bool,
+	big_endian: bool,
+	alignment: u16,
+	misaligned: bool,
+}
+
+impl Default for ExampleSpec {
+	/// Little-endian one-dimensional arrays 64-byte aligned without data descriptors and Zip64.
+	fn default() -> Self {
+		Self {
+			data_descriptor: false,
+			zip64: false,
+			fortran_order: false,
+			big_endian: false,
+			alignment: 64,
+			misaligned: false,
+		}
+	}
+}
+
+impl ExampleSpec {
+	/// Sets whether CRC-32 checksums and sizes are stored in data descriptors following the data
+	/// instead of the local headers.
+	#[must_use]
+	pub fn with_data_descriptor(mut self, data_descriptor: bool) -> Self {
+		self.data_descriptor = data_descriptor;
+		self
+	}
+	/// Sets whether to use Zip64 extra fields and end of central directory records.
+	#[must_use]
+	pub fn with_zip64(mut self, zip64: bool) -> Self {
+		self.zip64 = zip64;
+		self
+	}
+	/// Sets whether to store the arrays with shape `(2, 5)` in Fortran order instead of shape
+	/// `(10,)`.
+	#[must_use]
+	pub fn with_fortran_order(mut self, fortran_order: bool) -> Self {
+		self.fortran_order = fortran_order;
+		self
+	}
+	/// Sets whether to store multi-byte elements in big endian instead of little endian.
+	#[must_use]
+	pub fn with_big_endian(mut self, big_endian: bool) -> Self {
+		self.big_endian = big_endian;
+		self
+	}
+	/// Sets the alignment in bytes of the array data. Zero or one disables alignment.
+	#[must_use]
+	pub fn with_alignment(mut self, alignment: u16) -> Self {
+		self.alignment = alignment;
+		self
+	}
+	/// Sets whether to deliberately misalign the array data by one byte relative to the alignment.
+	#[must_use]
+	pub fn with_misaligned(mut self, misaligned: bool) -> Self {
+		self.misaligned = misaligned;
+		self
+	}
+}
+
+/// Generates a synthetic example `.npz` file according to `spec`.
+#[must_use]
+pub fn generate_example_npz(spec: &ExampleSpec) -> Vec<u8> {
+	generate(*spec, ELEMENTS.map(|element| (element, 10)))
+}
+
+/// Binary fixture of this crate's test suite, see [`generate_fixture`].
+///
+/// The fixtures were written once by NumPy and zip tools, whereas generating equivalent archives
+/// of the same files, `.npy` bytes, and zip features spares downstream crates from copying them.
+/// Only metadata irrelevant to reading them differs, e.g., modification times and extra fields,
+/// and so may the offsets of unaligned files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Fixture {
+	/// `examples_64_byte_aligned.npz` of `b8.npy` containing `[true, false]`, and `i8.npy` and
+	/// `u8.npy` containing `arange(10)`, 64-byte aligned.
+	Aligned,
+	/// `examples_little_endian_64_byte_aligned.npz` of `i16.npy`, `u16.npy`, `i32.npy`,
+	/// `u32.npy`, `i64.npy`, `u64.npy`, `f32.npy`, and `f64.npy` containing `arange(10)` in little
+	/// endian, 64-byte aligned.
+	LittleEndianAligned,
+	/// `examples_big_endian_64_byte_aligned.npz` like [`Self::LittleEndianAligned`] but in big
+	/// endian.
+	BigEndianAligned,
+	/// `examples_data_descriptor.npz` of `b8.npy`, `i8.npy`, and `u8.npy` each containing
+	/// `[true, false]` with data descriptors, unaligned.
+	DataDescriptor,
+}
+
+impl Fixture {
+	/// All fixtures.
+	pub const ALL: [Self; 4] = [
+		Self::Aligned,
+		Self::LittleEndianAligned,
+		Self::BigEndianAligned,
+		Self::DataDescriptor,
+	];
+
+	/// Returns the file name, e.g., `examples_64_byte_aligned.npz`.
+	#[must_use]
+	pub fn file_name(self) -> &'static str {
+		match self {
+			Self::Aligned => "examples_64_byte_aligned.npz",
+			Self::LittleEndianAligned => "examples_little_endian_64_byte_aligned.npz",
+			Self::BigEndianAligned => "examples_big_endian_64_byte_aligned.npz",
+			Self::DataDescriptor => "examples_data_descriptor.npz",
+		}
+	}
+}
+
+/// Generates the `fixture`.
+///
+/// # Example
+///
+/// ```
+/// use ndarray::{array, Array1};
+/// use ndarray_npz::{
+/// 	example::{generate_fixture, Fixture},
+/// 	NpzReader,
+/// };
+/// use std::io::Cursor;
+///
+/// let mut npz = NpzReader::new(Cursor::new(generate_fixture(Fixture::Aligned)))?;
+/// let b8: Array1<bool> = npz.by_name("b8.npy")?;
+/// assert_eq!(b8, array![true, false]);
+/// # Ok::<(), ndarray_npz::ReadNpzError>(())
+/// ```
+#[must_use]
+pub fn generate_fixture(fixture: Fixture) -> Vec<u8> {
+	let spec = ExampleSpec::default();
+	let [b8, i8, u8, multi_byte @ ..] = ELEMENTS;
+	match fixture {
+		Fixture::Aligned => generate(spec, [(b8, 2), (i8, 10), (u8, 10)]),
+		Fixture::LittleEndianAligned => generate(spec, multi_byte.map(|element| (element, 10))),
+		Fixture::BigEndianAligned => generate(
+			spec.with_big_endian(true),
+			multi_byte.map(|element| (element, 10)),
+		),
+		Fixture::DataDescriptor => {
+			let spec = spec.with_data_descriptor(true).with_alignment(0);
+			let npy = example_npy(spec, b8.1, b8.2, 2);
+			let mut zip = RawZip::new(spec);
+			for (name, _descr, _size) in [b8, i8, u8] {
+				zip.add(name, &npy);
+			}
+			zip.finish()
+		}
+	}
+}
+
+/// Writes all fixtures into `dir` named after [`Fixture::file_name`], replacing existing files.
+///
+/// # Errors
+///
+/// Fails with [`io::Error`] if writing a file fails.
+pub fn write_fixtures(dir: impl AsRef<Path>) -> io::Result<()> {
+	Fixture::ALL.into_iter().try_for_each(|fixture| {
+		fs::write(
+			dir.as_ref().join(fixture.file_name()),
+			generate_fixture(fixture),
+		)
+	})
+}
+
+/// Generates an archive of `.npy` files of the given elements and lengths.
+fn generate(spec: ExampleSpec, elements: impl IntoIterator<Item = (Element, u8)>) -> Vec<u8> {
+	let mut zip = RawZip::new(spec);
+	for ((name, descr, size), len) in elements {
+		zip.add(name, &example_npy(spec, descr, size, len));
+	}
+	zip.finish()
+}
+
+/// Name, type descriptor, and size of an element type.
+type Element = (&'static str, &'static str, usize);
+
+const ELEMENTS: [Element; 11] = [
+	("b8.npy", "|b1", 1),
+	("i8.npy", "|i1", 1),
+	("u8.npy", "|u1", 1),
+	("i16.npy", "i2", 2),
+	("u16.npy", "u2", 2),
+	("i32.npy", "i4", 4),
+	("u32.npy", "u4", 4),
+	("i64.npy", "i8", 8),
+	("u64.npy", "u8", 8),
+	("f32.npy", "f4", 4),
+	("f64.npy", "f8", 8),
+];
+
+/// Encodes `arange(len)` as `.npy` file, alternating between `true` and `false` for booleans.
+fn example_npy(spec: ExampleSpec, descr: &str, size: usize, len: u8) -> Vec<u8> {
+	let descr = if descr.starts_with('|') {
+		descr.to_string()
+	} else if spec.big_endian {
+		format!(">{descr}")
+	} else {
+		format!("<{descr}")
+	};
+	let (fortran_order, shape) = if spec.fortran_order {
+		("True", format!("(2, {})", len / 2))
+	} else {
+		("False", format!("({len},)"))
+	};
+	let mut npy = npy_header(&descr, fortran_order, &shape);
+	for index in 0..len {
+		// Column-major order of `arange(len).reshape(2, len / 2)`.
+		let value = if spec.fortran_order {
+			index / 2 + index % 2 * (len / 2)
+		} else {
+			index
+		};
+		let bytes: Vec<u8> = match &descr[1..] {
+			"b1" => vec![u8::from(value % 2 == 0)],
+			"f4" => f32::from(value).to_le_bytes().to_vec(),
+			"f8" => f64::from(value).to_le_bytes().to_vec(),
+			_ => u64::from(value).to_le_bytes()[..size].to_vec(),
+		};
+		if spec.big_endian {
+			npy.extend(bytes.iter().rev());
+		} else {
+			npy.extend(bytes);
+		}
+	}
+	npy
+}
+
+/// Encodes an `.npy` format version 1.0 header padded to a multiple of 64 bytes.
+fn npy_header(descr: &str, fortran_order: &str, shape: &str) -> Vec<u8> {
+	let dict =
+		format!("{{'descr': '{descr}', 'fortran_order': {fortran_order}, 'shape': {shape}, }}");
+	// Magic string, version, header length, dictionary, and newline.
+	let len = (10 + dict.len() + 1).next_multiple_of(64);
+	let mut header = Vec::with_capacity(len);
+	header.extend(b"\x93NUMPY\x01\x00");
+	header.extend(u16::try_from(len - 10).unwrap().to_le_bytes());
+	header.extend(dict.as_bytes());
+	header.resize(len - 1, b' ');
+	header.push(b'\n');
+	header
+}
+
+/// Minimal zip writer of uncompressed files.
+struct RawZip {
+	spec: ExampleSpec,
+	bytes: Vec<u8>,
+	central: Vec<u8>,
+	entries: u64,
+}
+
+impl RawZip {
+	fn new(spec: ExampleSpec) -> Self {
+		Self {
+			spec,
+			bytes: Vec::new(),
+			central: Vec::new(),
+			entries: 0,
+		}
+	}
+
+	fn add(&mut self, name: &str, data: &[u8]) {
+		let offset = self.bytes.len() as u64;
+		let size = data.len() as u64;
+		let crc32 = {
+			let mut hasher = crc32fast::Hasher::new();
+			hasher.update(data);
+			hasher.finalize()
+		};
+		let version: u16 = if self.spec.zip64 { 45 } else { 20 };
+		let flags: u16 = if self.spec.data_descriptor { 1 << 3 } else { 0 };
+		let (local_crc32, local_size) = if self.spec.data_descriptor {
+			(0, 0)
+		} else {
+			(crc32, size)
+		};
+		// Local extra fields.
+		let mut extra = Vec::new();
+		if self.spec.zip64 {
+			push_u16(&mut extra, 0x0001);
+			push_u16(&mut extra, 16);
+			push_u64(&mut extra, local_size);
+			push_u64(&mut extra, local_size);
+		}
+		// Alignment padding extra field.
+		let alignment = u64::from(self.spec.alignment.max(1));
+		let target = u64::from(self.spec.misaligned);
+		let start = offset + 30 + name.len() as u64 + extra.len() as u64;
+		let mut padding = (target + alignment - start % alignment) % alignment;
+		if padding > 0 && padding < 4 {
+			padding += alignment * 4u64.div_ceil(alignment);
+		}
+		if padding > 0 {
+			push_u16(&mut extra, 0xa11e);
+			push_u16(&mut extra, u16::try_from(padding - 4).unwrap());
+			extra.resize(extra.len() + usize::try_from(padding).unwrap() - 4, 0);
+		}
+		// Local header.
+		push_u32(&mut self.bytes, 0x0403_4b50);
+		push_u16(&mut self.bytes, version);
+		push_u16(&mut self.bytes, flags);
+		push_u16(&mut self.bytes, 0);
+		push_u16(&mut self.bytes, 0);
+		push_u16(&mut self.bytes, 0x21);
+		push_u32(&mut self.bytes, local_crc32);
+		push_size(&mut self.bytes, local_size, self.spec.zip64);
+		push_size(&mut self.bytes, local_size, self.spec.zip64);
+		push_u16(&mut self.bytes, u16::try_from(name.len()).unwrap());
+		push_u16(&mut self.bytes, u16::try_from(extra.len()).unwrap());
+		self.bytes.extend(name.as_bytes());
+		self.bytes.extend(&extra);
+		self.bytes.extend(data);
+		// Data descriptor.
+		if self.spec.data_descriptor {
+			push_u32(&mut self.bytes, 0x0807_4b50);
+			push_u32(&mut self.bytes, crc32);
+			if self.spec.zip64 {
+				push_u64(&mut self.bytes, size);
+				push_u64(&mut self.bytes, size);
+			} else {
+				push_u32(&mut self.bytes, u32::try_from(size).unwrap());
+				push_u32(&mut self.bytes, u32::try_from(size).unwrap());
+			}
+		}
+		// Central extra fields.
+		let mut extra = Vec::new();
+		if self.spec.zip64 {
+			push_u16(&mut extra, 0x0001);
+			push_u16(&mut extra, 24);
+			push_u64(&mut extra, size);
+			push_u64(&mut extra, size);
+			push_u64(&mut extra, offset);
+		}
+		// Central header.
+		push_u32(&mut self.central, 0x0201_4b50);
+		push_u16(&mut self.central, 0x0300 | version);
+		push_u16(&mut self.central, version);
+		push_u16(&mut self.central, flags);
+		push_u16(&mut self.central, 0);
+		push_u16(&mut self.central, 0);
+		push_u16(&mut self.central, 0x21);
+		push_u32(&mut self.central, crc32);
+		push_size(&mut self.central, size, self.spec.zip64);
+		push_size(&mut self.central, size, self.spec.zip64);
+		push_u16(&mut self.central, u16::try_from(name.len()).unwrap());
+		push_u16(&mut self.central, u16::try_from(extra.len()).unwrap());
+		push_u16(&mut self.central, 0);
+		push_u16(&mut self.central, 0);
+		push_u16(&mut self.central, 0);
+		push_u32(&mut self.central, 0o100_644 << 16);
+		push_size(&mut self.central, offset, self.spec.zip64);
+		self.central.extend(name.as_bytes());
+		self.central.extend(&extra);
+		self.entries += 1;
+	}
+
+	fn finish(mut self) -> Vec<u8> {
+		let offset = self.bytes.len() as u64;
+		let size = self.central.len() as u64;
+		self.bytes.append(&mut self.central);
+		if self.spec.zip64 {
+			let end = self.bytes.len() as u64;
+			// Zip64 end of central directory record.
+			push_u32(&mut self.bytes, 0x0606_4b50);
+			push_u64(&mut self.bytes, 44);
+			push_u16(&mut self.bytes, 0x032d);
+			push_u16(&mut self.bytes, 45);
+			push_u32(&mut self.bytes, 0);
+			push_u32(&mut self.bytes, 0);
+			push_u64(&mut self.bytes, self.entries);
+			push_u64(&mut self.bytes, self.entries);
+			push_u64(&mut self.bytes, size);
+			push_u64(&mut self.bytes, offset);
+			// Zip64 end of central directory locator.
+			push_u32(&mut self.bytes, 0x0706_4b50);
+			push_u32(&mut self.bytes, 0);
+			push_u64(&mut self.bytes, end);
+			push_u32(&mut self.bytes, 1);
+		}
+		// End of central directory record.
+		let entries = if self.spec.zip64 {
+			u16::MAX
+		} else {
+			u16::try_from(self.entries).unwrap()
+		};
+		push_u32(&mut self.bytes, 0x0605_4b50);
+		push_u16(&mut self.bytes, 0);
+		push_u16(&mut self.bytes, 0);
+		push_u16(&mut self.bytes, entries);
+		push_u16(&mut self.bytes, entries);
+		push_size(&mut self.bytes, size, self.spec.zip64);
+		push_size(&mut self.bytes, offset, self.spec.zip64);
+		push_u16(&mut self.bytes, 0);
+		self.bytes
+	}
+}
+
+fn push_u16(bytes: &mut Vec<u8>, value: u16) {
+	bytes.extend(value.to_le_bytes());
+}
+
+fn push_u32(bytes: &mut Vec<u8>, value: u32) {
+	bytes.extend(value.to_le_bytes());
+}
+
+fn push_u64(bytes: &mut Vec<u8>, value: u64) {
+	bytes.extend(value.to_le_bytes());
+}
+
+/// Pushes a 32-bit size or its Zip64 placeholder.
+fn push_size(bytes: &mut Vec<u8>, value: u64, zip64: bool) {
+	push_u32(
+		bytes,
+		if zip64 {
+			u32::MAX
+		} else {
+			u32::try_from(value).unwrap()
+		},
+	);
+}
@@ -0,0 +1,516 @@
+//! C foreign function interface for reading and writing `.npz` files.
+//!
+//! Enables C, C++, and Fortran codes to exchange `.npz` files without embedding Python. Build a
+//! shared library via `cargo rustc --release --features ffi --crate-type cdylib` and generate its
+//! C header via `cbindgen --lang c`.
+//!
+//! Functions return [`NPZ_OK`] on success or the value of the [`ErrorCode`] of the failure, e.g.,
+//! `400` if a file is not found. Null pointers, names of invalid UTF-8, and unknown element types
+//! fail with [`ErrorCode::InvalidArgument`]. Arrays are exchanged as contiguous buffers of native
+//! endianness in row-major (C) or column-major (Fortran) order of any supported element type, see
+//! [`NPZ_DTYPE_BOOL`] and following constants. Panics are caught and fail with
+//! [`ErrorCode::Other`].
+//!
+//! # Example
+//!
+//! ```c
+//! NpzFileWriter *writer;
+//! const size_t shape[2] = {2, 3};
+//! const double data[6] = {1, 2, 3, 4, 5, 6};
+//! npz_writer_create("arrays.npz", false, &writer);
+//! npz_writer_add(writer, "x.npy", NPZ_DTYPE_F64, false, 2, shape, data);
+//! npz_writer_finish(writer);
+//!
+//! NpzFileReader *reader;
+//! NpzArrayInfo info;
+//! double x[6];
+//! npz_reader_open("arrays.npz", &reader);
+//! npz_reader_describe(reader, "x.npy", &info);
+//! npz_reader_read(reader, "x.npy", NPZ_DTYPE_F64, false, x, info.len);
+//! npz_reader_free(reader);
+//! ```
+
+use super::{
+	code::zip_code, convert::Dtype, header::NpyHeader, BufSeekWriter, ErrorCode, NpzReader,
+	NpzWriter,
+};
+use ndarray::{ArrayD, ArrayViewD, IxDyn, ShapeBuilder};
+use ndarray_npy::{ReadableElement, WritableElement};
+use std::{
+	ffi::{c_char, c_int, c_void, CStr, CString},
+	fs::File,
+	io::{self, BufReader},
+	mem,
+	panic::{catch_unwind, AssertUnwindSafe},
+	slice,
+};
+
+/// Status of success.
+pub const NPZ_OK: c_int = 0;
+
+/// Maximum number of dimensions of [`NpzArrayInfo::shape`].
+pub const NPZ_MAX_NDIM: usize = 32;
+
+/// Element type `bool` of `|b1`.
+pub const NPZ_DTYPE_BOOL: u32 = 0;
+/// Element type `int8_t` of `|i1`.
+pub const NPZ_DTYPE_I8: u32 = 1;
+/// Element type `uint8_t` of `|u1`.
+pub const NPZ_DTYPE_U8: u32 = 2;
+/// Element type `int16_t` of `i2`.
+pub const NPZ_DTYPE_I16: u32 = 3;
+/// Element type `uint16_t` of `u2`.
+pub const NPZ_DTYPE_U16: u32 = 4;
+/// Element type `int32_t` of `i4`.
+pub const NPZ_DTYPE_I32: u32 = 5;
+/// Element type `uint32_t` of `u4`.
+pub const NPZ_DTYPE_U32: u32 = 6;
+/// Element type `int64_t` of `i8`.
+pub const NPZ_DTYPE_I64: u32 = 7;
+/// Element type `uint64_t` of `u8`.
+pub const NPZ_DTYPE_U64: u32 = 8;
+/// Element type `float` of `f4`.
+pub const NPZ_DTYPE_F32: u32 = 9;
+/// Element type `double` of `f8`.
+pub const NPZ_DTYPE_F64: u32 = 10;
+
+/// Element types in the order of their constants.
+const DTYPES: [Dtype; 11] = [
+	Dtype::Bool,
+	Dtype::I8,
+	Dtype::U8,
+	Dtype::I16,
+	Dtype::U16,
+	Dtype::I32,
+	Dtype::U32,
+	Dtype::I64,
+	Dtype::U64,
+	Dtype::F32,
+	Dtype::F64,
+];
+
+/// Opaque reader of a `.npz` file, see [`npz_reader_open`].
+pub struct NpzFileReader {
+	npz: NpzReader<BufReader<File>>,
+	names: Vec<CString>,
+}
+
+/// Opaque writer of a `.npz` file, see [`npz_writer_create`].
+pub struct NpzFileWriter {
+	npz: NpzWriter<BufSeekWriter<File>>,
+}
+
+/// Description of an array, see [`npz_reader_describe`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NpzArrayInfo {
+	/// Element type, see [`NPZ_DTYPE_BOOL`] and following constants.
+	pub dtype: u32,
+	/// Number of dimensions.
+	pub ndim: usize,
+	/// Length of each dimension, of which the first `ndim` ones are valid.
+	pub shape: [usize; NPZ_MAX_NDIM],
+	/// Number of elements.
+	pub len: usize,
+	/// Whether the data is stored in column-major order.
+	pub fortran_order: bool,
+}
+
+/// Runs `f` and returns its status.
+fn status(f: impl FnOnce() -> Result<(), ErrorCode>) -> c_int {
+	match catch_unwind(AssertUnwindSafe(f)) {
+		Ok(Ok(())) => NPZ_OK,
+		Ok(Err(code)) => c_int::from(code.value()),
+		Err(_) => c_int::from(ErrorCode::Other.value()),
+	}
+}
+
+/// Returns the element type of its constant.
+fn parse_dtype(dtype: u32) -> Result<Dtype, ErrorCode> {
+	usize::try_from(dtype)
+		.ok()
+		.and_then(|dtype| DTYPES.get(dtype).copied())
+		.ok_or(ErrorCode::InvalidArgument)
+}
+
+/// Returns the code of an I/O error.
+#[allow(clippy::needless_pass_by_value)]
+fn io_code(err: io::Error) -> ErrorCode {
+	zip_code(&err.into())
+}
+
+/// Returns the UTF-8 string `string`.
+unsafe fn str_arg<'a>(string: *const c_char) -> Result<&'a str, ErrorCode> {
+	if string.is_null() {
+		return Err(ErrorCode::InvalidArgument);
+	}
+	CStr::from_ptr(string)
+		.to_str()
+		.map_err(|_| ErrorCode::InvalidArgument)
+}
+
+/// Checks whether `data` points to `len` aligned elements of `T` within the address space.
+fn check_slice<T>(data: *const T, len: usize) -> Result<(), ErrorCode> {
+	if len
+		.checked_mul(mem::size_of::<T>())
+		.map_or(true, |size| isize::try_from(size).is_err())
+	{
+		return Err(ErrorCode::LengthOverflow);
+	}
+	if data.is_null() || data as usize % mem::align_of::<T>() != 0 {
+		return Err(ErrorCode::InvalidArgument);
+	}
+	Ok(())
+}
+
+/// Reads the array `name` of element type `T` into `data` of `len` elements.
+unsafe fn read<T: ReadableElement + Copy>(
+	npz: &mut NpzReader<BufReader<File>>,
+	name: &str,
+	fortran_order: bool,
+	data: *mut c_void,
+	len: usize,
+) -> Result<(), ErrorCode> {
+	let array: ArrayD<T> = npz.by_name(name).map_err(|err| err.code())?;
+	if array.len() != len {
+		return Err(ErrorCode::ElementCountMismatch);
+	}
+	if len == 0 {
+		return Ok(());
+	}
+	let data = data.cast::<T>();
+	check_slice(data, len)?;
+	let data = slice::from_raw_parts_mut(data, len);
+	let array = if fortran_order {
+		array.reversed_axes()
+	} else {
+		array
+	};
+	for (data, &value) in data.iter_mut().zip(&array) {
+		*data = value;
+	}
+	Ok(())
+}
+
+/// Adds the array `name` of element type `T` and `shape` from `data`.
+unsafe fn write<T: WritableElement>(
+	npz: &mut NpzWriter<BufSeekWriter<File>>,
+	name: &str,
+	fortran_order: bool,
+	shape: &[usize],
+	data: *const c_void,
+) -> Result<(), ErrorCode> {
+	let len = shape
+		.iter()
+		.try_fold(1usize, |len, &axis| len.checked_mul(axis))
+		.ok_or(ErrorCode::LengthOverflow)?;
+	let data = data.cast::<T>();
+	let data = if len == 0 {
+		&[]
+	} else {
+		check_slice(data, len)?;
+		slice::from_raw_parts(data, len)
+	};
+	let array = ArrayViewD::from_shape(IxDyn(shape).set_f(fortran_order), data)
+		.map_err(|_| ErrorCode::LengthOverflow)?;
+	npz.add_array(name, &array).map_err(|err| err.code())
+}
+
+/// Opens the `.npz` file at `path` for reading and stores its reader in `reader`.
+///
+/// The reader must be freed via [`npz_reader_free`].
+///
+/// # Safety
+///
+/// The `path` must be null or a valid C string, and the `reader` must be null or valid for
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn npz_reader_open(
+	path: *const c_char,
+	reader: *mut *mut NpzFileReader,
+) -> c_int {
+	status(|| {
+		let path = str_arg(path)?;
+		if reader.is_null() {
+			return Err(ErrorCode::InvalidArgument);
+		}
+		let file = File::open(path).map_err(io_code)?;
+		let mut npz = NpzReader::new(BufReader::new(file)).map_err(|err| err.code())?;
+		let names = npz
+			.npy_names()
+			.map_err(|err| err.code())?
+			.into_iter()
+			.map(|name| CString::new(name).map_err(|_| ErrorCode::InvalidArchive))
+			.collect::<Result<_, _>>()?;
+		*reader = Box::into_raw(Box::new(NpzFileReader { npz, names }));
+		Ok(())
+	})
+}
+
+/// Frees the `reader` if not null.
+///
+/// # Safety
+///
+/// The `reader` must be null or returned by [`npz_reader_open`] and not be freed already.
+#[no_mangle]
+pub unsafe extern "C" fn npz_reader_free(reader: *mut NpzFileReader) {
+	if !reader.is_null() {
+		drop(Box::from_raw(reader));
+	}
+}
+
+/// Stores the number of arrays of the `reader` in `len`.
+///
+/// # Safety
+///
+/// The `reader` must be null or valid, and the `len` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn npz_reader_len(reader: *const NpzFileReader, len: *mut usize) -> c_int {
+	status(|| {
+		let reader = reader.as_ref().ok_or(ErrorCode::InvalidArgument)?;
+		*len.as_mut().ok_or(ErrorCode::InvalidArgument)? = reader.names.len();
+		Ok(())
+	})
+}
+
+/// Stores the name of the array at `index` of the `reader` in `name`.
+///
+/// The name is borrowed from the `reader` and valid until it is freed. Fails with
+/// [`ErrorCode::FileNotFound`] if the `index` is out of bounds.
+///
+/// # Safety
+///
+/// The `reader` must be null or valid, and the `name` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn npz_reader_name(
+	reader: *const NpzFileReader,
+	index: usize,
+	name: *mut *const c_char,
+) -> c_int {
+	status(|| {
+		let reader = reader.as_ref().ok_or(ErrorCode::InvalidArgument)?;
+		let name = name.as_mut().ok_or(ErrorCode::InvalidArgument)?;
+		*name = reader
+			.names
+			.get(index)
+			.ok_or(ErrorCode::FileNotFound)?
+			.as_ptr();
+		Ok(())
+	})
+}
+
+/// Describes the array `name` of the `reader` in `info` by reading its `.npy` header only.
+///
+/// Fails with [`ErrorCode::UnsupportedDtype`] if the element type is not supported and with
+/// [`ErrorCode::LengthOverflow`] if the array has more than [`NPZ_MAX_NDIM`] dimensions or more
+/// elements than the address space.
+///
+/// # Safety
+///
+/// The `reader` must be null or valid, the `name` must be null or a valid C string, and the
+/// `info` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn npz_reader_describe(
+	reader: *mut NpzFileReader,
+	name: *const c_char,
+	info: *mut NpzArrayInfo,
+) -> c_int {
+	status(|| {
+		let reader = reader.as_mut().ok_or(ErrorCode::InvalidArgument)?;
+		let name = str_arg(name)?;
+		let info = info.as_mut().ok_or(ErrorCode::InvalidArgument)?;
+		let name = reader.npz.resolve_name(name).into_owned();
+		let mut file = reader
+			.npz
+			.zip
+			.by_name(&name)
+			.map_err(|err| zip_code(&err))?;
+		let header = NpyHeader::read(&mut file).map_err(|err| {
+			if err.kind() == io::ErrorKind::InvalidData {
+				ErrorCode::InvalidHeader
+			} else {
+				io_code(err)
+			}
+		})?;
+		let (dtype, _big_endian) =
+			Dtype::parse(&header.descr).ok_or(ErrorCode::UnsupportedDtype)?;
+		let mut shape = [0; NPZ_MAX_NDIM];
+		if header.shape.len() > NPZ_MAX_NDIM {
+			return Err(ErrorCode::LengthOverflow);
+		}
+		for (axis, &len) in shape.iter_mut().zip(&header.shape) {
+			*axis = usize::try_from(len).map_err(|_| ErrorCode::LengthOverflow)?;
+		}
+		let len = header
+			.elements()
+			.and_then(|len| usize::try_from(len).ok())
+			.ok_or(ErrorCode::LengthOverflow)?;
+		*info = NpzArrayInfo {
+			dtype: DTYPES
+				.iter()
+				.zip(0..)
+				.find_map(|(&other, code)| (other == dtype).then_some(code))
+				.ok_or(ErrorCode::UnsupportedDtype)?,
+			ndim: header.shape.len(),
+			shape,
+			len,
+			fortran_order: header.fortran_order,
+		};
+		Ok(())
+	})
+}
+
+/// Reads the array `name` of the `reader` into the buffer `data` of `len` elements.
+///
+/// The elements are written in column-major order if `fortran_order` is true, otherwise in
+/// row-major order, irrespective of the order they are stored in. Fails with
+/// [`ErrorCode::DtypeMismatch`] if the `dtype` differs from the stored one and with
+/// [`ErrorCode::ElementCountMismatch`] if `len` differs from the number of stored elements.
+///
+/// # Safety
+///
+/// The `reader` must be null or valid, the `name` must be null or a valid C string, and the
+/// `data` must be valid for writes of `len` elements of `dtype`.
+#[no_mangle]
+pub unsafe extern "C" fn npz_reader_read(
+	reader: *mut NpzFileReader,
+	name: *const c_char,
+	dtype: u32,
+	fortran_order: bool,
+	data: *mut c_void,
+	len: usize,
+) -> c_int {
+	status(|| {
+		let reader = reader.as_mut().ok_or(ErrorCode::InvalidArgument)?;
+		let name = str_arg(name)?;
+		let npz = &mut reader.npz;
+		match parse_dtype(dtype)? {
+			Dtype::Bool => read::<bool>(npz, name, fortran_order, data, len),
+			Dtype::I8 => read::<i8>(npz, name, fortran_order, data, len),
+			Dtype::U8 => read::<u8>(npz, name, fortran_order, data, len),
+			Dtype::I16 => read::<i16>(npz, name, fortran_order, data, len),
+			Dtype::U16 => read::<u16>(npz, name, fortran_order, data, len),
+			Dtype::I32 => read::<i32>(npz, name, fortran_order, data, len),
+			Dtype::U32 => read::<u32>(npz, name, fortran_order, data, len),
+			Dtype::I64 => read::<i64>(npz, name, fortran_order, data, len),
+			Dtype::U64 => read::<u64>(npz, name, fortran_order, data, len),
+			Dtype::F32 => read::<f32>(npz, name, fortran_order, data, len),
+			Dtype::F64 => read::<f64>(npz, name, fortran_order, data, len),
+		}
+	})
+}
+
+/// Creates the `.npz` file at `path` for writing and stores its writer in `writer`.
+///
+/// Compresses the arrays if `compressed` is true, which fails with
+/// [`ErrorCode::UnsupportedArchive`] unless the `compressed` feature is enabled. The writer must
+/// be finished via [`npz_writer_finish`] or discarded via [`npz_writer_free`].
+///
+/// # Safety
+///
+/// The `path` must be null or a valid C string, and the `writer` must be null or valid for
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn npz_writer_create(
+	path: *const c_char,
+	compressed: bool,
+	writer: *mut *mut NpzFileWriter,
+) -> c_int {
+	status(|| {
+		let path = str_arg(path)?;
+		if writer.is_null() {
+			return Err(ErrorCode::InvalidArgument);
+		}
+		if compressed && !cfg!(feature = "compressed") {
+			return Err(ErrorCode::UnsupportedArchive);
+		}
+		let file = BufSeekWriter::new(File::create(path).map_err(io_code)?).map_err(io_code)?;
+		#[cfg(feature = "compressed")]
+		let npz = if compressed {
+			NpzWriter::new_compressed(file)
+		} else {
+			NpzWriter::new(file)
+		};
+		#[cfg(not(feature = "compressed"))]
+		let npz = NpzWriter::new(file);
+		*writer = Box::into_raw(Box::new(NpzFileWriter { npz }));
+		Ok(())
+	})
+}
+
+/// Adds the array `name` of element type `dtype` and `shape` of `ndim` dimensions from `data`.
+///
+/// The elements are read in column-major order if `fortran_order` is true, otherwise in
+/// row-major order.
+///
+/// # Safety
+///
+/// The `writer` must be null or valid, the `name` must be null or a valid C string, the `shape`
+/// must be valid for reads of `ndim` lengths, and the `data` must be valid for reads of as many
+/// elements of `dtype` as the product of the lengths. Elements of [`NPZ_DTYPE_BOOL`] must be
+/// either `0` or `1`.
+#[no_mangle]
+pub unsafe extern "C" fn npz_writer_add(
+	writer: *mut NpzFileWriter,
+	name: *const c_char,
+	dtype: u32,
+	fortran_order: bool,
+	ndim: usize,
+	shape: *const usize,
+	data: *const c_void,
+) -> c_int {
+	status(|| {
+		let writer = writer.as_mut().ok_or(ErrorCode::InvalidArgument)?;
+		let name = str_arg(name)?;
+		let shape = if ndim == 0 {
+			&[]
+		} else {
+			check_slice(shape, ndim)?;
+			slice::from_raw_parts(shape, ndim)
+		};
+		let npz = &mut writer.npz;
+		match parse_dtype(dtype)? {
+			Dtype::Bool => write::<bool>(npz, name, fortran_order, shape, data),
+			Dtype::I8 => write::<i8>(npz, name, fortran_order, shape, data),
+			Dtype::U8 => write::<u8>(npz, name, fortran_order, shape, data),
+			Dtype::I16 => write::<i16>(npz, name, fortran_order, shape, data),
+			Dtype::U16 => write::<u16>(npz, name, fortran_order, shape, data),
+			Dtype::I32 => write::<i32>(npz, name, fortran_order, shape, data),
+			Dtype::U32 => write::<u32>(npz, name, fortran_order, shape, data),
+			Dtype::I64 => write::<i64>(npz, name, fortran_order, shape, data),
+			Dtype::U64 => write::<u64>(npz, name, fortran_order, shape, data),
+			Dtype::F32 => write::<f32>(npz, name, fortran_order, shape, data),
+			Dtype::F64 => write::<f64>(npz, name, fortran_order, shape, data),
+		}
+	})
+}
+
+/// Finishes writing the `.npz` file and frees the `writer` even on failure.
+///
+/// # Safety
+///
+/// The `writer` must be null or returned by [`npz_writer_create`] and not be freed already.
+#[no_mangle]
+pub unsafe extern "C" fn npz_writer_finish(writer: *mut NpzFileWriter) -> c_int {
+	status(|| {
+		if writer.is_null() {
+			return Err(ErrorCode::InvalidArgument);
+		}
+		let writer = Box::from_raw(writer);
+		let file = writer.npz.finish().map_err(|err| err.code())?;
+		file.into_inner().map_err(io_code)?;
+		Ok(())
+	})
+}
+
+/// Frees the `writer` if not null without finishing the `.npz` file.
+///
+/// # Safety
+///
+/// The `writer` must be null or returned by [`npz_writer_create`] and not be freed already.
+#[no_mangle]
+pub unsafe extern "C" fn npz_writer_free(writer: *mut NpzFileWriter) {
+	if !writer.is_null() {
+		drop(Box::from_raw(writer));
+	}
+}
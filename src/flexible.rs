@@ -0,0 +1,140 @@
+use super::{
+	convert::{Conversion, Dtype},
+	lenient, NpzView, ViewNpzError,
+};
+use ndarray::{Array, CowArray, Dimension};
+use ndarray_npy::{ReadNpyExt, ReadableElement, ViewElement};
+use std::io::{Cursor, Read};
+use zip::{result::ZipError, ZipArchive};
+
+/// Fallbacks of [`NpzView::get_flexible`] if an array cannot be viewed.
+///
+/// Defaults to reading but not casting, as casting may lose precision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fallbacks {
+	/// Whether to read arrays which cannot be viewed, e.g., compressed, misaligned, or in foreign
+	/// byte order.
+	pub read: bool,
+	/// Whether to cast arrays of other element types while reading them, see
+	/// [`Conversion::Cast`](crate::Conversion::Cast).
+	pub cast: bool,
+}
+
+impl Fallbacks {
+	/// Views arrays without any fallback.
+	pub const NONE: Self = Self {
+		read: false,
+		cast: false,
+	};
+	/// Reads and casts arrays which cannot be viewed.
+	pub const ALL: Self = Self {
+		read: true,
+		cast: true,
+	};
+}
+
+impl Default for Fallbacks {
+	fn default() -> Self {
+		Self {
+			read: true,
+			cast: false,
+		}
+	}
+}
+
+/// Access path taken by [`NpzView::get_flexible`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Access {
+	/// The array has been viewed without copying.
+	View,
+	/// The array has been read, including swapping its bytes if in foreign byte order.
+	Read,
+	/// The array has been read and cast from the given element type.
+	Cast(Dtype),
+}
+
+impl<'a> NpzView<'a> {
+	/// Returns the array of the file `name` as viewed without copying if possible, otherwise as
+	/// read or cast according to the `fallbacks`, together with the [`Access`] path taken.
+	///
+	/// The fallback chain is
+	///
+	///  1. viewing the array if stored, aligned, in native byte order, and of element type `A`,
+	///  2. reading the array if [`Fallbacks::read`], e.g., decompressing it, copying it to
+	///     aligned memory, or swapping its bytes, and
+	///  3. casting the array from another element type if [`Fallbacks::cast`] and the element
+	///     types are both a [`Dtype`].
+	///
+	/// Directories, encrypted files, blobs, and missing files fail right away. Reading parses the
+	/// central directory of the archive again.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use ndarray::Array1;
+	/// use ndarray_npz::{Access, Dtype, Fallbacks, NpzView, NpzWriter};
+	/// use std::io::Cursor;
+	///
+	/// let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	/// npz.add_array("x.npy", &Array1::<i32>::from_vec(vec![1, 2, 3]))?;
+	/// let bytes = npz.finish()?.into_inner();
+	/// let npz = NpzView::new(&bytes)?;
+	/// let (x, access) = npz.get_flexible::<f64, _>("x.npy", Fallbacks::ALL)?;
+	/// assert_eq!(x, Array1::from_vec(vec![1.0, 2.0, 3.0]));
+	/// assert_eq!(access, Access::Cast(Dtype::I32));
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Fails like [`Self::by_name`] and [`NpyView::view`](crate::NpyView::view) without fallbacks,
+	/// otherwise with [`ViewNpzError::ReadNpy`] if reading the array fails as well.
+	pub fn get_flexible<A, D>(
+		&self,
+		name: &str,
+		fallbacks: Fallbacks,
+	) -> Result<(CowArray<'a, A, D>, Access), ViewNpzError>
+	where
+		A: ViewElement + ReadableElement + 'static,
+		D: Dimension,
+	{
+		let viewed = self
+			.by_name(name)
+			.and_then(|file| Ok(lenient::view_npy::<A, D>(file.data, file.lenient)?));
+		let err = match viewed {
+			Ok(view) => return Ok((view.into(), Access::View)),
+			Err(err) => err,
+		};
+		if !fallbacks.read || !matches!(err, ViewNpzError::Npy(_) | ViewNpzError::CompressedFile) {
+			return Err(err);
+		}
+		let mut zip = ZipArchive::new(Cursor::new(self.bytes))?;
+		let mut bytes = Vec::new();
+		zip.by_name(name)?
+			.read_to_end(&mut bytes)
+			.map_err(ZipError::from)?;
+		let err = match Array::<A, D>::read_npy(bytes.as_slice()) {
+			Ok(array) => return Ok((array.into(), Access::Read)),
+			Err(err) => err,
+		};
+		let Some((bytes, source)) = fallbacks.cast.then(|| cast::<A>(&bytes)).flatten() else {
+			return Err(err.into());
+		};
+		let array = Array::<A, D>::read_npy(bytes.as_slice())?;
+		Ok((array.into(), Access::Cast(source)))
+	}
+}
+
+/// Casts the `.npy` file `bytes` to the element type `A` and returns it with the source element
+/// type if both are a [`Dtype`] and differ.
+fn cast<A: 'static>(bytes: &[u8]) -> Option<(Vec<u8>, Dtype)> {
+	let conversion = Conversion::Cast(Dtype::of::<A>()?);
+	let (header, source, big_endian) = conversion.header(bytes).ok()?;
+	if source == conversion.dtype() {
+		return None;
+	}
+	let data = bytes.get(header.len..)?;
+	let mut cast = header.to_bytes()?;
+	conversion.convert(source, big_endian, data, &mut cast);
+	Some((cast, source))
+}
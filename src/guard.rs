@@ -0,0 +1,132 @@
+use super::{NpzWriter, WriteNpzError};
+use ndarray::{ArrayBase, Data, Dimension};
+use ndarray_npy::{WritableElement, WriteNpyExt};
+use std::io::{self, Cursor, Seek, Write};
+use zip::{result::ZipError, CompressionMethod, ZipWriter};
+
+/// Entry which would have been larger compressed than stored, see
+/// [`NpzWriter::with_compression_guard`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Inflation {
+	/// Name of the entry.
+	pub name: String,
+	/// Size in bytes of the entry when stored.
+	pub size: u64,
+	/// Size in bytes of the entry when compressed.
+	pub compressed_size: u64,
+}
+
+impl Inflation {
+	/// Returns the compression ratio, i.e., the compressed size divided by the stored size.
+	#[must_use]
+	#[allow(clippy::cast_precision_loss)]
+	pub fn ratio(&self) -> f64 {
+		self.compressed_size as f64 / self.size as f64
+	}
+}
+
+/// Callback deciding whether to store an inflated entry or to abort.
+pub(crate) type CompressionGuard = Box<dyn FnMut(&Inflation) -> io::Result<()> + Send + Sync>;
+
+impl<W: Write + Seek> NpzWriter<W> {
+	/// Stores entries without compression which would have been larger compressed than stored.
+	///
+	/// Incompressible data, e.g., floating-point noise, grows slightly under *deflate* and loses
+	/// its alignment for memory-mapping via [`NpzView`](crate::NpzView) for nothing. With
+	/// the guard, each entry added via [`Self::add_array`] or [`Self::add_bytes`] is compressed in
+	/// memory first. If the compressed size is not smaller than the stored size, `on_inflation` is
+	/// called with the [`Inflation`], e.g., to warn about it, and the entry is rewritten as stored
+	/// and aligned via [`Self::with_alignment`], i.e., to 64 bytes by default. If `on_inflation` returns an error instead, adding the entry is aborted
+	/// and fails with [`ZipError::Io`] while the writer remains usable. Entries compressed to
+	/// smaller sizes are copied as is without compressing them again.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use ndarray::Array1;
+	/// use ndarray_npz::{NpzReader, NpzWriter};
+	/// use std::io::Cursor;
+	///
+	/// # #[cfg(feature = "compressed")]
+	/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+	/// let mut state = 1u32;
+	/// let noise = Array1::from_shape_simple_fn(1000, || {
+	/// 	state ^= state << 13;
+	/// 	state ^= state >> 17;
+	/// 	state ^= state << 5;
+	/// 	state
+	/// });
+	/// let mut npz = NpzWriter::new_compressed(Cursor::new(Vec::new()))
+	/// 	.with_compression_guard(|inflation| {
+	/// 		eprintln!("Storing {} at ratio {:.3}", inflation.name, inflation.ratio());
+	/// 		Ok(())
+	/// 	});
+	/// npz.add_array("noise.npy", &noise)?;
+	/// let mut npz = NpzReader::new(npz.finish()?)?;
+	/// let x: Array1<u32> = npz.by_name("noise.npy")?;
+	/// assert_eq!(x, noise);
+	/// # Ok(())
+	/// # }
+	/// # #[cfg(not(feature = "compressed"))]
+	/// # fn main() {}
+	/// ```
+	#[must_use]
+	pub fn with_compression_guard<F>(mut self, on_inflation: F) -> Self
+	where
+		F: FnMut(&Inflation) -> io::Result<()> + Send + Sync + 'static,
+	{
+		self.guard = Some(Box::new(on_inflation));
+		self
+	}
+
+	/// Adds the array guarded if enabled, returns whether it has been added.
+	pub(crate) fn add_guardable<S, D>(
+		&mut self,
+		name: &str,
+		array: &ArrayBase<S, D>,
+	) -> Result<bool, WriteNpzError>
+	where
+		S::Elem: WritableElement,
+		S: Data,
+		D: Dimension,
+	{
+		if self.guard.is_none() {
+			return Ok(false);
+		}
+		let mut bytes = Vec::new();
+		array.write_npy(&mut bytes)?;
+		self.add_guarded(name, &bytes)?;
+		Ok(true)
+	}
+
+	/// Adds the entry guarded if enabled, returns whether it has been added.
+	pub(crate) fn add_guarded(&mut self, name: &str, bytes: &[u8]) -> Result<bool, WriteNpzError> {
+		let Some(guard) = &mut self.guard else {
+			return Ok(false);
+		};
+		let mut scratch = ZipWriter::new(Cursor::new(Vec::new()));
+		scratch.start_file(name, self.options)?;
+		scratch.write_all(bytes).map_err(ZipError::from)?;
+		let mut scratch = scratch.finish_into_readable()?;
+		let file = scratch.by_index_raw(0)?;
+		let options = if file.compression() == CompressionMethod::Stored {
+			self.options
+		} else if file.compressed_size() < file.size() {
+			self.zip.raw_copy_file(file)?;
+			return Ok(true);
+		} else {
+			guard(&Inflation {
+				name: name.to_owned(),
+				size: file.size(),
+				compressed_size: file.compressed_size(),
+			})
+			.map_err(ZipError::from)?;
+			self.options
+				.compression_method(CompressionMethod::Stored)
+				.with_alignment(self.alignment)
+		};
+		self.zip.start_file(name, options)?;
+		self.zip.write_all(bytes).map_err(ZipError::from)?;
+		Ok(true)
+	}
+}
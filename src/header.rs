@@ -0,0 +1,164 @@
+use py_literal::Value;
+use std::io::{self, Read};
+
+/// Magic string of `.npy` files.
+pub(crate) const MAGIC: &[u8; 6] = b"\x93NUMPY";
+
+/// Parsed header of an `.npy` file.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct NpyHeader {
+	/// Data type descriptor.
+	pub descr: Value,
+	/// Whether the data is in column-major order.
+	pub fortran_order: bool,
+	/// Shape of the array.
+	pub shape: Vec<u64>,
+	/// Format version as major and minor number.
+	pub version: (u8, u8),
+	/// Length in bytes of the whole header including magic string and padding.
+	pub len: usize,
+}
+
+impl NpyHeader {
+	/// Returns the length of the whole header given its leading 12 bytes.
+	pub fn len(bytes: &[u8]) -> Option<usize> {
+		if bytes.get(..6)? != MAGIC {
+			return None;
+		}
+		match bytes.get(6)? {
+			1 => Some(10 + usize::from(u16::from_le_bytes(bytes.get(8..10)?.try_into().ok()?))),
+			2 | 3 => Some(
+				12 + usize::try_from(u32::from_le_bytes(bytes.get(8..12)?.try_into().ok()?))
+					.ok()?,
+			),
+			_ => None,
+		}
+	}
+	/// Parses the header at the start of `bytes`.
+	pub fn parse(bytes: &[u8]) -> Option<Self> {
+		let len = Self::len(bytes)?;
+		let version = (bytes[6], bytes[7]);
+		let start = if version.0 == 1 { 10 } else { 12 };
+		let dict = bytes.get(start..len)?;
+		let dict = if version.0 == 3 {
+			std::str::from_utf8(dict).ok()?
+		} else {
+			dict.is_ascii().then(|| std::str::from_utf8(dict).ok())??
+		};
+		let dict = dict.trim_end_matches(|c: char| c.is_whitespace() || c == '\0');
+		let Value::Dict(dict) = dict.parse::<Value>().ok()? else {
+			return None;
+		};
+		let mut descr = None;
+		let mut fortran_order = None;
+		let mut shape = None;
+		for (key, value) in dict {
+			match (key, value) {
+				(Value::String(key), value) if key == "descr" => descr = Some(value),
+				(Value::String(key), Value::Boolean(value)) if key == "fortran_order" => {
+					fortran_order = Some(value);
+				}
+				(Value::String(key), Value::Tuple(value)) if key == "shape" => {
+					shape = value
+						.into_iter()
+						.map(|axis| match axis {
+							Value::Integer(axis) => u64::try_from(axis).ok(),
+							_ => None,
+						})
+						.collect::<Option<Vec<u64>>>();
+				}
+				_ => return None,
+			}
+		}
+		Some(Self {
+			descr: descr?,
+			fortran_order: fortran_order?,
+			shape: shape?,
+			version,
+			len,
+		})
+	}
+	/// Reads and parses the header at the start of `reader`.
+	///
+	/// Fails with [`io::ErrorKind::InvalidData`] if the header is invalid.
+	pub fn read<R: Read>(reader: &mut R) -> io::Result<Self> {
+		let invalid = || io::Error::new(io::ErrorKind::InvalidData, "invalid npy header");
+		let mut bytes = vec![0; 12];
+		reader.read_exact(&mut bytes)?;
+		let len = Self::len(&bytes)
+			.filter(|&len| len >= 12)
+			.ok_or_else(invalid)?;
+		bytes.resize(len, 0);
+		reader.read_exact(&mut bytes[12..])?;
+		Self::parse(&bytes).ok_or_else(invalid)
+	}
+	/// Returns the number of elements.
+	pub fn elements(&self) -> Option<u64> {
+		self.shape
+			.iter()
+			.try_fold(1u64, |elements, &axis| elements.checked_mul(axis))
+	}
+	/// Returns the size in bytes of an element if the data type is a simple one.
+	pub fn item_size(&self) -> Option<u64> {
+		let Value::String(descr) = &self.descr else {
+			return None;
+		};
+		let descr = descr.trim_start_matches(['<', '>', '|', '=']);
+		let kind = descr.chars().next()?;
+		let size = descr[kind.len_utf8()..].parse::<u64>().ok()?;
+		match kind {
+			'b' | 'i' | 'u' | 'f' | 'c' | 'S' | 'a' | 'V' => Some(size),
+			'U' => size.checked_mul(4),
+			_ => None,
+		}
+	}
+	/// Encodes the header padded to a multiple of 64 bytes keeping its format version.
+	///
+	/// Returns `None` if the header exceeds the length representable by its format version.
+	pub fn to_bytes(&self) -> Option<Vec<u8>> {
+		let start = if self.version.0 == 1 { 10 } else { 12 };
+		let len = (start + self.dict().len() + 1).next_multiple_of(64);
+		self.to_bytes_with_len(len)
+	}
+	/// Encodes the header padded to exactly `len` bytes keeping its format version.
+	///
+	/// Returns `None` if the header does not fit.
+	pub fn to_bytes_with_len(&self, len: usize) -> Option<Vec<u8>> {
+		let dict = self.dict();
+		let start = if self.version.0 == 1 { 10 } else { 12 };
+		if start + dict.len() + 1 > len {
+			return None;
+		}
+		let mut bytes = Vec::with_capacity(len);
+		bytes.extend(MAGIC);
+		bytes.extend([self.version.0, self.version.1]);
+		if self.version.0 == 1 {
+			bytes.extend(u16::try_from(len - start).ok()?.to_le_bytes());
+		} else {
+			bytes.extend(u32::try_from(len - start).ok()?.to_le_bytes());
+		}
+		bytes.extend(dict.as_bytes());
+		bytes.resize(len - 1, b' ');
+		bytes.push(b'\n');
+		Some(bytes)
+	}
+	/// Formats the header dictionary as Python literal.
+	fn dict(&self) -> String {
+		let shape = match self.shape.as_slice() {
+			[axis] => format!("({axis},)"),
+			shape => format!(
+				"({})",
+				shape
+					.iter()
+					.map(ToString::to_string)
+					.collect::<Vec<_>>()
+					.join(", ")
+			),
+		};
+		let fortran_order = if self.fortran_order { "True" } else { "False" };
+		format!(
+			"{{'descr': {}, 'fortran_order': {fortran_order}, 'shape': {shape}, }}",
+			self.descr
+		)
+	}
+}
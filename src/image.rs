@@ -0,0 +1,184 @@
+use super::{
+	header::NpyHeader,
+	json::{json_strings, parse_json_strings},
+	NpzReader, NpzWriter, ReadNpzError, WriteNpzError,
+};
+use ndarray::{prelude::*, Data};
+use ndarray_npy::{ReadNpyError, ReadableElement, WritableElement};
+use py_literal::Value;
+use std::{
+	io::{Read, Seek, Write},
+	marker::PhantomData,
+	ops::Range,
+};
+use zip::{read::ZipFile, result::ZipError};
+
+/// Element type of images, i.e., `u8`, `u16`, and `f32`.
+pub trait ImageElement: ReadableElement + WritableElement {}
+
+impl ImageElement for u8 {}
+impl ImageElement for u16 {}
+impl ImageElement for f32 {}
+
+/// Returns the name of the member storing the frame attributes of the member `name`.
+fn frames_name(name: &str) -> String {
+	let stem = name.strip_suffix(".npy").unwrap_or(name);
+	format!("{stem}.frames.json")
+}
+
+impl<W: Write + Seek> NpzWriter<W> {
+	/// Adds a stack of images with the specified `name` of shape `(N, H, W, C)`, i.e., of `N`
+	/// frames of height `H`, width `W`, and `C` channels.
+	///
+	/// Optionally, `frame_attrs` provide one attribute per frame, e.g., a timestamp or file name,
+	/// stored as JSON array of strings in the member `x.frames.json` next to the stack `x.npy`.
+	///
+	/// # Errors
+	///
+	/// Adding an array can fail with [`WriteNpyError`](ndarray_npy::WriteNpyError) or
+	/// [`ZipError`].
+	///
+	/// # Panics
+	///
+	/// Panics if the number of `frame_attrs` differs from the number of frames.
+	pub fn add_image_stack<S, T>(
+		&mut self,
+		name: &str,
+		stack: &ArrayBase<S, Ix4>,
+		frame_attrs: Option<&[T]>,
+	) -> Result<(), WriteNpzError>
+	where
+		S::Elem: ImageElement,
+		S: Data,
+		T: AsRef<str>,
+	{
+		self.add_array(name, stack)?;
+		if let Some(frame_attrs) = frame_attrs {
+			assert_eq!(
+				frame_attrs.len(),
+				stack.len_of(Axis(0)),
+				"number of frame attributes differs from number of frames"
+			);
+			let frame_attrs = frame_attrs.iter().map(AsRef::as_ref).collect::<Vec<_>>();
+			self.zip.start_file(frames_name(name), self.options)?;
+			self.zip
+				.write_all(json_strings(&frame_attrs).as_bytes())
+				.map_err(ZipError::from)?;
+		}
+		Ok(())
+	}
+}
+
+impl<R: Read + Seek> NpzReader<R> {
+	/// Reads a stack of images added via [`NpzWriter::add_image_stack`] as a whole.
+	///
+	/// # Errors
+	///
+	/// Reading an array from an archive can fail with [`ReadNpyError`] or [`ZipError`], e.g.,
+	/// if the element type or the number of axes differ.
+	pub fn by_name_image_stack<A: ImageElement>(
+		&mut self,
+		name: &str,
+	) -> Result<Array4<A>, ReadNpzError> {
+		self.by_name(name)
+	}
+
+	/// Reads the frame attributes of a stack of images if any.
+	///
+	/// # Errors
+	///
+	/// Reading the attributes can fail with [`ZipError`].
+	pub fn frame_attrs(&mut self, name: &str) -> Result<Option<Vec<String>>, ReadNpzError> {
+		let mut file = match self.zip.by_name(&frames_name(name)) {
+			Err(ZipError::FileNotFound) => return Ok(None),
+			Err(err) => return Err(err.into()),
+			Ok(file) => file,
+		};
+		let mut frame_attrs = String::new();
+		file.read_to_string(&mut frame_attrs)
+			.map_err(ZipError::from)?;
+		Ok(Some(parse_json_strings(&frame_attrs).ok_or(
+			ZipError::InvalidArchive("Invalid frame attributes"),
+		)?))
+	}
+
+	/// Reads a stack of images frame by frame without loading the whole stack into memory.
+	///
+	/// # Errors
+	///
+	/// Fails with [`ZipError::InvalidArchive`] if the stack is not of shape `(N, H, W, C)` in
+	/// standard order. Reading the `.npy` header can fail with [`ZipError`].
+	pub fn image_frames<A: ImageElement>(
+		&mut self,
+		name: &str,
+	) -> Result<ImageFrames<'_, A>, ReadNpzError> {
+		let mut file = self.zip.by_name(name)?;
+		let header = NpyHeader::read(&mut file).map_err(ZipError::from)?;
+		let (&[frames, height, width, channels], false) =
+			(header.shape.as_slice(), header.fortran_order)
+		else {
+			return Err(ZipError::InvalidArchive("Image stack not of shape (N, H, W, C)").into());
+		};
+		let invalid = || ZipError::InvalidArchive("Image stack exceeds address space");
+		let shape = [height, width, channels].map(usize::try_from);
+		let [Ok(height), Ok(width), Ok(channels)] = shape else {
+			return Err(invalid().into());
+		};
+		let frames = usize::try_from(frames).map_err(|_| invalid())?;
+		let frame_len = height
+			.checked_mul(width)
+			.and_then(|len| len.checked_mul(channels))
+			.ok_or_else(invalid)?;
+		let frame_size = header
+			.item_size()
+			.and_then(|size| size.checked_mul(frame_len as u64))
+			.ok_or(ZipError::InvalidArchive(
+				"Image element type has no fixed size",
+			))?;
+		Ok(ImageFrames {
+			file,
+			descr: header.descr,
+			shape: (height, width, channels),
+			frame_size,
+			frames: 0..frames,
+			element: PhantomData,
+		})
+	}
+}
+
+/// Iterator over the frames of a stack of images, see [`NpzReader::image_frames`].
+pub struct ImageFrames<'a, A> {
+	file: ZipFile<'a>,
+	descr: Value,
+	shape: (usize, usize, usize),
+	frame_size: u64,
+	frames: Range<usize>,
+	element: PhantomData<A>,
+}
+
+impl<A: ImageElement> Iterator for ImageFrames<'_, A> {
+	type Item = Result<Array3<A>, ReadNpzError>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.frames.next()?;
+		let (height, width, channels) = self.shape;
+		let frame: Self::Item = A::read_to_end_exact_vec(
+			(&mut self.file).take(self.frame_size),
+			&self.descr,
+			height * width * channels,
+		)
+		.map_err(|err| ReadNpyError::from(err).into())
+		.map(|frame| Array3::from_shape_vec(self.shape, frame).unwrap());
+		if frame.is_err() {
+			// Stop after the first error.
+			self.frames = 0..0;
+		}
+		Some(frame)
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.frames.size_hint()
+	}
+}
+
+impl<A: ImageElement> ExactSizeIterator for ImageFrames<'_, A> {}
@@ -0,0 +1,451 @@
+use super::{
+	as_array_ref,
+	capability::{is_supported_file, u16_at, u32_at, u64_at, CENTRAL_SIGNATURE},
+	header::{NpyHeader, MAGIC},
+	poison, slice_at, NpyView, NpzReader, NpzView, NpzWriter, ReadNpzError,
+};
+use std::{
+	collections::{HashMap, HashSet},
+	io::{Cursor, Read, Seek, Write},
+	sync::OnceLock,
+};
+use zip::{result::ZipError, write::SimpleFileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+/// Reserved name of the member indexing the other files, see [`NpzWriter::with_index`].
+pub const INDEX_NAME: &str = "__index__.bin";
+
+/// Magic string and format version of the index.
+const INDEX_MAGIC: &[u8; 8] = b"NPZIDX\x01\x00";
+
+/// Flag of directories.
+const DIRECTORY: u8 = 1;
+/// Flag of compressed files.
+const COMPRESSED: u8 = 1 << 1;
+/// Flag of encrypted files.
+const ENCRYPTED: u8 = 1 << 2;
+/// Flag of `.npy` files.
+const NPY: u8 = 1 << 3;
+/// Flag of `.npy` files in column-major order.
+const FORTRAN_ORDER: u8 = 1 << 4;
+
+/// File summarized by the [`INDEX_NAME`] member, see [`NpzReader::index`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IndexEntry {
+	/// Name of the file.
+	pub name: String,
+	/// Offset of the local header within the archive.
+	pub header_start: u64,
+	/// Offset of the possibly compressed data within the archive.
+	pub data_start: u64,
+	/// Length of the possibly compressed data.
+	pub compressed_size: u64,
+	/// Length of the uncompressed data.
+	pub size: u64,
+	/// CRC-32 checksum of the uncompressed data.
+	pub crc32: u32,
+	/// Whether the file is a directory.
+	pub directory: bool,
+	/// Whether the file is compressed.
+	pub compressed: bool,
+	/// Whether the file is encrypted.
+	pub encrypted: bool,
+	/// Header of the `.npy` file, `None` for directories, encrypted files, and blobs.
+	pub npy: Option<IndexedNpy>,
+}
+
+/// Header of an `.npy` file summarized by an [`IndexEntry`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IndexedNpy {
+	/// Data type descriptor as Python literal, e.g., `'<f8'`.
+	pub descr: String,
+	/// Whether the data is in column-major order.
+	pub fortran_order: bool,
+	/// Shape of the array.
+	pub shape: Vec<u64>,
+	/// Length of the `.npy` header, i.e., offset of the array data within the `.npy` file.
+	pub header_len: u64,
+}
+
+/// Adds the index to a finished archive.
+pub(crate) type AddIndex<W> = fn(W) -> Result<W, ZipError>;
+
+impl<W: Read + Write + Seek> NpzWriter<W> {
+	/// Adds the [`INDEX_NAME`] member summarizing the other files on [`Self::finish`].
+	///
+	/// The index lists the offsets, checksums, shapes, and data types of all files, so
+	/// [`NpzReader::index`] lists them without reading the `.npy` headers, e.g., without
+	/// decompressing, and [`NpzView::new`] skips reading the local headers, which matters for cold
+	/// opens of memory-mapped files over network file systems. The index is added by a final pass
+	/// over the archive, hence the writer must be readable. It is the last member of the archive
+	/// and ignored when stale, i.e., when the names, offsets, or checksums of the files disagree
+	/// with the central directory, e.g., after editing or compacting, in which case the files are
+	/// read as without index.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use ndarray::Array2;
+	/// use ndarray_npz::{NpzReader, NpzWriter};
+	/// use std::io::Cursor;
+	///
+	/// let mut npz = NpzWriter::new(Cursor::new(Vec::new())).with_index();
+	/// npz.add_array("weights", &Array2::<f32>::zeros((64, 32)))?;
+	/// let mut npz = NpzReader::new(npz.finish()?)?;
+	/// let index = npz.index()?.unwrap();
+	/// let npy = index[0].npy.as_ref().unwrap();
+	/// assert_eq!((npy.descr.as_str(), npy.shape.as_slice()), ("'<f4'", [64, 32].as_slice()));
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	#[must_use]
+	pub fn with_index(mut self) -> Self {
+		self.index = Some(add_index::<W>);
+		self
+	}
+}
+
+impl<R: Read + Seek> NpzReader<R> {
+	/// Returns the files summarized by the [`INDEX_NAME`] member, see [`NpzWriter::with_index`].
+	///
+	/// Returns `None` if the index is absent or stale. The index is validated against the metadata
+	/// of the files without reading their contents.
+	///
+	/// # Errors
+	///
+	/// Reading the zip archive can fail with [`ZipError`].
+	pub fn index(&mut self) -> Result<Option<Vec<IndexEntry>>, ReadNpzError> {
+		let Some(position) = self.zip.index_for_name(INDEX_NAME) else {
+			return Ok(None);
+		};
+		if position + 1 != self.zip.len() {
+			return Ok(None);
+		}
+		let mut bytes = Vec::new();
+		self.zip
+			.by_index(position)?
+			.read_to_end(&mut bytes)
+			.map_err(ZipError::from)?;
+		let Some(entries) = decode(&bytes).filter(|entries| entries.len() == position) else {
+			return Ok(None);
+		};
+		for (index, entry) in entries.iter().enumerate() {
+			let file = self.zip.by_index_raw(index)?;
+			if file.name() != entry.name
+				|| file.crc32() != entry.crc32
+				|| file.header_start() != entry.header_start
+				|| file.data_start() != entry.data_start
+				|| file.compressed_size() != entry.compressed_size
+			{
+				return Ok(None);
+			}
+		}
+		Ok(Some(entries))
+	}
+}
+
+impl<'a> NpzView<'a> {
+	/// Creates the view via the [`INDEX_NAME`] member if present and valid.
+	///
+	/// Only the central directory is read to validate the index.
+	pub(crate) fn from_index(
+		bytes: &'a [u8],
+		zip: &mut ZipArchive<Cursor<&'a [u8]>>,
+	) -> Option<Self> {
+		let position = zip.index_for_name(INDEX_NAME)?;
+		if position + 1 != zip.len() {
+			return None;
+		}
+		let file = zip.by_index_raw(position).ok()?;
+		if file.compression() != CompressionMethod::Stored || file.encrypted() {
+			return None;
+		}
+		let index = slice_at(bytes, file.data_start(), 0..file.size()).ok()?;
+		if crc32fast::hash(index) != file.crc32() {
+			return None;
+		}
+		drop(file);
+		let entries = decode(index).filter(|entries| entries.len() == position)?;
+		let mut pos = usize::try_from(zip.central_directory_start()).ok()?;
+		let mut central_header_starts = Vec::with_capacity(entries.len());
+		for entry in &entries {
+			let central = bytes.get(pos..pos + 46)?;
+			let name_len = usize::from(u16_at(central, 28)?);
+			let extra_len = usize::from(u16_at(central, 30)?);
+			let comment_len = usize::from(u16_at(central, 32)?);
+			let extra = bytes.get(pos + 46 + name_len..pos + 46 + name_len + extra_len)?;
+			if local_header_start(central, extra)? != entry.header_start {
+				return None;
+			}
+			central_header_starts.push(pos as u64);
+			pos += 46 + name_len + extra_len + comment_len;
+		}
+		let entries = entries.into_iter().zip(central_header_starts);
+		let mut archive = Self::from_entries(bytes, entries)?;
+		archive.blobs.insert(INDEX_NAME.to_owned(), index);
+		Some(archive)
+	}
+
+	/// Creates the view of the `entries` with the offsets of their central headers.
+	///
+	/// The central headers are checked to match the names and checksums of the `entries`.
+	pub(crate) fn from_entries<I>(bytes: &'a [u8], entries: I) -> Option<Self>
+	where
+		I: IntoIterator<Item = (IndexEntry, u64)>,
+	{
+		let mut archive = Self {
+			bytes,
+			files: HashMap::new(),
+			names: HashMap::new(),
+			blobs: HashMap::new(),
+			directory_names: HashSet::new(),
+			compressed_names: HashSet::new(),
+			encrypted_names: HashSet::new(),
+			checksums: OnceLock::new(),
+		};
+		for (entry, central_header_start) in entries {
+			let pos = usize::try_from(central_header_start).ok()?;
+			let central = bytes.get(pos..pos + 46)?;
+			let name = bytes.get(pos + 46..pos + 46 + usize::from(u16_at(central, 28)?))?;
+			if u32_at(central, 0)? != CENTRAL_SIGNATURE
+				|| name != entry.name.as_bytes()
+				|| u32_at(central, 16)? != entry.crc32
+			{
+				return None;
+			}
+			let central_crc32 = as_array_ref(&central[16..20]);
+			if entry.encrypted {
+				archive.encrypted_names.insert(entry.name);
+			} else if entry.directory {
+				archive.directory_names.insert(entry.name);
+			} else if entry.compressed {
+				archive.compressed_names.insert(entry.name);
+			} else {
+				let data = slice_at(bytes, entry.data_start, 0..entry.size).ok()?;
+				if entry.npy.is_some() {
+					let index = archive.files.len();
+					archive.names.insert(entry.name, index);
+					archive.files.insert(
+						index,
+						NpyView {
+							data,
+							central_crc32,
+							status: poison::status(u16_at(central, 36)?),
+							lenient: false,
+						},
+					);
+				} else {
+					archive.blobs.insert(entry.name, data);
+				}
+			}
+		}
+		Some(archive)
+	}
+}
+
+/// Appends the index of the finished archive of `writer`.
+fn add_index<W: Read + Write + Seek>(mut writer: W) -> Result<W, ZipError> {
+	let mut zip = ZipArchive::new(&mut writer)?;
+	let entries = index_entries(&mut zip)?
+		.into_iter()
+		.map(|(entry, _central_header_start)| entry)
+		.collect::<Vec<_>>();
+	drop(zip);
+	let bytes = encode(&entries)?;
+	let mut zip = ZipWriter::new_append(writer)?;
+	let options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+	zip.start_file(INDEX_NAME, options)?;
+	zip.write_all(&bytes)?;
+	zip.finish()
+}
+
+/// Summarizes the files of `zip` with the offsets of their central headers.
+pub(crate) fn index_entries<R: Read + Seek>(
+	zip: &mut ZipArchive<R>,
+) -> Result<Vec<(IndexEntry, u64)>, ZipError> {
+	let mut entries = Vec::with_capacity(zip.len());
+	for index in 0..zip.len() {
+		let file = zip.by_index_raw(index)?;
+		let central_header_start = file.central_header_start();
+		let mut entry = IndexEntry {
+			name: file.name().to_owned(),
+			header_start: file.header_start(),
+			data_start: file.data_start(),
+			compressed_size: file.compressed_size(),
+			size: file.size(),
+			crc32: file.crc32(),
+			directory: file.is_dir(),
+			compressed: file.compression() != CompressionMethod::Stored,
+			encrypted: file.encrypted(),
+			npy: None,
+		};
+		let supported = is_supported_file(&file);
+		drop(file);
+		if !entry.directory && !entry.encrypted && supported {
+			let mut file = zip.by_index(index)?;
+			let mut magic = Vec::with_capacity(MAGIC.len());
+			(&mut file)
+				.take(MAGIC.len() as u64)
+				.read_to_end(&mut magic)?;
+			if magic == MAGIC {
+				let header = NpyHeader::read(&mut magic.as_slice().chain(&mut file))
+					.map_err(|_| ZipError::InvalidArchive("Invalid npy header"))?;
+				entry.npy = Some(IndexedNpy {
+					descr: header.descr.to_string(),
+					fortran_order: header.fortran_order,
+					shape: header.shape,
+					header_len: header.len as u64,
+				});
+			}
+		}
+		entries.push((entry, central_header_start));
+	}
+	Ok(entries)
+}
+
+/// Encodes the `entries` in little-endian byte order.
+///
+/// The index starts with the magic string `NPZIDX`, the format version `1.0`, and the number of
+/// entries as `u64`. Each entry consists of the name length as `u16`, the name, the offset of the
+/// local header, the offset of the data, the compressed and uncompressed length as `u64`, the
+/// CRC-32 checksum as `u32`, and the flags as `u8`. The flags of directories, compressed,
+/// encrypted, `.npy`, and column-major `.npy` files are bits zero to four. `.npy` files are
+/// followed by the header length as `u64`, the length of the data type descriptor as `u32`, the
+/// descriptor, the number of axes as `u8`, and the shape as `u64` each.
+pub(crate) fn encode(entries: &[IndexEntry]) -> Result<Vec<u8>, ZipError> {
+	let overflow = || ZipError::InvalidArchive("Index entry too large");
+	let mut bytes = INDEX_MAGIC.to_vec();
+	bytes.extend((entries.len() as u64).to_le_bytes());
+	for entry in entries {
+		let mut flags = 0;
+		for (set, flag) in [
+			(entry.directory, DIRECTORY),
+			(entry.compressed, COMPRESSED),
+			(entry.encrypted, ENCRYPTED),
+			(entry.npy.is_some(), NPY),
+			(
+				entry.npy.as_ref().is_some_and(|npy| npy.fortran_order),
+				FORTRAN_ORDER,
+			),
+		] {
+			if set {
+				flags |= flag;
+			}
+		}
+		let name_len = u16::try_from(entry.name.len()).map_err(|_| overflow())?;
+		bytes.extend(name_len.to_le_bytes());
+		bytes.extend(entry.name.as_bytes());
+		bytes.extend(entry.header_start.to_le_bytes());
+		bytes.extend(entry.data_start.to_le_bytes());
+		bytes.extend(entry.compressed_size.to_le_bytes());
+		bytes.extend(entry.size.to_le_bytes());
+		bytes.extend(entry.crc32.to_le_bytes());
+		bytes.push(flags);
+		if let Some(npy) = &entry.npy {
+			let descr_len = u32::try_from(npy.descr.len()).map_err(|_| overflow())?;
+			let ndim = u8::try_from(npy.shape.len()).map_err(|_| overflow())?;
+			bytes.extend(npy.header_len.to_le_bytes());
+			bytes.extend(descr_len.to_le_bytes());
+			bytes.extend(npy.descr.as_bytes());
+			bytes.push(ndim);
+			for axis in &npy.shape {
+				bytes.extend(axis.to_le_bytes());
+			}
+		}
+	}
+	Ok(bytes)
+}
+
+/// Decodes the entries, see [`encode`].
+pub(crate) fn decode(bytes: &[u8]) -> Option<Vec<IndexEntry>> {
+	let mut bytes = Decoder(bytes.strip_prefix(INDEX_MAGIC.as_slice())?);
+	let mut entries = Vec::new();
+	for _entry in 0..bytes.u64()? {
+		let name_len = usize::from(bytes.u16()?);
+		let name = bytes.string(name_len)?;
+		let header_start = bytes.u64()?;
+		let data_start = bytes.u64()?;
+		let compressed_size = bytes.u64()?;
+		let size = bytes.u64()?;
+		let crc32 = bytes.u32()?;
+		let flags = bytes.take(1)?[0];
+		let npy = if flags & NPY == 0 {
+			None
+		} else {
+			let header_len = bytes.u64()?;
+			let descr_len = usize::try_from(bytes.u32()?).ok()?;
+			let descr = bytes.string(descr_len)?;
+			let ndim = bytes.take(1)?[0];
+			let shape = (0..ndim)
+				.map(|_axis| bytes.u64())
+				.collect::<Option<Vec<_>>>()?;
+			Some(IndexedNpy {
+				descr,
+				fortran_order: flags & FORTRAN_ORDER != 0,
+				shape,
+				header_len,
+			})
+		};
+		entries.push(IndexEntry {
+			name,
+			header_start,
+			data_start,
+			compressed_size,
+			size,
+			crc32,
+			directory: flags & DIRECTORY != 0,
+			compressed: flags & COMPRESSED != 0,
+			encrypted: flags & ENCRYPTED != 0,
+			npy,
+		});
+	}
+	bytes.0.is_empty().then_some(entries)
+}
+
+/// Consumes little-endian values from the front of a byte slice.
+pub(crate) struct Decoder<'a>(pub &'a [u8]);
+
+impl<'a> Decoder<'a> {
+	/// Consumes `len` bytes.
+	pub fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+		let bytes = self.0;
+		self.0 = bytes.get(len..)?;
+		Some(&bytes[..len])
+	}
+	/// Consumes a `u16`.
+	pub fn u16(&mut self) -> Option<u16> {
+		u16_at(self.take(2)?, 0)
+	}
+	/// Consumes a `u32`.
+	pub fn u32(&mut self) -> Option<u32> {
+		u32_at(self.take(4)?, 0)
+	}
+	/// Consumes a `u64`.
+	pub fn u64(&mut self) -> Option<u64> {
+		u64_at(self.take(8)?, 0)
+	}
+	/// Consumes a UTF-8 string of `len` bytes.
+	pub fn string(&mut self, len: usize) -> Option<String> {
+		String::from_utf8(self.take(len)?.to_vec()).ok()
+	}
+}
+
+/// Returns the offset of the local header of the `central` header with its `extra` field.
+fn local_header_start(central: &[u8], extra: &[u8]) -> Option<u64> {
+	let offset = u32_at(central, 42)?;
+	if offset != u32::MAX {
+		return Some(u64::from(offset));
+	}
+	// Only values whose fixed fields are set to their maximum are present in order.
+	let skip = 8
+		* (usize::from(u32_at(central, 24)? == u32::MAX)
+			+ usize::from(u32_at(central, 20)? == u32::MAX));
+	let mut pos = 0;
+	while let (Some(id), Some(len)) = (u16_at(extra, pos), u16_at(extra, pos + 2)) {
+		let len = usize::from(len);
+		if id == 0x0001 {
+			let field = extra.get(pos + 4..pos + 4 + len)?;
+			return u64_at(field, skip);
+		}
+		pos += 4 + len;
+	}
+	None
+}
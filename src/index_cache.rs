@@ -0,0 +1,250 @@
+use super::{
+	index::{decode, encode, index_entries, Decoder, IndexEntry},
+	portable::long_path,
+	NpzView, ReadNpzError, ViewNpzError,
+};
+use std::{
+	env,
+	ffi::OsString,
+	fs::{self, File, Metadata},
+	io::BufReader,
+	path::{Path, PathBuf},
+	process,
+	time::UNIX_EPOCH,
+};
+use zip::{result::ZipError, ZipArchive};
+
+/// Magic string and format version of cache files.
+const CACHE_MAGIC: &[u8; 8] = b"NPZIC\x01\x00\x00";
+
+/// Location of the cache files of [`cached_index`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CacheLocation {
+	/// Next to the archive with `.index` appended to its name, e.g., `arrays.npz.index`.
+	NextToArchive,
+	/// In the `ndarray-npz` directory of the user's cache directory, i.e., of `$XDG_CACHE_HOME`,
+	/// `$HOME/.cache`, or `%LOCALAPPDATA%`.
+	UserCache,
+	/// In the given directory.
+	Dir(PathBuf),
+}
+
+impl CacheLocation {
+	/// Returns the path of the cache file of the archive at the `canonical` path.
+	fn path(&self, canonical: &Path) -> Option<PathBuf> {
+		let file_name = canonical.file_name()?;
+		let dir = match self {
+			Self::NextToArchive => {
+				let mut name = file_name.to_owned();
+				name.push(".index");
+				return Some(canonical.with_file_name(name));
+			}
+			Self::UserCache => env::var_os("XDG_CACHE_HOME")
+				.map(PathBuf::from)
+				.or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))
+				.or_else(|| env::var_os("LOCALAPPDATA").map(PathBuf::from))?
+				.join(env!("CARGO_PKG_NAME")),
+			Self::Dir(dir) => dir.clone(),
+		};
+		// Tell apart archives of equal names in different directories.
+		let hash = crc32fast::hash(canonical.to_string_lossy().as_bytes());
+		let mut name = file_name.to_owned();
+		name.push(format!(".{hash:08x}.index"));
+		Some(dir.join(name))
+	}
+}
+
+/// Parsed central directory and `.npy` headers of an archive, see [`cached_index`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedIndex {
+	len: u64,
+	entries: Vec<IndexEntry>,
+	central_header_starts: Vec<u64>,
+}
+
+impl CachedIndex {
+	/// Summaries of the files in archive order.
+	#[must_use]
+	pub fn entries(&self) -> &[IndexEntry] {
+		&self.entries
+	}
+	/// Creates an immutable view of the memory-mapped archive without parsing it.
+	///
+	/// Only the central headers of the files are read to check their names and checksums.
+	///
+	/// # Errors
+	///
+	/// Fails with [`ZipError::InvalidArchive`] if the archive disagrees with the index, e.g., if
+	/// `bytes` are not the ones of the indexed archive.
+	pub fn view<'a>(&self, bytes: &'a [u8]) -> Result<NpzView<'a>, ViewNpzError> {
+		let stale = || ZipError::InvalidArchive("Stale index cache").into();
+		if bytes.len() as u64 != self.len {
+			return Err(stale());
+		}
+		let entries = self
+			.entries
+			.iter()
+			.cloned()
+			.zip(self.central_header_starts.iter().copied());
+		NpzView::from_entries(bytes, entries).ok_or_else(stale)
+	}
+
+	/// Encodes the index of the archive at the `canonical` path with its `key`.
+	fn encode(&self, key: &Key, canonical: &Path) -> Result<Vec<u8>, ZipError> {
+		let path = canonical.to_string_lossy();
+		let path_len =
+			u32::try_from(path.len()).map_err(|_| ZipError::InvalidArchive("Path too long"))?;
+		let index = encode(&self.entries)?;
+		let mut bytes = CACHE_MAGIC.to_vec();
+		bytes.extend(key.len.to_le_bytes());
+		bytes.extend(key.secs.to_le_bytes());
+		bytes.extend(key.nanos.to_le_bytes());
+		bytes.extend(path_len.to_le_bytes());
+		bytes.extend(path.as_bytes());
+		bytes.extend((self.central_header_starts.len() as u64).to_le_bytes());
+		for central_header_start in &self.central_header_starts {
+			bytes.extend(central_header_start.to_le_bytes());
+		}
+		bytes.extend(index);
+		bytes.extend(crc32fast::hash(&bytes).to_le_bytes());
+		Ok(bytes)
+	}
+	/// Decodes the index if the cache file `bytes` are intact and match the `key` and `canonical`
+	/// path of the archive.
+	fn decode(bytes: &[u8], key: &Key, canonical: &Path) -> Option<Self> {
+		let (bytes, crc32) = bytes.split_at(bytes.len().checked_sub(4)?);
+		if crc32fast::hash(bytes).to_le_bytes() != crc32 {
+			return None;
+		}
+		let mut bytes = Decoder(bytes.strip_prefix(CACHE_MAGIC.as_slice())?);
+		let cached = Key {
+			len: bytes.u64()?,
+			secs: bytes.u64()?,
+			nanos: bytes.u32()?,
+		};
+		let path_len = usize::try_from(bytes.u32()?).ok()?;
+		if cached != *key || bytes.string(path_len)? != canonical.to_string_lossy() {
+			return None;
+		}
+		let count = usize::try_from(bytes.u64()?).ok()?;
+		let central_header_starts = bytes
+			.take(count.checked_mul(8)?)?
+			.chunks_exact(8)
+			.map(|start| Decoder(start).u64())
+			.collect::<Option<Vec<_>>>()?;
+		let entries = decode(bytes.0).filter(|entries| entries.len() == count)?;
+		Some(Self {
+			len: key.len,
+			entries,
+			central_header_starts,
+		})
+	}
+}
+
+/// Size and modification time of an archive keying its cache file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Key {
+	len: u64,
+	secs: u64,
+	nanos: u32,
+}
+
+impl Key {
+	/// Returns the key of the archive with `metadata` if its modification time is available.
+	fn new(metadata: &Metadata) -> Option<Self> {
+		let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+		Some(Self {
+			len: metadata.len(),
+			secs: modified.as_secs(),
+			nanos: modified.subsec_nanos(),
+		})
+	}
+}
+
+/// Returns the parsed central directory and `.npy` headers of the archive at `path` via a cache
+/// file at `location`.
+///
+/// Repeatedly opening the same huge archive across many short-lived processes parses it once, as
+/// long as its size and modification time stay the same. The cache file is written atomically by
+/// renaming a temporary file and checksummed, so concurrent processes never read a partially
+/// written or otherwise corrupted cache file but parse the archive again. Failing to write the
+/// cache file is ignored. The cache is strictly opt-in, nothing is cached unless calling this
+/// function. Modifying an archive without changing its size within the resolution of the file
+/// system's modification times goes unnoticed, see [`CachedIndex::view`] for a cheap check.
+///
+/// # Example
+///
+/// ```no_run
+/// use memmap2::Mmap;
+/// use ndarray::Ix2;
+/// use ndarray_npz::{cached_index, CacheLocation};
+/// use std::fs::File;
+///
+/// let index = cached_index("huge.npz", &CacheLocation::UserCache)?;
+/// for entry in index.entries() {
+/// 	println!("{}: {:?}", entry.name, entry.npy.as_ref().map(|npy| &npy.shape));
+/// }
+/// let mmap = unsafe { Mmap::map(&File::open("huge.npz")?)? };
+/// let npz = index.view(&mmap)?;
+/// let weights = npz.by_name("weights.npy")?;
+/// let weights = weights.view::<f32, Ix2>()?;
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// # Errors
+///
+/// Reading the archive can fail with [`ZipError`].
+pub fn cached_index<P: AsRef<Path>>(
+	path: P,
+	location: &CacheLocation,
+) -> Result<CachedIndex, ReadNpzError> {
+	let path = long_path(path.as_ref());
+	let file = File::open(&path).map_err(ZipError::from)?;
+	let metadata = file.metadata().map_err(ZipError::from)?;
+	let canonical = path.canonicalize().map_err(ZipError::from)?;
+	let key = Key::new(&metadata);
+	let cache_path = location.path(&canonical);
+	if let (Some(key), Some(cache_path)) = (&key, &cache_path) {
+		let bytes = fs::read(cache_path).ok();
+		if let Some(index) = bytes.and_then(|bytes| CachedIndex::decode(&bytes, key, &canonical)) {
+			return Ok(index);
+		}
+	}
+	let mut zip = ZipArchive::new(BufReader::new(file))?;
+	let (entries, central_header_starts) = index_entries(&mut zip)?.into_iter().unzip();
+	let index = CachedIndex {
+		len: metadata.len(),
+		entries,
+		central_header_starts,
+	};
+	// Store only if the archive has not been modified while parsing it.
+	let unchanged = fs::metadata(&canonical)
+		.ok()
+		.and_then(|metadata| Key::new(&metadata));
+	if let (Some(key), Some(cache_path)) = (key, cache_path) {
+		if unchanged == Some(key) {
+			let _ = store(&index, &key, &canonical, &cache_path);
+		}
+	}
+	Ok(index)
+}
+
+/// Writes the cache file atomically.
+fn store(
+	index: &CachedIndex,
+	key: &Key,
+	canonical: &Path,
+	cache_path: &Path,
+) -> Result<(), ZipError> {
+	let bytes = index.encode(key, canonical)?;
+	if let Some(dir) = cache_path.parent() {
+		fs::create_dir_all(dir)?;
+	}
+	let mut temp: OsString = cache_path.as_os_str().to_owned();
+	temp.push(format!(".{}.tmp", process::id()));
+	fs::write(&temp, bytes)?;
+	fs::rename(&temp, cache_path).map_err(|err| {
+		let _ = fs::remove_file(&temp);
+		err.into()
+	})
+}
@@ -0,0 +1,133 @@
+use std::{fmt::Write, str::CharIndices};
+
+/// Encodes `strings` as JSON array.
+pub(crate) fn json_strings(strings: &[&str]) -> String {
+	let strings = strings
+		.iter()
+		.map(|string| json_string(string))
+		.collect::<Vec<_>>();
+	format!("[{}]", strings.join(", "))
+}
+
+/// Encodes `string` as JSON string.
+pub(crate) fn json_string(string: &str) -> String {
+	let mut json = String::with_capacity(string.len() + 2);
+	json.push('"');
+	for char in string.chars() {
+		match char {
+			'"' => json.push_str("\\\""),
+			'\\' => json.push_str("\\\\"),
+			'\n' => json.push_str("\\n"),
+			'\r' => json.push_str("\\r"),
+			'\t' => json.push_str("\\t"),
+			char if char.is_control() => {
+				let _ = write!(json, "\\u{:04x}", u32::from(char));
+			}
+			char => json.push(char),
+		}
+	}
+	json.push('"');
+	json
+}
+
+/// Parses a JSON array of strings.
+pub(crate) fn parse_json_strings(json: &str) -> Option<Vec<String>> {
+	let mut json = json
+		.trim()
+		.strip_prefix('[')?
+		.strip_suffix(']')?
+		.trim_start();
+	let mut strings = Vec::new();
+	while !json.is_empty() {
+		let (string, rest) = parse_json_string(json)?;
+		strings.push(string);
+		let rest = rest.trim_start();
+		json = match rest.strip_prefix(',') {
+			Some(rest) => rest.trim_start(),
+			None if rest.is_empty() => rest,
+			None => return None,
+		};
+	}
+	Some(strings)
+}
+
+/// Parses a JSON object at the start of `json` whose values are parsed by `value` and returns its
+/// members with the remaining input.
+pub(crate) fn parse_json_object<'a, T, F>(
+	json: &'a str,
+	mut value: F,
+) -> Option<(Vec<(String, T)>, &'a str)>
+where
+	F: FnMut(&'a str) -> Option<(T, &'a str)>,
+{
+	let mut json = json.trim_start().strip_prefix('{')?.trim_start();
+	let mut members = Vec::new();
+	if let Some(rest) = json.strip_prefix('}') {
+		return Some((members, rest));
+	}
+	loop {
+		let (key, rest) = parse_json_string(json)?;
+		let rest = rest.trim_start().strip_prefix(':')?.trim_start();
+		let (item, rest) = value(rest)?;
+		members.push((key, item));
+		let rest = rest.trim_start();
+		match rest.strip_prefix(',') {
+			Some(rest) => json = rest.trim_start(),
+			None => return Some((members, rest.strip_prefix('}')?)),
+		}
+	}
+}
+
+/// Parses a JSON string at the start of `json` and returns it with the remaining input.
+pub(crate) fn parse_json_string(json: &str) -> Option<(String, &str)> {
+	let mut chars = json.strip_prefix('"')?.char_indices();
+	let mut string = String::new();
+	loop {
+		let (index, char) = chars.next()?;
+		match char {
+			'"' => return Some((string, &json[index + 2..])),
+			'\\' => {
+				let escaped = match chars.next()?.1 {
+					'"' => '"',
+					'\\' => '\\',
+					'/' => '/',
+					'b' => '\u{8}',
+					'f' => '\u{c}',
+					'n' => '\n',
+					'r' => '\r',
+					't' => '\t',
+					'u' => {
+						let high = parse_hex(&mut chars)?;
+						let code = if (0xd800..0xdc00).contains(&high) {
+							if chars.next()?.1 != '\\' || chars.next()?.1 != 'u' {
+								return None;
+							}
+							let low = parse_hex(&mut chars)?;
+							if !(0xdc00..0xe000).contains(&low) {
+								return None;
+							}
+							0x1_0000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+						} else {
+							high
+						};
+						char::from_u32(code)?
+					}
+					_ => return None,
+				};
+				string.push(escaped);
+			}
+			char if char.is_control() => return None,
+			char => string.push(char),
+		}
+	}
+}
+
+/// Parses four hexadecimal digits of a `\u` escape sequence.
+fn parse_hex(chars: &mut CharIndices) -> Option<u32> {
+	let hex = chars.take(4).map(|(_, char)| char).collect::<String>();
+	if hex.len() == 4 {
+		u32::from_str_radix(&hex, 16).ok()
+	} else {
+		None
+	}
+}
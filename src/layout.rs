@@ -0,0 +1,190 @@
+use super::{header::NpyHeader, Dtype, NpzViewMut, NpzWriter, ViewNpzError, WriteNpzError};
+use ndarray_npy::ViewNpyError;
+use std::io::{self, Cursor, Read, Seek, SeekFrom, Write};
+use zip::result::ZipError;
+
+/// Declared `.npy` file of an archive laid out in place, see [`NpzViewMut::create_in`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EntryLayout {
+	name: String,
+	dtype: Dtype,
+	shape: Vec<usize>,
+	fortran_order: bool,
+}
+
+impl EntryLayout {
+	/// Declares the `.npy` file `name` of an array of element type `dtype` and `shape` in
+	/// row-major order.
+	///
+	/// The `name` is used as is, i.e., `.npy` is not appended.
+	#[must_use]
+	pub fn new(name: impl Into<String>, dtype: Dtype, shape: &[usize]) -> Self {
+		Self {
+			name: name.into(),
+			dtype,
+			shape: shape.to_vec(),
+			fortran_order: false,
+		}
+	}
+	/// Sets whether the array is in column-major order.
+	#[must_use]
+	pub fn with_fortran_order(mut self, fortran_order: bool) -> Self {
+		self.fortran_order = fortran_order;
+		self
+	}
+	/// Returns the `.npy` header in native endianness and the size in bytes of the data.
+	fn header(&self) -> Result<(Vec<u8>, u64), ViewNpzError> {
+		let size = self
+			.shape
+			.iter()
+			.try_fold(self.dtype.size(), |size, &axis| size.checked_mul(axis))
+			.filter(|&size| isize::try_from(size).is_ok())
+			.ok_or(ViewNpyError::LengthOverflow)?;
+		let header = NpyHeader {
+			descr: self.dtype.descr(cfg!(target_endian = "big")),
+			fortran_order: self.fortran_order,
+			shape: self.shape.iter().map(|&axis| axis as u64).collect(),
+			version: (1, 0),
+			len: 0,
+		};
+		let header = header
+			.to_bytes()
+			.or_else(|| {
+				NpyHeader {
+					version: (2, 0),
+					..header
+				}
+				.to_bytes()
+			})
+			.ok_or(ViewNpyError::LengthOverflow)?;
+		Ok((header, size as u64))
+	}
+}
+
+impl<'a> NpzViewMut<'a> {
+	/// Returns the size in bytes of the archive laid out by [`Self::create_in`] for `entries`.
+	///
+	/// # Errors
+	///
+	/// Fails with [`ViewNpyError::LengthOverflow`] if the size of an array overflows `isize` or
+	/// with [`ZipError::InvalidArchive`](zip::result::ZipError::InvalidArchive) if names are duplicate.
+	pub fn required_size(entries: &[EntryLayout]) -> Result<usize, ViewNpzError> {
+		let region = lay_out(Region::default(), entries)?;
+		usize::try_from(region.len).map_err(|_| ViewNpyError::LengthOverflow.into())
+	}
+
+	/// Lays out an archive of zero-initialized `entries` in `bytes` and returns its mutable view.
+	///
+	/// Producers can fill a complete archive directly in a shared or memory-mapped buffer in one
+	/// pass by mutably viewing each `.npy` file, whose checksum is updated on [`Drop::drop`]. The
+	/// archive occupies the first [`Self::required_size`] bytes of `bytes`, which must be 64-byte
+	/// aligned like for [`Self::new`].
+	///
+	/// # Example
+	///
+	/// ```
+	/// use aligned_vec::AVec;
+	/// use ndarray::Ix2;
+	/// use ndarray_npz::{Dtype, EntryLayout, NpzReader, NpzViewMut};
+	/// use std::io::Cursor;
+	///
+	/// let entries = [EntryLayout::new("x.npy", Dtype::F64, &[2, 3])];
+	/// let size = NpzViewMut::required_size(&entries)?;
+	/// let mut buffer = AVec::<u8>::from_iter(64, (0..size).map(|_| 0));
+	/// let mut npz = NpzViewMut::create_in(&mut buffer, &entries)?;
+	/// npz.by_name("x.npy")?.view_mut::<f64, Ix2>()?.fill(1.0);
+	/// drop(npz);
+	/// let mut npz = NpzReader::new(Cursor::new(&buffer[..]))?;
+	/// assert_eq!(npz.by_name::<ndarray::OwnedRepr<f64>, Ix2>("x.npy")?.sum(), 6.0);
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Fails like [`Self::required_size`] or with [`ZipError::Io`](zip::result::ZipError::Io) if `bytes` is too small.
+	/// Viewing the laid out archive can fail like [`Self::new`].
+	pub fn create_in(
+		bytes: &'a mut [u8],
+		entries: &[EntryLayout],
+	) -> Result<NpzViewMut<'a>, ViewNpzError> {
+		let region = Region {
+			bytes: Some(&mut *bytes),
+			..Region::default()
+		};
+		let len = usize::try_from(lay_out(region, entries)?.len)
+			.map_err(|_| ViewNpyError::LengthOverflow)?;
+		let bytes = bytes.get_mut(..len).ok_or_else(|| {
+			ZipError::Io(io::Error::new(io::ErrorKind::WriteZero, "buffer too small"))
+		})?;
+		NpzViewMut::new(bytes)
+	}
+}
+
+/// Writes the archive of zero-initialized `entries` to `writer`.
+fn lay_out<W: Write + Seek>(writer: W, entries: &[EntryLayout]) -> Result<W, ViewNpzError> {
+	let mut npz = NpzWriter::new(writer);
+	for entry in entries {
+		let (header, size) = entry.header()?;
+		npz.add_npy_bytes(
+			&*entry.name,
+			Cursor::new(header).chain(io::repeat(0).take(size)),
+		)
+		.map_err(from_write)?;
+	}
+	npz.finish().map_err(from_write)
+}
+
+/// Converts an error of writing the laid out archive.
+fn from_write(err: WriteNpzError) -> ViewNpzError {
+	match err {
+		WriteNpzError::Zip(err) => ViewNpzError::Zip(err),
+		WriteNpzError::Npy(err) => ViewNpzError::WriteNpy(err),
+		WriteNpzError::DuplicateName(_) => {
+			ViewNpzError::Zip(ZipError::InvalidArchive("Duplicate filename"))
+		}
+		err => ViewNpzError::Layout(err),
+	}
+}
+
+/// Writer into the leading region of `bytes` tallying its length or discarding the bytes if none.
+///
+/// Bytes beyond `bytes` are discarded as well, so writing never fails and the archive is finished
+/// without errors logged on drop. Unlike [`Cursor`], seeking to the end seeks to the end of the
+/// region written so far.
+#[derive(Debug, Default)]
+struct Region<'b> {
+	bytes: Option<&'b mut [u8]>,
+	pos: u64,
+	len: u64,
+}
+
+impl Write for Region<'_> {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		if let Some(region) = self.bytes.as_mut().and_then(|bytes| {
+			let pos = usize::try_from(self.pos).ok()?;
+			bytes.get_mut(pos..pos.checked_add(buf.len())?)
+		}) {
+			region.copy_from_slice(buf);
+		}
+		self.pos += buf.len() as u64;
+		self.len = self.len.max(self.pos);
+		Ok(buf.len())
+	}
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+impl Seek for Region<'_> {
+	fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+		let (base, offset) = match pos {
+			SeekFrom::Start(offset) => (0, i64::try_from(offset).unwrap_or(i64::MAX)),
+			SeekFrom::End(offset) => (self.len, offset),
+			SeekFrom::Current(offset) => (self.pos, offset),
+		};
+		self.pos = base
+			.checked_add_signed(offset)
+			.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "seek before start"))?;
+		Ok(self.pos)
+	}
+}
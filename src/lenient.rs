@@ -0,0 +1,151 @@
+use super::header::{NpyHeader, MAGIC};
+use ndarray::{prelude::*, IxDyn, Shape, ShapeBuilder};
+use ndarray_npy::{ViewElement, ViewMutElement, ViewMutNpyExt, ViewNpyError, ViewNpyExt};
+use std::{
+	io::{self, Chain, Cursor, Read},
+	mem::size_of,
+};
+
+/// Strictness of parsing `.npy` headers, see [`NpzReaderBuilder::header_strictness`].
+///
+/// The format specification requires the header dictionary to be padded with spaces and
+/// terminated by a newline such that the whole header is a multiple of 64 bytes. Some third-party
+/// writers pad differently or omit the newline.
+///
+/// [`NpzReaderBuilder::header_strictness`]: crate::NpzReaderBuilder::header_strictness
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum HeaderStrictness {
+	/// Accepts headers with other padding than spaces, e.g., NUL bytes, or without the trailing
+	/// newline.
+	Lenient,
+	/// Accepts headers with any whitespace padding but requires the trailing newline as
+	/// [`ndarray_npy`] does.
+	#[default]
+	Standard,
+	/// Rejects archives with headers not conforming to the format specification on opening them.
+	Strict,
+}
+
+/// Whether the `.npy` header at the start of `bytes` conforms to the format specification.
+pub(crate) fn conforms(bytes: &[u8]) -> bool {
+	let Some(len) = NpyHeader::len(bytes) else {
+		return false;
+	};
+	let start = if bytes[6] == 1 { 10 } else { 12 };
+	let Some(dict) = bytes.get(start..len) else {
+		return false;
+	};
+	let Some((b'\n', dict)) = dict.split_last() else {
+		return false;
+	};
+	let end = dict
+		.iter()
+		.rposition(|&byte| byte == b'}')
+		.map_or(0, |end| end + 1);
+	len % 64 == 0
+		&& dict[end..].iter().all(|&byte| byte == b' ')
+		&& NpyHeader::parse(bytes).is_some()
+}
+
+/// Reads the bytes of the `.npy` header at the start of `reader`.
+///
+/// Returns less bytes if the header is invalid or truncated.
+pub(crate) fn read_header<R: Read>(mut reader: R) -> io::Result<Vec<u8>> {
+	let mut bytes = Vec::new();
+	(&mut reader).take(12).read_to_end(&mut bytes)?;
+	if let Some(len) = NpyHeader::len(&bytes).filter(|&len| len >= bytes.len()) {
+		reader
+			.take((len - bytes.len()) as u64)
+			.read_to_end(&mut bytes)?;
+	}
+	Ok(bytes)
+}
+
+/// Reads the `.npy` header at the start of `reader` and returns the reader of the `.npy` file with
+/// a header conforming to the format specification.
+///
+/// Headers which cannot be parsed are passed through as is.
+pub(crate) fn normalize<R: Read>(mut reader: R) -> io::Result<Chain<Cursor<Vec<u8>>, R>> {
+	let mut bytes = read_header(&mut reader)?;
+	if let Some(header) = NpyHeader::parse(&bytes).and_then(|header| header.to_bytes()) {
+		bytes = header;
+	}
+	Ok(Cursor::new(bytes).chain(reader))
+}
+
+/// Parses the `.npy` header of `data` as [`ndarray_npy`] does or leniently if `lenient`.
+pub(crate) fn header(data: &[u8], lenient: bool) -> Option<NpyHeader> {
+	let header = NpyHeader::parse(data)?;
+	let start = if header.version.0 == 1 { 10 } else { 12 };
+	let dict = &data[start..header.len];
+	(lenient || dict.ends_with(b"\n") && !dict.contains(&0)).then_some(header)
+}
+
+/// Returns the header of the `.npy` file of `data` if it is parsed leniently instead of by
+/// [`ndarray_npy`].
+fn lenient_header(data: &[u8], lenient: bool) -> Option<NpyHeader> {
+	(lenient && data.starts_with(MAGIC) && !conforms(data))
+		.then(|| NpyHeader::parse(data))
+		.flatten()
+}
+
+/// Returns the shape and the number of elements of type `A` described by `header`.
+fn shape<A>(header: &NpyHeader) -> Result<(Shape<IxDyn>, usize), ViewNpyError> {
+	let shape = header
+		.shape
+		.iter()
+		.map(|&axis| usize::try_from(axis).ok())
+		.collect::<Option<Vec<usize>>>()
+		.ok_or(ViewNpyError::LengthOverflow)?;
+	let len = header
+		.elements()
+		.and_then(|len| usize::try_from(len).ok())
+		.filter(|len| {
+			len.checked_mul(size_of::<A>())
+				.is_some_and(|size| isize::try_from(size).is_ok())
+		})
+		.ok_or(ViewNpyError::LengthOverflow)?;
+	Ok((IxDyn(&shape).set_f(header.fortran_order), len))
+}
+
+/// Views the `.npy` file of `data`, leniently parsing its header if `lenient`.
+pub(crate) fn view_npy<A, D>(
+	data: &[u8],
+	lenient: bool,
+) -> Result<ArrayView<'_, A, D>, ViewNpyError>
+where
+	A: ViewElement,
+	D: Dimension,
+{
+	let Some(header) = lenient_header(data, lenient) else {
+		return ArrayView::view_npy(data);
+	};
+	let (shape, len) = shape::<A>(&header)?;
+	let ndim = header.shape.len();
+	let slice = A::bytes_as_slice(&data[header.len..], &header.descr, len)?;
+	ArrayView::from_shape(shape, slice)
+		.map_err(|_| ViewNpyError::LengthOverflow)?
+		.into_dimensionality()
+		.map_err(|_| ViewNpyError::WrongNdim(D::NDIM, ndim))
+}
+
+/// Mutably views the `.npy` file of `data`, leniently parsing its header if `lenient`.
+pub(crate) fn view_mut_npy<A, D>(
+	data: &mut [u8],
+	lenient: bool,
+) -> Result<ArrayViewMut<'_, A, D>, ViewNpyError>
+where
+	A: ViewMutElement,
+	D: Dimension,
+{
+	let Some(header) = lenient_header(data, lenient) else {
+		return ArrayViewMut::view_mut_npy(data);
+	};
+	let (shape, len) = shape::<A>(&header)?;
+	let ndim = header.shape.len();
+	let slice = A::bytes_as_mut_slice(&mut data[header.len..], &header.descr, len)?;
+	ArrayViewMut::from_shape(shape, slice)
+		.map_err(|_| ViewNpyError::LengthOverflow)?
+		.into_dimensionality()
+		.map_err(|_| ViewNpyError::WrongNdim(D::NDIM, ndim))
+}
@@ -7,25 +7,147 @@
 //! # Accessing [`.npz`] Files
 //!
 //!   * Reading: [`NpzReader`]
+//!       * Listing arrays and blobs: [`NpzReader::npy_names`], [`NpzReader::blob_names`]
+//!       * Reducing arrays without reading them: [`NpzReader::reduce`], [`NpzReader::stats`]
+//!       * Finding `NaN` and infinity: [`NpzReader::find_nonfinite`]
+//!       * Opening `.npz` files compressed as a whole: [`NpzReader::open`]
+//!       * Spilling huge compressed arrays to temporary files for memory-mapping:
+//!         [`NpzReader::spill`]
+//!       * Caching decompressed arrays: [`NpzReader::with_cache`]
+//!       * Reporting zip features: [`NpzReader::capabilities`]
+//!       * Reading multi-volume archives: [`NpzReader::open_volumes`]
+//!       * Enforcing ingestion policies: [`NpzReaderBuilder`]
+//!       * Parsing nonconforming `.npy` headers: [`HeaderStrictness`]
+//!       * Listing files as JSON lines: [`NpzReader::list_entries_jsonl`]
+//!       * Reading arrays under a name prefix: [`NpzReader::read_prefixed`]
+//!       * Reading arrays of 7 or more axes: [`NpzReader::by_name_dyn`]
+//!       * Bounding the time of reading an array: [`NpzReader::with_entry_timeout`]
+//!       * Bounding the time of retrying: [`RetryPolicy::with_deadline`]
 //!   * Writing: [`NpzWriter`]
+//!       * Adding collections of arrays: [`NpzWriter::add_arrays`]
+//!       * Appending to existing files: [`NpzWriter::append`]
+//!       * Aligning files to page boundaries: [`NpzWriter::with_alignment`]
+//!       * Setting modification times and permissions: [`NpzWriter::with_last_modified_time`],
+//!         [`NpzWriter::with_unix_permissions`]
+//!       * Embedding comments: [`NpzWriter::set_comment`]
+//!       * Setting file options per array: [`NpzWriter::add_array_with_options`]
+//!       * Writing arrays in Fortran order: [`NpzWriter::add_array_f`]
+//!       * Fixing the byte order: [`NpzWriter::with_endianness`]
+//!       * Setting several options at once: [`NpzWriterBuilder`]
+//!       * Matching NumPy's output: [`NpzWriter::numpy_compat`]
+//!       * Compressing with *zstd* or *bzip2*: [`NpzWriter::new_zstd`], [`NpzWriter::new_bzip2`]
+//!       * Compressing large arrays on all cores: [`NpzWriter::with_parallel_deflate`]
+//!       * Adding blobs next to arrays: [`NpzWriter::add_bytes`]
+//!       * Adding pre-serialized `.npy` files: [`NpzWriter::add_npy_bytes`]
+//!       * Writing on a background thread with bounded memory: [`PipelinedWriter`]
+//!       * Ordering files: [`NpzWriter::set_entry_order`]
+//!       * Replacing files added under the same name: [`NpzWriter::overwrite_duplicates`]
+//!       * Packing tiny arrays into a single file: [`NpzWriter::with_packing`]
+//!       * Chunking huge arrays for delta synchronization: [`NpzWriter::with_chunking`]
+//!       * Storing incompressible files: [`NpzWriter::with_compression_guard`]
+//!       * Indexing files for faster opens: [`NpzWriter::with_index`]
+//!       * Synchronizing files with the storage device: [`NpzWriter::finish_and_sync`]
+//!       * Atomically replacing files: [`NpzWriter::create_atomic`]
+//!       * Checking written files: [`NpzWriter::with_self_check`]
+//!       * Reporting the progress of writing arrays: [`NpzWriter::on_progress`]
+//!   * Writing files in one call: [`write_npz`], [`write_npz_compressed`]
+//!   * Buffering writes of many tiny arrays to files: [`BufSeekWriter`]
+//!   * Reporting the layout of writes without touching storage: [`DryRunWriter`],
+//!     [`NpzWriter::finish_dry_run`]
+//!   * Verifying reproducible output: [`verify_deterministic`]
+//!   * Viewing the entries written so far: [`NpzWriter::snapshot`]
+//!   * Caching parsed archives across processes: [`cached_index`]
+//!   * Extracting files portable to all platforms: [`NpzReader::extract`], [`portable_path`]
+//!   * Validating names and building hierarchical ones: [`EntryName`]
 //!   * Immutable viewing (primarily for use with memory-mapped files):
 //!       * [`NpzView`] providing an [`NpyView`] for each uncompressed [`.npy`] file within
 //!         the archive
+//!       * Diagnosing compressed and misaligned files: [`NpzView::diagnostics`]
+//!       * Reporting checksums of all files once: [`NpzView::checksum_report`]
+//!       * Enforcing ingestion policies: [`NpzViewBuilder`]
+//!       * Falling back to reading or casting: [`NpzView::get_flexible`]
+//!       * Viewing small windows of giant arrays: [`NpyView::view_slice`]
+//!       * Listing viewable element types: [`supported_view_dtypes`]
 //!   * Mutable viewing (primarily for use with memory-mapped files):
 //!       * [`NpzViewMut`] providing an [`NpyViewMut`] for each uncompressed [`.npy`] file within
 //!         the archive
+//!       * Laying out whole archives in sized buffers: [`NpzViewMut::create_in`]
+//!       * Editing copy-on-write without touching files or checksums:
+//!         [`NpzViewMut::without_checksum_updates`]
+//!   * Editing in place (primarily for patching metadata of huge files): [`NpzEditor`]
+//!   * Patching small files in place via a reader: [`NpzReader::patch_bytes`],
+//!     [`NpzReader::patch_scalar`]
+//!   * Replacing data of equal shape in place: [`NpzViewMut::replace_entry_data`]
+//!   * Transforming many arrays in place: [`NpzViewMut::apply`], [`NpzViewMut::apply_parallel`],
+//!     [`NpzViewMut::scope`]
+//!   * Compacting after editing: [`compact`]
+//!   * Scrubbing metadata of files before publishing: [`strip_metadata`]
+//!   * Pruning files outside of a retention window: [`prune`]
+//!   * Validating expected files up front: [`NpzReader::require`], [`NpzView::require`]
+//!   * Comparing archives by checksums without reading arrays: [`quick_compare`]
+//!   * Comparing huge files by streaming them in blocks: [`block_diff`]
+//!   * Converting element types: [`convert_entry_dtype`]
+//!   * Reporting byte-swapping, casting, and layout changes required at load time:
+//!     [`coercion_report`]
+//!   * Bundling into a single `.npy` file of a structured data type: [`pack_bundle`],
+//!     [`unpack_bundle`]
+//!   * Quantizing arrays: [`NpzWriter::add_array_quantized`], [`NpzReader::read_dequantized`]
+//!   * Encoding snapshots as deltas: [`NpzWriter::add_array_delta`], [`NpzReader::by_name_delta`]
+//!   * Labeling axes: [`NpzWriter::add_axis_names`], [`NpzWriter::add_axis_coords`],
+//!     [`NpzReader::by_name_labeled`]
+//!   * Storing categorical arrays: [`Categorical`]
+//!   * Encoding boolean masks as run lengths: [`NpzWriter::add_bool_mask_rle`],
+//!     [`NpzReader::read_bool_mask`]
+//!   * Storing stacks of images: [`NpzWriter::add_image_stack`], [`NpzReader::image_frames`]
+//!   * Recording frames of fixed shape: [`RecorderWriter`]
+//!   * Auditing mutations: [`MutationLog`]
+//!   * Recording provenance: [`Provenance`]
+//!   * Plugging in custom encoders: [`EntrySink`], [`EntrySource`]
+//!   * Branching on failures via stable error codes: [`ErrorCode`], [`ErrorCategory`]
 //!
 //! [`.npy`]: https://numpy.org/doc/stable/reference/generated/numpy.lib.format.html
 //! [`.npz`]: https://numpy.org/doc/stable/reference/generated/numpy.savez.html
 //!
 //! # Features
 //!
-//! Both features are enabled by default.
+//! Enabled by default:
 //!
 //!   * `compressed`: Enables zip archives with *deflate* compression.
 //!   * `num-complex-0_4`: Enables complex element types of crate `num-complex`.
+//!
+//! Disabled by default:
+//!
+//!   * `lock`: Enables advisory file locking via [`LockedFile`] and [`LockedNpz`].
+//!   * `mmap`: Enables copy-on-write memory maps via [`NpzMmap`].
+//!   * `bench`: Enables throughput benchmarking via [`mod@bench`].
+//!   * `test-util`: Enables generating synthetic example archives and the fixtures of the test
+//!     suite via [`example`].
+//!   * `units`: Enables physical units of arrays via [`Unit`].
+//!   * `swap-endian`: Enables mutable native-endian views of foreign-endian `.npy` files via
+//!     [`NpyViewMut::with_native_endian`].
+//!   * `json`: Enables JSON members of serializable values via [`NpzWriter::add_json`].
+//!   * `gzip`: Enables `.npz` files compressed as a whole via *gzip*, see [`NpzReader::open`].
+//!   * `zstd`: Enables `.npz` files compressed as a whole via *zstd*, see [`NpzReader::open`],
+//!     and files compressed via *zstd*, see [`NpzWriter::new_zstd`].
+//!   * `unicode`: Enables Unicode normalization of names, see [`portable_path`] and
+//!     [`NpzReader::by_name`].
+//!   * `bzip2`: Enables reading and writing files compressed via *bzip2*, see
+//!     [`NpzReader::unsupported_names`] and [`NpzWriter::new_bzip2`].
+//!   * `lzma`: Enables reading files compressed via *LZMA* or *XZ*, see
+//!     [`NpzReader::unsupported_names`].
+//!   * `parallel-deflate`: Enables compressing large arrays on all cores via
+//!     [`NpzWriter::with_parallel_deflate`]. Implies `compressed`.
+//!   * `ffi`: Enables a C foreign function interface via [`ffi`].
+//!   * `pyo3`: Enables a Python extension module `ndarray_npz` of NumPy arrays.
 
-#![forbid(unsafe_code)]
+#![cfg_attr(
+	not(any(feature = "ffi", feature = "mmap", feature = "pyo3")),
+	forbid(unsafe_code)
+)]
+#![cfg_attr(
+	any(feature = "ffi", feature = "mmap", feature = "pyo3"),
+	deny(unsafe_code)
+)]
 #![deny(
 	missing_docs,
 	rustdoc::broken_intra_doc_links,
@@ -36,24 +158,188 @@
 
 // [`NpzReader`] and [`NpzWriter`] are derivative works of [`ndarray_npy`].
 
+mod append;
+mod apply;
+mod audit;
+mod axes;
+#[cfg(feature = "bench")]
+pub mod bench;
+mod blob;
+mod buffer;
+mod builder;
+mod bundle;
+mod cache;
+mod capability;
+mod categorical;
+mod chunking;
+mod code;
+mod coercion;
+mod compact;
+mod compare;
+#[cfg(feature = "json")]
+mod config;
+mod container;
+mod convert;
+mod delta;
+mod determinism;
+mod diagnostics;
+mod dry_run;
+mod edit;
+mod entry;
+#[cfg(feature = "test-util")]
+pub mod example;
+#[cfg(feature = "ffi")]
+#[allow(unsafe_code)]
+pub mod ffi;
+mod flexible;
+mod guard;
+mod header;
+mod image;
+mod index;
+mod index_cache;
+mod json;
+mod layout;
+mod lenient;
+mod limit;
+mod listing;
+#[cfg(feature = "lock")]
+mod lock;
+mod mask;
+#[cfg(feature = "mmap")]
+#[allow(unsafe_code)]
+mod mmap;
+mod name;
+mod order;
+mod packing;
+#[cfg(feature = "parallel-deflate")]
+mod parallel;
+mod patch;
+mod pipeline;
+mod poison;
+mod portable;
+mod prefix;
+mod presence;
+mod progress;
+mod provenance;
+mod prune;
+#[cfg(feature = "pyo3")]
+#[allow(unsafe_code)]
+mod python;
+mod quantize;
+mod recorder;
+mod reduce;
+mod replace;
+mod retry;
+mod save;
+mod scope;
+mod snapshot;
+mod spill;
+#[cfg(feature = "swap-endian")]
+mod swap;
+mod sync;
+mod timeout;
+#[cfg(feature = "units")]
+mod unit;
+mod verify;
+mod viewable;
+mod volume;
+mod window;
+
+pub use audit::{Mutation, MutationLog, MUTATION_LOG_NAME};
+pub use axes::LabeledArray;
+pub use buffer::BufSeekWriter;
+pub use builder::{
+	Duplicates, Endianness, Limits, NpzReaderBuilder, NpzViewBuilder, NpzWriterBuilder,
+};
+pub use bundle::{pack_bundle, unpack_bundle};
+pub use cache::CacheStats;
+pub use capability::Capabilities;
+pub use categorical::Categorical;
+pub use chunking::{CHUNKS_NAME, CHUNK_PREFIX};
+pub use code::{ErrorCategory, ErrorCode};
+pub use coercion::{coercion_report, Coercion};
+pub use compact::{compact, strip_metadata, Compaction};
+pub use compare::{block_diff, quick_compare, BlockDiff, ComparisonSummary};
+#[cfg(feature = "json")]
+pub use config::JsonNpzError;
+pub use container::{Buffering, Container, ContainerReader};
+pub use convert::{convert_entry_dtype, Conversion, Dtype};
+pub use delta::{DeltaElement, DeltaEncoding};
+pub use determinism::{verify_deterministic, Divergence, Region};
+pub use diagnostics::{Diagnostic, Fix, Issue};
+pub use dry_run::{DryRunReport, DryRunWriter};
+pub use edit::{EditNpzError, NpzEditor};
+pub use entry::{EntrySink, EntrySource};
+pub use flexible::{Access, Fallbacks};
+pub use guard::Inflation;
+pub use image::{ImageElement, ImageFrames};
+pub use index::{IndexEntry, IndexedNpy, INDEX_NAME};
+pub use index_cache::{cached_index, CacheLocation, CachedIndex};
+pub use layout::EntryLayout;
+pub use lenient::HeaderStrictness;
+#[cfg(feature = "lock")]
+pub use lock::{LockedFile, LockedNpz};
+#[cfg(feature = "mmap")]
+pub use mmap::NpzMmap;
+pub use name::{EntryName, EntryNameError};
 pub use ndarray;
 pub use ndarray_npy;
+pub use order::Order;
+pub use packing::{PACKED_INDEX_NAME, PACKED_NAME};
+pub use pipeline::{PipelineMetrics, PipelinedWriter};
+pub use portable::portable_path;
+pub use presence::MissingReport;
+pub use provenance::{Provenance, PROVENANCE_NAME};
+pub use prune::{prune, EntryInfo, Pruning};
+pub use quantize::{DequantizedElement, Quantization, QuantizedElement};
+pub use recorder::RecorderWriter;
+pub use reduce::{Reduction, Stats};
+pub use retry::{RetryPolicy, RetryReader};
+pub use save::write_npz;
+#[cfg(feature = "compressed")]
+pub use save::write_npz_compressed;
+pub use snapshot::Snapshot;
+pub use spill::SpilledNpy;
+pub use sync::{AtomicFile, SyncData};
+#[cfg(feature = "units")]
+pub use unit::{Ampere, Candela, Kelvin, Kilogram, Metre, Mole, ReadUnitError, Second, Unit};
+pub use verify::{ChecksumReport, Sample, SampleReport};
+pub use viewable::supported_view_dtypes;
+pub use volume::VolumeReader;
 
+use builder::BUFFER_SIZE;
+use cache::EntryCache;
+use capability::{unsupported_names, UNSUPPORTED_COMPRESSION};
+use chunking::{Chunked, Chunking};
+use determinism::SelfCheck;
+use guard::CompressionGuard;
+use header::MAGIC;
+use index::AddIndex;
+use limit::Bounded;
 use ndarray::{
 	prelude::*,
 	{Data, DataOwned},
 };
 use ndarray_npy::{
-	ReadNpyError, ReadNpyExt, ReadableElement, ViewElement, ViewMutElement, ViewMutNpyExt,
-	ViewNpyError, ViewNpyExt, WritableElement, WriteNpyError, WriteNpyExt,
+	ReadNpyError, ReadNpyExt, ReadableElement, ViewElement, ViewMutElement, ViewNpyError,
+	WritableElement, WriteNpyError, WriteNpyExt,
 };
+use order::Reorder;
+use packing::{Packed, Packing};
+use poison::POISONED;
+use progress::{npy_len, Progress, ProgressHook};
 use std::{
 	collections::{BTreeMap, HashMap, HashSet},
 	error::Error,
 	fmt,
 	io::{self, BufWriter, Cursor, Read, Seek, Write},
+	mem,
 	ops::Range,
+	str::{self, Utf8Error},
+	sync::OnceLock,
+	time::Duration,
 };
+use timeout::Deadline;
 use zip::{
 	result::ZipError,
 	write::SimpleFileOptions,
@@ -62,11 +348,16 @@ use zip::{
 
 /// An error writing a `.npz` file.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum WriteNpzError {
 	/// An error caused by the zip file.
 	Zip(ZipError),
 	/// An error caused by writing an inner `.npy` file.
 	Npy(WriteNpyError),
+	/// The writer has already been finished or has failed, hence no further files can be added.
+	Finished,
+	/// A file has already been added under the name, see [`Duplicates`].
+	DuplicateName(String),
 }
 
 impl Error for WriteNpzError {
@@ -74,6 +365,7 @@ impl Error for WriteNpzError {
 		match self {
 			WriteNpzError::Zip(err) => Some(err),
 			WriteNpzError::Npy(err) => Some(err),
+			WriteNpzError::Finished | WriteNpzError::DuplicateName(_) => None,
 		}
 	}
 }
@@ -83,6 +375,10 @@ impl fmt::Display for WriteNpzError {
 		match self {
 			WriteNpzError::Zip(err) => write!(f, "zip file error: {err}"),
 			WriteNpzError::Npy(err) => write!(f, "error writing npy file to npz archive: {err}"),
+			WriteNpzError::Finished => write!(f, "npz writer already finished"),
+			WriteNpzError::DuplicateName(name) => {
+				write!(f, "duplicate name in npz archive: {name:?}")
+			}
 		}
 	}
 }
@@ -110,6 +406,11 @@ impl From<WriteNpyError> for WriteNpzError {
 /// standard or Fortran layout, so it's not recommended without testing to
 /// compare.
 ///
+/// Arrays smaller than the buffer are serialized into a reused buffer instead
+/// and written at once. When writing many tiny arrays to a file, e.g., tens of
+/// thousands of scalars, the per-array seeks of the zip archive dominate, which
+/// wrapping the file in a [`BufSeekWriter`] avoids.
+///
 /// # Example
 ///
 /// ```no_run
@@ -131,8 +432,32 @@ impl From<WriteNpyError> for WriteNpzError {
 pub struct NpzWriter<W: Write + Seek> {
 	zip: ZipWriter<W>,
 	options: SimpleFileOptions,
+	alignment: u16,
+	npy_extension: bool,
+	provenance: Option<Provenance>,
+	order: Option<(Order, Reorder<W>)>,
+	packing: Option<Packing>,
+	chunking: Option<Chunking>,
+	guard: Option<CompressionGuard>,
+	index: Option<AddIndex<W>>,
+	check: Option<SelfCheck<W>>,
+	endianness: Endianness,
+	names: HashSet<String>,
+	duplicates: Duplicates,
+	overwrites: Option<HashMap<String, String>>,
+	buffer_size: usize,
+	scratch: Vec<u8>,
+	progress: Option<ProgressHook>,
+	#[cfg(feature = "parallel-deflate")]
+	parallel: Option<parallel::ParallelDeflate>,
 }
 
+// Guarantees writers to be `Send` and `Sync` if their underlying writer is.
+const _: () = {
+	const fn assert_send_sync<T: Send + Sync>() {}
+	assert_send_sync::<NpzWriter<io::Cursor<Vec<u8>>>>();
+};
+
 impl<W: Write + Seek> NpzWriter<W> {
 	/// Creates a new `.npz` file without compression. See [`numpy.savez`].
 	///
@@ -141,33 +466,277 @@ impl<W: Write + Seek> NpzWriter<W> {
 	/// [`numpy.savez`]: https://numpy.org/doc/stable/reference/generated/numpy.savez.html
 	#[must_use]
 	pub fn new(writer: W) -> NpzWriter<W> {
-		NpzWriter {
-			zip: ZipWriter::new(writer),
-			options: SimpleFileOptions::default()
+		NpzWriter::with_options(
+			ZipWriter::new(writer),
+			SimpleFileOptions::default()
 				.with_alignment(64)
 				.compression_method(CompressionMethod::Stored),
+		)
+	}
+
+	/// Creates a new `.npz` file without compression matching the byte layout of [`numpy.savez`]
+	/// as closely as possible, e.g., for golden-file tests against Python's output.
+	///
+	/// Like NumPy, it appends `.npy` to names of added arrays lacking it, forces Zip64 extra
+	/// fields, sets the permissions to `0o600`, and does not align the `.npy` files. The `.npy`
+	/// headers are padded to a multiple of 64 bytes as by NumPy anyway. What remains to differ are
+	/// the modification times, which NumPy sets to the current local time, see
+	/// [`Self::with_last_modified_time`].
+	///
+	/// [`numpy.savez`]: https://numpy.org/doc/stable/reference/generated/numpy.savez.html
+	#[must_use]
+	pub fn numpy_compat(writer: W) -> NpzWriter<W> {
+		NpzWriter {
+			alignment: 1,
+			npy_extension: true,
+			..NpzWriter::with_options(
+				ZipWriter::new(writer),
+				SimpleFileOptions::default()
+					.compression_method(CompressionMethod::Stored)
+					.large_file(true)
+					.unix_permissions(0o600),
+			)
 		}
 	}
 
+	/// Sets the modification time of subsequently added files.
+	#[must_use]
+	pub fn with_last_modified_time(mut self, time: zip::DateTime) -> Self {
+		self.options = self.options.last_modified_time(time);
+		self
+	}
+
+	/// Sets the Unix permissions of subsequently added files, e.g., `0o644`.
+	///
+	/// Only the permission bits of the `mode` are kept, whereas the file type is set by the zip
+	/// archive. Defaults to `0o644` except for [`Self::numpy_compat`]. Permissions are applied on
+	/// extraction by tools respecting them, e.g., `unzip`.
+	#[must_use]
+	pub fn with_unix_permissions(mut self, mode: u32) -> Self {
+		self.options = self.options.unix_permissions(mode);
+		self
+	}
+
+	/// Sets the `comment` of the archive, e.g., a provenance string like the git hash and the
+	/// parameters of the producing run, see [`NpzReader::comment`].
+	///
+	/// The comment is stored in the end of central directory record without adding a file, hence
+	/// it is truncated to 65535 bytes and ignored by NumPy.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use ndarray::Array1;
+	/// use ndarray_npz::{NpzReader, NpzWriter};
+	/// use std::io::Cursor;
+	///
+	/// let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	/// npz.set_comment("git 1a2b3c4 lr=0.001");
+	/// npz.add_array("x", &Array1::<f64>::zeros(3))?;
+	/// let npz = NpzReader::new(npz.finish()?)?;
+	/// assert_eq!(npz.comment()?, "git 1a2b3c4 lr=0.001");
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	pub fn set_comment(&mut self, comment: impl Into<String>) {
+		let mut comment = comment.into();
+		if comment.len() > usize::from(u16::MAX) {
+			let mut len = usize::from(u16::MAX);
+			while !comment.is_char_boundary(len) {
+				len -= 1;
+			}
+			comment.truncate(len);
+		}
+		self.zip.set_comment(comment);
+	}
+
 	/// Creates a new `.npz` file with compression. See [`numpy.savez_compressed`].
 	///
+	/// Compresses with the default level unless set via [`NpzWriter::with_compression_level`].
+	///
 	/// [`numpy.savez_compressed`]: https://numpy.org/doc/stable/reference/generated/numpy.savez_compressed.html
 	#[cfg(feature = "compressed")]
 	#[must_use]
 	pub fn new_compressed(writer: W) -> NpzWriter<W> {
+		NpzWriter::with_options(
+			ZipWriter::new(writer),
+			SimpleFileOptions::default().compression_method(CompressionMethod::Deflated),
+		)
+	}
+
+	/// Creates a new `.npz` file with *zstd* compression, which is faster and smaller than
+	/// deflate but cannot be read by NumPy.
+	///
+	/// Compresses with the default level unless set via [`NpzWriter::with_compression_level`].
+	/// Files compressed via *zstd* are read transparently by [`NpzReader`] with the `zstd`
+	/// feature.
+	///
+	/// # Example
+	///
+	/// ```
+	/// # #[cfg(feature = "zstd")]
+	/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+	/// use ndarray::Array1;
+	/// use ndarray_npz::{NpzReader, NpzWriter};
+	/// use std::io::Cursor;
+	///
+	/// let mut npz = NpzWriter::new_zstd(Cursor::new(Vec::new())).with_compression_level(9);
+	/// npz.add_array("x", &Array1::<f64>::zeros(1000))?;
+	/// let mut npz = NpzReader::new(npz.finish()?)?;
+	/// let x: Array1<f64> = npz.by_name("x")?;
+	/// # Ok(())
+	/// # }
+	/// # #[cfg(not(feature = "zstd"))]
+	/// # fn main() {}
+	/// ```
+	#[cfg(feature = "zstd")]
+	#[must_use]
+	pub fn new_zstd(writer: W) -> NpzWriter<W> {
+		NpzWriter::with_options(
+			ZipWriter::new(writer),
+			SimpleFileOptions::default().compression_method(CompressionMethod::Zstd),
+		)
+	}
+
+	/// Creates a new `.npz` file with *bzip2* compression as used by some older Python tooling.
+	///
+	/// Compresses with the default level unless set via [`NpzWriter::with_compression_level`].
+	/// Files compressed via *bzip2* are read transparently by [`NpzReader`] with the `bzip2`
+	/// feature.
+	#[cfg(feature = "bzip2")]
+	#[must_use]
+	pub fn new_bzip2(writer: W) -> NpzWriter<W> {
+		NpzWriter::with_options(
+			ZipWriter::new(writer),
+			SimpleFileOptions::default().compression_method(CompressionMethod::Bzip2),
+		)
+	}
+
+	/// Creates a new `.npz` file of `zip` adding files with `options` and the defaults otherwise.
+	pub(crate) fn with_options(zip: ZipWriter<W>, options: SimpleFileOptions) -> NpzWriter<W> {
 		NpzWriter {
-			zip: ZipWriter::new(writer),
-			options: SimpleFileOptions::default().compression_method(CompressionMethod::Deflated),
+			zip,
+			options,
+			alignment: 64,
+			npy_extension: false,
+			provenance: None,
+			order: None,
+			packing: None,
+			chunking: None,
+			guard: None,
+			index: None,
+			check: None,
+			endianness: Endianness::Native,
+			names: HashSet::new(),
+			duplicates: Duplicates::Reject,
+			overwrites: None,
+			buffer_size: BUFFER_SIZE,
+			scratch: Vec::new(),
+			progress: None,
+			#[cfg(feature = "parallel-deflate")]
+			parallel: None,
 		}
 	}
 
+	/// Sets the compression `level` of subsequently added files, trading speed for size.
+	///
+	/// Deflate and *bzip2* support levels from `1` (fastest) to `9` (smallest) and default to `6`,
+	/// *zstd* supports levels from `-7` to `22` and defaults to `3`. Adding a file with an
+	/// unsupported level or without compression fails with [`WriteNpzError::Zip`].
+	///
+	/// # Example
+	///
+	/// ```
+	/// # #[cfg(feature = "compressed")]
+	/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+	/// use ndarray::Array1;
+	/// use ndarray_npz::NpzWriter;
+	/// use std::io::Cursor;
+	///
+	/// let mut npz = NpzWriter::new_compressed(Cursor::new(Vec::new())).with_compression_level(1);
+	/// npz.add_array("x", &Array1::<f64>::zeros(1000))?;
+	/// npz.finish()?;
+	/// # Ok(())
+	/// # }
+	/// # #[cfg(not(feature = "compressed"))]
+	/// # fn main() {}
+	/// ```
+	#[cfg(any(feature = "compressed", feature = "bzip2", feature = "zstd"))]
+	#[must_use]
+	pub fn with_compression_level(mut self, level: i64) -> Self {
+		self.options = self.options.compression_level(Some(level));
+		self
+	}
+
+	/// Sets the `alignment` in bytes of subsequently added files, see
+	/// [`NpzWriterBuilder::alignment`]. Defaults to 64 bytes without compression.
+	///
+	/// Aligning to page boundaries, e.g., `4096` bytes, suits direct I/O and staging buffers of
+	/// GPUs, whereas the array data within the `.npy` file follows its header, which is padded to
+	/// a multiple of 64 bytes. Only `.npy` files aligned to their element size can be viewed, hence
+	/// `0` or `1` disables alignment when the file size matters more than memory-mapping.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use ndarray::Array1;
+	/// use ndarray_npz::NpzWriter;
+	/// use std::io::Cursor;
+	///
+	/// let mut npz = NpzWriter::new(Cursor::new(Vec::new())).with_alignment(4096);
+	/// npz.add_array("x", &Array1::<f64>::zeros(1000))?;
+	/// npz.finish()?;
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	#[must_use]
+	pub fn with_alignment(mut self, alignment: u16) -> Self {
+		self.options = self.options.with_alignment(alignment);
+		self.alignment = alignment;
+		self
+	}
+
+	/// Sets the byte order of subsequently added arrays, see [`NpzWriterBuilder::endianness`].
+	/// Defaults to [`Endianness::Native`].
+	///
+	/// Fixing the byte order makes archives written on big-endian targets, e.g., s390x, identical
+	/// to those written on little-endian ones, e.g., x86. Arrays of foreign byte order are swapped
+	/// while writing them, including arrays packed via [`Self::with_packing`] or chunked via
+	/// [`Self::with_chunking`]. This requires an element type of [`Dtype`] or complex numbers,
+	/// whereas other element types are written in native byte order. Arrays are read in either
+	/// byte order on any target, but only viewed via [`NpzView`] on targets of their byte order.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use ndarray::Array1;
+	/// use ndarray_npz::{Endianness, NpzReader, NpzWriter};
+	/// use std::io::Cursor;
+	///
+	/// let mut npz = NpzWriter::new(Cursor::new(Vec::new())).with_endianness(Endianness::Big);
+	/// npz.add_array("x", &Array1::from(vec![1.0f64, 2.0]))?;
+	/// let mut npz = NpzReader::new(npz.finish()?)?;
+	/// let x: Array1<f64> = npz.by_name("x")?;
+	/// assert_eq!(x, Array1::from(vec![1.0, 2.0]));
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	#[must_use]
+	pub fn with_endianness(mut self, endianness: Endianness) -> Self {
+		self.endianness = endianness;
+		self
+	}
+
 	/// Adds an array with the specified `name` to the `.npz` file.
 	///
 	/// To write a scalar value, create a zero-dimensional array using [`arr0`] or [`aview0`].
 	///
+	/// Arrays which may exceed 4 GiB are written with Zip64 extra fields like by NumPy, whereas
+	/// archives exceeding 4 GiB as a whole get a Zip64 end of central directory record anyway.
+	///
 	/// # Errors
 	///
-	/// Adding an array can fail with [`WriteNpyError`].
+	/// Adding an array can fail with [`WriteNpyError`]. Adding an array under a name already taken
+	/// fails with [`WriteNpzError::DuplicateName`] unless configured otherwise, see [`Duplicates`]
+	/// and [`Self::overwrite_duplicates`].
+	#[allow(clippy::case_sensitive_file_extension_comparisons)]
 	pub fn add_array<N, S, D>(
 		&mut self,
 		name: N,
@@ -179,11 +748,203 @@ impl<W: Write + Seek> NpzWriter<W> {
 		S: Data,
 		D: Dimension,
 	{
-		self.zip.start_file(name.into(), self.options)?;
-		array.write_npy(BufWriter::new(&mut self.zip))?;
+		let mut name = name.into();
+		if self.npy_extension && !name.ends_with(".npy") {
+			name.push_str(".npy");
+		}
+		let len = array.len().saturating_mul(mem::size_of::<S::Elem>());
+		let options = self.sized_options(u64::try_from(len).unwrap_or(u64::MAX));
+		let options = mem::replace(&mut self.options, options);
+		let result = self.add_array_sized(name, array);
+		self.options = options;
+		result
+	}
+
+	/// Adds an array with the options sized by [`Self::sized_options`].
+	fn add_array_sized<S, D>(
+		&mut self,
+		mut name: String,
+		array: &ArrayBase<S, D>,
+	) -> Result<(), WriteNpzError>
+	where
+		S::Elem: WritableElement,
+		S: Data,
+		D: Dimension,
+	{
+		if self.claim_name(&mut name)? {
+			return Ok(());
+		}
+		if self.add_packable(&name, array)?
+			|| self.add_chunkable(&name, array)?
+			|| self.add_foreign_endian(&name, array)?
+			|| self.add_guardable(&name, array)?
+		{
+			self.report_written(&name, || npy_len(array));
+			return Ok(());
+		}
+		#[cfg(feature = "parallel-deflate")]
+		if self.add_parallel(&name, array)? {
+			return Ok(());
+		}
+		self.zip.start_file(name.as_str(), self.options)?;
+		let total = || npy_len(array);
+		let mut zip = Progress::new(&mut self.zip, &name, total, self.progress.as_mut());
+		if array.len().saturating_mul(mem::size_of::<S::Elem>()) < self.buffer_size {
+			// Serializes tiny arrays into a reused buffer and writes them at once, avoiding both
+			// the allocation of a buffered writer and many small writes per file.
+			self.scratch.clear();
+			array.write_npy(&mut self.scratch)?;
+			zip.write_all(&self.scratch).map_err(ZipError::from)?;
+		} else {
+			array.write_npy(BufWriter::with_capacity(self.buffer_size, zip))?;
+		}
 		Ok(())
 	}
 
+	/// Returns the file options enabling Zip64 extra fields if a file of `len` bytes of data may
+	/// exceed 4 GiB, leaving headroom for its `.npy` header and for the expansion of
+	/// incompressible data by compression.
+	pub(crate) fn sized_options(&self, len: u64) -> SimpleFileOptions {
+		let len = len.saturating_add(len / 256).saturating_add(1 << 16);
+		if len >= u64::from(u32::MAX) {
+			self.options.large_file(true)
+		} else {
+			self.options
+		}
+	}
+
+	/// Like [`Self::add_array`] but with the file `options` of this array only, e.g., its
+	/// compression method, level, alignment, and modification time.
+	///
+	/// Note that without alignment, the `.npy` file cannot be memory-mapped via [`NpzView`] or
+	/// [`NpzViewMut`] unless it happens to be aligned.
+	///
+	/// # Example
+	///
+	/// ```
+	/// # #[cfg(feature = "compressed")]
+	/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+	/// use ndarray::{array, Array2};
+	/// use ndarray_npz::NpzWriter;
+	/// use std::io::Cursor;
+	/// use zip::{write::SimpleFileOptions, CompressionMethod};
+	///
+	/// let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	/// npz.add_array("meta", &array![1, 2, 3])?;
+	/// let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+	/// npz.add_array_with_options("data", &Array2::<f64>::zeros((100, 100)), options)?;
+	/// npz.finish()?;
+	/// # Ok(())
+	/// # }
+	/// # #[cfg(not(feature = "compressed"))]
+	/// # fn main() {}
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Adding an array can fail with [`WriteNpyError`] or with [`ZipError`] if the `options` are
+	/// not supported.
+	pub fn add_array_with_options<N, S, D>(
+		&mut self,
+		name: N,
+		array: &ArrayBase<S, D>,
+		options: SimpleFileOptions,
+	) -> Result<(), WriteNpzError>
+	where
+		N: Into<String>,
+		S::Elem: WritableElement,
+		S: Data,
+		D: Dimension,
+	{
+		let options = mem::replace(&mut self.options, options);
+		let result = self.add_array(name, array);
+		self.options = options;
+		result
+	}
+
+	/// Like [`Self::add_array`] but writes the `.npy` file in column-major order, i.e., with
+	/// `fortran_order: True` in its header, e.g., for consumers in Fortran or Julia.
+	///
+	/// Arrays in Fortran layout are passed through without copying, whereas others are copied
+	/// into Fortran layout first. Arrays of less than two dimensions are the same in either order.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use ndarray::{array, Array2};
+	/// use ndarray_npz::{NpzReader, NpzWriter};
+	/// use std::io::Cursor;
+	///
+	/// let x = array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]];
+	/// let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	/// npz.add_array_f("x", &x)?;
+	/// let mut npz = NpzReader::new(npz.finish()?)?;
+	/// let y: Array2<f64> = npz.by_name("x")?;
+	/// assert_eq!(y, x);
+	/// assert!(y.t().is_standard_layout());
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Adding an array can fail with [`WriteNpyError`].
+	pub fn add_array_f<N, S, D>(
+		&mut self,
+		name: N,
+		array: &ArrayBase<S, D>,
+	) -> Result<(), WriteNpzError>
+	where
+		N: Into<String>,
+		S::Elem: WritableElement + Clone,
+		S: Data,
+		D: Dimension,
+	{
+		// The reversed axes of the transposed array in standard layout are in Fortran layout.
+		let transposed = array.t();
+		let array = transposed.as_standard_layout().reversed_axes();
+		self.add_array(name, &array)
+	}
+
+	/// Adds each array of `arrays` with its name in iteration order, see [`Self::add_array`].
+	///
+	/// Writes collections of named arrays in one call, e.g., a [`BTreeMap`] of arrays or an
+	/// iterator zipping names with arrays of the same type.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use ndarray::Array1;
+	/// use ndarray_npz::{NpzReader, NpzWriter};
+	/// use std::{collections::BTreeMap, io::Cursor};
+	///
+	/// let arrays = BTreeMap::from([
+	/// 	("bias".to_owned(), Array1::<f32>::zeros(4)),
+	/// 	("weights".to_owned(), Array1::<f32>::ones(16)),
+	/// ]);
+	/// let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	/// npz.add_arrays(&arrays)?;
+	/// let mut npz = NpzReader::new(npz.finish()?)?;
+	/// assert_eq!(npz.names()?, ["bias", "weights"]);
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Fails like [`Self::add_array`] on the first array which cannot be added, whereas the
+	/// arrays added before are kept.
+	pub fn add_arrays<'a, I, N, S, D>(&mut self, arrays: I) -> Result<(), WriteNpzError>
+	where
+		I: IntoIterator<Item = (N, &'a ArrayBase<S, D>)>,
+		N: Into<String>,
+		S::Elem: WritableElement,
+		S: Data + 'a,
+		D: Dimension + 'a,
+	{
+		arrays
+			.into_iter()
+			.try_for_each(|(name, array)| self.add_array(name, array))
+	}
+
 	/// Calls [`.finish()`](ZipWriter::finish) on the zip file and
 	/// [`.flush()`](Write::flush) on the writer, and then returns the writer.
 	///
@@ -196,8 +957,25 @@ impl<W: Write + Seek> NpzWriter<W> {
 	/// # Errors
 	///
 	/// Finishing the zip archive can fail with [`ZipError`].
-	pub fn finish(self) -> Result<W, WriteNpzError> {
+	///
+	/// As the writer is consumed, no further arrays can be added afterwards. Writers which can be
+	/// used after finishing, e.g., [`PipelinedWriter`] after its background thread failed, fail
+	/// with [`WriteNpzError::Finished`] instead.
+	pub fn finish(mut self) -> Result<W, WriteNpzError> {
+		self.add_provenance()?;
+		self.add_packed()?;
+		self.add_chunked()?;
 		let mut writer = self.zip.finish()?;
+		if let Some((order, reorder)) = self.order {
+			let overwrites = self.overwrites.unwrap_or_default();
+			reorder(&mut writer, order, &overwrites, self.alignment)?;
+		}
+		if let Some(add_index) = self.index {
+			writer = add_index(writer)?;
+		}
+		if let Some(check) = self.check {
+			check(&mut writer)?;
+		}
 		writer.flush().map_err(ZipError::from)?;
 		Ok(writer)
 	}
@@ -205,11 +983,18 @@ impl<W: Write + Seek> NpzWriter<W> {
 
 /// An error reading a `.npz` file.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum ReadNpzError {
 	/// An error caused by the zip archive.
 	Zip(ZipError),
 	/// An error caused by reading an inner `.npy` file.
 	Npy(ReadNpyError),
+	/// Reading the file has exceeded its deadline, see [`NpzReader::with_entry_timeout`] and
+	/// [`RetryPolicy::with_deadline`].
+	TimedOut {
+		/// Name of the file.
+		name: String,
+	},
 }
 
 impl Error for ReadNpzError {
@@ -217,6 +1002,7 @@ impl Error for ReadNpzError {
 		match self {
 			ReadNpzError::Zip(err) => Some(err),
 			ReadNpzError::Npy(err) => Some(err),
+			ReadNpzError::TimedOut { .. } => None,
 		}
 	}
 }
@@ -226,6 +1012,7 @@ impl fmt::Display for ReadNpzError {
 		match self {
 			ReadNpzError::Zip(err) => write!(f, "zip file error: {err}"),
 			ReadNpzError::Npy(err) => write!(f, "error reading npy file in npz archive: {err}"),
+			ReadNpzError::TimedOut { name } => write!(f, "timed out reading {name:?}"),
 		}
 	}
 }
@@ -260,69 +1047,216 @@ impl From<ReadNpyError> for ReadNpzError {
 /// ```
 pub struct NpzReader<R: Read + Seek> {
 	zip: ZipArchive<R>,
+	cache: Option<EntryCache>,
+	packed: Option<Packed>,
+	chunked: Option<Chunked>,
+	unsupported: Vec<(String, u16)>,
+	directories: usize,
+	normalize: bool,
+	lenient: bool,
+	timeout: Option<Duration>,
+	max_entry_size: Option<u64>,
 }
 
 impl<R: Read + Seek> NpzReader<R> {
 	/// Creates a new `.npz` file reader.
 	///
+	/// Archives without arrays, e.g., written by `numpy.savez()` without arguments or containing
+	/// directories only, are empty.
+	///
 	/// # Errors
 	///
 	/// Reading a zip archive can fail with [`ZipError`].
 	pub fn new(reader: R) -> Result<NpzReader<R>, ReadNpzError> {
+		let mut zip = ZipArchive::new(reader)?;
 		Ok(NpzReader {
-			zip: ZipArchive::new(reader)?,
+			unsupported: unsupported_names(&mut zip)?,
+			packed: Packed::read(&mut zip)?,
+			chunked: Chunked::read(&mut zip)?,
+			directories: zip.file_names().filter(|name| is_directory(name)).count(),
+			zip,
+			cache: None,
+			normalize: true,
+			lenient: false,
+			timeout: None,
+			max_entry_size: None,
 		})
 	}
 
+	/// Returns the comment of the archive, see [`NpzWriter::set_comment`].
+	///
+	/// # Errors
+	///
+	/// Fails with [`Utf8Error`] if the comment is not valid UTF-8.
+	pub fn comment(&self) -> Result<&str, Utf8Error> {
+		str::from_utf8(self.zip.comment())
+	}
+
 	/// Returns `true` iff the `.npz` file doesn't contain any arrays.
 	#[must_use]
 	pub fn is_empty(&self) -> bool {
-		self.zip.len() == 0
+		self.len() == 0
 	}
 
 	/// Returns the number of arrays in the `.npz` file.
+	///
+	/// Arrays packed via [`NpzWriter::with_packing`] or chunked via [`NpzWriter::with_chunking`]
+	/// are counted instead of the packed members or chunks. Directories are not counted.
 	#[must_use]
 	pub fn len(&self) -> usize {
-		self.zip.len()
+		let mut len = self.zip.len() - self.directories;
+		if let Some(packed) = &self.packed {
+			len = len - 2 + packed.names().len();
+		}
+		if let Some(chunked) = &self.chunked {
+			len = len - chunked.reserved() + chunked.names().len();
+		}
+		len
 	}
 
 	/// Returns the names of all of the arrays in the file.
 	///
+	/// Names of arrays packed via [`NpzWriter::with_packing`] follow the names of the other files,
+	/// followed by the names of arrays chunked via [`NpzWriter::with_chunking`]. Directories are
+	/// excluded.
+	///
 	/// # Errors
 	///
 	/// Reading a zip archive can fail with [`ZipError`].
 	pub fn names(&mut self) -> Result<Vec<String>, ReadNpzError> {
-		Ok((0..self.zip.len())
-			.map(|i| Ok(self.zip.by_index(i)?.name().to_owned()))
-			.collect::<Result<_, ZipError>>()?)
+		let mut names = self
+			.zip
+			.file_names()
+			.filter(|name| !is_directory(name))
+			.map(str::to_owned)
+			.collect::<Vec<_>>();
+		if let Some(packed) = &self.packed {
+			names.retain(|name| !Packed::is_reserved(name));
+			names.extend_from_slice(packed.names());
+		}
+		if let Some(chunked) = &self.chunked {
+			names.retain(|name| !Chunked::is_reserved(name));
+			names.extend_from_slice(chunked.names());
+		}
+		Ok(names)
 	}
 
 	/// Reads an array by name.
 	///
+	/// With the `unicode` feature, a file whose name only differs in its Unicode normalization
+	/// form is found as well, e.g., a decomposed name written on macOS.
+	///
 	/// # Errors
 	///
-	/// Reading an array from an archive can fail with [`ReadNpyError`] or [`ZipError`].
+	/// Reading an array from an archive can fail with [`ReadNpyError`] or [`ZipError`], e.g., with
+	/// [`ZipError::UnsupportedArchive`] for files listed by [`Self::unsupported_names`], or with
+	/// [`ReadNpzError::TimedOut`] once a deadline has passed.
 	pub fn by_name<S, D>(&mut self, name: &str) -> Result<ArrayBase<S, D>, ReadNpzError>
 	where
 		S::Elem: ReadableElement,
 		S: DataOwned,
 		D: Dimension,
 	{
-		Ok(ArrayBase::<S, D>::read_npy(self.zip.by_name(name)?)?)
+		self.read_by_name(name)
+			.map_err(|err| limit::exceeded_size(timeout::timed_out(err, name)))
+	}
+
+	fn read_by_name<S, D>(&mut self, name: &str) -> Result<ArrayBase<S, D>, ReadNpzError>
+	where
+		S::Elem: ReadableElement,
+		S: DataOwned,
+		D: Dimension,
+	{
+		if let Some(array) = self.by_name_packed(name)? {
+			return Ok(array);
+		}
+		if let Some(array) = self.by_name_chunked(name)? {
+			return Ok(array);
+		}
+		let name = self.resolve_name(name);
+		if self
+			.unsupported
+			.iter()
+			.any(|(unsupported, _method)| *unsupported == name)
+		{
+			return Err(ZipError::UnsupportedArchive(UNSUPPORTED_COMPRESSION).into());
+		}
+		if let Some(bytes) = self.cached(&name)? {
+			if self.lenient {
+				return Ok(ArrayBase::<S, D>::read_npy(
+					lenient::normalize(&*bytes).map_err(ZipError::from)?,
+				)?);
+			}
+			return Ok(ArrayBase::<S, D>::read_npy(&*bytes)?);
+		}
+		let deadline = self.deadline();
+		let file = Deadline::new(self.zip.by_name(&name)?, deadline);
+		let file = Bounded::new(file, self.max_entry_size).map_err(limit::zip_error)?;
+		if self.lenient {
+			let file = lenient::normalize(file).map_err(ZipError::from)?;
+			return Ok(ArrayBase::<S, D>::read_npy(file)?);
+		}
+		Ok(ArrayBase::<S, D>::read_npy(file)?)
+	}
+
+	/// Reads an array of any number of axes by name, see [`Self::by_name`].
+	///
+	/// Unlike fixed dimensions, which end with [`Ix6`], this reads arrays with 7 or more axes.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use ndarray::{ArrayD, IxDyn};
+	/// use ndarray_npz::{NpzReader, NpzWriter};
+	/// use std::io::Cursor;
+	///
+	/// let cube = ArrayD::<f32>::zeros(IxDyn(&[2, 1, 3, 1, 2, 1, 4]));
+	/// let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	/// npz.add_array("cube", &cube)?;
+	/// let mut npz = NpzReader::new(npz.finish()?)?;
+	/// assert_eq!(npz.by_name_dyn::<f32>("cube")?, cube);
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Fails like [`Self::by_name`].
+	pub fn by_name_dyn<A>(&mut self, name: &str) -> Result<ArrayD<A>, ReadNpzError>
+	where
+		A: ReadableElement,
+	{
+		self.by_name(name)
 	}
 
 	/// Reads an array by index in the `.npz` file.
 	///
+	/// The index refers to the order of [`Self::names`].
+	///
 	/// # Errors
 	///
-	/// Reading an array from an archive can fail with [`ReadNpyError`] or [`ZipError`].
+	/// Reading an array from an archive can fail with [`ReadNpyError`] or [`ZipError`], or with
+	/// [`ReadNpzError::TimedOut`] once a deadline has passed.
 	pub fn by_index<S, D>(&mut self, index: usize) -> Result<ArrayBase<S, D>, ReadNpzError>
 	where
 		S::Elem: ReadableElement,
 		S: DataOwned,
 		D: Dimension,
 	{
-		Ok(ArrayBase::<S, D>::read_npy(self.zip.by_index(index)?)?)
+		if self.packed.is_some() || self.chunked.is_some() || self.directories > 0 {
+			let name = self.names()?.into_iter().nth(index);
+			return self.by_name(&name.ok_or(ZipError::FileNotFound)?);
+		}
+		if self.cache.is_some() || self.lenient {
+			let name = self.zip.by_index(index)?.name().to_owned();
+			return self.by_name(&name);
+		}
+		let deadline = self.deadline();
+		let file = self.zip.by_index(index)?;
+		let name = file.name().to_owned();
+		Bounded::new(Deadline::new(file, deadline), self.max_entry_size)
+			.map_err(|err| ReadNpzError::from(limit::zip_error(err)))
+			.and_then(|file| Ok(ArrayBase::<S, D>::read_npy(file)?))
+			.map_err(|err| limit::exceeded_size(timeout::timed_out(err, &name)))
 	}
 }
 
@@ -342,6 +1276,22 @@ pub enum ViewNpzError {
 	CompressedFile,
 	/// Encrypted files cannot be viewed.
 	EncryptedFile,
+	/// Blobs, i.e., other files than `.npy` files, cannot be viewed as arrays.
+	Blob,
+	/// An error caused by writing a replacing `.npy` file.
+	WriteNpy(WriteNpyError),
+	/// The element type of the replacing array differs.
+	DtypeMismatch,
+	/// The shape of the replacing array differs.
+	ShapeMismatch,
+	/// The checksum is stale as a thread panicked while mutably viewing the `.npy` file.
+	Poisoned,
+	/// An error caused by reading an inner `.npy` file as fallback of viewing it.
+	ReadNpy(ReadNpyError),
+	/// The slice is out of bounds or has a zero step, see [`NpyView::view_slice`].
+	InvalidSlice,
+	/// An error caused by laying out an archive, see [`NpzViewMut::create_in`].
+	Layout(WriteNpzError),
 }
 
 impl Error for ViewNpzError {
@@ -349,10 +1299,18 @@ impl Error for ViewNpzError {
 		match self {
 			ViewNpzError::Zip(err) => Some(err),
 			ViewNpzError::Npy(err) => Some(err),
+			ViewNpzError::WriteNpy(err) => Some(err),
+			ViewNpzError::ReadNpy(err) => Some(err),
+			ViewNpzError::Layout(err) => Some(err),
 			ViewNpzError::MovedNpyViewMut
 			| ViewNpzError::Directory
 			| ViewNpzError::CompressedFile
-			| ViewNpzError::EncryptedFile => None,
+			| ViewNpzError::EncryptedFile
+			| ViewNpzError::Blob
+			| ViewNpzError::DtypeMismatch
+			| ViewNpzError::ShapeMismatch
+			| ViewNpzError::Poisoned
+			| ViewNpzError::InvalidSlice => None,
 		}
 	}
 }
@@ -369,6 +1327,14 @@ impl fmt::Display for ViewNpzError {
 			ViewNpzError::Directory => write!(f, "directories cannot be viewed"),
 			ViewNpzError::CompressedFile => write!(f, "compressed files cannot be viewed"),
 			ViewNpzError::EncryptedFile => write!(f, "encrypted files cannot be viewed"),
+			ViewNpzError::Blob => write!(f, "blobs cannot be viewed as arrays"),
+			ViewNpzError::WriteNpy(err) => write!(f, "error writing npy file: {err}"),
+			ViewNpzError::DtypeMismatch => write!(f, "element type differs"),
+			ViewNpzError::ShapeMismatch => write!(f, "shape differs"),
+			ViewNpzError::Poisoned => write!(f, "checksum poisoned by panic"),
+			ViewNpzError::ReadNpy(err) => write!(f, "error reading npy file: {err}"),
+			ViewNpzError::InvalidSlice => write!(f, "slice out of bounds or with zero step"),
+			ViewNpzError::Layout(err) => write!(f, "error laying out npz file: {err}"),
 		}
 	}
 }
@@ -385,6 +1351,18 @@ impl From<ViewNpyError> for ViewNpzError {
 	}
 }
 
+impl From<WriteNpyError> for ViewNpzError {
+	fn from(err: WriteNpyError) -> ViewNpzError {
+		ViewNpzError::WriteNpy(err)
+	}
+}
+
+impl From<ReadNpyError> for ViewNpzError {
+	fn from(err: ReadNpyError) -> ViewNpzError {
+		ViewNpzError::ReadNpy(err)
+	}
+}
+
 /// Immutable view for memory-mapped `.npz` files.
 ///
 /// The primary use-case for this is viewing `.npy` files within a memory-mapped
@@ -406,6 +1384,11 @@ impl From<ViewNpyError> for ViewNpzError {
 ///
 /// [`rezip`]: https://crates.io/crates/rezip
 ///
+/// # Thread Safety
+///
+/// [`NpzView`] and [`NpyView`] are guaranteed to be [`Send`] and [`Sync`], so `.npy` file views
+/// can be shared across threads, e.g., to view arrays in parallel.
+///
 /// # Example
 ///
 /// This is an example of opening an immutably memory-mapped `.npz` archive as
@@ -461,39 +1444,59 @@ impl From<ViewNpyError> for ViewNpzError {
 /// ```
 #[derive(Debug, Clone)]
 pub struct NpzView<'a> {
+	bytes: &'a [u8],
 	files: HashMap<usize, NpyView<'a>>,
 	names: HashMap<String, usize>,
+	blobs: HashMap<String, &'a [u8]>,
 	directory_names: HashSet<String>,
 	compressed_names: HashSet<String>,
 	encrypted_names: HashSet<String>,
+	checksums: OnceLock<ChecksumReport>,
 }
 
 impl<'a> NpzView<'a> {
 	/// Creates a new immutable view of a memory-mapped `.npz` file.
 	///
+	/// Skips reading the local headers if the archive has a valid index, see
+	/// [`NpzWriter::with_index`].
+	///
 	/// # Errors
 	///
 	/// Viewing an archive can fail with [`ZipError`].
 	pub fn new(bytes: &'a [u8]) -> Result<Self, ViewNpzError> {
 		let mut zip = ZipArchive::new(Cursor::new(bytes))?;
+		if let Some(archive) = Self::from_index(bytes, &mut zip) {
+			return Ok(archive);
+		}
 		let mut archive = Self {
+			bytes,
 			files: HashMap::new(),
 			names: HashMap::new(),
+			blobs: HashMap::new(),
 			directory_names: HashSet::new(),
 			compressed_names: HashSet::new(),
 			encrypted_names: zip.file_names().map(From::from).collect(),
+			checksums: OnceLock::new(),
 		};
 		// Initially assume all files to be encrypted.
 		let mut index = 0;
 		for zip_index in 0..zip.len() {
+			let name = zip
+				.name_for_index(zip_index)
+				.unwrap_or_default()
+				.to_string();
 			// Skip encrypted files.
 			let file = match zip.by_index(zip_index) {
 				Err(ZipError::UnsupportedArchive(ZipError::PASSWORD_REQUIRED)) => continue,
+				// Files compressed by unsupported methods cannot be viewed either.
+				Err(ZipError::UnsupportedArchive(_)) => {
+					archive.encrypted_names.remove(&name);
+					archive.compressed_names.insert(name);
+					continue;
+				}
 				Err(err) => return Err(err.into()),
 				Ok(file) => file,
 			};
-			// File name of non-encrypted file.
-			let name = file.name().to_string();
 			// Remove file name from encrypted files.
 			archive.encrypted_names.remove(&name);
 			// Skip directories and compressed files.
@@ -505,13 +1508,21 @@ impl<'a> NpzView<'a> {
 				archive.compressed_names.insert(name);
 				continue;
 			}
+			// Store other files than `.npy` files as blobs.
+			let data = slice_at(bytes, file.data_start(), 0..file.size())?;
+			if !data.starts_with(MAGIC) {
+				archive.blobs.insert(name, data);
+				continue;
+			}
 			// Store file index by file names.
 			archive.names.insert(name, index);
 			let file = NpyView {
-				data: slice_at(bytes, file.data_start(), 0..file.size())?,
+				data,
 				central_crc32: slice_at(bytes, file.central_header_start(), 16..20)
 					.map(as_array_ref)?,
-				status: ChecksumStatus::default(),
+				status: slice_at(bytes, file.central_header_start(), 36..38)
+					.map(|attributes| poison::status(u16_at(attributes, 0..2)))?,
+				lenient: false,
 			};
 			// Store file view by file index.
 			archive.files.insert(index, file);
@@ -523,7 +1534,7 @@ impl<'a> NpzView<'a> {
 
 	/// Returns `true` iff the `.npz` file doesn't contain any viewable arrays.
 	///
-	/// Viewable arrays are neither directories, nor compressed, nor encrypted.
+	/// Viewable arrays are neither directories, nor compressed, nor encrypted, nor blobs.
 	#[must_use]
 	pub fn is_empty(&self) -> bool {
 		self.names.is_empty()
@@ -531,7 +1542,7 @@ impl<'a> NpzView<'a> {
 
 	/// Returns the number of viewable arrays in the `.npz` file.
 	///
-	/// Viewable arrays are neither directories, nor compressed, nor encrypted.
+	/// Viewable arrays are neither directories, nor compressed, nor encrypted, nor blobs.
 	#[must_use]
 	pub fn len(&self) -> usize {
 		self.names.len()
@@ -539,10 +1550,17 @@ impl<'a> NpzView<'a> {
 
 	/// Returns the names of all of the viewable arrays in the `.npz` file.
 	///
-	/// Viewable arrays are neither directories, nor compressed, nor encrypted.
+	/// Viewable arrays are neither directories, nor compressed, nor encrypted, nor blobs. They
+	/// are detected by the magic string of `.npy` files regardless of their names.
 	pub fn names(&self) -> impl Iterator<Item = &str> {
 		self.names.keys().map(String::as_str)
 	}
+	/// Returns the names of all of the blobs in the `.npz` file.
+	///
+	/// Blobs are uncompressed and unencrypted files other than `.npy` files, e.g., `.json` files.
+	pub fn blob_names(&self) -> impl Iterator<Item = &str> {
+		self.blobs.keys().map(String::as_str)
+	}
 	/// Returns the names of all of the directories in the `.npz` file.
 	pub fn directory_names(&self) -> impl Iterator<Item = &str> {
 		self.directory_names.iter().map(String::as_str)
@@ -562,11 +1580,14 @@ impl<'a> NpzView<'a> {
 	///
 	/// Viewing an `.npy` file can fail with [`ViewNpyError`]. Trying to view a directory,
 	/// compressed file, or encrypted file, fails with [`ViewNpzError::Directory`],
-	/// [`ViewNpzError::CompressedFile`], or [`ViewNpzError::CompressedFile`]. Fails with
-	/// [`ZipError::FileNotFound`] if the `name` is not found.
+	/// [`ViewNpzError::CompressedFile`], or [`ViewNpzError::CompressedFile`]. Trying to view a
+	/// blob fails with [`ViewNpzError::Blob`]. Fails with [`ZipError::FileNotFound`] if the `name`
+	/// is not found.
 	pub fn by_name(&self, name: &str) -> Result<NpyView<'a>, ViewNpzError> {
 		self.by_index(self.names.get(name).copied().ok_or_else(|| {
-			if self.directory_names.contains(name) {
+			if self.blobs.contains_key(name) {
+				ViewNpzError::Blob
+			} else if self.directory_names.contains(name) {
 				ViewNpzError::Directory
 			} else if self.compressed_names.contains(name) {
 				ViewNpzError::CompressedFile
@@ -578,6 +1599,20 @@ impl<'a> NpzView<'a> {
 		})?)
 	}
 
+	/// Returns the raw bytes of an uncompressed and unencrypted file by name.
+	///
+	/// Unlike [`Self::by_name`], this also provides access to blobs.
+	///
+	/// # Errors
+	///
+	/// Fails like [`Self::by_name`] except for blobs.
+	pub fn raw(&self, name: &str) -> Result<&'a [u8], ViewNpzError> {
+		match self.blobs.get(name) {
+			Some(blob) => Ok(blob),
+			None => self.by_name(name).map(|file| file.data),
+		}
+	}
+
 	/// Returns an immutable `.npy` file view by index in `0..len()`.
 	///
 	/// The index **does not** necessarily correspond to the index of the zip archive as
@@ -603,6 +1638,7 @@ pub struct NpyView<'a> {
 	data: &'a [u8],
 	central_crc32: &'a [u8; 4],
 	status: ChecksumStatus,
+	lenient: bool,
 }
 
 impl<'a> NpyView<'a> {
@@ -618,8 +1654,12 @@ impl<'a> NpyView<'a> {
 	///
 	/// # Errors
 	///
-	/// Fails with [`ZipError::Io`] if the checksum is invalid.
+	/// Fails with [`ZipError::Io`] if the checksum is invalid or with [`ViewNpzError::Poisoned`]
+	/// without reading the array if the checksum is [poisoned](`ChecksumStatus::Poisoned`).
 	pub fn verify(&mut self) -> Result<u32, ViewNpzError> {
+		if self.status == ChecksumStatus::Poisoned {
+			return Err(ViewNpzError::Poisoned);
+		}
 		self.status = ChecksumStatus::Outdated;
 		// Like the `zip` crate, verify only against central CRC-32.
 		let crc32 = crc32_verify(self.data, *self.central_crc32)?;
@@ -639,7 +1679,18 @@ impl<'a> NpyView<'a> {
 		A: ViewElement,
 		D: Dimension,
 	{
-		Ok(ArrayView::view_npy(self.data)?)
+		Ok(lenient::view_npy(self.data, self.lenient)?)
+	}
+	/// Returns an immutable view of any number of axes, see [`Self::view`].
+	///
+	/// # Errors
+	///
+	/// Viewing an `.npy` file can fail with [`ViewNpyError`].
+	pub fn view_dyn<A>(&self) -> Result<ArrayViewD<'_, A>, ViewNpzError>
+	where
+		A: ViewElement,
+	{
+		self.view()
 	}
 }
 
@@ -666,6 +1717,12 @@ impl<'a> NpyView<'a> {
 ///
 /// [`rezip`]: https://crates.io/crates/rezip
 ///
+/// # Thread Safety
+///
+/// [`NpzViewMut`] and [`NpyViewMut`] are guaranteed to be [`Send`] and [`Sync`], so `.npy` file
+/// views moved out via [`NpzViewMut::by_name`] can be mutated in parallel on different threads,
+/// each updating its own checksum.
+///
 /// # Example
 ///
 /// This is an example of opening a mutably memory-mapped `.npz` archive as an
@@ -733,6 +1790,7 @@ impl<'a> NpyView<'a> {
 pub struct NpzViewMut<'a> {
 	files: HashMap<usize, NpyViewMut<'a>>,
 	names: HashMap<String, usize>,
+	blob_names: HashSet<String>,
 	directory_names: HashSet<String>,
 	compressed_names: HashSet<String>,
 	encrypted_names: HashSet<String>,
@@ -744,11 +1802,13 @@ impl<'a> NpzViewMut<'a> {
 	/// # Errors
 	///
 	/// Viewing an archive can fail with [`ZipError`].
+	#[allow(clippy::too_many_lines)]
 	pub fn new(mut bytes: &'a mut [u8]) -> Result<Self, ViewNpzError> {
 		let mut zip = ZipArchive::new(Cursor::new(&bytes))?;
 		let mut archive = Self {
 			files: HashMap::new(),
 			names: HashMap::new(),
+			blob_names: HashSet::new(),
 			directory_names: HashSet::new(),
 			compressed_names: HashSet::new(),
 			encrypted_names: zip.file_names().map(From::from).collect(),
@@ -758,14 +1818,22 @@ impl<'a> NpzViewMut<'a> {
 		let mut splits = BTreeMap::new();
 		let mut index = 0;
 		for zip_index in 0..zip.len() {
+			let name = zip
+				.name_for_index(zip_index)
+				.unwrap_or_default()
+				.to_string();
 			// Skip encrypted files.
 			let file = match zip.by_index(zip_index) {
 				Err(ZipError::UnsupportedArchive(ZipError::PASSWORD_REQUIRED)) => continue,
+				// Files compressed by unsupported methods cannot be viewed either.
+				Err(ZipError::UnsupportedArchive(_)) => {
+					archive.encrypted_names.remove(&name);
+					archive.compressed_names.insert(name);
+					continue;
+				}
 				Err(err) => return Err(err.into()),
 				Ok(file) => file,
 			};
-			// File name of non-encrypted file.
-			let name = file.name().to_string();
 			// Remove file name from encrypted files.
 			archive.encrypted_names.remove(&name);
 			// Skip directories and compressed files.
@@ -781,16 +1849,26 @@ impl<'a> NpzViewMut<'a> {
 			if file.is_dir() || file.compression() != CompressionMethod::Stored {
 				continue;
 			}
-			// Store file index by file names.
-			archive.names.insert(name, index);
 			// Get data range.
 			let data_range = range_at(file.data_start(), 0..file.size())?;
+			// Skip other files than `.npy` files.
+			if !bytes
+				.get(data_range.clone())
+				.is_some_and(|data| data.starts_with(MAGIC))
+			{
+				archive.blob_names.insert(name);
+				continue;
+			}
+			// Store file index by file names.
+			archive.names.insert(name, index);
 			// Get central general purpose bit flag range.
 			let central_flag_range = range_at(file.central_header_start(), 8..10)?;
 			// Parse central general purpose bit flag range.
 			let central_flag = u16_at(bytes, central_flag_range);
 			// Get central CRC-32 range.
 			let central_crc32_range = range_at(file.central_header_start(), 16..20)?;
+			// Get central internal file attributes range.
+			let attributes_range = range_at(file.central_header_start(), 36..38)?;
 			// Whether local CRC-32 is located in header or data descriptor.
 			let use_data_descriptor = central_flag & (1 << 3) != 0;
 			// Get local CRC-32 range.
@@ -823,8 +1901,15 @@ impl<'a> NpzViewMut<'a> {
 			splits.insert(crc32_range.start, crc32_range.end);
 			splits.insert(data_range.start, data_range.end);
 			splits.insert(central_crc32_range.start, central_crc32_range.end);
+			splits.insert(attributes_range.start, attributes_range.end);
 			// Store ranges by file index.
-			ranges.insert(index, (data_range, crc32_range, central_crc32_range));
+			let file_ranges = (
+				data_range,
+				crc32_range,
+				central_crc32_range,
+				attributes_range,
+			);
+			ranges.insert(index, file_ranges);
 			// Increment index of non-compressed and non-encrypted files.
 			index += 1;
 		}
@@ -854,9 +1939,9 @@ impl<'a> NpzViewMut<'a> {
 			bytes = remaining_bytes;
 		}
 		// Collect split borrows as file views.
-		for (&index, (data_range, crc32_range, central_crc32_range)) in &ranges {
+		for (&index, (data_range, crc32_range, central_crc32_range, attributes_range)) in &ranges {
 			let ambiguous_offset = || ZipError::InvalidArchive("Ambiguous offsets");
-			let file = NpyViewMut {
+			let mut file = NpyViewMut {
 				data: slices
 					.remove(&data_range.start)
 					.ok_or_else(ambiguous_offset)?,
@@ -868,8 +1953,17 @@ impl<'a> NpzViewMut<'a> {
 					.remove(&central_crc32_range.start)
 					.map(as_array_mut)
 					.ok_or_else(ambiguous_offset)?,
+				attributes: slices
+					.remove(&attributes_range.start)
+					.map(as_array_mut)
+					.ok_or_else(ambiguous_offset)?,
 				status: ChecksumStatus::default(),
+				log: None,
+				lenient: false,
+				checksums: true,
 			};
+			// Surface poisoned checksum of previous mutable view.
+			file.status = poison::status(u16::from_le_bytes(*file.attributes));
 			archive.files.insert(index, file);
 		}
 		Ok(archive)
@@ -877,7 +1971,7 @@ impl<'a> NpzViewMut<'a> {
 
 	/// Returns `true` iff the `.npz` file doesn't contain any viewable arrays.
 	///
-	/// Viewable arrays are neither directories, nor compressed, nor encrypted.
+	/// Viewable arrays are neither directories, nor compressed, nor encrypted, nor blobs.
 	#[must_use]
 	pub fn is_empty(&self) -> bool {
 		self.names.is_empty()
@@ -885,7 +1979,7 @@ impl<'a> NpzViewMut<'a> {
 
 	/// Returns the number of viewable arrays in the `.npz` file.
 	///
-	/// Viewable arrays are neither directories, nor compressed, nor encrypted.
+	/// Viewable arrays are neither directories, nor compressed, nor encrypted, nor blobs.
 	#[must_use]
 	pub fn len(&self) -> usize {
 		self.names.len()
@@ -893,10 +1987,17 @@ impl<'a> NpzViewMut<'a> {
 
 	/// Returns the names of all of the viewable arrays in the `.npz` file.
 	///
-	/// Viewable arrays are neither directories, nor compressed, nor encrypted.
+	/// Viewable arrays are neither directories, nor compressed, nor encrypted, nor blobs. They
+	/// are detected by the magic string of `.npy` files regardless of their names.
 	pub fn names(&self) -> impl Iterator<Item = &str> {
 		self.names.keys().map(String::as_str)
 	}
+	/// Returns the names of all of the blobs in the `.npz` file.
+	///
+	/// Blobs are uncompressed and unencrypted files other than `.npy` files, e.g., `.json` files.
+	pub fn blob_names(&self) -> impl Iterator<Item = &str> {
+		self.blob_names.iter().map(String::as_str)
+	}
 	/// Returns the names of all of the directories in the `.npz` file.
 	pub fn directory_names(&self) -> impl Iterator<Item = &str> {
 		self.directory_names.iter().map(String::as_str)
@@ -910,17 +2011,53 @@ impl<'a> NpzViewMut<'a> {
 		self.encrypted_names.iter().map(String::as_str)
 	}
 
+	/// Records every [`NpyViewMut::update`] of the `.npy` file views into the mutation `log`.
+	///
+	/// Applies to the `.npy` file views which have not yet been moved out of the `.npz` file view.
+	#[must_use]
+	pub fn with_mutation_log(mut self, log: &MutationLog) -> Self {
+		for (name, index) in &self.names {
+			if let Some(file) = self.files.get_mut(index) {
+				file.log = Some((log.clone(), name.clone()));
+			}
+		}
+		self
+	}
+
+	/// Suppresses updating and poisoning checksums of the `.npy` file views, e.g., of
+	/// copy-on-write memory maps whose edits never reach the file.
+	///
+	/// Leaves the archive bytes outside of the data untouched, so the checksums of edited `.npy`
+	/// files become invalid and [`NpyViewMut::status`] stays
+	/// [`Outdated`](ChecksumStatus::Outdated). Applies to the `.npy` file views which have not yet
+	/// been moved out of the `.npz` file view.
+	#[must_use]
+	pub fn without_checksum_updates(mut self) -> Self {
+		for file in self.files.values_mut() {
+			file.checksums = false;
+		}
+		self
+	}
+
 	/// Moves a mutable `.npy` file view by name out of the `.npz` file view.
 	///
 	/// # Errors
 	///
 	/// Viewing an `.npy` file can fail with [`ViewNpyError`]. Trying to view a directory,
 	/// compressed file, or encrypted file, fails with [`ViewNpzError::Directory`],
-	/// [`ViewNpzError::CompressedFile`], or [`ViewNpzError::CompressedFile`]. Fails with
-	/// [`ZipError::FileNotFound`] if the `name` is not found.
+	/// [`ViewNpzError::CompressedFile`], or [`ViewNpzError::CompressedFile`]. Trying to view a
+	/// blob fails with [`ViewNpzError::Blob`]. Fails with [`ZipError::FileNotFound`] if the `name`
+	/// is not found.
 	pub fn by_name(&mut self, name: &str) -> Result<NpyViewMut<'a>, ViewNpzError> {
-		self.by_index(self.names.get(name).copied().ok_or_else(|| {
-			if self.directory_names.contains(name) {
+		self.by_index(self.index(name)?)
+	}
+
+	/// Returns the index of a viewable `.npy` file by name.
+	fn index(&self, name: &str) -> Result<usize, ViewNpzError> {
+		self.names.get(name).copied().ok_or_else(|| {
+			if self.blob_names.contains(name) {
+				ViewNpzError::Blob
+			} else if self.directory_names.contains(name) {
 				ViewNpzError::Directory
 			} else if self.compressed_names.contains(name) {
 				ViewNpzError::CompressedFile
@@ -929,7 +2066,7 @@ impl<'a> NpzViewMut<'a> {
 			} else {
 				ZipError::FileNotFound.into()
 			}
-		})?)
+		})
 	}
 
 	/// Moves a mutable `.npy` file view by index in `0..len()` out of the `.npz` file view.
@@ -957,15 +2094,29 @@ impl<'a> NpzViewMut<'a> {
 ///
 /// Does **not** automatically [verify](`Self::verify`) the CRC-32 checksum but **does**
 /// [update](`Self::update`) it on [`Drop::drop`] if [`view_mut`](`Self::view_mut`) has been invoked
-/// and the checksum has not manually been updated by invoking [`update`](`Self::update`).
+/// and the checksum has not manually been updated by invoking [`update`](`Self::update`). If the
+/// thread is panicking, the checksum is [poisoned](`ChecksumStatus::Poisoned`) instead.
 #[derive(Debug)]
 pub struct NpyViewMut<'a> {
 	data: &'a mut [u8],
 	crc32: &'a mut [u8; 4],
 	central_crc32: &'a mut [u8; 4],
+	attributes: &'a mut [u8; 2],
 	status: ChecksumStatus,
+	log: Option<(MutationLog, String)>,
+	lenient: bool,
+	checksums: bool,
 }
 
+// Guarantees views to be `Send` and `Sync` as documented.
+const _: () = {
+	const fn assert_send_sync<T: Send + Sync>() {}
+	assert_send_sync::<NpzView<'_>>();
+	assert_send_sync::<NpyView<'_>>();
+	assert_send_sync::<NpzViewMut<'_>>();
+	assert_send_sync::<NpyViewMut<'_>>();
+};
+
 impl<'a> NpyViewMut<'a> {
 	/// CRC-32 checksum status.
 	#[must_use]
@@ -979,8 +2130,13 @@ impl<'a> NpyViewMut<'a> {
 	///
 	/// # Errors
 	///
-	/// Fails with [`ZipError::Io`] if the checksum is invalid.
+	/// Fails with [`ZipError::Io`] if the checksum is invalid or with [`ViewNpzError::Poisoned`]
+	/// without reading the array if the checksum is [poisoned](`ChecksumStatus::Poisoned`), in
+	/// which case the data can be accepted by [updating](`Self::update`) the checksum.
 	pub fn verify(&mut self) -> Result<u32, ViewNpzError> {
+		if self.status == ChecksumStatus::Poisoned {
+			return Err(ViewNpzError::Poisoned);
+		}
 		self.status = ChecksumStatus::Outdated;
 		// Like the `zip` crate, verify only against central CRC-32.
 		let crc32 = crc32_verify(self.data, *self.central_crc32)?;
@@ -989,15 +2145,30 @@ impl<'a> NpyViewMut<'a> {
 	}
 	/// Updates and returns CRC-32 checksum by reading the whole array.
 	///
-	/// Changes checksum [`status`](`Self::status()`) to [`Correct`](`ChecksumStatus::Correct`).
+	/// Changes checksum [`status`](`Self::status()`) to [`Correct`](`ChecksumStatus::Correct`) and
+	/// clears a [poisoned](`ChecksumStatus::Poisoned`) mark.
 	///
 	/// Automatically updated on [`Drop::drop`] iff checksum [`status`](`Self::status()`) is
-	/// [`Outdated`](`ChecksumStatus::Outdated`).
+	/// [`Outdated`](`ChecksumStatus::Outdated`) unless the thread is panicking, in which case the
+	/// checksum is [poisoned](`ChecksumStatus::Poisoned`) instead.
+	///
+	/// Records the update if a [`MutationLog`] has been attached via
+	/// [`NpzViewMut::with_mutation_log`].
+	///
+	/// Only returns the checksum without updating it if updates have been suppressed via
+	/// [`NpzViewMut::without_checksum_updates`].
 	pub fn update(&mut self) -> u32 {
-		self.status = ChecksumStatus::Correct;
 		let crc32 = crc32_update(self.data);
+		if !self.checksums {
+			return crc32;
+		}
+		self.status = ChecksumStatus::Correct;
 		*self.central_crc32 = crc32.to_le_bytes();
 		*self.crc32 = *self.central_crc32;
+		*self.attributes = (u16::from_le_bytes(*self.attributes) & !POISONED).to_le_bytes();
+		if let Some((log, name)) = &self.log {
+			log.record(name, crc32);
+		}
 		crc32
 	}
 
@@ -1013,7 +2184,7 @@ impl<'a> NpyViewMut<'a> {
 		A: ViewElement,
 		D: Dimension,
 	{
-		Ok(ArrayView::<A, D>::view_npy(self.data)?)
+		Ok(lenient::view_npy(self.data, self.lenient)?)
 	}
 	/// Returns a mutable view of a memory-mapped `.npy` file.
 	///
@@ -1030,20 +2201,32 @@ impl<'a> NpyViewMut<'a> {
 		D: Dimension,
 	{
 		self.status = ChecksumStatus::Outdated;
-		Ok(ArrayViewMut::<A, D>::view_mut_npy(self.data)?)
+		Ok(lenient::view_mut_npy(self.data, self.lenient)?)
+	}
+	/// Returns a mutable view of any number of axes, see [`Self::view_mut`].
+	///
+	/// Changes checksum [`status`](`Self::status()`) to [`Outdated`](`ChecksumStatus::Outdated`).
+	///
+	/// # Errors
+	///
+	/// Viewing an `.npy` file can fail with [`ViewNpyError`].
+	pub fn view_mut_dyn<A>(&mut self) -> Result<ArrayViewMutD<'_, A>, ViewNpzError>
+	where
+		A: ViewMutElement,
+	{
+		self.view_mut()
 	}
 }
 
 impl<'a> Drop for NpyViewMut<'a> {
 	fn drop(&mut self) {
-		if self.status == ChecksumStatus::Outdated {
-			self.update();
-		}
+		self.settle();
 	}
 }
 
 /// Checksum status of an [`NpyView`] or [`NpyViewMut`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum ChecksumStatus {
 	/// The checksum has not been computed and the data has not changed.
 	Unverified,
@@ -1051,6 +2234,13 @@ pub enum ChecksumStatus {
 	Correct,
 	/// The data may have changed.
 	Outdated,
+	/// A thread panicked while the data may have been changing, so the checksum is stale.
+	///
+	/// Instead of reading the whole array at an arbitrary time while unwinding, an outdated
+	/// checksum is marked as poisoned in the central directory when its [`NpyViewMut`] is dropped
+	/// on panic. The mark persists until the checksum is [updated](`NpyViewMut::update`) and is
+	/// surfaced when viewing the archive again, see [`NpzView::poisoned_names`].
+	Poisoned,
 }
 
 impl Default for ChecksumStatus {
@@ -1078,6 +2268,69 @@ fn crc32_update(bytes: &[u8]) -> u32 {
 	hasher.finalize()
 }
 
+/// Combines the CRC-32 checksums of two consecutive byte sequences.
+#[must_use]
+fn crc32_combine(crc32: u32, other_crc32: u32, other_len: u64) -> u32 {
+	let mut hasher = crc32fast::Hasher::new_with_initial(crc32);
+	hasher.combine(&crc32fast::Hasher::new_with_initial_len(
+		other_crc32,
+		other_len,
+	));
+	hasher.finalize()
+}
+
+/// Computes the CRC-32 checksum of `len` zero bytes in logarithmic time.
+#[must_use]
+fn crc32_zeros(mut len: u64) -> u32 {
+	let mut crc32 = 0;
+	let mut block = (crc32_update(&[0]), 1);
+	while len > 0 {
+		if len & 1 == 1 {
+			crc32 = crc32_combine(crc32, block.0, block.1);
+		}
+		block = (crc32_combine(block.0, block.0, block.1), block.1 * 2);
+		len >>= 1;
+	}
+	crc32
+}
+
+/// Updates the CRC-32 checksum of a byte sequence after replacing its prefix of same length
+/// without reading the remaining `suffix_len` bytes.
+///
+/// Exploits the linearity of CRC-32 as in `crc(a) ^ crc(b) = crc(a ^ b) ^ crc(zeros)` for byte
+/// sequences of equal length.
+#[must_use]
+fn crc32_replace(crc32: u32, old_prefix: &[u8], new_prefix: &[u8], suffix_len: u64) -> u32 {
+	debug_assert_eq!(old_prefix.len(), new_prefix.len());
+	let diff = old_prefix
+		.iter()
+		.zip(new_prefix)
+		.map(|(old, new)| old ^ new)
+		.collect::<Vec<u8>>();
+	let zeros = crc32_zeros(suffix_len);
+	let diff = crc32_combine(crc32_update(&diff), zeros, suffix_len);
+	let zeros = crc32_combine(crc32_zeros(old_prefix.len() as u64), zeros, suffix_len);
+	crc32 ^ diff ^ zeros
+}
+
+/// Updates the CRC-32 checksum of a byte sequence after replacing its bytes at `offset` of same
+/// length without reading the other bytes, of which `suffix_len` follow the replaced ones.
+///
+/// Generalizes [`crc32_replace`] by prepending the difference with `offset` zeros.
+#[must_use]
+fn crc32_patch(crc32: u32, offset: u64, old: &[u8], new: &[u8], suffix_len: u64) -> u32 {
+	debug_assert_eq!(old.len(), new.len());
+	let diff = old
+		.iter()
+		.zip(new)
+		.map(|(old, new)| old ^ new)
+		.collect::<Vec<u8>>();
+	let len = diff.len() as u64 + suffix_len;
+	let diff = crc32_combine(crc32_update(&diff), crc32_zeros(suffix_len), suffix_len);
+	let diff = crc32_combine(crc32_zeros(offset), diff, len);
+	crc32 ^ diff ^ crc32_zeros(offset + len)
+}
+
 fn range_at<T>(index: T, range: Range<T>) -> Result<Range<usize>, ZipError>
 where
 	T: TryInto<usize> + Copy,
@@ -1119,6 +2372,21 @@ fn as_array_ref(slice: &[u8]) -> &[u8; 4] {
 }
 
 #[must_use]
-fn as_array_mut(slice: &mut [u8]) -> &mut [u8; 4] {
+fn as_array_mut<const N: usize>(slice: &mut [u8]) -> &mut [u8; N] {
 	slice.try_into().unwrap()
 }
+
+/// Whether the file `name` denotes a directory like [`zip::read::ZipFile::is_dir`].
+#[must_use]
+fn is_directory(name: &str) -> bool {
+	name.ends_with(['/', '\\'])
+}
+
+/// Returns the name of the member storing `suffix` metadata of the member `name`.
+///
+/// Strips an `.npy` extension off `name`, so `x.npy` and `x` both result in `x.{suffix}.npy`.
+#[must_use]
+fn sibling_name(name: &str, suffix: &str) -> String {
+	let stem = name.strip_suffix(".npy").unwrap_or(name);
+	format!("{stem}.{suffix}.npy")
+}
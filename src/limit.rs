@@ -0,0 +1,115 @@
+use super::{header::NpyHeader, lenient::read_header, ReadNpzError};
+use ndarray_npy::ReadNpyError;
+use std::{
+	error::Error,
+	fmt,
+	io::{self, Cursor, Read, Take},
+};
+use zip::result::ZipError;
+
+/// Message of a file exceeding [`Limits::max_entry_size`](crate::Limits::max_entry_size).
+pub(crate) const EXCEEDED: &str = "File exceeds limit of size";
+
+/// Cause of an I/O error of a [`Bounded`] reader exceeding its limit.
+#[derive(Debug)]
+struct Exceeded;
+
+impl fmt::Display for Exceeded {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(EXCEEDED)
+	}
+}
+
+impl Error for Exceeded {}
+
+/// Returns the I/O error of exceeding the limit.
+fn exceeded() -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, Exceeded)
+}
+
+/// Reader failing once it has read more bytes than its limit.
+///
+/// Unlike the size declared in the central directory, the limit holds for the decompressed bytes
+/// actually read.
+pub(crate) struct Bounded<R: Read> {
+	header: Cursor<Vec<u8>>,
+	inner: Take<R>,
+	limited: bool,
+}
+
+impl<R: Read> Bounded<R> {
+	/// Wraps `inner` bounded by `max_size` if any.
+	///
+	/// Reads ahead the `.npy` header at the start of `inner` if any and fails if the header
+	/// declares more bytes than `max_size` as reading the array allocates them up front.
+	pub(crate) fn new(mut inner: R, max_size: Option<u64>) -> io::Result<Self> {
+		let Some(max_size) = max_size else {
+			return Ok(Self {
+				header: Cursor::default(),
+				inner: inner.take(u64::MAX),
+				limited: false,
+			});
+		};
+		let header = read_header(&mut inner)?;
+		let header_size = header.len() as u64;
+		if header_size > max_size {
+			return Err(exceeded());
+		}
+		if let Some(npy) = NpyHeader::parse(&header) {
+			let size = npy
+				.elements()
+				.and_then(|elements| elements.checked_mul(npy.item_size().unwrap_or_default()))
+				.and_then(|size| size.checked_add(header_size));
+			if size.map_or(true, |size| size > max_size) {
+				return Err(exceeded());
+			}
+		}
+		Ok(Self {
+			header: Cursor::new(header),
+			inner: inner.take((max_size - header_size).saturating_add(1)),
+			limited: true,
+		})
+	}
+}
+
+impl<R: Read> Read for Bounded<R> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		let len = self.header.read(buf)?;
+		if len > 0 {
+			return Ok(len);
+		}
+		let len = self.inner.read(buf)?;
+		if self.limited && self.inner.limit() == 0 {
+			return Err(exceeded());
+		}
+		Ok(len)
+	}
+}
+
+/// Surfaces an I/O error of exceeding the limit as [`ZipError::UnsupportedArchive`].
+pub(crate) fn zip_error(err: io::Error) -> ZipError {
+	if is_exceeded(&err) {
+		ZipError::UnsupportedArchive(EXCEEDED)
+	} else {
+		ZipError::Io(err)
+	}
+}
+
+/// Surfaces an I/O error of exceeding the limit while reading a file as
+/// [`ZipError::UnsupportedArchive`].
+pub(crate) fn exceeded_size(err: ReadNpzError) -> ReadNpzError {
+	let (ReadNpzError::Zip(ZipError::Io(io)) | ReadNpzError::Npy(ReadNpyError::Io(io))) = &err
+	else {
+		return err;
+	};
+	if is_exceeded(io) {
+		ZipError::UnsupportedArchive(EXCEEDED).into()
+	} else {
+		err
+	}
+}
+
+/// Whether `err` is caused by exceeding the limit.
+fn is_exceeded(err: &io::Error) -> bool {
+	matches!(err.get_ref(), Some(err) if err.is::<Exceeded>())
+}
@@ -0,0 +1,120 @@
+use super::{
+	capability::is_supported_file, header::NpyHeader, json::json_string, NpzReader, NpzView,
+	ReadNpzError, ViewNpzError,
+};
+use py_literal::Value;
+use std::{
+	fmt::Write as _,
+	io::{Cursor, Read, Seek, Write},
+};
+use zip::{result::ZipError, ZipArchive};
+
+impl<R: Read + Seek> NpzReader<R> {
+	/// Writes one JSON object per line for each file in archive order, e.g., for piping into `jq`
+	/// or catalog tools.
+	///
+	/// Each object has the keys
+	///
+	///   * `name`: name of the file,
+	///   * `dtype`: type descriptor of the `.npy` file, e.g., `"<f8"`, otherwise `null`,
+	///   * `fortran_order`: whether the `.npy` file is in column-major order, otherwise `null`,
+	///   * `shape`: array of axis lengths of the `.npy` file, otherwise `null`,
+	///   * `size`: length of the uncompressed data,
+	///   * `compressed_size`: length of the possibly compressed data,
+	///   * `method`: numeric zip compression method, e.g., `0` if stored or `8` if deflated,
+	///   * `header_offset`: offset of the local file header within the archive,
+	///   * `data_offset`: offset of the possibly compressed data within the archive, and
+	///   * `crc32`: CRC-32 checksum of the uncompressed data,
+	///
+	/// in this order. Keys are never removed or renamed but new keys may be appended. Blobs,
+	/// directories, encrypted files, and files compressed by unsupported methods have `null`
+	/// `.npy` keys. Reserved members, e.g., of [`NpzWriter::with_packing`], are listed as is.
+	///
+	/// # Example
+	///
+	/// ```no_run
+	/// use ndarray_npz::NpzReader;
+	/// use std::{fs::File, io::stdout};
+	///
+	/// let mut npz = NpzReader::new(File::open("arrays.npz")?)?;
+	/// npz.list_entries_jsonl(stdout().lock())?;
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Reading the zip archive or writing to `writer` can fail with [`ZipError`].
+	///
+	/// [`NpzWriter::with_packing`]: crate::NpzWriter::with_packing
+	pub fn list_entries_jsonl<W: Write>(&mut self, writer: W) -> Result<(), ReadNpzError> {
+		Ok(list_entries(&mut self.zip, writer)?)
+	}
+}
+
+impl NpzView<'_> {
+	/// Writes one JSON object per line for each file in archive order.
+	///
+	/// See [`NpzReader::list_entries_jsonl`]. Listing parses the central directory of the archive
+	/// again.
+	///
+	/// # Errors
+	///
+	/// Reading the zip archive or writing to `writer` can fail with [`ZipError`].
+	pub fn list_entries_jsonl<W: Write>(&self, writer: W) -> Result<(), ViewNpzError> {
+		let mut zip = ZipArchive::new(Cursor::new(self.bytes))?;
+		Ok(list_entries(&mut zip, writer)?)
+	}
+}
+
+/// Writes one JSON object per line for each file of `zip`.
+#[allow(deprecated)]
+fn list_entries<R, W>(zip: &mut ZipArchive<R>, mut writer: W) -> Result<(), ZipError>
+where
+	R: Read + Seek,
+	W: Write,
+{
+	for index in 0..zip.len() {
+		let file = zip.by_index_raw(index)?;
+		let readable = !file.is_dir() && !file.encrypted() && is_supported_file(&file);
+		let name = json_string(file.name());
+		let trailer = format!(
+			r#""size": {}, "compressed_size": {}, "method": {}, "header_offset": {}, "data_offset": {}, "crc32": {}"#,
+			file.size(),
+			file.compressed_size(),
+			file.compression().to_u16(),
+			file.header_start(),
+			file.data_start(),
+			file.crc32(),
+		);
+		drop(file);
+		let header = if readable {
+			NpyHeader::read(&mut zip.by_index(index)?).ok()
+		} else {
+			None
+		};
+		let mut line = format!(r#"{{"name": {name}, "#);
+		match header {
+			Some(header) => {
+				let dtype = match &header.descr {
+					Value::String(descr) => descr.clone(),
+					descr => descr.to_string(),
+				};
+				let shape = header
+					.shape
+					.iter()
+					.map(ToString::to_string)
+					.collect::<Vec<_>>()
+					.join(", ");
+				let _ = write!(
+					line,
+					r#""dtype": {}, "fortran_order": {}, "shape": [{shape}], "#,
+					json_string(&dtype),
+					header.fortran_order,
+				);
+			}
+			None => line.push_str(r#""dtype": null, "fortran_order": null, "shape": null, "#),
+		}
+		writeln!(writer, "{line}{trailer}}}")?;
+	}
+	Ok(())
+}
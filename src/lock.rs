@@ -0,0 +1,222 @@
+use super::{
+	portable::long_path, NpzReader, NpzView, NpzViewMut, NpzWriter, ReadNpzError, Snapshot,
+	ViewNpzError,
+};
+use fs4::fs_std::FileExt;
+use std::{
+	fs::{File, OpenOptions},
+	io::{self, Read, Seek, SeekFrom, Write},
+	path::Path,
+};
+use zip::result::ZipError;
+
+/// Advisory lock of a `.npz` file held until dropped.
+///
+/// Coordinates concurrent readers and a single writer on the same file on POSIX (via `flock`) and
+/// Windows (via `LockFileEx`). The lock is *advisory*, that is, it only coordinates processes
+/// which lock the file as well.
+///
+/// Implements [`Read`], [`Write`], and [`Seek`] by forwarding to the locked [`File`], so it can be
+/// passed to [`NpzReader`] and [`NpzWriter`]. For editing in place via [`NpzViewMut`], see
+/// [`LockedNpz`]. For memory-mapping via [`NpzView`] or [`NpzViewMut`], map [`Self::file`] while
+/// keeping the lock alive.
+#[derive(Debug)]
+pub struct LockedFile {
+	file: File,
+}
+
+impl LockedFile {
+	/// Opens an existing file for reading and acquires a shared lock, blocking until available.
+	///
+	/// # Errors
+	///
+	/// Fails with [`io::Error`] if the file cannot be opened or locked.
+	pub fn open_shared<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+		let file = File::open(long_path(path.as_ref()))?;
+		FileExt::lock_shared(&file)?;
+		Ok(Self { file })
+	}
+	/// Opens or creates a file for reading and writing and acquires an exclusive lock, blocking
+	/// until available.
+	///
+	/// The file is **not** truncated before the lock is acquired, see [`Self::truncate`].
+	///
+	/// # Errors
+	///
+	/// Fails with [`io::Error`] if the file cannot be opened or locked.
+	pub fn open_exclusive<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+		let file = OpenOptions::new()
+			.read(true)
+			.write(true)
+			.create(true)
+			.truncate(false)
+			.open(long_path(path.as_ref()))?;
+		FileExt::lock_exclusive(&file)?;
+		Ok(Self { file })
+	}
+	/// Like [`Self::open_shared`] but returns `None` instead of blocking if the file is locked.
+	///
+	/// # Errors
+	///
+	/// Fails with [`io::Error`] if the file cannot be opened or locked.
+	pub fn try_open_shared<P: AsRef<Path>>(path: P) -> io::Result<Option<Self>> {
+		let file = File::open(long_path(path.as_ref()))?;
+		Ok(FileExt::try_lock_shared(&file)?.then_some(Self { file }))
+	}
+	/// Like [`Self::open_exclusive`] but returns `None` instead of blocking if the file is locked.
+	///
+	/// # Errors
+	///
+	/// Fails with [`io::Error`] if the file cannot be opened or locked.
+	pub fn try_open_exclusive<P: AsRef<Path>>(path: P) -> io::Result<Option<Self>> {
+		let file = OpenOptions::new()
+			.read(true)
+			.write(true)
+			.create(true)
+			.truncate(false)
+			.open(long_path(path.as_ref()))?;
+		Ok(FileExt::try_lock_exclusive(&file)?.then_some(Self { file }))
+	}
+	/// Truncates the exclusively locked file and rewinds it, e.g., before writing a new archive.
+	///
+	/// # Errors
+	///
+	/// Fails with [`io::Error`] if the file cannot be truncated.
+	pub fn truncate(&mut self) -> io::Result<()> {
+		self.file.set_len(0)?;
+		self.file.rewind()
+	}
+	/// Returns the locked file, e.g., for memory-mapping.
+	#[must_use]
+	pub fn file(&self) -> &File {
+		&self.file
+	}
+}
+
+impl Drop for LockedFile {
+	fn drop(&mut self) {
+		// Closing the file releases the lock anyway.
+		let _ = FileExt::unlock(&self.file);
+	}
+}
+
+impl Read for LockedFile {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		self.file.read(buf)
+	}
+}
+
+impl Write for LockedFile {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.file.write(buf)
+	}
+	fn flush(&mut self) -> io::Result<()> {
+		self.file.flush()
+	}
+}
+
+impl Seek for LockedFile {
+	fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+		self.file.seek(pos)
+	}
+}
+
+impl NpzReader<LockedFile> {
+	/// Opens a `.npz` file for reading while holding a shared lock.
+	///
+	/// # Errors
+	///
+	/// Opening or locking the file can fail with [`ZipError::Io`], reading the zip archive with
+	/// [`ZipError`].
+	pub fn open_shared<P: AsRef<Path>>(path: P) -> Result<Self, ReadNpzError> {
+		Self::new(LockedFile::open_shared(path).map_err(ZipError::from)?)
+	}
+}
+
+impl NpzWriter<LockedFile> {
+	/// Creates a new `.npz` file without compression while holding an exclusive lock.
+	///
+	/// An existing file is truncated after the lock has been acquired. See [`NpzWriter::new`].
+	///
+	/// # Errors
+	///
+	/// Opening, locking, or truncating the file can fail with [`io::Error`].
+	pub fn create_exclusive<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+		let mut file = LockedFile::open_exclusive(path)?;
+		file.truncate()?;
+		Ok(Self::new(file))
+	}
+}
+
+/// Exclusively locked `.npz` file read into memory for editing in place via [`NpzViewMut`].
+///
+/// The file is read into a 64-byte aligned buffer while holding the lock, edited via
+/// [`Self::view_mut`], and written back via [`Self::commit`]. The lock is held until dropped, so
+/// concurrent readers locking the file never observe partial edits. Unlike memory-mapping the
+/// file, this requires no `unsafe` code.
+///
+/// # Example
+///
+/// ```no_run
+/// use ndarray::Ix1;
+/// use ndarray_npz::LockedNpz;
+///
+/// let mut npz = LockedNpz::open_exclusive("arrays.npz")?;
+/// npz.view_mut()?.by_name("x.npy")?.view_mut::<f64, Ix1>()?.fill(0.0);
+/// npz.commit()?;
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug)]
+pub struct LockedNpz {
+	file: LockedFile,
+	bytes: Snapshot,
+}
+
+impl LockedNpz {
+	/// Opens an existing `.npz` file for editing and acquires an exclusive lock, blocking until
+	/// available.
+	///
+	/// # Errors
+	///
+	/// Fails with [`io::Error`] if the file cannot be opened, locked, or read.
+	pub fn open_exclusive<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+		let file = OpenOptions::new()
+			.read(true)
+			.write(true)
+			.open(long_path(path.as_ref()))?;
+		FileExt::lock_exclusive(&file)?;
+		let mut file = LockedFile { file };
+		let mut bytes = Vec::new();
+		file.read_to_end(&mut bytes)?;
+		Ok(Self {
+			file,
+			bytes: Snapshot::new(&bytes),
+		})
+	}
+	/// Returns an immutable view including the edits so far.
+	///
+	/// # Errors
+	///
+	/// Fails like [`NpzView::new`].
+	pub fn view(&self) -> Result<NpzView<'_>, ViewNpzError> {
+		self.bytes.view()
+	}
+	/// Returns a mutable view updating the checksums of edited files, see [`NpzViewMut::new`].
+	///
+	/// # Errors
+	///
+	/// Fails like [`NpzViewMut::new`].
+	pub fn view_mut(&mut self) -> Result<NpzViewMut<'_>, ViewNpzError> {
+		NpzViewMut::new(self.bytes.as_bytes_mut())
+	}
+	/// Writes the edits back to the file and syncs it to disk while still holding the lock.
+	///
+	/// # Errors
+	///
+	/// Fails with [`io::Error`] if the file cannot be written or synced.
+	pub fn commit(&mut self) -> io::Result<()> {
+		self.file.rewind()?;
+		self.file.write_all(self.bytes.as_bytes())?;
+		self.file.file.sync_data()
+	}
+}
@@ -0,0 +1,95 @@
+use super::{sibling_name, NpzReader, NpzWriter, ReadNpzError, WriteNpzError};
+use ndarray::{prelude::*, Data};
+use std::io::{Read, Seek, Write};
+use zip::result::ZipError;
+
+impl<W: Write + Seek> NpzWriter<W> {
+	/// Adds a boolean mask with the specified `name` encoded as run lengths.
+	///
+	/// The elements in standard order are stored as `u64` array `x.rle.npy` of alternating run
+	/// lengths starting with a run of `false`, which is empty if the first element is `true`. The
+	/// shape is stored as `u64` array `x.shape.npy`. In Python, the mask can be restored via
+	/// `numpy.repeat(numpy.arange(len(rle)) % 2 == 1, rle).reshape(shape)`.
+	///
+	/// Optionally, the mask is stored as plain `bool` array `x.npy` as well if `fallback` is
+	/// `true`, so consumers unaware of this encoding can read it at the expense of space.
+	///
+	/// # Errors
+	///
+	/// Adding an array can fail with [`WriteNpyError`](ndarray_npy::WriteNpyError).
+	pub fn add_bool_mask_rle<S, D>(
+		&mut self,
+		name: &str,
+		mask: &ArrayBase<S, D>,
+		fallback: bool,
+	) -> Result<(), WriteNpzError>
+	where
+		S: Data<Elem = bool>,
+		D: Dimension,
+	{
+		let mut runs = Vec::new();
+		let mut value = false;
+		let mut run = 0u64;
+		for &element in mask {
+			if element != value {
+				runs.push(run);
+				value = element;
+				run = 0;
+			}
+			run += 1;
+		}
+		runs.push(run);
+		let shape = mask.shape().iter().map(|&axis| axis as u64);
+		self.add_array(sibling_name(name, "rle"), &Array1::from_vec(runs))?;
+		self.add_array(sibling_name(name, "shape"), &Array1::from_iter(shape))?;
+		if fallback {
+			self.add_array(name, mask)?;
+		}
+		Ok(())
+	}
+}
+
+impl<R: Read + Seek> NpzReader<R> {
+	/// Reads a boolean mask added via [`NpzWriter::add_bool_mask_rle`].
+	///
+	/// Falls back to reading a plain `bool` array if the mask is not encoded as run lengths.
+	///
+	/// # Errors
+	///
+	/// Reading an array from an archive can fail with [`ReadNpyError`](ndarray_npy::ReadNpyError)
+	/// or [`ZipError`]. Fails with [`ZipError::InvalidArchive`] if the run lengths do not match
+	/// the shape.
+	pub fn read_bool_mask<D: Dimension>(
+		&mut self,
+		name: &str,
+	) -> Result<Array<bool, D>, ReadNpzError> {
+		let runs: Array1<u64> = match self.by_name(&sibling_name(name, "rle")) {
+			Err(ReadNpzError::Zip(ZipError::FileNotFound)) => return self.by_name(name),
+			runs => runs?,
+		};
+		let shape: Array1<u64> = self.by_name(&sibling_name(name, "shape"))?;
+		let invalid = || ZipError::InvalidArchive("Run lengths differ from shape");
+		let shape = shape
+			.iter()
+			.map(|&axis| usize::try_from(axis).map_err(|_| invalid()))
+			.collect::<Result<Vec<_>, _>>()?;
+		let len = shape
+			.iter()
+			.try_fold(1usize, |len, &axis| len.checked_mul(axis))
+			.ok_or_else(invalid)?;
+		let mut mask = Vec::with_capacity(len);
+		for (index, &run) in runs.iter().enumerate() {
+			let run = usize::try_from(run).map_err(|_| invalid())?;
+			if run > len - mask.len() {
+				return Err(invalid().into());
+			}
+			mask.resize(mask.len() + run, index % 2 == 1);
+		}
+		if mask.len() != len {
+			return Err(invalid().into());
+		}
+		Array::from_shape_vec(IxDyn(&shape), mask)
+			.and_then(ArrayBase::into_dimensionality)
+			.map_err(|_| invalid().into())
+	}
+}
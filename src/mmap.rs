@@ -0,0 +1,87 @@
+use super::{portable::long_path, NpzView, NpzViewMut, ViewNpzError};
+use memmap2::{MmapMut, MmapOptions};
+use std::{fs::File, io, path::Path};
+
+/// Memory-mapped `.npz` file.
+///
+/// Maps the file copy-on-write via [`Self::open_cow`], i.e., via `MAP_PRIVATE` on POSIX and
+/// `FILE_MAP_COPY` on Windows, so edits via [`NpyViewMut`](crate::NpyViewMut) affect the memory of
+/// this process only and never the file, e.g., for what-if analyses over shared read-only
+/// datasets. Only the pages being edited are copied.
+#[derive(Debug)]
+pub struct NpzMmap {
+	mmap: MmapMut,
+}
+
+impl NpzMmap {
+	/// Memory-maps the `.npz` file at `path` copy-on-write.
+	///
+	/// The file is opened read-only, so mapping a file without write permission succeeds.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use ndarray::Ix1;
+	/// use ndarray_npz::NpzMmap;
+	///
+	/// # #[cfg(target_endian = "little")]
+	/// let path = "tests/examples_little_endian_64_byte_aligned.npz";
+	/// # #[cfg(target_endian = "big")]
+	/// # let path = "tests/examples_big_endian_64_byte_aligned.npz";
+	/// // Safety: The example files are not modified while mapped.
+	/// let mut mmap = unsafe { NpzMmap::open_cow(path)? };
+	/// let mut npz = mmap.view_mut()?;
+	/// npz.by_name("f64.npy")?.view_mut::<f64, Ix1>()?.fill(0.0);
+	/// drop(npz);
+	/// // The edit is visible via the mapping but not via the file.
+	/// let npz = mmap.view()?;
+	/// assert!(npz.by_name("f64.npy")?.view::<f64, Ix1>()?.iter().all(|&x| x == 0.0));
+	/// let npz = unsafe { NpzMmap::open_cow(path)? };
+	/// assert!(npz.view()?.by_name("f64.npy")?.view::<f64, Ix1>()?.iter().any(|&x| x != 0.0));
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Fails with [`io::Error`] if the file cannot be opened or mapped.
+	///
+	/// # Safety
+	///
+	/// The file must neither be truncated nor modified, e.g., by another process, while mapped, as
+	/// pages not yet edited reflect its modifications and accessing truncated pages is undefined
+	/// behavior, see [`MmapOptions::map_copy`].
+	pub unsafe fn open_cow<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+		let file = File::open(long_path(path.as_ref()))?;
+		// Safety: The caller guarantees the file is not modified while mapped.
+		let mmap = unsafe { MmapOptions::new().map_copy(&file)? };
+		Ok(Self { mmap })
+	}
+
+	/// Returns an immutable view including the edits so far.
+	///
+	/// # Errors
+	///
+	/// Fails like [`NpzView::new`].
+	pub fn view(&self) -> Result<NpzView<'_>, ViewNpzError> {
+		NpzView::new(&self.mmap)
+	}
+
+	/// Returns a mutable view whose checksum updates are suppressed, see
+	/// [`NpzViewMut::without_checksum_updates`].
+	///
+	/// As the checksums are left untouched, viewing edited files via [`Self::view`] and verifying
+	/// their checksums fails.
+	///
+	/// # Errors
+	///
+	/// Fails like [`NpzViewMut::new`].
+	pub fn view_mut(&mut self) -> Result<NpzViewMut<'_>, ViewNpzError> {
+		Ok(NpzViewMut::new(&mut self.mmap)?.without_checksum_updates())
+	}
+
+	/// Returns the mapped bytes including the edits so far.
+	#[must_use]
+	pub fn as_bytes(&self) -> &[u8] {
+		&self.mmap
+	}
+}
@@ -0,0 +1,265 @@
+use super::portable::normalize;
+use std::{borrow::Borrow, error::Error, fmt, ops::Deref, path::Path, str::FromStr};
+
+/// Validated name of a file in an `.npz` file.
+///
+/// Names are validated and normalized once on construction, so writers and readers agree on them
+/// across platforms and tools. A name
+///
+///   * is split into components at `/` whereas empty and `.` components are dropped, i.e., leading,
+///     trailing, and repeated separators are removed,
+///   * must neither contain `\` nor NUL nor `..` components, which are rejected instead of being
+///     reinterpreted, and
+///   * must not be empty and not exceed 65535 bytes as limited by zip archives.
+///
+/// With the `unicode` feature, names are normalized to Unicode normalization form C, see
+/// [`portable_path`](crate::portable_path).
+///
+/// It converts into [`String`] and dereferences to [`str`], so it can be passed to all methods
+/// taking names, e.g., [`NpzWriter::add_array`](crate::NpzWriter::add_array) and
+/// [`NpzReader::by_name`](crate::NpzReader::by_name).
+///
+/// # Example
+///
+/// ```
+/// use ndarray::Array1;
+/// use ndarray_npz::{EntryName, NpzReader, NpzWriter};
+/// use std::io::Cursor;
+///
+/// let layer = EntryName::new("model//layer1/")?;
+/// assert_eq!(layer, "model/layer1");
+/// let weights = layer.join("weights")?.with_npy_extension();
+/// assert_eq!(weights, "model/layer1/weights.npy");
+/// assert!(layer.join("../optimizer").is_err());
+/// assert!(EntryName::new(r"model\layer1").is_err());
+///
+/// let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+/// npz.add_array(weights.clone(), &Array1::<f32>::zeros(4))?;
+/// let mut npz = NpzReader::new(npz.finish()?)?;
+/// let weights: Array1<f32> = npz.by_name(&weights)?;
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EntryName(String);
+
+impl EntryName {
+	/// Validates and normalizes `name`.
+	///
+	/// # Errors
+	///
+	/// Fails with [`EntryNameError`] if `name` is invalid.
+	pub fn new(name: impl AsRef<str>) -> Result<Self, EntryNameError> {
+		let name = normalize(name.as_ref());
+		let mut normalized = String::with_capacity(name.len());
+		for component in name.split('/') {
+			match validate(component)? {
+				"" | "." => {}
+				component => {
+					if !normalized.is_empty() {
+						normalized.push('/');
+					}
+					normalized.push_str(component);
+				}
+			}
+		}
+		Self::checked(normalized)
+	}
+
+	/// Appends a single `component`, e.g., a user-provided key which must not add hierarchy.
+	///
+	/// # Errors
+	///
+	/// Fails with [`EntryNameError::Separator`] if `component` contains `/`, with
+	/// [`EntryNameError::Empty`] if it is empty or `.`, or otherwise like [`Self::new`].
+	pub fn join(&self, component: impl AsRef<str>) -> Result<Self, EntryNameError> {
+		let component = normalize(component.as_ref());
+		if component.contains('/') {
+			return Err(EntryNameError::Separator);
+		}
+		match validate(&component)? {
+			"" | "." => Err(EntryNameError::Empty),
+			component => Self::checked(format!("{}/{component}", self.0)),
+		}
+	}
+
+	/// Returns the components separated by `/`.
+	pub fn components(&self) -> impl Iterator<Item = &str> {
+		self.0.split('/')
+	}
+
+	/// Returns the last component.
+	#[must_use]
+	pub fn file_name(&self) -> &str {
+		self.0.rsplit('/').next().unwrap_or_default()
+	}
+
+	/// Returns the name without its last component or `None` if it has a single component.
+	#[must_use]
+	pub fn parent(&self) -> Option<Self> {
+		self.0
+			.rsplit_once('/')
+			.map(|(parent, _name)| Self(parent.to_owned()))
+	}
+
+	/// Returns `true` iff the last component has the extension `npy` ignoring ASCII case.
+	#[must_use]
+	pub fn has_npy_extension(&self) -> bool {
+		Path::new(self.file_name())
+			.extension()
+			.is_some_and(|extension| extension.eq_ignore_ascii_case("npy"))
+	}
+
+	/// Appends `.npy` unless present, e.g., for names written like by [`numpy.savez`].
+	///
+	/// [`numpy.savez`]: https://numpy.org/doc/stable/reference/generated/numpy.savez.html
+	#[must_use]
+	pub fn with_npy_extension(mut self) -> Self {
+		if !self.has_npy_extension() {
+			self.0.push_str(".npy");
+		}
+		self
+	}
+
+	/// Removes `.npy` if present unless the last component would become empty, e.g., for keys
+	/// like those of [`numpy.load`].
+	///
+	/// [`numpy.load`]: https://numpy.org/doc/stable/reference/generated/numpy.load.html
+	#[must_use]
+	pub fn without_npy_extension(mut self) -> Self {
+		if self.has_npy_extension() && self.file_name().len() > ".npy".len() {
+			self.0.truncate(self.0.len() - ".npy".len());
+		}
+		self
+	}
+
+	/// Returns the name as string slice.
+	#[must_use]
+	pub fn as_str(&self) -> &str {
+		&self.0
+	}
+
+	/// Checks the length of the already validated `name`.
+	fn checked(name: String) -> Result<Self, EntryNameError> {
+		if name.is_empty() {
+			Err(EntryNameError::Empty)
+		} else if name.len() > usize::from(u16::MAX) {
+			Err(EntryNameError::TooLong)
+		} else {
+			Ok(Self(name))
+		}
+	}
+}
+
+/// Rejects invalid characters and `..` in a single `component`.
+fn validate(component: &str) -> Result<&str, EntryNameError> {
+	if component.contains('\0') {
+		Err(EntryNameError::Nul)
+	} else if component.contains('\\') {
+		Err(EntryNameError::Backslash)
+	} else if component == ".." {
+		Err(EntryNameError::ParentDir)
+	} else {
+		Ok(component)
+	}
+}
+
+impl Deref for EntryName {
+	type Target = str;
+
+	fn deref(&self) -> &str {
+		&self.0
+	}
+}
+
+impl AsRef<str> for EntryName {
+	fn as_ref(&self) -> &str {
+		&self.0
+	}
+}
+
+impl Borrow<str> for EntryName {
+	fn borrow(&self) -> &str {
+		&self.0
+	}
+}
+
+impl From<EntryName> for String {
+	fn from(name: EntryName) -> String {
+		name.0
+	}
+}
+
+impl TryFrom<&str> for EntryName {
+	type Error = EntryNameError;
+
+	fn try_from(name: &str) -> Result<Self, Self::Error> {
+		Self::new(name)
+	}
+}
+
+impl TryFrom<String> for EntryName {
+	type Error = EntryNameError;
+
+	fn try_from(name: String) -> Result<Self, Self::Error> {
+		Self::new(name)
+	}
+}
+
+impl FromStr for EntryName {
+	type Err = EntryNameError;
+
+	fn from_str(name: &str) -> Result<Self, Self::Err> {
+		Self::new(name)
+	}
+}
+
+impl PartialEq<str> for EntryName {
+	fn eq(&self, other: &str) -> bool {
+		self.0 == other
+	}
+}
+
+impl PartialEq<&str> for EntryName {
+	fn eq(&self, other: &&str) -> bool {
+		self.0 == *other
+	}
+}
+
+impl fmt::Display for EntryName {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(&self.0)
+	}
+}
+
+/// An error validating an [`EntryName`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum EntryNameError {
+	/// The name has no components.
+	Empty,
+	/// The name contains NUL.
+	Nul,
+	/// The name contains `\`, which some tools treat as separator.
+	Backslash,
+	/// The name contains a `..` component.
+	ParentDir,
+	/// A single component contains `/`, see [`EntryName::join`].
+	Separator,
+	/// The name exceeds 65535 bytes.
+	TooLong,
+}
+
+impl Error for EntryNameError {}
+
+impl fmt::Display for EntryNameError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			EntryNameError::Empty => write!(f, "empty name"),
+			EntryNameError::Nul => write!(f, "name contains NUL"),
+			EntryNameError::Backslash => write!(f, "name contains backslash"),
+			EntryNameError::ParentDir => write!(f, "name contains `..` component"),
+			EntryNameError::Separator => write!(f, "name component contains `/`"),
+			EntryNameError::TooLong => write!(f, "name exceeds 65535 bytes"),
+		}
+	}
+}
@@ -0,0 +1,346 @@
+use super::{
+	capability::{u16_at, CENTRAL_SIGNATURE},
+	container::TempFile,
+	volume::{relocate_central_header, write_eocd},
+	NpzWriter,
+};
+use std::{
+	cmp::Reverse,
+	collections::HashMap,
+	fs::File,
+	io::{self, Read, Seek, SeekFrom, Write},
+};
+use zip::{result::ZipError, CompressionMethod, ZipArchive};
+
+/// Order of files within an `.npz` file, see [`NpzWriter::set_entry_order`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Order {
+	/// Order in which the files have been added.
+	#[default]
+	Insertion,
+	/// Byte-wise order of names.
+	Alphabetical,
+	/// Descending order of uncompressed sizes, keeping files of equal size in insertion order.
+	SizeDescending,
+}
+
+/// Reorders the files of a finished archive in place, replacing files by the renamed ones.
+pub(crate) type Reorder<W> =
+	fn(&mut W, Order, &HashMap<String, String>, u16) -> Result<(), ZipError>;
+
+impl<W: Read + Write + Seek> NpzWriter<W> {
+	/// Sets the `order` of the files within the `.npz` file applied by [`Self::finish`].
+	///
+	/// As the files are written as they are added, [`Self::finish`] rewrites them in the `order`
+	/// via a final pass over the archive, hence the writer must be readable. Stored files aligned
+	/// via [`Self::with_alignment`] stay aligned. Placing small frequently-read files first, e.g., metadata next to
+	/// huge arrays, reduces the number of requests of cold reads over network file systems.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use ndarray::{Array1, Array2};
+	/// use ndarray_npz::{NpzReader, NpzWriter, Order};
+	/// use std::io::Cursor;
+	///
+	/// let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	/// npz.set_entry_order(Order::Alphabetical);
+	/// npz.add_array("weights", &Array2::<f32>::zeros((64, 64)))?;
+	/// npz.add_array("bias", &Array1::<f32>::zeros(64))?;
+	/// let mut npz = NpzReader::new(npz.finish()?)?;
+	/// assert_eq!(npz.names()?, ["bias", "weights"]);
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	pub fn set_entry_order(&mut self, order: Order) {
+		self.order = Some((order, reorder::<W>));
+	}
+
+	/// Replaces files by later ones added under the same name instead of applying the
+	/// [`Duplicates`](crate::Duplicates) policy.
+	///
+	/// Like in a Python `dict`, the replacing file takes the place of the replaced one. As the zip
+	/// archive cannot hold duplicate names, a replacing file is added under a placeholder name,
+	/// whereas [`Self::finish`] drops the replaced files and renames the replacing ones via the
+	/// final pass of [`Self::set_entry_order`], hence the writer must be readable. Until then,
+	/// e.g., in snapshots, the replaced files remain. Arrays packed via [`Self::with_packing`] or
+	/// chunked via [`Self::with_chunking`] cannot be replaced.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use ndarray::Array1;
+	/// use ndarray_npz::{NpzReader, NpzWriter};
+	/// use std::io::Cursor;
+	///
+	/// let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	/// npz.overwrite_duplicates();
+	/// npz.add_array("loss", &Array1::<f64>::zeros(3))?;
+	/// npz.add_array("step", &Array1::<u64>::zeros(1))?;
+	/// npz.add_array("loss", &Array1::<f64>::ones(3))?;
+	/// let mut npz = NpzReader::new(npz.finish()?)?;
+	/// assert_eq!(npz.names()?, ["loss", "step"]);
+	/// let loss: Array1<f64> = npz.by_name("loss")?;
+	/// assert_eq!(loss, Array1::<f64>::ones(3));
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	pub fn overwrite_duplicates(&mut self) {
+		self.overwrites.get_or_insert_with(HashMap::new);
+		self.order.get_or_insert((Order::Insertion, reorder::<W>));
+	}
+}
+
+/// Location of a file within the archive.
+struct Entry {
+	name: String,
+	size: u64,
+	stored: bool,
+	header_start: u64,
+	data_start: u64,
+	compressed_size: u64,
+	gap: u64,
+	central_header_start: u64,
+	rename: Option<String>,
+}
+
+/// ID of the extra field padding files to their alignment.
+pub(crate) const PADDING_ID: u16 = 0xa11e;
+
+/// Rewrites the files of the finished archive of `writer` in the `order`.
+///
+/// Files named by a key of `renames` replace the earlier file named by its value, taking its place
+/// and name. Stored files aligned to `alignment` or to the alignment recorded in their padding
+/// stay aligned. Data descriptors are copied along with the data. The archive is copied to a
+/// temporary file and rewritten starting at its first local header. The central directory is
+/// placed such that the archive does not shrink, as the writer cannot be truncated.
+fn reorder<W: Read + Write + Seek>(
+	writer: &mut W,
+	order: Order,
+	renames: &HashMap<String, String>,
+	alignment: u16,
+) -> Result<(), ZipError> {
+	if order == Order::Insertion && renames.is_empty() {
+		return Ok(());
+	}
+	let mut zip = ZipArchive::new(&mut *writer)?;
+	let comment = zip.comment().to_vec();
+	let mut entries = entries(&mut zip)?;
+	drop(zip);
+	let Some(start) = entries.iter().map(|entry| entry.header_start).min() else {
+		return Ok(());
+	};
+	if !renames.is_empty() {
+		entries = replace(entries, renames);
+	}
+	match order {
+		Order::Insertion => {}
+		Order::Alphabetical => entries.sort_by(|a, b| a.name.cmp(&b.name)),
+		Order::SizeDescending => entries.sort_by_key(|entry| Reverse(entry.size)),
+	}
+	let len = writer.seek(SeekFrom::End(0))?;
+	let mut temp = TempFile::new()?;
+	writer.seek(SeekFrom::Start(start))?;
+	io::copy(&mut (&mut *writer).take(len - start), &mut temp.file)?;
+	writer.seek(SeekFrom::Start(start))?;
+	let mut pos = start;
+	let mut cd = Vec::new();
+	for entry in &entries {
+		let (local, central, data_len) = headers(&mut temp.file, entry, start, pos, alignment)?;
+		writer.write_all(&local)?;
+		temp.file.seek(SeekFrom::Start(entry.data_start - start))?;
+		io::copy(&mut (&mut temp.file).take(data_len), writer)?;
+		relocate_central_header(&central, &mut cd, |_disk, _offset| Ok(pos))?;
+		pos += local.len() as u64 + data_len;
+	}
+	let cd_size = cd.len() as u64;
+	let mut eocd = Vec::new();
+	write_eocd(&mut eocd, entries.len() as u64, cd_size, pos, &comment);
+	let cd_offset = pos.max(len.saturating_sub(cd_size + eocd.len() as u64));
+	eocd.clear();
+	write_eocd(
+		&mut eocd,
+		entries.len() as u64,
+		cd_size,
+		cd_offset,
+		&comment,
+	);
+	io::copy(&mut io::repeat(0).take(cd_offset - pos), writer)?;
+	writer.write_all(&cd)?;
+	writer.write_all(&eocd)?;
+	Ok(())
+}
+
+/// Returns the locations of the files of `zip` in insertion order.
+fn entries<R: Read + Seek>(zip: &mut ZipArchive<R>) -> Result<Vec<Entry>, ZipError> {
+	let mut entries = Vec::with_capacity(zip.len());
+	for index in 0..zip.len() {
+		let file = zip.by_index_raw(index)?;
+		entries.push(Entry {
+			name: file.name().to_owned(),
+			size: file.size(),
+			stored: file.compression() == CompressionMethod::Stored,
+			header_start: file.header_start(),
+			data_start: file.data_start(),
+			compressed_size: file.compressed_size(),
+			gap: 0,
+			central_header_start: file.central_header_start(),
+			rename: None,
+		});
+	}
+	// The gap up to the next local header or the central directory holds the data descriptor.
+	let mut ends = entries
+		.iter()
+		.map(|entry| entry.header_start)
+		.chain(entries.iter().map(|entry| entry.central_header_start).min())
+		.collect::<Vec<_>>();
+	ends.sort_unstable();
+	for entry in &mut entries {
+		let data_end = entry.data_start + entry.compressed_size;
+		let next = ends.partition_point(|&end| end < data_end);
+		entry.gap = ends.get(next).map_or(0, |&end| end - data_end);
+	}
+	Ok(entries)
+}
+
+/// Replaces the files named by the values of `renames` by the files named by its keys.
+fn replace(entries: Vec<Entry>, renames: &HashMap<String, String>) -> Vec<Entry> {
+	let mut slots = HashMap::new();
+	let mut replaced = Vec::with_capacity(entries.len());
+	for mut entry in entries {
+		entry.rename = renames.get(&entry.name).cloned();
+		let name = entry.rename.as_ref().unwrap_or(&entry.name).clone();
+		if let Some(&slot) = slots.get(&name) {
+			replaced[slot] = entry;
+		} else {
+			slots.insert(name, replaced.len());
+			replaced.push(entry);
+		}
+	}
+	replaced
+}
+
+/// Returns the local and central header of `entry` moved to `pos` and the length of its data
+/// including its data descriptor.
+///
+/// The headers are read from the `temp` copy of the archive starting at `start`.
+fn headers(
+	temp: &mut File,
+	entry: &Entry,
+	start: u64,
+	pos: u64,
+	alignment: u16,
+) -> Result<(Vec<u8>, Vec<u8>, u64), ZipError> {
+	let invalid = || ZipError::InvalidArchive("Invalid local header");
+	let header_len = entry.data_start - entry.header_start;
+	let header = read_at(temp, entry.header_start - start, header_len)?;
+	let name_end = 30 + usize::from(u16_at(&header, 26).ok_or_else(invalid)?);
+	let extra_end = name_end + usize::from(u16_at(&header, 28).ok_or_else(invalid)?);
+	let mut local = header[..name_end].to_vec();
+	let mut central = central_header(temp, entry.central_header_start - start)?;
+	if let Some(name) = &entry.rename {
+		let name_len = u16::try_from(name.len()).map_err(|_| invalid())?;
+		local.splice(30.., name.bytes());
+		local[26..28].copy_from_slice(&name_len.to_le_bytes());
+		let old_len = usize::from(u16_at(&central, 28).ok_or_else(invalid)?);
+		central.splice(46..46 + old_len, name.bytes());
+		central[28..30].copy_from_slice(&name_len.to_le_bytes());
+	}
+	let extra = header.get(name_end..extra_end).ok_or_else(invalid)?;
+	// Keeps the largest of the alignments the data is aligned to.
+	let alignment = [Some(alignment), padding_alignment(extra)]
+		.into_iter()
+		.flatten()
+		.filter(|&alignment| alignment > 1 && entry.data_start % u64::from(alignment) == 0)
+		.max();
+	let mut extra = without_padding(extra);
+	if let Some(alignment) = alignment.filter(|_| entry.stored) {
+		let offset = pos + (local.len() + extra.len()) as u64;
+		pad(&mut extra, offset, alignment).ok_or_else(invalid)?;
+	}
+	let extra_len = u16::try_from(extra.len()).map_err(|_| invalid())?;
+	local[28..30].copy_from_slice(&extra_len.to_le_bytes());
+	local.extend(extra);
+	let has_descriptor = u16_at(&header, 6).ok_or_else(invalid)? & 0x0008 != 0;
+	let data_len = entry.compressed_size + if has_descriptor { entry.gap } else { 0 };
+	Ok((local, central, data_len))
+}
+
+/// Appends a padding field to `extra` ending at `offset` such that the data is aligned to
+/// `alignment`, returns `None` if the padding does not fit.
+fn pad(extra: &mut Vec<u8>, offset: u64, alignment: u16) -> Option<()> {
+	let unaligned = offset % u64::from(alignment);
+	if unaligned == 0 {
+		return Some(());
+	}
+	// Like the zip crate, the padding field stores the alignment.
+	let mut padding = u64::from(alignment) - unaligned;
+	while padding < 6 {
+		padding += u64::from(alignment);
+	}
+	let padding = usize::try_from(padding).ok()?;
+	extra.extend(PADDING_ID.to_le_bytes());
+	extra.extend(u16::try_from(padding - 4).ok()?.to_le_bytes());
+	extra.extend(alignment.to_le_bytes());
+	extra.resize(extra.len() + padding - 6, 0);
+	Some(())
+}
+
+/// Returns the `extra` field without alignment padding.
+fn without_padding(extra: &[u8]) -> Vec<u8> {
+	let mut fields = Vec::with_capacity(extra.len());
+	let mut pos = 0;
+	while let (Some(id), Some(len)) = (u16_at(extra, pos), u16_at(extra, pos + 2)) {
+		let end = (pos + 4 + usize::from(len)).min(extra.len());
+		if id != PADDING_ID {
+			fields.extend(&extra[pos..end]);
+		}
+		pos = end;
+	}
+	fields
+}
+
+/// Returns the alignment recorded in the padding field of `extra` if any.
+pub(crate) fn padding_alignment(extra: &[u8]) -> Option<u16> {
+	let mut pos = 0;
+	while let (Some(id), Some(len)) = (u16_at(extra, pos), u16_at(extra, pos + 2)) {
+		if id == PADDING_ID && len >= 2 {
+			return u16_at(extra, pos + 4).filter(|&alignment| alignment > 1);
+		}
+		pos += 4 + usize::from(len);
+	}
+	None
+}
+
+/// Returns the largest alignment recorded in the padding fields of the files of `zip` or 64 bytes
+/// if none is recorded.
+pub(crate) fn archive_alignment<R: Read + Seek>(zip: &mut ZipArchive<R>) -> Result<u16, ZipError> {
+	let mut alignment = None;
+	for index in 0..zip.len() {
+		let file = zip.by_index_raw(index)?;
+		let recorded = file.extra_data().and_then(padding_alignment);
+		alignment = alignment.max(recorded);
+	}
+	Ok(alignment.unwrap_or(64))
+}
+
+/// Reads the central header at `offset`.
+fn central_header(file: &mut File, offset: u64) -> Result<Vec<u8>, ZipError> {
+	let invalid = || ZipError::InvalidArchive("Invalid central header");
+	let mut header = read_at(file, offset, 46)?;
+	if header[..4] != CENTRAL_SIGNATURE.to_le_bytes() {
+		return Err(invalid());
+	}
+	let len = usize::from(u16_at(&header, 28).ok_or_else(invalid)?)
+		+ usize::from(u16_at(&header, 30).ok_or_else(invalid)?)
+		+ usize::from(u16_at(&header, 32).ok_or_else(invalid)?);
+	header.resize(46 + len, 0);
+	file.read_exact(&mut header[46..])?;
+	Ok(header)
+}
+
+/// Reads `len` bytes at `offset`.
+fn read_at(file: &mut File, offset: u64, len: u64) -> io::Result<Vec<u8>> {
+	let mut bytes = vec![0; usize::try_from(len).unwrap_or_default()];
+	file.seek(SeekFrom::Start(offset))?;
+	file.read_exact(&mut bytes)?;
+	Ok(bytes)
+}
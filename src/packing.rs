@@ -0,0 +1,226 @@
+use super::{
+	json::{json_string, parse_json_object},
+	NpzReader, NpzWriter, ReadNpzError, WriteNpzError,
+};
+use ndarray::{ArrayBase, Data, DataOwned, Dimension};
+use ndarray_npy::{ReadNpyExt, ReadableElement, WritableElement};
+use std::{
+	collections::HashMap,
+	io::{Read, Seek, Write},
+	mem,
+	ops::Range,
+};
+use zip::{result::ZipError, ZipArchive};
+
+/// Reserved name of the member concatenating packed `.npy` files.
+pub const PACKED_NAME: &str = "__packed__.bin";
+/// Reserved name of the member indexing the packed `.npy` files.
+pub const PACKED_INDEX_NAME: &str = "__packed__.json";
+
+/// Alignment of packed `.npy` files within the [`PACKED_NAME`] member.
+const ALIGNMENT: usize = 64;
+
+/// Arrays to be packed on [`NpzWriter::finish`].
+#[derive(Debug)]
+pub(crate) struct Packing {
+	max_bytes: usize,
+	bytes: Vec<u8>,
+	index: Vec<(String, Range<usize>)>,
+}
+
+/// Packed `.npy` files read on [`NpzReader::new`].
+#[derive(Debug)]
+pub(crate) struct Packed {
+	bytes: Vec<u8>,
+	names: Vec<String>,
+	index: HashMap<String, Range<usize>>,
+}
+
+impl<W: Write + Seek> NpzWriter<W> {
+	/// Packs arrays encoded to at most `max_bytes` into a single member on [`Self::finish`].
+	///
+	/// For archives of thousands of tiny arrays, e.g., scalars, the per-file overhead of zip
+	/// dominates. Packed arrays are concatenated as `.npy` files in the [`PACKED_NAME`] member,
+	/// each 64-byte aligned, and indexed by the [`PACKED_INDEX_NAME`] member of UTF-8 JSON in the
+	/// form of `{"a.npy": [64, 136], "b.npy": [256, 128]}` mapping names to byte offsets and
+	/// lengths. The member starts with 64 zero bytes, so it is not mistaken for an `.npy` file.
+	/// Reading packed arrays via [`NpzReader`] is transparent, whereas Python reads them via
+	///
+	/// ```python
+	/// import io, json, numpy as np
+	///
+	/// npz = np.load("arrays.npz")
+	/// index = json.loads(npz["__packed__.json"])
+	/// packed = npz["__packed__.bin"]
+	/// arrays = {
+	///     name: np.load(io.BytesIO(packed[start:start + len]))
+	///     for name, (start, len) in index.items()
+	/// }
+	/// ```
+	///
+	/// # Example
+	///
+	/// ```
+	/// use ndarray::{arr0, Array0};
+	/// use ndarray_npz::{NpzReader, NpzWriter};
+	/// use std::io::Cursor;
+	///
+	/// let mut npz = NpzWriter::new(Cursor::new(Vec::new())).with_packing(256);
+	/// for index in 0..1000 {
+	/// 	npz.add_array(format!("x{index}.npy"), &arr0(f64::from(index)))?;
+	/// }
+	/// let mut npz = NpzReader::new(npz.finish()?)?;
+	/// assert_eq!(npz.len(), 1000);
+	/// let x: Array0<f64> = npz.by_name("x42.npy")?;
+	/// assert_eq!(x, arr0(42.0));
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	#[must_use]
+	pub fn with_packing(mut self, max_bytes: usize) -> Self {
+		self.packing = Some(Packing {
+			max_bytes,
+			bytes: vec![0; ALIGNMENT],
+			index: Vec::new(),
+		});
+		self
+	}
+
+	/// Packs the array if enabled and small enough, returns whether it has been added.
+	pub(crate) fn add_packable<S, D>(
+		&mut self,
+		name: &str,
+		array: &ArrayBase<S, D>,
+	) -> Result<bool, WriteNpzError>
+	where
+		S::Elem: WritableElement,
+		S: Data,
+		D: Dimension,
+	{
+		let Some(max_bytes) = self.packing.as_ref().map(|packing| packing.max_bytes) else {
+			return Ok(false);
+		};
+		if array.len().saturating_mul(mem::size_of::<S::Elem>()) > max_bytes {
+			return Ok(false);
+		}
+		let bytes = self.npy_bytes(array)?;
+		if !self.add_packable_bytes(name, &bytes) {
+			self.zip.start_file(name, self.options)?;
+			self.zip.write_all(&bytes).map_err(ZipError::from)?;
+		}
+		Ok(true)
+	}
+
+	/// Packs the encoded array if enabled and small enough, returns whether it has been packed.
+	pub(crate) fn add_packable_bytes(&mut self, name: &str, bytes: &[u8]) -> bool {
+		let Some(packing) = &mut self.packing else {
+			return false;
+		};
+		if bytes.len() > packing.max_bytes {
+			return false;
+		}
+		let start = packing.bytes.len().next_multiple_of(ALIGNMENT);
+		packing.bytes.resize(start, 0);
+		packing.bytes.extend_from_slice(bytes);
+		let range = start..packing.bytes.len();
+		packing.index.push((name.to_owned(), range));
+		true
+	}
+
+	/// Adds the packed arrays and their index if any.
+	pub(crate) fn add_packed(&mut self) -> Result<(), WriteNpzError> {
+		let Some(packing) = self.packing.take() else {
+			return Ok(());
+		};
+		if packing.index.is_empty() {
+			return Ok(());
+		}
+		let index = packing
+			.index
+			.iter()
+			.map(|(name, range)| {
+				format!("{}: [{}, {}]", json_string(name), range.start, range.len())
+			})
+			.collect::<Vec<_>>();
+		self.zip.start_file(PACKED_NAME, self.options)?;
+		self.zip.write_all(&packing.bytes).map_err(ZipError::from)?;
+		self.zip.start_file(PACKED_INDEX_NAME, self.options)?;
+		self.zip
+			.write_all(format!("{{{}}}\n", index.join(", ")).as_bytes())
+			.map_err(ZipError::from)?;
+		Ok(())
+	}
+}
+
+impl Packed {
+	/// Reads the packed `.npy` files of `zip` if any.
+	pub(crate) fn read<R: Read + Seek>(zip: &mut ZipArchive<R>) -> Result<Option<Self>, ZipError> {
+		let invalid = || ZipError::InvalidArchive("Invalid index of packed files");
+		if zip.index_for_name(PACKED_INDEX_NAME).is_none() {
+			return Ok(None);
+		}
+		let mut json = String::new();
+		zip.by_name(PACKED_INDEX_NAME)?.read_to_string(&mut json)?;
+		let (members, rest) = parse_json_object(&json, parse_range).ok_or_else(invalid)?;
+		if !rest.trim().is_empty() {
+			return Err(invalid());
+		}
+		let mut bytes = Vec::new();
+		zip.by_name(PACKED_NAME)?.read_to_end(&mut bytes)?;
+		if members.iter().any(|(_name, range)| range.end > bytes.len()) {
+			return Err(invalid());
+		}
+		Ok(Some(Self {
+			bytes,
+			names: members.iter().map(|(name, _range)| name.clone()).collect(),
+			index: members.into_iter().collect(),
+		}))
+	}
+	/// Whether `name` is one of the reserved members.
+	pub(crate) fn is_reserved(name: &str) -> bool {
+		name == PACKED_NAME || name == PACKED_INDEX_NAME
+	}
+	/// Names of the packed `.npy` files in packing order.
+	pub(crate) fn names(&self) -> &[String] {
+		&self.names
+	}
+	/// Returns the packed `.npy` file `name` if any.
+	fn get(&self, name: &str) -> Option<&[u8]> {
+		self.index.get(name).map(|range| &self.bytes[range.clone()])
+	}
+}
+
+impl<R: Read + Seek> NpzReader<R> {
+	/// Reads a packed array by name if any, see [`NpzWriter::with_packing`].
+	pub(crate) fn by_name_packed<S, D>(
+		&self,
+		name: &str,
+	) -> Result<Option<ArrayBase<S, D>>, ReadNpzError>
+	where
+		S::Elem: ReadableElement,
+		S: DataOwned,
+		D: Dimension,
+	{
+		let Some(bytes) = self.packed.as_ref().and_then(|packed| packed.get(name)) else {
+			return Ok(None);
+		};
+		Ok(Some(ArrayBase::<S, D>::read_npy(bytes)?))
+	}
+}
+
+/// Parses a JSON array of a byte offset and length at the start of `json`.
+fn parse_range(json: &str) -> Option<(Range<usize>, &str)> {
+	let json = json.strip_prefix('[')?.trim_start();
+	let (start, json) = parse_integer(json)?;
+	let json = json.trim_start().strip_prefix(',')?.trim_start();
+	let (len, json) = parse_integer(json)?;
+	let json = json.trim_start().strip_prefix(']')?;
+	Some((start..start.checked_add(len)?, json))
+}
+
+/// Parses a non-negative JSON integer at the start of `json`.
+fn parse_integer(json: &str) -> Option<(usize, &str)> {
+	let len = json
+		.find(|char: char| !char.is_ascii_digit())
+		.unwrap_or(json.len());
+	Some((json[..len].parse().ok()?, &json[len..]))
+}
@@ -0,0 +1,296 @@
+use super::{
+	container::TempFile,
+	progress::{npy_len, Progress},
+	NpzWriter, WriteNpzError,
+};
+use crc32fast::Hasher;
+use flate2::{Compress, Compression, FlushCompress, Status};
+use ndarray::{ArrayBase, Data, Dimension};
+use ndarray_npy::{WritableElement, WriteNpyExt};
+use std::{
+	io::{self, BufWriter, Seek, SeekFrom, Write},
+	mem,
+	num::NonZeroUsize,
+	panic, thread,
+};
+use zip::{result::ZipError, CompressionMethod, ZipArchive, ZipWriter};
+
+/// Size in bytes of the blocks compressed independently.
+const BLOCK_SIZE: usize = 1 << 20;
+
+/// Configuration of [`NpzWriter::with_parallel_deflate`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ParallelDeflate {
+	level: u32,
+	threads: usize,
+}
+
+impl<W: Write + Seek> NpzWriter<W> {
+	/// Compresses large arrays with *deflate* at `level` from 0 to 9 using all available cores.
+	///
+	/// Like [`pigz`], arrays of at least two blocks of 1 MiB are split into blocks compressed
+	/// independently on as many scoped threads as there is
+	/// [available parallelism](thread::available_parallelism), whose outputs are concatenated
+	/// into a single *deflate* stream readable by any zip tool. Compressing each block without the
+	/// preceding one as dictionary increases the compressed size only marginally. The compressed
+	/// data is staged in a temporary file and copied into the archive with the options of the
+	/// writer, e.g., its modification time, but as deflated regardless of its compression method.
+	/// Smaller arrays are added as usual, e.g., compressed on the calling thread by
+	/// [`Self::new_compressed`].
+	///
+	/// [`pigz`]: https://zlib.net/pigz/
+	///
+	/// # Example
+	///
+	/// ```
+	/// use ndarray::Array1;
+	/// use ndarray_npz::{NpzReader, NpzWriter};
+	/// use std::io::Cursor;
+	///
+	/// let x = Array1::from_shape_fn(1 << 20, |i| (i % 1000) as f32);
+	/// let mut npz = NpzWriter::new_compressed(Cursor::new(Vec::new())).with_parallel_deflate(6);
+	/// npz.add_array("x", &x)?;
+	/// let mut npz = NpzReader::new(npz.finish()?)?;
+	/// assert_eq!(npz.by_name::<ndarray::OwnedRepr<f32>, ndarray::Ix1>("x")?, x);
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	#[must_use]
+	pub fn with_parallel_deflate(mut self, level: u32) -> Self {
+		self.parallel = Some(ParallelDeflate {
+			level: level.min(9),
+			threads: thread::available_parallelism().map_or(1, NonZeroUsize::get),
+		});
+		self
+	}
+
+	/// Adds the array compressed in parallel if enabled and large enough, returns whether it has
+	/// been added.
+	pub(crate) fn add_parallel<S, D>(
+		&mut self,
+		name: &str,
+		array: &ArrayBase<S, D>,
+	) -> Result<bool, WriteNpzError>
+	where
+		S::Elem: WritableElement,
+		S: Data,
+		D: Dimension,
+	{
+		let len = array.len().saturating_mul(mem::size_of::<S::Elem>());
+		let buffer_size = self.buffer_size;
+		self.add_parallel_with(
+			name,
+			len,
+			|| npy_len(array),
+			|writer| Ok(array.write_npy(BufWriter::with_capacity(buffer_size, writer))?),
+		)
+	}
+
+	/// Adds the encoded array compressed in parallel if enabled and large enough, returns whether
+	/// it has been added.
+	pub(crate) fn add_parallel_bytes(
+		&mut self,
+		name: &str,
+		bytes: &[u8],
+	) -> Result<bool, WriteNpzError> {
+		self.add_parallel_with(
+			name,
+			bytes.len(),
+			|| bytes.len() as u64,
+			|writer| Ok(writer.write_all(bytes).map_err(ZipError::from)?),
+		)
+	}
+
+	/// Adds the `.npy` file of `total` bytes written by `write` compressed in parallel if enabled
+	/// and its data of `len` bytes is large enough, returns whether it has been added.
+	fn add_parallel_with(
+		&mut self,
+		name: &str,
+		len: usize,
+		total: impl FnOnce() -> u64,
+		write: impl FnOnce(&mut dyn Write) -> Result<(), WriteNpzError>,
+	) -> Result<bool, WriteNpzError> {
+		let Some(parallel) = self.parallel else {
+			return Ok(false);
+		};
+		if len < 2 * BLOCK_SIZE {
+			return Ok(false);
+		}
+		let mut temp = TempFile::new().map_err(ZipError::from)?;
+		let mut scratch = ZipWriter::new(&mut temp.file);
+		scratch.start_file(
+			name,
+			self.options.compression_method(CompressionMethod::Stored),
+		)?;
+		let mut deflater = Deflater::new(&mut scratch, parallel);
+		write(&mut Progress::new(
+			&mut deflater,
+			name,
+			total,
+			self.progress.as_mut(),
+		))?;
+		let (crc32, size) = deflater.finish().map_err(ZipError::from)?;
+		scratch.finish()?;
+		let mut archive = ZipArchive::new(&mut temp.file)?;
+		let central_header_start = archive.by_index_raw(0)?.central_header_start();
+		patch_central_header(&mut temp.file, central_header_start, crc32, size)?;
+		let mut archive = ZipArchive::new(&mut temp.file)?;
+		self.zip.raw_copy_file(archive.by_index_raw(0)?)?;
+		Ok(true)
+	}
+}
+
+/// Patches the central header at `offset` of the stored file into a deflated one of the
+/// uncompressed `size` and `crc32`.
+fn patch_central_header<F: io::Read + Write + Seek>(
+	file: &mut F,
+	offset: u64,
+	crc32: u32,
+	size: u64,
+) -> Result<(), ZipError> {
+	let mut header = [0; 46];
+	file.seek(SeekFrom::Start(offset))?;
+	file.read_exact(&mut header)?;
+	header[10..12].copy_from_slice(&8u16.to_le_bytes());
+	header[16..20].copy_from_slice(&crc32.to_le_bytes());
+	let name_len = u16::from_le_bytes([header[28], header[29]]);
+	let extra_len = u16::from_le_bytes([header[30], header[31]]);
+	if header[24..28] == u32::MAX.to_le_bytes() {
+		// The uncompressed size is the first value of the Zip64 extra field.
+		let mut extra = vec![0; usize::from(extra_len)];
+		file.seek(SeekFrom::Start(offset + 46 + u64::from(name_len)))?;
+		file.read_exact(&mut extra)?;
+		let mut pos = 0;
+		loop {
+			let field = extra
+				.get(pos..pos + 4)
+				.ok_or(ZipError::InvalidArchive("Missing Zip64 extra field"))?;
+			let field_len = usize::from(u16::from_le_bytes([field[2], field[3]]));
+			if field[..2] == 0x0001u16.to_le_bytes() && field_len >= 8 {
+				extra[pos + 4..pos + 12].copy_from_slice(&size.to_le_bytes());
+				break;
+			}
+			pos += 4 + field_len;
+		}
+		file.seek(SeekFrom::Start(offset + 46 + u64::from(name_len)))?;
+		file.write_all(&extra)?;
+	} else {
+		let size = u32::try_from(size)
+			.ok()
+			.filter(|&size| size != u32::MAX)
+			.ok_or(ZipError::InvalidArchive(
+				"Large file option has not been set",
+			))?;
+		header[24..28].copy_from_slice(&size.to_le_bytes());
+	}
+	file.seek(SeekFrom::Start(offset))?;
+	file.write_all(&header)?;
+	Ok(())
+}
+
+/// Writer compressing batches of blocks in parallel into a single *deflate* stream.
+struct Deflater<W: Write> {
+	inner: W,
+	parallel: ParallelDeflate,
+	batch: Vec<u8>,
+	hasher: Hasher,
+	size: u64,
+}
+
+impl<W: Write> Deflater<W> {
+	fn new(inner: W, parallel: ParallelDeflate) -> Self {
+		Self {
+			inner,
+			parallel,
+			batch: Vec::with_capacity(parallel.threads * BLOCK_SIZE),
+			hasher: Hasher::new(),
+			size: 0,
+		}
+	}
+
+	/// Compresses the batch, ending the stream if `last`.
+	fn compress_batch(&mut self, last: bool) -> io::Result<()> {
+		let level = self.parallel.level;
+		let blocks = self.batch.chunks(BLOCK_SIZE).collect::<Vec<_>>();
+		let count = blocks.len();
+		let outputs = thread::scope(|scope| {
+			blocks
+				.into_iter()
+				.enumerate()
+				.map(|(index, block)| {
+					let last = last && index + 1 == count;
+					scope.spawn(move || {
+						let mut hasher = Hasher::new();
+						hasher.update(block);
+						deflate(block, level, last).map(|output| (hasher, output))
+					})
+				})
+				.collect::<Vec<_>>()
+				.into_iter()
+				.map(|thread| {
+					thread
+						.join()
+						.unwrap_or_else(|err| panic::resume_unwind(err))
+				})
+				.collect::<io::Result<Vec<_>>>()
+		})?;
+		for (hasher, output) in outputs {
+			self.hasher.combine(&hasher);
+			self.inner.write_all(&output)?;
+		}
+		if last && count == 0 {
+			self.inner.write_all(&deflate(&[], level, true)?)?;
+		}
+		self.size += self.batch.len() as u64;
+		self.batch.clear();
+		Ok(())
+	}
+
+	/// Ends the stream and returns the checksum and size of the uncompressed data.
+	fn finish(mut self) -> io::Result<(u32, u64)> {
+		self.compress_batch(true)?;
+		Ok((self.hasher.finalize(), self.size))
+	}
+}
+
+impl<W: Write> Write for Deflater<W> {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		let len = buf.len().min(self.batch.capacity() - self.batch.len());
+		self.batch.extend_from_slice(&buf[..len]);
+		if self.batch.len() == self.batch.capacity() {
+			self.compress_batch(false)?;
+		}
+		Ok(len)
+	}
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+/// Compresses the `block` into raw *deflate* ending with the final block if `last` and byte
+/// aligned by a sync flush otherwise.
+fn deflate(block: &[u8], level: u32, last: bool) -> io::Result<Vec<u8>> {
+	let mut compress = Compress::new(Compression::new(level), false);
+	let flush = if last {
+		FlushCompress::Finish
+	} else {
+		FlushCompress::Sync
+	};
+	let mut output = Vec::with_capacity(block.len() / 2 + 64);
+	loop {
+		if output.len() == output.capacity() {
+			output.reserve(output.capacity());
+		}
+		let consumed = usize::try_from(compress.total_in()).unwrap_or(usize::MAX);
+		let status = compress
+			.compress_vec(&block[consumed..], &mut output, flush)
+			.map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+		let done = if last {
+			status == Status::StreamEnd
+		} else {
+			compress.total_in() == block.len() as u64 && output.len() < output.capacity()
+		};
+		if done {
+			return Ok(output);
+		}
+	}
+}
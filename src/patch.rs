@@ -0,0 +1,74 @@
+use super::{Chunked, EditNpzError, NpzEditor, NpzReader, Packed};
+use ndarray_npy::WritableElement;
+use std::io::{Read, Seek, Write};
+use zip::ZipArchive;
+
+impl<R: Read + Write + Seek> NpzReader<R> {
+	/// Overwrites the stored bytes of the file `name` at `offset` with `bytes` and returns the
+	/// reader of the patched archive.
+	///
+	/// Seeks to and overwrites the bytes and the CRC-32 checksums in place without constructing
+	/// views, e.g., for quick metadata fixes in scripts of read-write files. The reader is consumed
+	/// and returned since the archive is parsed again to pick up the new checksum. Files packed
+	/// via [`NpzWriter::with_packing`](crate::NpzWriter::with_packing) cannot be patched. See
+	/// [`NpzEditor::patch_bytes`].
+	///
+	/// # Example
+	///
+	/// ```no_run
+	/// use ndarray::Array0;
+	/// use ndarray_npz::NpzReader;
+	/// use std::fs::OpenOptions;
+	///
+	/// let file = OpenOptions::new().read(true).write(true).open("arrays.npz")?;
+	/// let npz = NpzReader::new(file)?;
+	/// let mut npz = npz.patch_scalar("version.npy", 2u32)?;
+	/// let version: Array0<u32> = npz.by_name("version.npy")?;
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Fails like [`NpzEditor::patch_bytes`], in which case the reader is dropped.
+	pub fn patch_bytes(self, name: &str, offset: u64, bytes: &[u8]) -> Result<Self, EditNpzError> {
+		self.patch(|npz| npz.patch_bytes(name, offset, bytes))
+	}
+
+	/// Overwrites the single element of the array of the file `name` with `value` and returns the
+	/// reader of the patched archive.
+	///
+	/// See [`Self::patch_bytes`] and [`NpzEditor::patch_scalar`].
+	///
+	/// # Errors
+	///
+	/// Fails like [`NpzEditor::patch_scalar`], in which case the reader is dropped.
+	pub fn patch_scalar<A: WritableElement>(
+		self,
+		name: &str,
+		value: A,
+	) -> Result<Self, EditNpzError> {
+		self.patch(|npz| npz.patch_scalar(name, value))
+	}
+
+	/// Applies `f` to an editor of the inner reader and parses the patched archive again.
+	fn patch<F>(self, f: F) -> Result<Self, EditNpzError>
+	where
+		F: FnOnce(&mut NpzEditor<R>) -> Result<u32, EditNpzError>,
+	{
+		let mut npz = NpzEditor::new(self.zip.into_inner())?;
+		f(&mut npz)?;
+		let mut zip = ZipArchive::new(npz.into_inner())?;
+		Ok(Self {
+			packed: Packed::read(&mut zip)?,
+			chunked: Chunked::read(&mut zip)?,
+			zip,
+			cache: self.cache,
+			unsupported: self.unsupported,
+			directories: self.directories,
+			normalize: self.normalize,
+			lenient: self.lenient,
+			timeout: self.timeout,
+			max_entry_size: self.max_entry_size,
+		})
+	}
+}
@@ -0,0 +1,354 @@
+use super::{builder::swap_npy, progress::Progress, Endianness, NpzWriter, WriteNpzError};
+use ndarray::{ArrayBase, Data, Dimension};
+use ndarray_npy::{WritableElement, WriteNpyExt};
+use std::{
+	fmt,
+	io::{self, Seek, Write},
+	mem, panic,
+	sync::{
+		mpsc::{self, Sender},
+		Arc, Condvar, Mutex, MutexGuard, PoisonError,
+	},
+	thread::{self, JoinHandle},
+};
+use zip::result::ZipError;
+
+/// Snapshot of the state of a [`PipelinedWriter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct PipelineMetrics {
+	/// Number of encoded arrays queued but not yet written.
+	pub queue_len: usize,
+	/// Number of encoded bytes queued but not yet written.
+	pub bytes_in_flight: u64,
+	/// Number of arrays written so far.
+	pub entries_written: u64,
+	/// Number of encoded bytes written so far.
+	pub bytes_written: u64,
+}
+
+/// Callback observing the [`PipelineMetrics`] whenever they change.
+type MetricsHook = Box<dyn Fn(&PipelineMetrics) + Send>;
+
+/// Writer of `.npz` files encoding arrays on the calling thread while writing them on a
+/// background thread.
+///
+/// Encoded arrays are queued in memory until written. To bound the memory of the pipeline, the
+/// queue is limited in depth and in bytes in flight and [`Self::add_array`] blocks while either
+/// limit is hit, so producers are slowed down to the pace of the underlying writer. A single array
+/// exceeding the limit of bytes in flight is admitted once the queue has drained. Arrays whose
+/// encoding exceeds the per-entry limit are rejected.
+///
+/// # Example
+///
+/// ```no_run
+/// use ndarray::Array2;
+/// use ndarray_npz::{NpzWriter, PipelinedWriter};
+/// use std::fs::File;
+///
+/// let npz = NpzWriter::new(File::create("arrays.npz")?);
+/// let mut npz = PipelinedWriter::new(npz)
+/// 	.with_queue_depth(4)
+/// 	.with_max_bytes_in_flight(256 << 20)
+/// 	.with_metrics(|metrics| eprintln!("{} bytes in flight", metrics.bytes_in_flight));
+/// for index in 0..100 {
+/// 	let frame = Array2::<f32>::from_elem((1024, 1024), index as f32);
+/// 	npz.add_array(format!("frame_{index}"), &frame)?;
+/// }
+/// npz.finish()?;
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct PipelinedWriter<W: Write + Seek + Send + 'static> {
+	sender: Option<Sender<(String, Vec<u8>)>>,
+	worker: Option<JoinHandle<Result<NpzWriter<W>, WriteNpzError>>>,
+	shared: Arc<Shared>,
+	npy_extension: bool,
+	endianness: Endianness,
+	queue_depth: usize,
+	max_bytes_in_flight: u64,
+	max_entry_bytes: u64,
+}
+
+/// State shared with the background thread.
+struct Shared {
+	state: Mutex<State>,
+	changed: Condvar,
+}
+
+struct State {
+	metrics: PipelineMetrics,
+	hook: Option<MetricsHook>,
+	failed: bool,
+}
+
+impl Shared {
+	/// Locks the state ignoring poisoning as the state is consistent at any time.
+	fn lock(&self) -> MutexGuard<'_, State> {
+		self.state.lock().unwrap_or_else(PoisonError::into_inner)
+	}
+}
+
+impl State {
+	/// Calls the hook if any.
+	fn notify(&self) {
+		if let Some(hook) = &self.hook {
+			hook(&self.metrics);
+		}
+	}
+}
+
+impl<W: Write + Seek + Send + 'static> PipelinedWriter<W> {
+	/// Moves the `npz` writer to a background thread.
+	///
+	/// By default, the queue is limited to 8 arrays and 1 GiB in flight without a per-entry
+	/// limit. Arrays are encoded and added like by [`NpzWriter::add_array`] of the `npz` writer,
+	/// e.g., in its byte order, packed, or chunked, and their names are completed alike, e.g., by
+	/// appending `.npy` in case of [`NpzWriter::numpy_compat`].
+	#[must_use]
+	pub fn new(mut npz: NpzWriter<W>) -> Self {
+		let npy_extension = npz.npy_extension;
+		let endianness = npz.endianness;
+		let shared = Arc::new(Shared {
+			state: Mutex::new(State {
+				metrics: PipelineMetrics::default(),
+				hook: None,
+				failed: false,
+			}),
+			changed: Condvar::new(),
+		});
+		let (sender, receiver) = mpsc::channel::<(String, Vec<u8>)>();
+		let worker = {
+			let shared = shared.clone();
+			thread::spawn(move || -> Result<NpzWriter<W>, WriteNpzError> {
+				for (name, bytes) in receiver {
+					let result = npz.add_encoded(name, &bytes);
+					let mut state = shared.lock();
+					state.metrics.queue_len -= 1;
+					state.metrics.bytes_in_flight -= bytes.len() as u64;
+					if result.is_ok() {
+						state.metrics.entries_written += 1;
+						state.metrics.bytes_written += bytes.len() as u64;
+					} else {
+						state.failed = true;
+					}
+					state.notify();
+					drop(state);
+					shared.changed.notify_all();
+					result?;
+				}
+				Ok(npz)
+			})
+		};
+		Self {
+			sender: Some(sender),
+			worker: Some(worker),
+			shared,
+			npy_extension,
+			endianness,
+			queue_depth: 8,
+			max_bytes_in_flight: 1 << 30,
+			max_entry_bytes: u64::MAX,
+		}
+	}
+	/// Sets the maximum number of queued arrays, at least one.
+	#[must_use]
+	pub fn with_queue_depth(mut self, queue_depth: usize) -> Self {
+		self.queue_depth = queue_depth.max(1);
+		self
+	}
+	/// Sets the maximum number of queued bytes.
+	#[must_use]
+	pub fn with_max_bytes_in_flight(mut self, max_bytes_in_flight: u64) -> Self {
+		self.max_bytes_in_flight = max_bytes_in_flight;
+		self
+	}
+	/// Sets the maximum number of bytes of a single encoded array.
+	#[must_use]
+	pub fn with_max_entry_bytes(mut self, max_entry_bytes: u64) -> Self {
+		self.max_entry_bytes = max_entry_bytes;
+		self
+	}
+	/// Sets the `hook` observing the metrics whenever they change, e.g., for monitoring.
+	///
+	/// The `hook` is called on the calling thread when an array is queued and on the background
+	/// thread when an array is written, hence it must be quick.
+	#[must_use]
+	pub fn with_metrics<F>(self, hook: F) -> Self
+	where
+		F: Fn(&PipelineMetrics) + Send + 'static,
+	{
+		self.shared.lock().hook = Some(Box::new(hook));
+		self
+	}
+	/// Returns a snapshot of the metrics.
+	#[must_use]
+	pub fn metrics(&self) -> PipelineMetrics {
+		self.shared.lock().metrics
+	}
+
+	/// Encodes and queues an array with the specified `name`, blocking while the queue is full.
+	///
+	/// # Errors
+	///
+	/// Fails with [`WriteNpyError`](ndarray_npy::WriteNpyError) if encoding fails or exceeds the
+	/// per-entry limit, or with an earlier error of the background thread once and with
+	/// [`WriteNpzError::Finished`] afterwards.
+	#[allow(clippy::case_sensitive_file_extension_comparisons)]
+	pub fn add_array<N, S, D>(
+		&mut self,
+		name: N,
+		array: &ArrayBase<S, D>,
+	) -> Result<(), WriteNpzError>
+	where
+		N: Into<String>,
+		S::Elem: WritableElement,
+		S: Data,
+		D: Dimension,
+	{
+		let mut name = name.into();
+		if self.npy_extension && !name.ends_with(".npy") {
+			name.push_str(".npy");
+		}
+		let mut buffer = LimitedBuffer {
+			bytes: Vec::new(),
+			limit: self.max_entry_bytes,
+		};
+		array.write_npy(&mut buffer)?;
+		let mut bytes = buffer.bytes;
+		swap_npy(&mut bytes, self.endianness)?;
+		let len = bytes.len() as u64;
+		let mut state = self.shared.lock();
+		while !state.failed
+			&& state.metrics.queue_len > 0
+			&& (state.metrics.queue_len >= self.queue_depth
+				|| state.metrics.bytes_in_flight + len > self.max_bytes_in_flight)
+		{
+			state = self
+				.shared
+				.changed
+				.wait(state)
+				.unwrap_or_else(PoisonError::into_inner);
+		}
+		if state.failed {
+			drop(state);
+			return Err(self.join_failed());
+		}
+		state.metrics.queue_len += 1;
+		state.metrics.bytes_in_flight += len;
+		state.notify();
+		drop(state);
+		let sent = self
+			.sender
+			.as_ref()
+			.is_some_and(|sender| sender.send((name, bytes)).is_ok());
+		if sent {
+			Ok(())
+		} else {
+			Err(self.join_failed())
+		}
+	}
+
+	/// Waits for the queue to drain, finishes the `.npz` file, and returns the writer.
+	///
+	/// # Errors
+	///
+	/// Fails with an error of the background thread or like [`NpzWriter::finish`].
+	pub fn finish(mut self) -> Result<W, WriteNpzError> {
+		self.sender = None;
+		self.join()?.finish()
+	}
+
+	/// Joins the background thread propagating its panic.
+	fn join(&mut self) -> Result<NpzWriter<W>, WriteNpzError> {
+		self.worker
+			.take()
+			.ok_or(WriteNpzError::Finished)?
+			.join()
+			.unwrap_or_else(|err| panic::resume_unwind(err))
+	}
+
+	/// Joins the failed background thread returning its error.
+	fn join_failed(&mut self) -> WriteNpzError {
+		self.sender = None;
+		match self.join() {
+			Err(err) => err,
+			Ok(_npz) => ZipError::Io(io::ErrorKind::BrokenPipe.into()).into(),
+		}
+	}
+}
+
+impl<W: Write + Seek + Send + 'static> Drop for PipelinedWriter<W> {
+	fn drop(&mut self) {
+		self.sender = None;
+		if let Some(worker) = self.worker.take() {
+			let _ = worker.join();
+		}
+	}
+}
+
+impl<W: Write + Seek + Send + 'static> fmt::Debug for PipelinedWriter<W> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("PipelinedWriter")
+			.field("metrics", &self.metrics())
+			.field("queue_depth", &self.queue_depth)
+			.field("max_bytes_in_flight", &self.max_bytes_in_flight)
+			.field("max_entry_bytes", &self.max_entry_bytes)
+			.finish_non_exhaustive()
+	}
+}
+
+impl<W: Write + Seek> NpzWriter<W> {
+	/// Adds an array encoded in the byte order of the writer like [`Self::add_array`].
+	fn add_encoded(&mut self, name: String, bytes: &[u8]) -> Result<(), WriteNpzError> {
+		let options = self.sized_options(bytes.len() as u64);
+		let options = mem::replace(&mut self.options, options);
+		let result = self.add_encoded_sized(name, bytes);
+		self.options = options;
+		result
+	}
+
+	/// Adds an encoded array with the options sized by [`Self::sized_options`].
+	fn add_encoded_sized(&mut self, mut name: String, bytes: &[u8]) -> Result<(), WriteNpzError> {
+		if self.claim_name(&mut name)? {
+			return Ok(());
+		}
+		if self.add_packable_bytes(&name, bytes)
+			|| self.add_chunkable_bytes(&name, bytes)?
+			|| self.add_guarded(&name, bytes)?
+		{
+			self.report_written(&name, || bytes.len() as u64);
+			return Ok(());
+		}
+		#[cfg(feature = "parallel-deflate")]
+		if self.add_parallel_bytes(&name, bytes)? {
+			return Ok(());
+		}
+		self.zip.start_file(name.as_str(), self.options)?;
+		let total = || bytes.len() as u64;
+		Progress::new(&mut self.zip, &name, total, self.progress.as_mut())
+			.write_all(bytes)
+			.map_err(ZipError::from)?;
+		Ok(())
+	}
+}
+
+/// Buffer failing to grow beyond its limit.
+struct LimitedBuffer {
+	bytes: Vec<u8>,
+	limit: u64,
+}
+
+impl Write for LimitedBuffer {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		if (self.bytes.len() + buf.len()) as u64 > self.limit {
+			return Err(io::Error::new(
+				io::ErrorKind::OutOfMemory,
+				"array exceeds per-entry limit",
+			));
+		}
+		self.bytes.extend_from_slice(buf);
+		Ok(buf.len())
+	}
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
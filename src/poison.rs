@@ -0,0 +1,71 @@
+use super::{ChecksumStatus, NpyViewMut, NpzView, NpzViewMut};
+use std::thread;
+
+/// Bit of the internal file attributes of a central header marking a poisoned checksum.
+///
+/// The bit is unused by the zip specification, hence ignored by other zip readers.
+pub(crate) const POISONED: u16 = 1 << 15;
+
+/// Returns the initial checksum status of a file with the internal file `attributes`.
+pub(crate) fn status(attributes: u16) -> ChecksumStatus {
+	if attributes & POISONED == 0 {
+		ChecksumStatus::default()
+	} else {
+		ChecksumStatus::Poisoned
+	}
+}
+
+impl NpyViewMut<'_> {
+	/// [Updates](Self::update) an outdated checksum unless the thread is panicking, in which case
+	/// the checksum is [poisoned](ChecksumStatus::Poisoned) instead, or neither if checksum
+	/// updates are suppressed.
+	pub(crate) fn settle(&mut self) {
+		if self.checksums && self.status == ChecksumStatus::Outdated {
+			if thread::panicking() {
+				self.poison();
+			} else {
+				self.update();
+			}
+		}
+	}
+	/// Marks the checksum as stale in the central directory without reading the array.
+	fn poison(&mut self) {
+		let attributes = u16::from_le_bytes(*self.attributes) | POISONED;
+		*self.attributes = attributes.to_le_bytes();
+		self.status = ChecksumStatus::Poisoned;
+	}
+}
+
+/// Settles the checksum of a borrowed `.npy` file view on drop, even when unwinding.
+pub(crate) struct Settle<'s, 'a>(pub(crate) &'s mut NpyViewMut<'a>);
+
+impl Drop for Settle<'_, '_> {
+	fn drop(&mut self) {
+		self.0.settle();
+	}
+}
+
+impl NpzView<'_> {
+	/// Returns the names of the `.npy` files whose checksums have been
+	/// [poisoned](ChecksumStatus::Poisoned).
+	///
+	/// The names are known without reading any array, as poisoning is marked in the central
+	/// directory.
+	pub fn poisoned_names(&self) -> impl Iterator<Item = &str> {
+		self.names.iter().filter_map(|(name, index)| {
+			let file = self.files.get(index)?;
+			(file.status == ChecksumStatus::Poisoned).then_some(name.as_str())
+		})
+	}
+}
+
+impl NpzViewMut<'_> {
+	/// Returns the names of the `.npy` files whose checksums have been
+	/// [poisoned](ChecksumStatus::Poisoned) and whose views have not been moved out.
+	pub fn poisoned_names(&self) -> impl Iterator<Item = &str> {
+		self.names.iter().filter_map(|(name, index)| {
+			let file = self.files.get(index)?;
+			(file.status == ChecksumStatus::Poisoned).then_some(name.as_str())
+		})
+	}
+}
@@ -0,0 +1,238 @@
+use super::{NpzReader, ReadNpzError};
+use std::{
+	borrow::Cow,
+	collections::HashSet,
+	fs::{self, File},
+	io::{self, Read, Seek},
+	path::{Path, PathBuf},
+};
+use zip::result::ZipError;
+
+/// Device names reserved on Windows regardless of their extension and case.
+const RESERVED_NAMES: [&str; 30] = [
+	"CON", "PRN", "AUX", "NUL", "COM0", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7",
+	"COM8", "COM9", "COM¹", "COM²", "COM³", "LPT0", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6",
+	"LPT7", "LPT8", "LPT9", "LPT¹", "LPT²", "LPT³",
+];
+
+/// Returns the relative path of the file `name` of an archive when extracted, portable to all
+/// platforms.
+///
+/// Archives written on Linux may contain names which cannot be created on Windows. The name is
+/// split into components at `/` and each component is made portable by
+///
+///   * replacing `\`, `<`, `>`, `:`, `"`, `|`, `?`, `*`, and control characters with `_`,
+///   * replacing trailing dots and spaces with `_`, and
+///   * appending `_` to the stem of device names reserved on Windows, e.g., `CON.npy` becomes
+///     `CON_.npy` and `nul` becomes `nul_`.
+///
+/// With the `unicode` feature, each component is normalized to Unicode normalization form C as
+/// names written on macOS are usually decomposed. The same name results in the same path on all
+/// platforms. Empty and `.` components are skipped.
+///
+/// Returns `None` if the name would escape the directory it is extracted to, i.e., if it is
+/// absolute or contains `..` components, or if it has no components.
+///
+/// # Example
+///
+/// ```
+/// use ndarray_npz::portable_path;
+/// use std::path::PathBuf;
+///
+/// assert_eq!(portable_path("aux/con.npy"), Some(["aux_", "con_.npy"].iter().collect()));
+/// assert_eq!(portable_path("a:b?.npy"), Some(PathBuf::from("a_b_.npy")));
+/// assert_eq!(portable_path("../x.npy"), None);
+/// ```
+#[must_use]
+pub fn portable_path(name: &str) -> Option<PathBuf> {
+	if name.starts_with('/') {
+		return None;
+	}
+	let mut path = PathBuf::new();
+	for component in name.split('/') {
+		match component {
+			"" | "." => {}
+			".." => return None,
+			component => path.push(portable_component(component)),
+		}
+	}
+	(!path.as_os_str().is_empty()).then_some(path)
+}
+
+/// Makes a single path component portable, see [`portable_path`].
+fn portable_component(component: &str) -> String {
+	let component = normalize(component);
+	let mut component = component
+		.chars()
+		.map(|char| match char {
+			'\\' | '<' | '>' | ':' | '"' | '|' | '?' | '*' => '_',
+			char if char.is_control() => '_',
+			char => char,
+		})
+		.collect::<String>();
+	let trimmed = component.trim_end_matches(['.', ' ']).len();
+	let trailing = component.len() - trimmed;
+	component.truncate(trimmed);
+	component.push_str(&"_".repeat(trailing));
+	let stem = component.split('.').next().unwrap_or_default();
+	let stem_len = stem.len();
+	if RESERVED_NAMES
+		.iter()
+		.any(|reserved| stem.trim_end().eq_ignore_ascii_case(reserved))
+	{
+		component.insert(stem_len, '_');
+	}
+	component
+}
+
+/// Normalizes `name` to Unicode normalization form C.
+#[cfg(feature = "unicode")]
+pub(crate) fn normalize(name: &str) -> Cow<'_, str> {
+	use unicode_normalization::{is_nfc, UnicodeNormalization};
+	if is_nfc(name) {
+		Cow::Borrowed(name)
+	} else {
+		Cow::Owned(name.nfc().collect())
+	}
+}
+
+/// Leaves `name` as is without the `unicode` feature.
+#[cfg(not(feature = "unicode"))]
+pub(crate) fn normalize(name: &str) -> Cow<'_, str> {
+	Cow::Borrowed(name)
+}
+
+/// Returns `path` in its extended-length form on Windows if it is too long for the legacy
+/// `MAX_PATH` limit of 260 characters, otherwise as is.
+///
+/// Relative paths are made absolute as extended-length paths are not normalized by Windows, i.e.,
+/// `.` and `..` components are resolved lexically beforehand.
+#[cfg(windows)]
+pub(crate) fn long_path(path: &Path) -> Cow<'_, Path> {
+	use std::{
+		env,
+		ffi::{OsStr, OsString},
+		path::{Component, Prefix},
+	};
+	// Leave room for the 8.3 file names appended to directories.
+	if path.as_os_str().len() < 248 {
+		return Cow::Borrowed(path);
+	}
+	let Ok(absolute) = env::current_dir().map(|dir| dir.join(path)) else {
+		return Cow::Borrowed(path);
+	};
+	let mut components = absolute.components();
+	let Some(Component::Prefix(prefix)) = components.next() else {
+		return Cow::Borrowed(path);
+	};
+	let mut long = match prefix.kind() {
+		Prefix::Disk(_) => {
+			let mut long = OsString::from(r"\\?\");
+			long.push(prefix.as_os_str());
+			long
+		}
+		Prefix::UNC(server, share) => {
+			let mut long = OsString::from(r"\\?\UNC\");
+			long.push(server);
+			long.push(r"\");
+			long.push(share);
+			long
+		}
+		Prefix::Verbatim(_)
+		| Prefix::VerbatimUNC(..)
+		| Prefix::VerbatimDisk(_)
+		| Prefix::DeviceNS(_) => return Cow::Borrowed(path),
+	};
+	let mut parts = Vec::<&OsStr>::new();
+	for component in components {
+		match component {
+			Component::Normal(part) => parts.push(part),
+			Component::ParentDir => {
+				parts.pop();
+			}
+			Component::Prefix(_) | Component::RootDir | Component::CurDir => {}
+		}
+	}
+	for part in parts {
+		long.push(r"\");
+		long.push(part);
+	}
+	Cow::Owned(PathBuf::from(long))
+}
+
+/// Returns `path` as is on other platforms than Windows.
+#[cfg(not(windows))]
+pub(crate) fn long_path(path: &Path) -> Cow<'_, Path> {
+	Cow::Borrowed(path)
+}
+
+impl<R: Read + Seek> NpzReader<R> {
+	/// Returns the name of the file equal to `name` in Unicode normalization form C if `name`
+	/// itself is not found and the `unicode` feature and normalization are enabled, otherwise
+	/// `name` as is.
+	pub(crate) fn resolve_name<'n>(&self, name: &'n str) -> Cow<'n, str> {
+		if !cfg!(feature = "unicode") || !self.normalize || self.zip.index_for_name(name).is_some()
+		{
+			return Cow::Borrowed(name);
+		}
+		let normalized = normalize(name);
+		self.zip
+			.file_names()
+			.find(|file_name| normalize(file_name) == normalized)
+			.map_or(Cow::Borrowed(name), |file_name| {
+				Cow::Owned(file_name.to_owned())
+			})
+	}
+
+	/// Extracts all files of the archive into the directory `dir` and returns their paths.
+	///
+	/// The files are extracted as stored, e.g., as `.npy` files, to their [`portable_path`], so
+	/// archives written on Linux can be extracted on Windows. Missing directories are created and
+	/// existing files are overwritten. Long paths are supported on Windows.
+	///
+	/// # Example
+	///
+	/// ```no_run
+	/// use ndarray_npz::NpzReader;
+	/// use std::fs::File;
+	///
+	/// let mut npz = NpzReader::new(File::open("arrays.npz")?)?;
+	/// for path in npz.extract("arrays")? {
+	/// 	println!("{}", path.display());
+	/// }
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Fails with [`ZipError::InvalidArchive`] if a name has no [`portable_path`] or if two names
+	/// result in the same path regardless of case, which would overwrite each other. Nothing is
+	/// extracted in that case. Reading the archive or writing the files can fail with
+	/// [`ZipError`].
+	pub fn extract<P: AsRef<Path>>(&mut self, dir: P) -> Result<Vec<PathBuf>, ReadNpzError> {
+		let mut paths = Vec::with_capacity(self.zip.len());
+		let mut unique = HashSet::with_capacity(self.zip.len());
+		for index in 0..self.zip.len() {
+			let name = self.zip.by_index_raw(index)?.name().to_owned();
+			let path = portable_path(&name).ok_or(ZipError::InvalidArchive("Unsafe file name"))?;
+			if !unique.insert(path.to_string_lossy().to_lowercase()) {
+				return Err(ZipError::InvalidArchive("Ambiguous file name").into());
+			}
+			paths.push(dir.as_ref().join(path));
+		}
+		for (index, path) in paths.iter().enumerate() {
+			let mut file = self.zip.by_index(index)?;
+			let long = long_path(path);
+			if file.is_dir() {
+				fs::create_dir_all(&long).map_err(ZipError::from)?;
+				continue;
+			}
+			if let Some(parent) = long.parent() {
+				fs::create_dir_all(parent).map_err(ZipError::from)?;
+			}
+			let mut target = File::create(&long).map_err(ZipError::from)?;
+			io::copy(&mut file, &mut target).map_err(ZipError::from)?;
+		}
+		Ok(paths)
+	}
+}
@@ -0,0 +1,135 @@
+use super::{lenient, NpzReader, ReadNpzError};
+use ndarray::ArrayD;
+use ndarray_npy::{ReadNpyExt, ReadableElement};
+use std::{
+	collections::BTreeMap,
+	io::{Read, Seek},
+	num::NonZeroUsize,
+	panic, thread,
+};
+use zip::result::ZipError;
+
+impl<R: Read + Seek> NpzReader<R> {
+	/// Reads all arrays whose names start with `prefix`, keyed by their names relative to it.
+	///
+	/// Loads subsets of a checkpoint in one call, e.g., `model/` or `optimizer/`, where
+	/// `model/layer1/weights` is keyed by `layer1/weights`. Names are used as is, e.g., including
+	/// `.npy` if present, and an empty `prefix` reads all arrays. Only `.npy` files are read, see
+	/// [`Self::npy_names`], whereas blobs under the `prefix` are skipped.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use ndarray::{Array1, Array2};
+	/// use ndarray_npz::{NpzReader, NpzWriter};
+	/// use std::io::Cursor;
+	///
+	/// let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	/// npz.add_array("model/weights", &Array2::<f32>::zeros((4, 4)))?;
+	/// npz.add_array("model/bias", &Array1::<f32>::zeros(4))?;
+	/// npz.add_array("optimizer/step", &Array1::<f32>::zeros(1))?;
+	/// let mut npz = NpzReader::new(npz.finish()?)?;
+	/// let model = npz.read_prefixed::<f32>("model/")?;
+	/// assert_eq!(model.keys().collect::<Vec<_>>(), ["bias", "weights"]);
+	/// assert_eq!(model["weights"].shape(), [4, 4]);
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Reading an array can fail like [`Self::by_name`], e.g., if its element type is not `T`.
+	pub fn read_prefixed<T>(
+		&mut self,
+		prefix: &str,
+	) -> Result<BTreeMap<String, ArrayD<T>>, ReadNpzError>
+	where
+		T: ReadableElement,
+	{
+		let mut arrays = BTreeMap::new();
+		for name in self.prefixed_names(prefix)? {
+			let array = self.by_name(&name)?;
+			arrays.insert(name[prefix.len()..].to_owned(), array);
+		}
+		Ok(arrays)
+	}
+
+	/// Returns the names of the `.npy` files starting with `prefix`.
+	fn prefixed_names(&mut self, prefix: &str) -> Result<Vec<String>, ReadNpzError> {
+		let mut names = self.npy_names()?;
+		names.retain(|name| name.starts_with(prefix));
+		Ok(names)
+	}
+}
+
+impl<R: Read + Seek + Clone + Send> NpzReader<R> {
+	/// Like [`Self::read_prefixed`] but reads the arrays in parallel.
+	///
+	/// The `.npy` files are distributed evenly over as many scoped threads as there is
+	/// [available parallelism](thread::available_parallelism), each reading via its own clone of
+	/// the reader, e.g., a [`Cursor`](std::io::Cursor) of a memory-mapped file. This pays off for
+	/// compressed files whose decompression dominates. Arrays packed via
+	/// [`NpzWriter::with_packing`] or chunked via [`NpzWriter::with_chunking`] are read
+	/// sequentially, and the cache of [`Self::with_cache`] is bypassed.
+	///
+	/// [`NpzWriter::with_packing`]: crate::NpzWriter::with_packing
+	/// [`NpzWriter::with_chunking`]: crate::NpzWriter::with_chunking
+	///
+	/// # Errors
+	///
+	/// Fails like [`Self::read_prefixed`].
+	pub fn read_prefixed_parallel<T>(
+		&mut self,
+		prefix: &str,
+	) -> Result<BTreeMap<String, ArrayD<T>>, ReadNpzError>
+	where
+		T: ReadableElement + Send,
+	{
+		let (files, members): (Vec<_>, Vec<_>) = self
+			.prefixed_names(prefix)?
+			.into_iter()
+			.partition(|name| self.zip.index_for_name(name).is_some());
+		let mut arrays = BTreeMap::new();
+		for name in members {
+			let array = self.by_name(&name)?;
+			arrays.insert(name[prefix.len()..].to_owned(), array);
+		}
+		let threads = thread::available_parallelism()
+			.map_or(1, NonZeroUsize::get)
+			.min(files.len())
+			.max(1);
+		let chunk = files.len().div_ceil(threads).max(1);
+		let lenient = self.lenient;
+		let zip = &self.zip;
+		thread::scope(|scope| {
+			files
+				.chunks(chunk)
+				.map(|files| {
+					let mut zip = zip.clone();
+					scope.spawn(move || -> Result<Vec<(String, ArrayD<T>)>, ReadNpzError> {
+						let mut arrays = Vec::with_capacity(files.len());
+						for name in files {
+							let file = zip.by_name(name)?;
+							let array = if lenient {
+								let file = lenient::normalize(file).map_err(ZipError::from)?;
+								ArrayD::read_npy(file)?
+							} else {
+								ArrayD::read_npy(file)?
+							};
+							arrays.push((name[prefix.len()..].to_owned(), array));
+						}
+						Ok(arrays)
+					})
+				})
+				.collect::<Vec<_>>()
+				.into_iter()
+				.try_for_each(|thread| -> Result<(), ReadNpzError> {
+					let chunk = thread
+						.join()
+						.unwrap_or_else(|err| panic::resume_unwind(err))?;
+					arrays.extend(chunk);
+					Ok(())
+				})
+		})?;
+		Ok(arrays)
+	}
+}
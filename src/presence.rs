@@ -0,0 +1,163 @@
+use super::{chunking::Chunked, is_directory, packing::Packed, NpzReader, NpzView, NpzViewMut};
+use std::{
+	error::Error,
+	fmt,
+	io::{Read, Seek},
+};
+
+/// Names missing from an `.npz` file, see [`NpzReader::contains_all`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct MissingReport {
+	/// Names of the missing files in the order they have been checked.
+	pub missing: Vec<String>,
+}
+
+impl MissingReport {
+	/// Checks the `names` via `contains`.
+	fn new<N: AsRef<str>>(names: &[N], contains: impl Fn(&str) -> bool) -> Self {
+		Self {
+			missing: names
+				.iter()
+				.map(AsRef::as_ref)
+				.filter(|name| !contains(name))
+				.map(str::to_owned)
+				.collect(),
+		}
+	}
+
+	/// Returns `true` iff no names are missing.
+	#[must_use]
+	pub fn is_complete(&self) -> bool {
+		self.missing.is_empty()
+	}
+
+	/// Returns `Ok` iff no names are missing, otherwise the report as error.
+	fn into_result(self) -> Result<(), MissingReport> {
+		if self.is_complete() {
+			Ok(())
+		} else {
+			Err(self)
+		}
+	}
+}
+
+impl Error for MissingReport {}
+
+impl fmt::Display for MissingReport {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "files missing from npz archive: {:?}", self.missing)
+	}
+}
+
+impl<R: Read + Seek> NpzReader<R> {
+	/// Returns `true` iff the `.npz` file contains the array or blob `name`, see [`Self::names`].
+	///
+	/// Only the central directory is consulted. Like [`Self::by_name`], names of arrays packed via
+	/// [`NpzWriter::with_packing`] or chunked via [`NpzWriter::with_chunking`] are found, and so
+	/// are names only differing in their Unicode normalization form with the `unicode` feature.
+	///
+	/// [`NpzWriter::with_packing`]: crate::NpzWriter::with_packing
+	/// [`NpzWriter::with_chunking`]: crate::NpzWriter::with_chunking
+	#[must_use]
+	pub fn contains(&self, name: &str) -> bool {
+		if let Some(packed) = &self.packed {
+			if packed.names().iter().any(|packed| packed == name) {
+				return true;
+			}
+			if Packed::is_reserved(name) {
+				return false;
+			}
+		}
+		if let Some(chunked) = &self.chunked {
+			if chunked.names().iter().any(|chunked| chunked == name) {
+				return true;
+			}
+			if Chunked::is_reserved(name) {
+				return false;
+			}
+		}
+		!is_directory(name) && self.zip.index_for_name(&self.resolve_name(name)).is_some()
+	}
+
+	/// Reports which of the `names` the `.npz` file does not contain, see [`Self::contains`].
+	#[must_use]
+	pub fn contains_all<N: AsRef<str>>(&self, names: &[N]) -> MissingReport {
+		MissingReport::new(names, |name| self.contains(name))
+	}
+
+	/// Validates up front that the `.npz` file contains all of the `names`, e.g., the members
+	/// expected by a pipeline, instead of failing on reading the first missing one.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use ndarray::Array1;
+	/// use ndarray_npz::{NpzReader, NpzWriter};
+	/// use std::io::Cursor;
+	///
+	/// let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	/// npz.add_array("weights", &Array1::<f32>::zeros(3))?;
+	/// let npz = NpzReader::new(npz.finish()?)?;
+	/// npz.require(&["weights"])?;
+	/// let err = npz.require(&["weights", "bias", "steps"]).unwrap_err();
+	/// assert_eq!(err.missing, ["bias", "steps"]);
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Fails with [`MissingReport`] listing all of the missing names.
+	pub fn require<N: AsRef<str>>(&self, names: &[N]) -> Result<(), MissingReport> {
+		self.contains_all(names).into_result()
+	}
+}
+
+impl NpzView<'_> {
+	/// Returns `true` iff the `.npz` file contains the viewable array `name`, see [`Self::names`].
+	#[must_use]
+	pub fn contains(&self, name: &str) -> bool {
+		self.names.contains_key(name)
+	}
+
+	/// Reports which of the `names` the `.npz` file does not contain, see [`Self::contains`].
+	#[must_use]
+	pub fn contains_all<N: AsRef<str>>(&self, names: &[N]) -> MissingReport {
+		MissingReport::new(names, |name| self.contains(name))
+	}
+
+	/// Validates up front that the `.npz` file contains all of the `names`, see
+	/// [`NpzReader::require`].
+	///
+	/// # Errors
+	///
+	/// Fails with [`MissingReport`] listing all of the missing names.
+	pub fn require<N: AsRef<str>>(&self, names: &[N]) -> Result<(), MissingReport> {
+		self.contains_all(names).into_result()
+	}
+}
+
+impl NpzViewMut<'_> {
+	/// Returns `true` iff the `.npz` file contains the viewable array `name`, see [`Self::names`].
+	///
+	/// Arrays already moved out via [`Self::by_name`] are still contained.
+	#[must_use]
+	pub fn contains(&self, name: &str) -> bool {
+		self.names.contains_key(name)
+	}
+
+	/// Reports which of the `names` the `.npz` file does not contain, see [`Self::contains`].
+	#[must_use]
+	pub fn contains_all<N: AsRef<str>>(&self, names: &[N]) -> MissingReport {
+		MissingReport::new(names, |name| self.contains(name))
+	}
+
+	/// Validates up front that the `.npz` file contains all of the `names`, see
+	/// [`NpzReader::require`].
+	///
+	/// # Errors
+	///
+	/// Fails with [`MissingReport`] listing all of the missing names.
+	pub fn require<N: AsRef<str>>(&self, names: &[N]) -> Result<(), MissingReport> {
+		self.contains_all(names).into_result()
+	}
+}
@@ -0,0 +1,134 @@
+use super::{header::NpyHeader, NpzWriter};
+use ndarray::{ArrayBase, Data, Dimension};
+use ndarray_npy::WritableElement;
+use std::{
+	io::{self, Seek, Write},
+	mem,
+};
+
+/// Maximum number of bytes written between two reports of a streamed array.
+const STEP: usize = 1 << 20;
+
+/// Callback observing the progress of writing an array, see [`NpzWriter::on_progress`].
+pub(crate) type ProgressHook = Box<dyn FnMut(&str, u64, u64) + Send + Sync>;
+
+impl<W: Write + Seek> NpzWriter<W> {
+	/// Sets the `hook` observing the progress of serializing and compressing arrays, e.g., for
+	/// displaying progress bars of multi-gigabyte arrays.
+	///
+	/// The `hook` is called with the name of the array, the number of bytes of its `.npy` file
+	/// written so far, and the total number of bytes of its `.npy` file. Streamed arrays are
+	/// reported at least every MiB written, whereas arrays serialized in memory first, e.g.,
+	/// packed or byte-swapped ones, are reported once written.
+	/// The last call of an array reports its total. The `hook` is called on the writing thread,
+	/// hence it must be quick.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use ndarray::Array1;
+	/// use ndarray_npz::NpzWriter;
+	/// use std::{
+	/// 	io::Cursor,
+	/// 	sync::{Arc, Mutex},
+	/// };
+	///
+	/// let progress = Arc::new(Mutex::new(Vec::new()));
+	/// let reported = progress.clone();
+	/// let mut npz = NpzWriter::new(Cursor::new(Vec::new())).on_progress(move |name, written, total| {
+	/// 	reported.lock().unwrap().push((name.to_owned(), written, total));
+	/// });
+	/// npz.add_array("x", &Array1::<f64>::zeros(100_000))?;
+	/// npz.finish()?;
+	/// let progress = progress.lock().unwrap();
+	/// assert!(progress.len() > 1);
+	/// assert_eq!(progress.last(), Some(&("x".to_owned(), 800_128, 800_128)));
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	#[must_use]
+	pub fn on_progress<F>(mut self, hook: F) -> Self
+	where
+		F: FnMut(&str, u64, u64) + Send + Sync + 'static,
+	{
+		self.progress = Some(Box::new(hook));
+		self
+	}
+
+	/// Reports an array of `total` bytes serialized in memory as written.
+	pub(crate) fn report_written(&mut self, name: &str, total: impl FnOnce() -> u64) {
+		if let Some(hook) = &mut self.progress {
+			let total = total();
+			hook(name, total, total);
+		}
+	}
+}
+
+/// Writer reporting the bytes of an `.npy` file written so far.
+pub(crate) struct Progress<'a, W: Write> {
+	inner: W,
+	name: &'a str,
+	written: u64,
+	total: u64,
+	hook: Option<&'a mut ProgressHook>,
+}
+
+impl<'a, W: Write> Progress<'a, W> {
+	/// Wraps `inner` writing an `.npy` file of `total` bytes under `name` reporting to `hook` if
+	/// any.
+	pub(crate) fn new(
+		inner: W,
+		name: &'a str,
+		total: impl FnOnce() -> u64,
+		hook: Option<&'a mut ProgressHook>,
+	) -> Self {
+		Self {
+			inner,
+			name,
+			written: 0,
+			total: if hook.is_some() { total() } else { 0 },
+			hook,
+		}
+	}
+}
+
+impl<W: Write> Write for Progress<'_, W> {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		let Some(hook) = &mut self.hook else {
+			return self.inner.write(buf);
+		};
+		let len = self.inner.write(&buf[..buf.len().min(STEP)])?;
+		self.written += len as u64;
+		hook(self.name, self.written, self.total.max(self.written));
+		Ok(len)
+	}
+	fn flush(&mut self) -> io::Result<()> {
+		self.inner.flush()
+	}
+}
+
+/// Returns the length of the `.npy` file of `array` as written by [`ndarray_npy`].
+pub(crate) fn npy_len<S, D>(array: &ArrayBase<S, D>) -> u64
+where
+	S::Elem: WritableElement,
+	S: Data,
+	D: Dimension,
+{
+	// Arrays in neither standard nor Fortran layout are written in standard layout.
+	let fortran_order = !array.is_standard_layout() && array.t().is_standard_layout();
+	let header = NpyHeader {
+		descr: S::Elem::type_descriptor(),
+		fortran_order,
+		shape: array.shape().iter().map(|&axis| axis as u64).collect(),
+		version: (1, 0),
+		len: 0,
+	};
+	let header = header.to_bytes().or_else(|| {
+		NpyHeader {
+			version: (2, 0),
+			..header
+		}
+		.to_bytes()
+	});
+	let header = header.map_or(0, |header| header.len() as u64);
+	header + (array.len() * mem::size_of::<S::Elem>()) as u64
+}
@@ -0,0 +1,211 @@
+use super::{
+	json::{json_string, parse_json_object, parse_json_string},
+	NpzReader, NpzWriter, ReadNpzError, WriteNpzError,
+};
+use std::{
+	collections::BTreeMap,
+	fmt,
+	io::{Read, Seek, Write},
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use zip::result::ZipError;
+
+/// Reserved name of the member recording the provenance of an `.npz` file.
+pub const PROVENANCE_NAME: &str = "__meta__.json";
+
+/// Provenance of an `.npz` file.
+///
+/// Written by [`NpzWriter::with_provenance`] as [`PROVENANCE_NAME`] member of UTF-8 JSON in the
+/// form of
+///
+/// ```json
+/// {
+///   "creator": "train 1.2.0",
+///   "crate": "ndarray-npz",
+///   "crate_version": "0.4.1",
+///   "created": 1735689600.000000000,
+///   "fields": {"commit": "8f2e1c0", "seed": "42"}
+/// }
+/// ```
+///
+/// where `created` is in seconds relative to the [`UNIX_EPOCH`], so Python reads it via
+/// `datetime.fromtimestamp(meta["created"])`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Provenance {
+	/// Creator of the `.npz` file, e.g., the name and version of the producing application.
+	pub creator: String,
+	/// Version of this crate which wrote the `.npz` file.
+	pub crate_version: String,
+	/// Time of creation.
+	pub created: SystemTime,
+	/// User fields, e.g., the commit of the producing code or the seed of a random generator.
+	pub fields: BTreeMap<String, String>,
+}
+
+impl Provenance {
+	/// Creates a new provenance of the `creator` created now by this version of the crate.
+	#[must_use]
+	pub fn new(creator: impl Into<String>) -> Self {
+		Self {
+			creator: creator.into(),
+			crate_version: env!("CARGO_PKG_VERSION").to_string(),
+			created: SystemTime::now(),
+			fields: BTreeMap::new(),
+		}
+	}
+	/// Adds or replaces the user field `key` with `value`.
+	#[must_use]
+	pub fn with_field(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+		self.fields.insert(key.into(), value.into());
+		self
+	}
+	/// Parses a provenance from the content of a [`PROVENANCE_NAME`] member.
+	///
+	/// Unknown keys are ignored.
+	///
+	/// # Errors
+	///
+	/// Fails with [`ZipError::InvalidArchive`] if the provenance is malformed.
+	pub fn parse(json: &str) -> Result<Self, ZipError> {
+		let invalid = || ZipError::InvalidArchive("Invalid provenance");
+		let (members, rest) = parse_json_object(json, parse_member).ok_or_else(invalid)?;
+		if !rest.trim().is_empty() {
+			return Err(invalid());
+		}
+		let mut creator = None;
+		let mut crate_version = None;
+		let mut created = None;
+		let mut fields = None;
+		for (key, member) in members {
+			match (key.as_str(), member) {
+				("creator", Member::String(value)) => creator = Some(value),
+				("crate_version", Member::String(value)) => crate_version = Some(value),
+				("created", Member::Number(value)) => created = Some(parse_time(value)),
+				("fields", Member::Object(value)) => fields = Some(value.into_iter().collect()),
+				("creator" | "crate_version" | "created" | "fields", _) => return Err(invalid()),
+				_ => {}
+			}
+		}
+		Ok(Self {
+			creator: creator.ok_or_else(invalid)?,
+			crate_version: crate_version.ok_or_else(invalid)?,
+			created: created.flatten().ok_or_else(invalid)?,
+			fields: fields.unwrap_or_default(),
+		})
+	}
+}
+
+impl fmt::Display for Provenance {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let created = self.created.duration_since(UNIX_EPOCH).unwrap_or_default();
+		let fields = self
+			.fields
+			.iter()
+			.map(|(key, value)| format!("{}: {}", json_string(key), json_string(value)))
+			.collect::<Vec<_>>();
+		writeln!(f, "{{")?;
+		writeln!(f, "  \"creator\": {},", json_string(&self.creator))?;
+		writeln!(f, "  \"crate\": \"{}\",", env!("CARGO_PKG_NAME"))?;
+		writeln!(
+			f,
+			"  \"crate_version\": {},",
+			json_string(&self.crate_version)
+		)?;
+		writeln!(
+			f,
+			"  \"created\": {}.{:09},",
+			created.as_secs(),
+			created.subsec_nanos()
+		)?;
+		writeln!(f, "  \"fields\": {{{}}}", fields.join(", "))?;
+		writeln!(f, "}}")
+	}
+}
+
+/// Member value of a provenance.
+enum Member<'a> {
+	String(String),
+	Number(&'a str),
+	Object(Vec<(String, String)>),
+}
+
+/// Parses a member value of a provenance at the start of `json`.
+fn parse_member(json: &str) -> Option<(Member<'_>, &str)> {
+	if json.starts_with('"') {
+		parse_json_string(json).map(|(value, rest)| (Member::String(value), rest))
+	} else if json.starts_with('{') {
+		parse_json_object(json, parse_json_string)
+			.map(|(value, rest)| (Member::Object(value), rest))
+	} else {
+		let len = json
+			.find(|char: char| !matches!(char, '0'..='9' | '.' | '-' | '+' | 'e' | 'E'))
+			.unwrap_or(json.len());
+		(len > 0).then(|| (Member::Number(&json[..len]), &json[len..]))
+	}
+}
+
+/// Parses a time in seconds relative to the [`UNIX_EPOCH`] with up to nanosecond precision.
+fn parse_time(seconds: &str) -> Option<SystemTime> {
+	let (secs, nanos) = seconds.split_once('.').unwrap_or((seconds, "0"));
+	if !nanos.bytes().all(|byte| byte.is_ascii_digit()) {
+		return None;
+	}
+	let nanos = format!("{nanos:0<9}");
+	let duration = Duration::new(secs.parse().ok()?, nanos.get(..9)?.parse().ok()?);
+	UNIX_EPOCH.checked_add(duration)
+}
+
+impl<W: Write + Seek> NpzWriter<W> {
+	/// Records the `provenance` as [`PROVENANCE_NAME`] member on [`Self::finish`].
+	///
+	/// # Example
+	///
+	/// ```
+	/// use ndarray::array;
+	/// use ndarray_npz::{NpzReader, NpzWriter, Provenance};
+	/// use std::io::Cursor;
+	///
+	/// let provenance = Provenance::new("train 1.2.0").with_field("seed", "42");
+	/// let mut buffer = Vec::new();
+	/// let mut npz = NpzWriter::new(Cursor::new(&mut buffer)).with_provenance(provenance.clone());
+	/// npz.add_array("x.npy", &array![1.0, 2.0])?;
+	/// npz.finish()?;
+	/// let mut npz = NpzReader::new(Cursor::new(&buffer))?;
+	/// assert_eq!(npz.provenance()?, Some(provenance));
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	#[must_use]
+	pub fn with_provenance(mut self, provenance: Provenance) -> Self {
+		self.provenance = Some(provenance);
+		self
+	}
+
+	/// Adds the provenance if any.
+	pub(crate) fn add_provenance(&mut self) -> Result<(), WriteNpzError> {
+		if let Some(provenance) = self.provenance.take() {
+			self.zip.start_file(PROVENANCE_NAME, self.options)?;
+			self.zip
+				.write_all(provenance.to_string().as_bytes())
+				.map_err(ZipError::from)?;
+		}
+		Ok(())
+	}
+}
+
+impl<R: Read + Seek> NpzReader<R> {
+	/// Reads the provenance of the [`PROVENANCE_NAME`] member if any.
+	///
+	/// # Errors
+	///
+	/// Reading the provenance can fail with [`ZipError`].
+	pub fn provenance(&mut self) -> Result<Option<Provenance>, ReadNpzError> {
+		let mut file = match self.zip.by_name(PROVENANCE_NAME) {
+			Err(ZipError::FileNotFound) => return Ok(None),
+			Err(err) => return Err(err.into()),
+			Ok(file) => file,
+		};
+		let mut json = String::new();
+		file.read_to_string(&mut json).map_err(ZipError::from)?;
+		Ok(Some(Provenance::parse(&json)?))
+	}
+}
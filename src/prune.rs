@@ -0,0 +1,119 @@
+use super::{
+	compact::{copy_entry, Metadata},
+	order::archive_alignment,
+	EditNpzError,
+};
+use std::io::{Read, Seek, SeekFrom, Write};
+use zip::{DateTime, ZipArchive, ZipWriter};
+
+/// File of a `.npz` file considered by [`prune`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EntryInfo {
+	/// Name of the file.
+	pub name: String,
+	/// Modification time of the file as stored in the archive if any.
+	pub last_modified: Option<DateTime>,
+	/// Length of the uncompressed data.
+	pub size: u64,
+	/// Length of the possibly compressed data.
+	pub compressed_size: u64,
+}
+
+impl EntryInfo {
+	/// Whether the file has been modified before `time`, i.e., lies outside of a retention window
+	/// starting at `time`.
+	///
+	/// Files without modification time are never older.
+	#[must_use]
+	pub fn is_older_than(&self, time: DateTime) -> bool {
+		self.last_modified
+			.is_some_and(|last_modified| last_modified < time)
+	}
+}
+
+/// Report of [`prune`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pruning {
+	/// Names of the dropped files in archive order.
+	pub dropped: Vec<String>,
+	/// Size in bytes of the original archive.
+	pub original_size: u64,
+	/// Size in bytes of the pruned archive.
+	pub pruned_size: u64,
+}
+
+impl Pruning {
+	/// Returns the number of bytes reclaimed by pruning.
+	#[must_use]
+	pub fn reclaimed(&self) -> u64 {
+		self.original_size.saturating_sub(self.pruned_size)
+	}
+}
+
+/// Rewrites the `.npz` file of `reader` into `writer` keeping only the files for which `keep`
+/// returns `true` and reports the dropped files.
+///
+/// Keeps rolling archives, e.g., of long-running recorders, bounded in size by dropping members
+/// outside of a retention window via [`EntryInfo::is_older_than`] or by name, e.g., by the member
+/// index of a [`RecorderWriter`](crate::RecorderWriter). Kept files are copied like by
+/// [`compact`](crate::compact) without decoding them, i.e., uncompressed files are rewritten
+/// aligned like in the original archive and others are copied as is. Reserved members, e.g., the
+/// [`INDEX_NAME`](crate::INDEX_NAME) member, are passed to `keep` as well and an index becomes
+/// stale once other files are dropped.
+///
+/// # Example
+///
+/// ```no_run
+/// use ndarray_npz::prune;
+/// use std::fs::{rename, File};
+/// use zip::DateTime;
+///
+/// let cutoff = DateTime::from_date_and_time(2024, 6, 1, 0, 0, 0)?;
+/// let pruning = prune(
+/// 	File::open("log.npz")?,
+/// 	File::create("log.npz.tmp")?,
+/// 	|entry| !entry.is_older_than(cutoff),
+/// )?;
+/// rename("log.npz.tmp", "log.npz")?;
+/// println!("Dropped {:?} reclaiming {} bytes", pruning.dropped, pruning.reclaimed());
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// # Errors
+///
+/// Reading or writing the zip archives can fail with [`ZipError`](zip::result::ZipError).
+pub fn prune<R, W, F>(mut reader: R, writer: W, mut keep: F) -> Result<Pruning, EditNpzError>
+where
+	R: Read + Seek,
+	W: Write + Seek,
+	F: FnMut(&EntryInfo) -> bool,
+{
+	let original_size = reader.seek(SeekFrom::End(0))?;
+	let mut zip = ZipArchive::new(reader)?;
+	let mut pruned = ZipWriter::new(writer);
+	let mut dropped = Vec::new();
+	let alignment = archive_alignment(&mut zip)?;
+	for index in 0..zip.len() {
+		let file = zip.by_index_raw(index)?;
+		let entry = EntryInfo {
+			name: file.name().to_string(),
+			last_modified: file.last_modified(),
+			size: file.size(),
+			compressed_size: file.compressed_size(),
+		};
+		drop(file);
+		if keep(&entry) {
+			copy_entry(&mut zip, index, &mut pruned, Metadata::Preserve, alignment)?;
+		} else {
+			dropped.push(entry.name);
+		}
+	}
+	let mut writer = pruned.finish()?;
+	let pruned_size = writer.seek(SeekFrom::End(0))?;
+	writer.flush()?;
+	Ok(Pruning {
+		dropped,
+		original_size,
+		pruned_size,
+	})
+}
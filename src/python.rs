@@ -0,0 +1,436 @@
+//! Python extension module `ndarray_npz` of NumPy arrays.
+//!
+//! Provides Python users the alignment-preserving writer and memory-mapped views missing in
+//! NumPy. Build the extension module via
+//! `cargo rustc --release --features pyo3,pyo3/extension-module --crate-type cdylib` and rename
+//! the shared library to `ndarray_npz.so`, or via `maturin` with the same features.
+//!
+//! Arrays of element types `bool`, `int8` to `int64`, `uint8` to `uint64`, `float32`, and
+//! `float64` are supported. Errors are raised as `OSError` for I/O, `KeyError` for missing
+//! files, `TypeError` for mismatching element types or shapes, and `ValueError` otherwise with
+//! their [`ErrorCode`] in the message.
+//!
+//! # Example
+//!
+//! ```python
+//! import numpy as np
+//! import ndarray_npz
+//!
+//! npz = ndarray_npz.NpzWriter("arrays.npz")
+//! npz.add("x.npy", np.arange(6.0).reshape(2, 3))
+//! npz.finish()
+//!
+//! npz = ndarray_npz.NpzReader("arrays.npz")
+//! assert npz.names() == ["x.npy"]
+//! x = npz.read("x.npy")
+//!
+//! npz = ndarray_npz.NpzView("arrays.npz")
+//! x = npz.view("x.npy")  # Read-only without copying as stored uncompressed and aligned.
+//!
+//! npz = ndarray_npz.NpzView.map_trusted("arrays.npz")  # Must not be modified while mapped.
+//! x = npz.view("x.npy")
+//! ```
+
+use super::{
+	convert::Dtype, header::NpyHeader, ErrorCategory, ErrorCode, Fallbacks, NpzReader, NpzView,
+	NpzWriter, ReadNpzError, Snapshot, ViewNpzError, WriteNpzError,
+};
+use memmap2::Mmap;
+use ndarray::{ArrayD, IxDyn};
+use ndarray_npy::{ReadableElement, ViewElement};
+use numpy::{Element, IntoPyArray, PyArray, PyArrayDyn, PyArrayMethods};
+use pyo3::{
+	exceptions::{PyKeyError, PyOSError, PyTypeError, PyValueError},
+	prelude::*,
+	types::IntoPyDict,
+};
+use std::{
+	fmt,
+	fs::{self, File},
+	io::{BufReader, BufWriter, Cursor, Read, Seek},
+	ops::Deref,
+	path::PathBuf,
+};
+use zip::{result::ZipError, ZipArchive};
+
+/// Returns the Python exception of an error with `code`.
+fn py_err(code: ErrorCode, err: impl fmt::Display) -> PyErr {
+	let message = format!("{err} (code {})", code.value());
+	match code.category() {
+		ErrorCategory::Io => PyOSError::new_err(message),
+		ErrorCategory::NotFound => PyKeyError::new_err(message),
+		ErrorCategory::Mismatch => PyTypeError::new_err(message),
+		_ => PyValueError::new_err(message),
+	}
+}
+
+impl From<ReadNpzError> for PyErr {
+	fn from(err: ReadNpzError) -> PyErr {
+		py_err(err.code(), err)
+	}
+}
+
+impl From<WriteNpzError> for PyErr {
+	fn from(err: WriteNpzError) -> PyErr {
+		py_err(err.code(), err)
+	}
+}
+
+impl From<ViewNpzError> for PyErr {
+	fn from(err: ViewNpzError) -> PyErr {
+		py_err(err.code(), err)
+	}
+}
+
+/// Returns the supported element type of the `.npy` header.
+fn dtype(header: &NpyHeader) -> PyResult<Dtype> {
+	Dtype::parse(&header.descr)
+		.map(|(dtype, _big_endian)| dtype)
+		.ok_or_else(|| py_err(ErrorCode::UnsupportedDtype, "element type not supported"))
+}
+
+/// Calls the generic function `$f` with the element type of `$dtype` and the arguments `$args`.
+macro_rules! dispatch {
+	($dtype:expr, $f:ident($($args:expr),*)) => {
+		match $dtype {
+			Dtype::Bool => $f::<bool>($($args),*),
+			Dtype::I8 => $f::<i8>($($args),*),
+			Dtype::U8 => $f::<u8>($($args),*),
+			Dtype::I16 => $f::<i16>($($args),*),
+			Dtype::U16 => $f::<u16>($($args),*),
+			Dtype::I32 => $f::<i32>($($args),*),
+			Dtype::U32 => $f::<u32>($($args),*),
+			Dtype::I64 => $f::<i64>($($args),*),
+			Dtype::U64 => $f::<u64>($($args),*),
+			Dtype::F32 => $f::<f32>($($args),*),
+			Dtype::F64 => $f::<f64>($($args),*),
+		}
+	};
+}
+
+/// Reader of `.npz` files, see [`NpzReader`].
+#[pyclass(name = "NpzReader", module = "ndarray_npz", unsendable)]
+pub struct PyNpzReader {
+	npz: NpzReader<BufReader<File>>,
+}
+
+#[pymethods]
+impl PyNpzReader {
+	/// Opens the `.npz` file at `path`.
+	#[new]
+	fn new(path: PathBuf) -> PyResult<Self> {
+		let file = File::open(path)?;
+		Ok(Self {
+			npz: NpzReader::new(BufReader::new(file))?,
+		})
+	}
+
+	/// Returns the names of the arrays.
+	fn names(&mut self) -> PyResult<Vec<String>> {
+		Ok(self.npz.npy_names()?)
+	}
+
+	/// Reads the array `name`.
+	fn read<'py>(&mut self, py: Python<'py>, name: &str) -> PyResult<Bound<'py, PyAny>> {
+		/// Reads the array `name` of element type `A`.
+		fn read<'py, A: ReadableElement + Element>(
+			py: Python<'py>,
+			npz: &mut NpzReader<BufReader<File>>,
+			name: &str,
+		) -> PyResult<Bound<'py, PyAny>> {
+			let array: ArrayD<A> = npz.by_name(name)?;
+			Ok(array.into_pyarray(py).into_any())
+		}
+		let header = read_header(&mut self.npz, name)?;
+		dispatch!(dtype(&header)?, read(py, &mut self.npz, name))
+	}
+}
+
+/// Reads the `.npy` header of the array `name`.
+fn read_header<R: Read + Seek>(npz: &mut NpzReader<R>, name: &str) -> PyResult<NpyHeader> {
+	let name = npz.resolve_name(name).into_owned();
+	let mut file = npz.zip.by_name(&name).map_err(ReadNpzError::from)?;
+	Ok(NpyHeader::read(&mut file).map_err(|err| ReadNpzError::from(ZipError::from(err)))?)
+}
+
+/// Writer of `.npz` files aligning arrays for memory-mapping, see [`NpzWriter`].
+#[pyclass(name = "NpzWriter", module = "ndarray_npz", unsendable)]
+pub struct PyNpzWriter {
+	npz: Option<NpzWriter<BufWriter<File>>>,
+}
+
+#[pymethods]
+impl PyNpzWriter {
+	/// Creates the `.npz` file at `path` with compression if `compressed` is true.
+	#[new]
+	#[pyo3(signature = (path, compressed = false))]
+	fn new(path: PathBuf, compressed: bool) -> PyResult<Self> {
+		let file = BufWriter::new(File::create(path)?);
+		let npz = if compressed {
+			#[cfg(feature = "compressed")]
+			{
+				NpzWriter::new_compressed(file)
+			}
+			#[cfg(not(feature = "compressed"))]
+			return Err(py_err(
+				ErrorCode::UnsupportedArchive,
+				"compression requires feature `compressed`",
+			));
+		} else {
+			NpzWriter::new(file)
+		};
+		Ok(Self { npz: Some(npz) })
+	}
+
+	/// Adds the NumPy `array` as `name`.
+	fn add(&mut self, name: &str, array: &Bound<'_, PyAny>) -> PyResult<()> {
+		/// Adds the `array` of element type `A` if it is one.
+		fn add<A: Element + ndarray_npy::WritableElement>(
+			npz: &mut NpzWriter<BufWriter<File>>,
+			name: &str,
+			array: &Bound<'_, PyAny>,
+		) -> PyResult<bool> {
+			let Ok(array) = array.cast::<PyArrayDyn<A>>() else {
+				return Ok(false);
+			};
+			npz.add_array(name, &array.readonly().as_array())?;
+			Ok(true)
+		}
+		let npz = self.npz.as_mut().ok_or_else(finished)?;
+		for dtype in DTYPES {
+			if dispatch!(dtype, add(npz, name, array))? {
+				return Ok(());
+			}
+		}
+		Err(py_err(
+			ErrorCode::UnsupportedDtype,
+			"element type not supported",
+		))
+	}
+
+	/// Finishes writing the `.npz` file.
+	fn finish(&mut self) -> PyResult<()> {
+		let npz = self.npz.take().ok_or_else(finished)?;
+		npz.finish()?
+			.into_inner()
+			.map_err(std::io::IntoInnerError::into_error)?;
+		Ok(())
+	}
+}
+
+/// Supported element types.
+const DTYPES: [Dtype; 11] = [
+	Dtype::Bool,
+	Dtype::I8,
+	Dtype::U8,
+	Dtype::I16,
+	Dtype::U16,
+	Dtype::I32,
+	Dtype::U32,
+	Dtype::I64,
+	Dtype::U64,
+	Dtype::F32,
+	Dtype::F64,
+];
+
+/// Returns the error of using a finished writer.
+fn finished() -> PyErr {
+	py_err(ErrorCode::WriterFinished, "npz file already finished")
+}
+
+/// Bytes of a viewed `.npz` file.
+enum Bytes {
+	/// Bytes read into memory.
+	Read(Snapshot),
+	/// Bytes of a trusted file memory-mapped.
+	Mapped(Mmap),
+}
+
+impl Deref for Bytes {
+	type Target = [u8];
+
+	fn deref(&self) -> &[u8] {
+		match self {
+			Bytes::Read(snapshot) => snapshot.as_bytes(),
+			Bytes::Mapped(mmap) => mmap,
+		}
+	}
+}
+
+/// View of `.npz` files read into memory or memory-mapped, see [`NpzView`].
+#[pyclass(name = "NpzView", module = "ndarray_npz", frozen)]
+pub struct PyNpzView {
+	bytes: Bytes,
+}
+
+#[pymethods]
+impl PyNpzView {
+	/// Reads the `.npz` file at `path` into 64-byte aligned memory.
+	#[new]
+	fn new(path: PathBuf) -> PyResult<Self> {
+		let bytes = Bytes::Read(Snapshot::new(&fs::read(path)?));
+		NpzView::new(&bytes)?;
+		Ok(Self { bytes })
+	}
+
+	/// Memory-maps the trusted `.npz` file at `path` instead of reading it.
+	///
+	/// The file must neither be modified nor truncated while mapped, e.g., by another process, as
+	/// this changes the viewed arrays under Python or crashes it.
+	#[staticmethod]
+	fn map_trusted(path: PathBuf) -> PyResult<Self> {
+		let file = File::open(path)?;
+		// Safety: Mapping a file is documented to require it to be trusted not to change.
+		let bytes = Bytes::Mapped(unsafe { Mmap::map(&file)? });
+		NpzView::new(&bytes)?;
+		Ok(Self { bytes })
+	}
+
+	/// Returns the names of the uncompressed arrays.
+	fn names(&self) -> PyResult<Vec<String>> {
+		Ok(NpzView::new(&self.bytes)?
+			.names()
+			.map(str::to_owned)
+			.collect())
+	}
+
+	/// Returns a read-only view of the array `name`.
+	///
+	/// Arrays stored uncompressed, aligned, and in native endianness are viewed without copying,
+	/// otherwise they are read.
+	fn view<'py>(slf: &Bound<'py, Self>, name: &str) -> PyResult<Bound<'py, PyAny>> {
+		/// Views or reads the array `name` of element type `A`.
+		fn view<'py, A: ViewElement + ReadableElement + Element + Clone + 'static>(
+			slf: &Bound<'py, PyNpzView>,
+			npz: &NpzView<'_>,
+			name: &str,
+		) -> PyResult<Bound<'py, PyAny>> {
+			let fallbacks = Fallbacks {
+				read: true,
+				cast: false,
+			};
+			let (array, _access) = npz.get_flexible::<A, IxDyn>(name, fallbacks)?;
+			let array = if array.is_view() {
+				// Safety: The view borrows the bytes owned by `slf`, which the array keeps alive as
+				// its base object, and it is flagged read-only.
+				unsafe { PyArray::borrow_from_array(&array, slf.clone().into_any()) }
+			} else {
+				array.into_owned().into_pyarray(slf.py())
+			};
+			let write = [("write", false)].into_py_dict(slf.py())?;
+			array.call_method("setflags", (), Some(&write))?;
+			Ok(array.into_any())
+		}
+		let bytes = &slf.get().bytes;
+		let npz = NpzView::new(bytes)?;
+		let header = view_header(&npz, bytes, name)?;
+		dispatch!(dtype(&header)?, view(slf, &npz, name))
+	}
+}
+
+/// Returns the `.npy` header of the array `name` of `npz` viewing `bytes`.
+///
+/// Arrays which cannot be viewed, e.g., compressed ones, are read instead.
+fn view_header(npz: &NpzView<'_>, bytes: &[u8], name: &str) -> PyResult<NpyHeader> {
+	if let Ok(file) = npz.by_name(name) {
+		NpyHeader::parse(file.data)
+	} else {
+		let mut zip = ZipArchive::new(Cursor::new(bytes)).map_err(ViewNpzError::from)?;
+		let mut file = zip.by_name(name).map_err(ViewNpzError::from)?;
+		NpyHeader::read(&mut file).ok()
+	}
+	.ok_or_else(|| py_err(ErrorCode::InvalidHeader, "invalid npy header"))
+}
+
+/// Python extension module `ndarray_npz`.
+#[pymodule]
+fn ndarray_npz(module: &Bound<'_, PyModule>) -> PyResult<()> {
+	module.add_class::<PyNpzReader>()?;
+	module.add_class::<PyNpzWriter>()?;
+	module.add_class::<PyNpzView>()?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{dtype, py_err, read_header, view_header, PyNpzView, DTYPES};
+	use crate::{convert::Dtype, ErrorCode, NpzReader, NpzView, NpzWriter};
+	use aligned_vec::AVec;
+	use ndarray::ArrayD;
+	use ndarray_npy::WritableElement;
+	use pyo3::{
+		exceptions::{PyKeyError, PyOSError, PyTypeError, PyValueError},
+		Python,
+	};
+	use std::io::Cursor;
+
+	#[test]
+	fn dtype_round_trip() {
+		/// Adds a zero-filled array of element type `A` as `name`.
+		fn add<A: WritableElement + Clone + Default>(
+			npz: &mut NpzWriter<Cursor<Vec<u8>>>,
+			name: &str,
+		) {
+			npz.add_array(name, &ArrayD::<A>::default(vec![2, 3]))
+				.unwrap();
+		}
+		let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+		for dtype in DTYPES {
+			dispatch!(dtype, add(&mut npz, &format!("{dtype:?}")));
+		}
+		let buffer = npz.finish().unwrap().into_inner();
+		let mut reader = NpzReader::new(Cursor::new(&buffer)).unwrap();
+		let bytes = AVec::<u8>::from_slice(64, &buffer);
+		let view = NpzView::new(&bytes).unwrap();
+		for dtype in DTYPES {
+			let name = format!("{dtype:?}");
+			let header = read_header(&mut reader, &name).unwrap();
+			assert_eq!(header.shape, [2, 3]);
+			assert_eq!(super::dtype(&header).unwrap(), dtype);
+			let header = view_header(&view, &bytes, &name).unwrap();
+			assert_eq!(super::dtype(&header).unwrap(), dtype);
+		}
+		assert!(read_header(&mut reader, "missing").is_err());
+		assert!(view_header(&view, &bytes, "missing").is_err());
+	}
+
+	#[cfg(feature = "compressed")]
+	#[test]
+	fn dtype_compressed() {
+		let mut npz = NpzWriter::new_compressed(Cursor::new(Vec::new()));
+		npz.add_array("x", &ArrayD::<i16>::zeros(vec![4])).unwrap();
+		let buffer = npz.finish().unwrap().into_inner();
+		let bytes = AVec::<u8>::from_slice(64, &buffer);
+		let view = NpzView::new(&bytes).unwrap();
+		let header = view_header(&view, &bytes, "x").unwrap();
+		assert_eq!(dtype(&header).unwrap(), Dtype::I16);
+	}
+
+	#[test]
+	fn py_err_category() {
+		Python::initialize();
+		Python::attach(|py| {
+			assert!(py_err(ErrorCode::Io, "").is_instance_of::<PyOSError>(py));
+			assert!(py_err(ErrorCode::FileNotFound, "").is_instance_of::<PyKeyError>(py));
+			assert!(py_err(ErrorCode::DtypeMismatch, "").is_instance_of::<PyTypeError>(py));
+			assert!(py_err(ErrorCode::InvalidHeader, "").is_instance_of::<PyValueError>(py));
+			let err = py_err(ErrorCode::WriterFinished, "finished");
+			let code = ErrorCode::WriterFinished.value();
+			assert_eq!(err.value(py).to_string(), format!("finished (code {code})"));
+		});
+	}
+
+	#[test]
+	fn view_read_and_mapped() {
+		let path = std::env::temp_dir().join("ndarray_npz_py_view.npz");
+		let mut npz = NpzWriter::new(std::fs::File::create(&path).unwrap());
+		npz.add_array("x", &ArrayD::<i16>::zeros(vec![4])).unwrap();
+		npz.finish().unwrap();
+		let read = PyNpzView::new(path.clone()).unwrap();
+		let mapped = PyNpzView::map_trusted(path.clone()).unwrap();
+		assert_eq!(read.names().unwrap(), ["x"]);
+		assert_eq!(mapped.names().unwrap(), ["x"]);
+		drop(mapped);
+		std::fs::remove_file(path).unwrap();
+	}
+}
@@ -0,0 +1,177 @@
+use super::{sibling_name, NpzReader, NpzWriter, ReadNpzError, WriteNpzError};
+use ndarray::{prelude::*, Data};
+use ndarray_npy::{ReadableElement, WritableElement};
+use std::io::{Read, Seek, Write};
+
+/// Integer element type of quantized arrays.
+pub trait QuantizedElement: ReadableElement + WritableElement + Copy {
+	/// Smallest representable value.
+	const MIN: f64;
+	/// Largest representable value.
+	const MAX: f64;
+	/// Converts a rounded value saturating at [`Self::MIN`] and [`Self::MAX`].
+	fn from_f64(value: f64) -> Self;
+	/// Converts into a floating point value.
+	fn to_f64(self) -> f64;
+}
+
+/// Floating point element type of arrays to quantize and of dequantized arrays.
+pub trait DequantizedElement: Copy {
+	/// Converts from a double-precision value.
+	fn from_f64(value: f64) -> Self;
+	/// Converts into a double-precision value.
+	fn to_f64(self) -> f64;
+}
+
+macro_rules! impl_quantized_element {
+	($($ty:ty),*) => {$(
+		impl QuantizedElement for $ty {
+			const MIN: f64 = <$ty>::MIN as f64;
+			const MAX: f64 = <$ty>::MAX as f64;
+			#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+			fn from_f64(value: f64) -> Self {
+				value as $ty
+			}
+			fn to_f64(self) -> f64 {
+				f64::from(self)
+			}
+		}
+	)*};
+}
+
+impl_quantized_element!(i8, u8, i16, u16);
+
+impl DequantizedElement for f32 {
+	#[allow(clippy::cast_possible_truncation)]
+	fn from_f64(value: f64) -> Self {
+		value as f32
+	}
+	fn to_f64(self) -> f64 {
+		f64::from(self)
+	}
+}
+
+impl DequantizedElement for f64 {
+	fn from_f64(value: f64) -> Self {
+		value
+	}
+	fn to_f64(self) -> f64 {
+		self
+	}
+}
+
+/// Affine quantization parameters mapping values `x` to integers `q = round(x / scale) +
+/// zero_point` and back to `x = (q - zero_point) * scale`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quantization {
+	/// Step size between consecutive integers.
+	pub scale: f64,
+	/// Integer representing zero.
+	pub zero_point: i64,
+}
+
+impl Quantization {
+	/// Fits the parameters to the range of finite `values` extended to include zero, so zero is
+	/// represented exactly by the integer element type `Q`.
+	#[must_use]
+	#[allow(clippy::cast_possible_truncation)]
+	pub fn fit<Q: QuantizedElement>(values: impl IntoIterator<Item = f64>) -> Self {
+		let (min, max) = values
+			.into_iter()
+			.filter(|value| value.is_finite())
+			.fold((0f64, 0f64), |(min, max), value| {
+				(min.min(value), max.max(value))
+			});
+		let scale = if max > min {
+			(max - min) / (Q::MAX - Q::MIN)
+		} else {
+			1.0
+		};
+		let zero_point = (Q::MIN - min / scale).round().clamp(Q::MIN, Q::MAX) as i64;
+		Self { scale, zero_point }
+	}
+	/// Quantizes `value` saturating at the bounds of `Q`.
+	#[must_use]
+	#[allow(clippy::cast_precision_loss)]
+	pub fn quantize<Q: QuantizedElement>(&self, value: f64) -> Q {
+		Q::from_f64((value / self.scale).round() + self.zero_point as f64)
+	}
+	/// Dequantizes `value`.
+	#[must_use]
+	#[allow(clippy::cast_precision_loss)]
+	pub fn dequantize<Q: QuantizedElement>(&self, value: Q) -> f64 {
+		(value.to_f64() - self.zero_point as f64) * self.scale
+	}
+}
+
+impl<W: Write + Seek> NpzWriter<W> {
+	/// Adds an array with the specified `name` quantized to the integer element type `Q` and
+	/// returns the fitted [`Quantization`].
+	///
+	/// The parameters are stored as scalar members next to the quantized array, i.e., `x.npy` is
+	/// accompanied by `x.scale.npy` of type `f64` and `x.zero_point.npy` of type `i64`. This way,
+	/// the archive remains readable by NumPy as in `(x - x.zero_point) * x.scale`. Read it back
+	/// via [`NpzReader::read_dequantized`].
+	///
+	/// # Errors
+	///
+	/// Adding an array can fail with [`WriteNpyError`](ndarray_npy::WriteNpyError).
+	pub fn add_array_quantized<Q, N, S, D>(
+		&mut self,
+		name: N,
+		array: &ArrayBase<S, D>,
+	) -> Result<Quantization, WriteNpzError>
+	where
+		Q: QuantizedElement,
+		N: Into<String>,
+		S::Elem: DequantizedElement,
+		S: Data,
+		D: Dimension,
+	{
+		let name = name.into();
+		let quantization = Quantization::fit::<Q>(array.iter().map(|value| value.to_f64()));
+		let quantized = array.mapv(|value| quantization.quantize::<Q>(value.to_f64()));
+		self.add_array(sibling_name(&name, "scale"), &arr0(quantization.scale))?;
+		self.add_array(
+			sibling_name(&name, "zero_point"),
+			&arr0(quantization.zero_point),
+		)?;
+		self.add_array(name, &quantized)?;
+		Ok(quantization)
+	}
+}
+
+impl<R: Read + Seek> NpzReader<R> {
+	/// Reads the [`Quantization`] of an array added via [`NpzWriter::add_array_quantized`].
+	///
+	/// # Errors
+	///
+	/// Reading the parameters can fail with [`ReadNpyError`](ndarray_npy::ReadNpyError) or
+	/// [`ZipError`](zip::result::ZipError), e.g., if the array is not quantized.
+	pub fn quantization(&mut self, name: &str) -> Result<Quantization, ReadNpzError> {
+		let scale: Array0<f64> = self.by_name(&sibling_name(name, "scale"))?;
+		let zero_point: Array0<i64> = self.by_name(&sibling_name(name, "zero_point"))?;
+		Ok(Quantization {
+			scale: scale.into_scalar(),
+			zero_point: zero_point.into_scalar(),
+		})
+	}
+
+	/// Reads an array of integer element type `Q` added via [`NpzWriter::add_array_quantized`] and
+	/// dequantizes it.
+	///
+	/// # Errors
+	///
+	/// Reading an array from an archive can fail with [`ReadNpyError`](ndarray_npy::ReadNpyError)
+	/// or [`ZipError`](zip::result::ZipError).
+	pub fn read_dequantized<Q, A, D>(&mut self, name: &str) -> Result<Array<A, D>, ReadNpzError>
+	where
+		Q: QuantizedElement,
+		A: DequantizedElement,
+		D: Dimension,
+	{
+		let quantization = self.quantization(name)?;
+		let quantized: Array<Q, D> = self.by_name(name)?;
+		Ok(quantized.mapv(|value| A::from_f64(quantization.dequantize(value))))
+	}
+}
@@ -0,0 +1,189 @@
+use super::{
+	header::NpyHeader, EditNpzError, NpzEditor, NpzReader, NpzWriter, ReadNpzError, WriteNpzError,
+};
+use ndarray::{concatenate, prelude::*, Data, OwnedRepr};
+use ndarray_npy::{ReadableElement, WritableElement, WriteNpyExt};
+use std::{
+	io::{self, Read, Seek, Write},
+	iter,
+	marker::PhantomData,
+};
+use zip::result::ZipError;
+
+/// Returns the name of the member with `index` of the recording `name`.
+fn member_name(name: &str, index: usize) -> String {
+	let stem = name.strip_suffix(".npy").unwrap_or(name);
+	format!("{stem}.{index}.npy")
+}
+
+/// Writer appending frames of fixed shape, e.g., audio blocks or sensor samples, to a growing
+/// member of an uncompressed `.npz` file.
+///
+/// Each member of the recording `x` is named `x.0.npy`, `x.1.npy`, and so on, with a `.npy` header
+/// reserving `capacity` frames along a new first axis. Frames are streamed into the current member
+/// and once its capacity is exhausted, the recording rolls over to a new member. On
+/// [`Self::finish`], the partially filled last member is truncated to the recorded frames via
+/// [`NpzEditor::truncate_entry`]. Read the recording back via [`NpzReader::read_recording`].
+///
+/// # Example
+///
+/// ```no_run
+/// use ndarray_npz::{ndarray::Array1, RecorderWriter};
+/// use std::fs::OpenOptions;
+///
+/// let file = OpenOptions::new()
+/// 	.read(true)
+/// 	.write(true)
+/// 	.create(true)
+/// 	.truncate(true)
+/// 	.open("audio.npz")?;
+/// let mut recorder = RecorderWriter::<_, f32>::new(file, "audio", &[512], 1024);
+/// for _block in 0..3000 {
+/// 	recorder.append(&Array1::<f32>::zeros(512))?;
+/// }
+/// recorder.finish()?;
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct RecorderWriter<W: Read + Write + Seek, A> {
+	npz: NpzWriter<W>,
+	name: String,
+	frame_shape: Vec<usize>,
+	frame_size: usize,
+	capacity: usize,
+	members: usize,
+	len: usize,
+	element: PhantomData<A>,
+}
+
+impl<W: Read + Write + Seek, A: WritableElement + Clone> RecorderWriter<W, A> {
+	/// Creates a new `.npz` file recording frames of `frame_shape` as `name` with `capacity`
+	/// frames per member.
+	///
+	/// # Panics
+	///
+	/// Panics if `capacity` is zero.
+	#[must_use]
+	pub fn new(writer: W, name: &str, frame_shape: &[usize], capacity: usize) -> Self {
+		assert!(capacity > 0, "capacity must be positive");
+		Self {
+			npz: NpzWriter::new(writer),
+			name: name.to_string(),
+			frame_shape: frame_shape.to_vec(),
+			frame_size: 0,
+			capacity,
+			members: 0,
+			len: 0,
+			element: PhantomData,
+		}
+	}
+
+	/// Returns the number of recorded frames.
+	#[must_use]
+	pub fn len(&self) -> usize {
+		self.members.saturating_sub(1) * self.capacity + self.len
+	}
+
+	/// Returns `true` iff no frames have been recorded.
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.members == 0
+	}
+
+	/// Appends a frame to the current member, rolling over to a new member if it is full.
+	///
+	/// # Errors
+	///
+	/// Writing the frame can fail with [`WriteNpyError`](ndarray_npy::WriteNpyError) or
+	/// [`ZipError`].
+	///
+	/// # Panics
+	///
+	/// Panics if the shape of `frame` differs from the frame shape.
+	pub fn append<S, D>(&mut self, frame: &ArrayBase<S, D>) -> Result<(), WriteNpzError>
+	where
+		S: Data<Elem = A>,
+		D: Dimension,
+	{
+		assert_eq!(
+			frame.shape(),
+			self.frame_shape.as_slice(),
+			"frame shape differs"
+		);
+		let mut npy = Vec::new();
+		frame.as_standard_layout().write_npy(&mut npy)?;
+		let mut header = NpyHeader::parse(&npy).expect("valid npy header");
+		let data = &npy[header.len..];
+		if self.members == 0 || self.len == self.capacity {
+			header.shape = iter::once(self.capacity)
+				.chain(self.frame_shape.iter().copied())
+				.map(|axis| axis as u64)
+				.collect();
+			let header = header.to_bytes().ok_or(ZipError::InvalidArchive(
+				"Header exceeds length of npy format version",
+			))?;
+			self.npz
+				.zip
+				.start_file(member_name(&self.name, self.members), self.npz.options)?;
+			self.npz.zip.write_all(&header).map_err(ZipError::from)?;
+			self.members += 1;
+			self.len = 0;
+		}
+		self.npz.zip.write_all(data).map_err(ZipError::from)?;
+		self.frame_size = data.len();
+		self.len += 1;
+		Ok(())
+	}
+
+	/// Finishes the recording and returns the writer.
+	///
+	/// # Errors
+	///
+	/// Finishing the zip archive can fail with [`ZipError`]. Truncating the last member can fail
+	/// with [`EditNpzError`].
+	pub fn finish(mut self) -> Result<W, EditNpzError> {
+		let partial = self.members > 0 && self.len < self.capacity;
+		if partial {
+			// Fill reserved frames with zeros before truncating them.
+			let padding = (self.capacity - self.len) as u64 * self.frame_size as u64;
+			io::copy(&mut io::repeat(0).take(padding), &mut self.npz.zip)?;
+		}
+		let mut writer = self.npz.zip.finish()?;
+		writer.flush()?;
+		if partial {
+			writer.rewind()?;
+			let mut npz = NpzEditor::new(writer)?;
+			npz.truncate_entry(&member_name(&self.name, self.members - 1), self.len)?;
+			writer = npz.into_inner();
+		}
+		Ok(writer)
+	}
+}
+
+impl<R: Read + Seek> NpzReader<R> {
+	/// Reads all members of a recording written via [`RecorderWriter`] concatenated along the first
+	/// axis.
+	///
+	/// # Errors
+	///
+	/// Reading an array from an archive can fail with [`ReadNpyError`](ndarray_npy::ReadNpyError)
+	/// or [`ZipError`]. Fails with [`ZipError::FileNotFound`] if there are no members and with
+	/// [`ZipError::InvalidArchive`] if the frame shapes of the members differ.
+	pub fn read_recording<A>(&mut self, name: &str) -> Result<ArrayD<A>, ReadNpzError>
+	where
+		A: ReadableElement + Clone,
+	{
+		let mut members = Vec::new();
+		loop {
+			match self.by_name::<OwnedRepr<A>, IxDyn>(&member_name(name, members.len())) {
+				Err(ReadNpzError::Zip(ZipError::FileNotFound)) => break,
+				member => members.push(member?),
+			}
+		}
+		if members.is_empty() {
+			return Err(ZipError::FileNotFound.into());
+		}
+		let members = members.iter().map(ArrayBase::view).collect::<Vec<_>>();
+		concatenate(Axis(0), &members)
+			.map_err(|_| ZipError::InvalidArchive("Frame shapes of recording differ").into())
+	}
+}
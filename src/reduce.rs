@@ -0,0 +1,328 @@
+use super::{
+	convert::{decode, Dtype, Scalar, CHUNK_LEN},
+	header::NpyHeader,
+	NpzReader, ReadNpzError,
+};
+use ndarray::IxDyn;
+use std::io::{Read, Seek};
+use zip::{read::ZipFile, result::ZipError};
+
+/// Statistics of an array, see [`NpzReader::stats`].
+///
+/// `NaN` elements are counted but otherwise ignored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+	/// Number of elements.
+	pub len: u64,
+	/// Number of `NaN` elements.
+	pub nan_count: u64,
+	/// Minimum, `NaN` if there are no elements other than `NaN`.
+	pub min: f64,
+	/// Maximum, `NaN` if there are no elements other than `NaN`.
+	pub max: f64,
+	/// Arithmetic mean, `NaN` if there are no elements other than `NaN`.
+	pub mean: f64,
+	/// Population standard deviation, `NaN` if there are no elements other than `NaN`.
+	pub std: f64,
+	/// Number of elements per bin.
+	pub histogram: Vec<u64>,
+}
+
+/// Reduction of an array to a scalar, see [`NpzReader::reduce`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Reduction {
+	/// Minimum, `NaN` if any element is `NaN` or if the array is empty.
+	Min,
+	/// Maximum, `NaN` if any element is `NaN` or if the array is empty.
+	Max,
+	/// Sum, `0` if the array is empty.
+	Sum,
+	/// Arithmetic mean, `NaN` if the array is empty.
+	Mean,
+	/// Number of `NaN` elements.
+	NanCount,
+}
+
+impl<R: Read + Seek> NpzReader<R> {
+	/// Reduces the array `name` to a scalar without reading it into memory.
+	///
+	/// The elements are streamed in chunks, decompressing compressed files on the fly. They are
+	/// accumulated as `f64`, hence 64-bit integers beyond 2<sup>53</sup> lose precision. The
+	/// element type must be one of [`Dtype`].
+	///
+	/// # Example
+	///
+	/// ```no_run
+	/// use ndarray_npz::{NpzReader, Reduction};
+	/// use std::fs::File;
+	///
+	/// let mut npz = NpzReader::new(File::open("arrays.npz")?)?;
+	/// for name in npz.names()? {
+	/// 	let nan_count = npz.reduce(&name, Reduction::NanCount)?;
+	/// 	let mean = npz.reduce(&name, Reduction::Mean)?;
+	/// 	println!("{name}: {nan_count} NaN, mean {mean}");
+	/// }
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Fails with [`ZipError::InvalidArchive`] if the element type is not supported. Reading the
+	/// `.npy` file can fail with [`ZipError`].
+	pub fn reduce(&mut self, name: &str, reduction: Reduction) -> Result<f64, ReadNpzError> {
+		let elements = self.elements(name)?;
+		let empty = elements.len == 0;
+		#[allow(clippy::cast_precision_loss)]
+		let len = elements.len as f64;
+		let mut value = match reduction {
+			Reduction::Min => f64::INFINITY,
+			Reduction::Max => f64::NEG_INFINITY,
+			Reduction::Sum | Reduction::Mean | Reduction::NanCount => 0.0,
+		};
+		elements.for_each(|element| {
+			let element = element.to_f64();
+			match reduction {
+				Reduction::Min => {
+					if element.is_nan() || element < value {
+						value = element;
+					}
+				}
+				Reduction::Max => {
+					if element.is_nan() || element > value {
+						value = element;
+					}
+				}
+				Reduction::Sum | Reduction::Mean => value += element,
+				Reduction::NanCount => value += f64::from(u8::from(element.is_nan())),
+			}
+		})?;
+		Ok(match reduction {
+			Reduction::Min | Reduction::Max if empty => f64::NAN,
+			Reduction::Mean => value / len,
+			_ => value,
+		})
+	}
+
+	/// Computes the [`Stats`] of the array `name` in a single pass without reading it into
+	/// memory.
+	///
+	/// The histogram counts the elements falling into the bins of the monotonically increasing
+	/// edges `bins`, i.e., `bins.len() - 1` bins. Like [`numpy.histogram`], all but the last bin
+	/// are half-open and elements outside the edges are not counted. The edges are fixed
+	/// beforehand, so histograms of different arrays or archives are comparable. The mean and
+	/// standard deviation are accumulated via Welford's algorithm. See [`Self::reduce`] regarding
+	/// supported element types and precision.
+	///
+	/// [`numpy.histogram`]: https://numpy.org/doc/stable/reference/generated/numpy.histogram.html
+	///
+	/// # Errors
+	///
+	/// Fails like [`Self::reduce`].
+	///
+	/// # Panics
+	///
+	/// Panics if `bins` is not monotonically increasing.
+	pub fn stats(&mut self, name: &str, bins: &[f64]) -> Result<Stats, ReadNpzError> {
+		assert!(
+			bins.windows(2).all(|edges| edges[0] < edges[1]),
+			"bins must increase monotonically"
+		);
+		let mut stats = Stats {
+			len: 0,
+			nan_count: 0,
+			min: f64::NAN,
+			max: f64::NAN,
+			mean: f64::NAN,
+			std: f64::NAN,
+			histogram: vec![0; bins.len().saturating_sub(1)],
+		};
+		let (mut min, mut max) = (f64::INFINITY, f64::NEG_INFINITY);
+		let (mut mean, mut m2) = (0.0, 0.0);
+		let mut count = 0u64;
+		self.elements(name)?.for_each(|element| {
+			let element = element.to_f64();
+			stats.len += 1;
+			if element.is_nan() {
+				stats.nan_count += 1;
+				return;
+			}
+			count += 1;
+			min = element.min(min);
+			max = element.max(max);
+			#[allow(clippy::cast_precision_loss)]
+			let n = count as f64;
+			let delta = element - mean;
+			mean += delta / n;
+			m2 += delta * (element - mean);
+			if let [first, .., last] = bins {
+				if (*first..=*last).contains(&element) {
+					let bin = bins.partition_point(|&edge| edge <= element) - 1;
+					stats.histogram[bin.min(bins.len() - 2)] += 1;
+				}
+			}
+		})?;
+		if count > 0 {
+			stats.min = min;
+			stats.max = max;
+			stats.mean = mean;
+			#[allow(clippy::cast_precision_loss)]
+			let variance = m2 / count as f64;
+			stats.std = variance.sqrt();
+		}
+		Ok(stats)
+	}
+
+	/// Computes the [`Stats`] of all floating-point arrays in archive order, see [`Self::stats`].
+	///
+	/// # Example
+	///
+	/// ```no_run
+	/// use ndarray_npz::NpzReader;
+	/// use std::fs::File;
+	///
+	/// let mut npz = NpzReader::new(File::open("arrays.npz")?)?;
+	/// let bins = [-1.0, -0.5, 0.0, 0.5, 1.0];
+	/// for (name, stats) in npz.stats_all(&bins)? {
+	/// 	println!("{name}: {:.3} ± {:.3} {:?}", stats.mean, stats.std, stats.histogram);
+	/// }
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Fails like [`Self::stats`].
+	///
+	/// # Panics
+	///
+	/// Panics like [`Self::stats`].
+	#[allow(clippy::case_sensitive_file_extension_comparisons)]
+	pub fn stats_all(&mut self, bins: &[f64]) -> Result<Vec<(String, Stats)>, ReadNpzError> {
+		let mut stats = Vec::new();
+		for name in self.npy_names()? {
+			let mut file = self.zip.by_name(&name)?;
+			let header = NpyHeader::read(&mut file).map_err(ZipError::from)?;
+			drop(file);
+			if let Some((Dtype::F32 | Dtype::F64, _)) = Dtype::parse(&header.descr) {
+				let array = self.stats(&name, bins)?;
+				stats.push((name, array));
+			}
+		}
+		Ok(stats)
+	}
+
+	/// Finds the indices of non-finite elements, i.e., of `NaN` and infinite elements, of the
+	/// floating-point arrays `names` without reading them into memory.
+	///
+	/// Returns the name and the indices of each array containing non-finite elements in the order
+	/// of `names`. At most `cap` indices are reported per array in memory order. Arrays of other
+	/// element types are skipped.
+	///
+	/// # Example
+	///
+	/// ```no_run
+	/// use ndarray_npz::NpzReader;
+	/// use std::fs::File;
+	///
+	/// let mut npz = NpzReader::new(File::open("arrays.npz")?)?;
+	/// let names = npz.names()?;
+	/// for (name, indices) in npz.find_nonfinite(&names, 10)? {
+	/// 	println!("{name}: {indices:?}");
+	/// }
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Fails with [`ZipError::InvalidArchive`] if an element type is not supported. Reading the
+	/// `.npy` files can fail with [`ZipError`].
+	pub fn find_nonfinite<N: AsRef<str>>(
+		&mut self,
+		names: &[N],
+		cap: usize,
+	) -> Result<Vec<(String, Vec<IxDyn>)>, ReadNpzError> {
+		let mut nonfinite = Vec::new();
+		for name in names {
+			let name = name.as_ref();
+			let elements = self.elements(name)?;
+			if !matches!(elements.dtype, Dtype::F32 | Dtype::F64) {
+				continue;
+			}
+			let shape = elements.header.shape.clone();
+			let fortran_order = elements.header.fortran_order;
+			let mut indices = Vec::new();
+			let mut index = 0;
+			elements.for_each(|element| {
+				if indices.len() < cap && !element.to_f64().is_finite() {
+					indices.push(unravel(index, &shape, fortran_order));
+				}
+				index += 1;
+			})?;
+			if !indices.is_empty() {
+				nonfinite.push((name.to_owned(), indices));
+			}
+		}
+		Ok(nonfinite)
+	}
+
+	/// Returns a stream of the elements of the array `name` in memory order.
+	pub(crate) fn elements(&mut self, name: &str) -> Result<Elements<'_>, ReadNpzError> {
+		let mut file = self.zip.by_name(name)?;
+		let header = NpyHeader::read(&mut file).map_err(ZipError::from)?;
+		let (dtype, big_endian) = Dtype::parse(&header.descr)
+			.ok_or(ZipError::InvalidArchive("Element type not supported"))?;
+		let len = header
+			.elements()
+			.ok_or(ZipError::InvalidArchive("Number of elements overflows"))?;
+		Ok(Elements {
+			file,
+			header,
+			dtype,
+			big_endian,
+			len,
+		})
+	}
+}
+
+/// Stream of the elements of an `.npy` file, see [`NpzReader::elements`].
+pub(crate) struct Elements<'a> {
+	file: ZipFile<'a>,
+	header: NpyHeader,
+	dtype: Dtype,
+	big_endian: bool,
+	/// Number of elements.
+	pub len: u64,
+}
+
+impl Elements<'_> {
+	/// Decodes the elements chunk by chunk and passes them to `f` in memory order.
+	pub fn for_each<F: FnMut(Scalar)>(mut self, mut f: F) -> Result<(), ReadNpzError> {
+		let size = self.dtype.size();
+		let mut bytes = vec![0; CHUNK_LEN * size];
+		let mut remaining = self.len;
+		while remaining > 0 {
+			let chunk = usize::try_from(remaining.min(CHUNK_LEN as u64)).unwrap_or(CHUNK_LEN);
+			let bytes = &mut bytes[..chunk * size];
+			self.file.read_exact(bytes).map_err(ZipError::from)?;
+			for bytes in bytes.chunks_exact(size) {
+				f(decode(self.dtype, bytes, self.big_endian));
+			}
+			remaining -= chunk as u64;
+		}
+		Ok(())
+	}
+}
+
+/// Converts the linear `index` in memory order into an index of `shape`.
+fn unravel(mut index: u64, shape: &[u64], fortran_order: bool) -> IxDyn {
+	let mut indices = vec![0; shape.len()];
+	let mut unravel = |(axis, &len): (&mut usize, &u64)| {
+		*axis = usize::try_from(index % len).unwrap_or(usize::MAX);
+		index /= len;
+	};
+	if fortran_order {
+		indices.iter_mut().zip(shape).for_each(&mut unravel);
+	} else {
+		indices.iter_mut().zip(shape).rev().for_each(&mut unravel);
+	}
+	IxDyn(&indices)
+}
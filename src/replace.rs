@@ -0,0 +1,79 @@
+use super::{header::NpyHeader, NpzViewMut, ViewNpzError};
+use ndarray::{ArrayBase, Data, Dimension};
+use ndarray_npy::{WritableElement, WriteNpyExt};
+use zip::result::ZipError;
+
+impl NpzViewMut<'_> {
+	/// Overwrites the data of the `.npy` file `name` with `array` in place and returns the updated
+	/// CRC-32 checksum.
+	///
+	/// The element type and shape of `array` must equal the ones of the `.npy` file, so its data
+	/// fits exactly and neither the `.npy` file nor the archive is resized. The `.npy` header is
+	/// rewritten within its existing length, e.g., to switch between standard and Fortran order.
+	/// The checksum is [updated](crate::NpyViewMut::update) right away, hence the replacement is
+	/// recorded if a [`MutationLog`](crate::MutationLog) is attached.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use aligned_vec::AVec;
+	/// use ndarray::{array, Array1};
+	/// use ndarray_npz::{NpzViewMut, NpzWriter};
+	/// use std::io::Cursor;
+	///
+	/// let mut buffer = Vec::new();
+	/// let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+	/// npz.add_array("x.npy", &Array1::<f64>::zeros(3))?;
+	/// npz.finish()?;
+	/// let mut buffer = AVec::<u8>::from_slice(64, &buffer);
+	/// let mut npz = NpzViewMut::new(&mut buffer)?;
+	/// npz.replace_entry_data("x.npy", &array![1.0, 2.0, 3.0])?;
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Fails with [`ViewNpzError::DtypeMismatch`] or [`ViewNpzError::ShapeMismatch`] if the element
+	/// type or shape differs, with [`ViewNpzError::MovedNpyViewMut`] if the `.npy` file view has
+	/// already been moved out, with [`ViewNpzError::WriteNpy`] if encoding `array` fails, or like
+	/// [`Self::by_name`] if `name` cannot be viewed. Fails with [`ZipError::InvalidArchive`] if the
+	/// `.npy` header is invalid or the new one exceeds its length.
+	pub fn replace_entry_data<S, D>(
+		&mut self,
+		name: &str,
+		array: &ArrayBase<S, D>,
+	) -> Result<u32, ViewNpzError>
+	where
+		S::Elem: WritableElement,
+		S: Data,
+		D: Dimension,
+	{
+		let index = self.index(name)?;
+		let file = self
+			.files
+			.get_mut(&index)
+			.ok_or(ViewNpzError::MovedNpyViewMut)?;
+		let invalid = || ZipError::InvalidArchive("Invalid npy header");
+		let old = NpyHeader::parse(file.data).ok_or_else(invalid)?;
+		let mut bytes = Vec::new();
+		array.write_npy(&mut bytes)?;
+		let mut new = NpyHeader::parse(&bytes).ok_or_else(invalid)?;
+		if new.descr != old.descr {
+			return Err(ViewNpzError::DtypeMismatch);
+		}
+		if new.shape != old.shape {
+			return Err(ViewNpzError::ShapeMismatch);
+		}
+		let data = new.len;
+		if file.data.len() - old.len != bytes.len() - data {
+			return Err(ZipError::InvalidArchive("Data length mismatch").into());
+		}
+		new.version = old.version;
+		let header = new
+			.to_bytes_with_len(old.len)
+			.ok_or(ZipError::InvalidArchive("Npy header exceeds its length"))?;
+		file.data[..old.len].copy_from_slice(&header);
+		file.data[old.len..].copy_from_slice(&bytes[data..]);
+		Ok(file.update())
+	}
+}
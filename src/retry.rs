@@ -0,0 +1,222 @@
+use std::{
+	io::{self, Read, Seek, SeekFrom},
+	thread,
+	time::{Duration, Instant},
+};
+
+/// Retry policy of a [`RetryReader`] with exponential backoff.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+	max_retries: u32,
+	initial_backoff: Duration,
+	max_backoff: Duration,
+	multiplier: u32,
+	deadline: Option<Duration>,
+	is_transient: fn(&io::Error) -> bool,
+}
+
+impl Default for RetryPolicy {
+	/// Retries up to 5 times starting with a backoff of 100 ms doubling up to 10 s.
+	fn default() -> Self {
+		Self {
+			max_retries: 5,
+			initial_backoff: Duration::from_millis(100),
+			max_backoff: Duration::from_secs(10),
+			multiplier: 2,
+			deadline: None,
+			is_transient,
+		}
+	}
+}
+
+impl RetryPolicy {
+	/// Sets the maximum number of retries per operation.
+	///
+	/// Interruptions are retried immediately without counting against it.
+	#[must_use]
+	pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+		self.max_retries = max_retries;
+		self
+	}
+	/// Sets the backoff before the first retry.
+	#[must_use]
+	pub fn with_initial_backoff(mut self, initial_backoff: Duration) -> Self {
+		self.initial_backoff = initial_backoff;
+		self
+	}
+	/// Sets the upper bound of the backoff.
+	#[must_use]
+	pub fn with_max_backoff(mut self, max_backoff: Duration) -> Self {
+		self.max_backoff = max_backoff;
+		self
+	}
+	/// Sets the factor the backoff is multiplied with after each retry.
+	#[must_use]
+	pub fn with_multiplier(mut self, multiplier: u32) -> Self {
+		self.multiplier = multiplier;
+		self
+	}
+	/// Sets the deadline per operation including its retries and backoffs.
+	///
+	/// Instead of retrying or backing off beyond the deadline, the operation fails with
+	/// [`io::ErrorKind::TimedOut`], which [`NpzReader`](crate::NpzReader) surfaces as
+	/// [`ReadNpzError::TimedOut`](crate::ReadNpzError::TimedOut). A single blocking read of the
+	/// inner reader cannot be interrupted, see [`NpzReader::with_entry_timeout`] for bounding
+	/// the reads of a whole file.
+	///
+	/// [`NpzReader::with_entry_timeout`]: crate::NpzReader::with_entry_timeout
+	#[must_use]
+	pub fn with_deadline(mut self, deadline: Duration) -> Self {
+		self.deadline = Some(deadline);
+		self
+	}
+	/// Sets the predicate deciding whether an error is transient and hence worth retrying.
+	///
+	/// By default, interruptions, timeouts, connection resets and aborts, as well as generic
+	/// I/O errors (`EIO` on Unix) are considered transient.
+	#[must_use]
+	pub fn with_transient(mut self, is_transient: fn(&io::Error) -> bool) -> Self {
+		self.is_transient = is_transient;
+		self
+	}
+}
+
+fn is_transient(err: &io::Error) -> bool {
+	matches!(
+		err.kind(),
+		io::ErrorKind::Interrupted
+			| io::ErrorKind::WouldBlock
+			| io::ErrorKind::TimedOut
+			| io::ErrorKind::ConnectionReset
+			| io::ErrorKind::ConnectionAborted
+	) || cfg!(unix) && err.raw_os_error() == Some(5)
+}
+
+/// Reader retrying transient I/O errors, e.g., of network file systems like NFS or FUSE mounts.
+///
+/// Keeps track of the stream position and seeks back to it before each retry, so a failed read
+/// is repeated as idempotent range read and never skips or duplicates bytes of the zip or `.npy`
+/// framing.
+///
+/// # Example
+///
+/// ```no_run
+/// use ndarray_npz::{
+/// 	ndarray::Array1,
+/// 	NpzReader, RetryPolicy, RetryReader,
+/// };
+/// use std::{fs::File, time::Duration};
+///
+/// let policy = RetryPolicy::default()
+/// 	.with_max_retries(10)
+/// 	.with_initial_backoff(Duration::from_millis(500));
+/// let file = RetryReader::new(File::open("/mnt/nfs/arrays.npz")?, policy)?;
+/// let mut npz = NpzReader::new(file)?;
+/// let a: Array1<f64> = npz.by_name("a.npy")?;
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug)]
+pub struct RetryReader<R: Read + Seek> {
+	reader: R,
+	policy: RetryPolicy,
+	position: u64,
+	retries: u64,
+}
+
+impl<R: Read + Seek> RetryReader<R> {
+	/// Wraps `reader` retrying according to `policy`.
+	///
+	/// # Errors
+	///
+	/// Fails with [`io::Error`] if the current stream position cannot be queried.
+	pub fn new(mut reader: R, policy: RetryPolicy) -> io::Result<Self> {
+		let position = reader.stream_position()?;
+		Ok(Self {
+			reader,
+			policy,
+			position,
+			retries: 0,
+		})
+	}
+	/// Returns the total number of retries so far.
+	#[must_use]
+	pub fn retries(&self) -> u64 {
+		self.retries
+	}
+	/// Returns the inner reader.
+	#[must_use]
+	pub fn into_inner(self) -> R {
+		self.reader
+	}
+
+	fn retry<T>(&mut self, mut op: impl FnMut(&mut R) -> io::Result<T>) -> io::Result<T> {
+		let mut backoff = self.policy.initial_backoff;
+		let mut retry = 0;
+		let mut restore = false;
+		let start = Instant::now();
+		loop {
+			// Restore position as a failed operation might have partially advanced it.
+			let result = if restore {
+				let position = self.position;
+				self.reader
+					.seek(SeekFrom::Start(position))
+					.and_then(|_| op(&mut self.reader))
+			} else {
+				op(&mut self.reader)
+			};
+			match result {
+				Err(err)
+					if (err.kind() == io::ErrorKind::Interrupted
+						|| retry < self.policy.max_retries)
+						&& (self.policy.is_transient)(&err) =>
+				{
+					// Immediately retry interruptions as `std::io` does without consuming retries.
+					let interrupted = err.kind() == io::ErrorKind::Interrupted;
+					let wait = if interrupted { Duration::ZERO } else { backoff };
+					if let Some(deadline) = self.policy.deadline {
+						if start.elapsed().saturating_add(wait) >= deadline {
+							return Err(io::Error::new(
+								io::ErrorKind::TimedOut,
+								format!("deadline exceeded after {retry} retries: {err}"),
+							));
+						}
+					}
+					if !interrupted {
+						thread::sleep(backoff);
+						backoff = backoff
+							.saturating_mul(self.policy.multiplier)
+							.min(self.policy.max_backoff);
+						retry += 1;
+					}
+					self.retries += 1;
+					restore = true;
+				}
+				result => return result,
+			}
+		}
+	}
+}
+
+impl<R: Read + Seek> Read for RetryReader<R> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		let read = self.retry(|reader| reader.read(buf))?;
+		self.position += read as u64;
+		Ok(read)
+	}
+}
+
+impl<R: Read + Seek> Seek for RetryReader<R> {
+	fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+		// Resolve relative positions once, so retries are idempotent.
+		let pos = match pos {
+			SeekFrom::Current(offset) => self
+				.position
+				.checked_add_signed(offset)
+				.map(SeekFrom::Start)
+				.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Invalid seek"))?,
+			pos => pos,
+		};
+		self.position = self.retry(|reader| reader.seek(pos))?;
+		Ok(self.position)
+	}
+}
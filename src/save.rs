@@ -0,0 +1,84 @@
+use super::{portable::long_path, BufSeekWriter, NpzWriter, WriteNpzError};
+use ndarray::{ArrayBase, Data, Dimension};
+use ndarray_npy::WritableElement;
+use std::{fs::File, path::Path};
+use zip::result::ZipError;
+
+/// Writes the named `arrays` to a new `.npz` file at `path` without compression in one call.
+///
+/// Mirrors [`numpy.savez`] by creating the file, adding each array via
+/// [`NpzWriter::add_arrays`], and finishing it, see [`NpzWriter::new`]. The file is buffered via
+/// [`BufSeekWriter`].
+///
+/// # Example
+///
+/// ```no_run
+/// use ndarray::{array, Array1};
+/// use ndarray_npz::write_npz;
+///
+/// let x = array![[1.0, 2.0], [3.0, 4.0]];
+/// let y = array![[5.0, 6.0], [7.0, 8.0]];
+/// write_npz("arrays.npz", [("x", &x), ("y", &y)])?;
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// [`numpy.savez`]: https://numpy.org/doc/stable/reference/generated/numpy.savez.html
+///
+/// # Errors
+///
+/// Fails with [`ZipError::Io`] if the file cannot be created, otherwise like
+/// [`NpzWriter::add_arrays`] and [`NpzWriter::finish`].
+pub fn write_npz<'a, P, I, N, S, D>(path: P, arrays: I) -> Result<(), WriteNpzError>
+where
+	P: AsRef<Path>,
+	I: IntoIterator<Item = (N, &'a ArrayBase<S, D>)>,
+	N: Into<String>,
+	S::Elem: WritableElement,
+	S: Data + 'a,
+	D: Dimension + 'a,
+{
+	save(path.as_ref(), arrays, NpzWriter::new)
+}
+
+/// Like [`write_npz`] but with compression. Mirrors [`numpy.savez_compressed`], see
+/// [`NpzWriter::new_compressed`].
+///
+/// [`numpy.savez_compressed`]: https://numpy.org/doc/stable/reference/generated/numpy.savez_compressed.html
+///
+/// # Errors
+///
+/// Fails like [`write_npz`].
+#[cfg(feature = "compressed")]
+pub fn write_npz_compressed<'a, P, I, N, S, D>(path: P, arrays: I) -> Result<(), WriteNpzError>
+where
+	P: AsRef<Path>,
+	I: IntoIterator<Item = (N, &'a ArrayBase<S, D>)>,
+	N: Into<String>,
+	S::Elem: WritableElement,
+	S: Data + 'a,
+	D: Dimension + 'a,
+{
+	save(path.as_ref(), arrays, NpzWriter::new_compressed)
+}
+
+/// Writes the `arrays` to a new file at `path` via the writer created by `new`.
+fn save<'a, I, N, S, D>(
+	path: &Path,
+	arrays: I,
+	new: fn(BufSeekWriter<File>) -> NpzWriter<BufSeekWriter<File>>,
+) -> Result<(), WriteNpzError>
+where
+	I: IntoIterator<Item = (N, &'a ArrayBase<S, D>)>,
+	N: Into<String>,
+	S::Elem: WritableElement,
+	S: Data + 'a,
+	D: Dimension + 'a,
+{
+	let file = File::create(long_path(path))
+		.and_then(BufSeekWriter::new)
+		.map_err(ZipError::from)?;
+	let mut npz = new(file);
+	npz.add_arrays(arrays)?;
+	npz.finish()?;
+	Ok(())
+}
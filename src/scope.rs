@@ -0,0 +1,81 @@
+use super::{NpyViewMut, NpzViewMut};
+use std::mem;
+
+impl<'a> NpzViewMut<'a> {
+	/// Hands out the mutable views of all `.npy` files within `f` and updates their checksums at
+	/// the end of the scope.
+	///
+	/// The views are passed as pairs of names and views in archive order, e.g., to be distributed
+	/// over the threads of [`std::thread::scope`] or a rayon scope. When `f` returns, the checksums
+	/// of all views whose [`status`](NpyViewMut::status) is
+	/// [`Outdated`](crate::ChecksumStatus::Outdated) are [updated](NpyViewMut::update). When `f`
+	/// panics, they are [poisoned](crate::ChecksumStatus::Poisoned) instead. Either way, the views
+	/// are returned to the `.npz` file view, so they can be viewed again via [`Self::by_name`] or
+	/// another scope. Views already moved out via [`Self::by_name`] or [`Self::by_index`] are
+	/// skipped.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use aligned_vec::AVec;
+	/// use ndarray::{Array1, Ix1};
+	/// use ndarray_npz::{NpzViewMut, NpzWriter};
+	/// use std::{io::Cursor, thread};
+	///
+	/// let mut buffer = Vec::new();
+	/// let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+	/// npz.add_array("x.npy", &Array1::<f64>::ones(3))?;
+	/// npz.add_array("y.npy", &Array1::<f64>::ones(5))?;
+	/// npz.finish()?;
+	/// let mut buffer = AVec::<u8>::from_slice(64, &buffer);
+	/// let mut npz = NpzViewMut::new(&mut buffer)?;
+	/// npz.scope(|views| {
+	/// 	thread::scope(|scope| {
+	/// 		for (_name, view) in views {
+	/// 			scope.spawn(move || {
+	/// 				let mut array = view.view_mut::<f64, Ix1>().unwrap();
+	/// 				array *= 2.0;
+	/// 			});
+	/// 		}
+	/// 	});
+	/// });
+	/// npz.by_name("x.npy")?.verify()?;
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	pub fn scope<R, F>(&mut self, f: F) -> R
+	where
+		F: for<'s> FnOnce(Vec<(&'s str, &'s mut NpyViewMut<'a>)>) -> R,
+	{
+		let mut names = self
+			.names
+			.iter()
+			.map(|(name, &index)| (index, name.clone()))
+			.collect::<Vec<_>>();
+		names.sort_unstable_by_key(|&(index, _)| index);
+		let files = names
+			.into_iter()
+			.filter_map(|(index, name)| Some((index, name, self.files.remove(&index)?)))
+			.collect();
+		let mut scope = Scope { npz: self, files };
+		f(scope
+			.files
+			.iter_mut()
+			.map(|(_index, name, file)| (name.as_str(), file))
+			.collect())
+	}
+}
+
+/// Returns the views of a scope to the `.npz` file view on drop, even when unwinding.
+struct Scope<'s, 'a> {
+	npz: &'s mut NpzViewMut<'a>,
+	files: Vec<(usize, String, NpyViewMut<'a>)>,
+}
+
+impl Drop for Scope<'_, '_> {
+	fn drop(&mut self) {
+		for (index, _name, mut file) in mem::take(&mut self.files) {
+			file.settle();
+			self.npz.files.insert(index, file);
+		}
+	}
+}
@@ -0,0 +1,90 @@
+use super::{NpzView, NpzWriter, ViewNpzError, WriteNpzError};
+use std::{io::Cursor, mem};
+use zip::ZipWriter;
+
+/// Alignment of the snapshot bytes for viewing 64-byte aligned `.npy` files.
+const ALIGNMENT: usize = 64;
+
+/// Finished copy of the entries written so far, see [`NpzWriter::snapshot`].
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+	buffer: Vec<u8>,
+	offset: usize,
+	len: usize,
+}
+
+impl Snapshot {
+	/// Copies `bytes` into a 64-byte aligned region.
+	pub(crate) fn new(bytes: &[u8]) -> Self {
+		let mut buffer = vec![0; bytes.len() + ALIGNMENT];
+		let offset = buffer.as_ptr().align_offset(ALIGNMENT);
+		buffer[offset..offset + bytes.len()].copy_from_slice(bytes);
+		Self {
+			buffer,
+			offset,
+			len: bytes.len(),
+		}
+	}
+
+	/// Returns the bytes of the `.npz` file, 64-byte aligned in memory.
+	#[must_use]
+	pub fn as_bytes(&self) -> &[u8] {
+		&self.buffer[self.offset..self.offset + self.len]
+	}
+
+	/// Returns the bytes of the `.npz` file mutably, 64-byte aligned in memory.
+	pub(crate) fn as_bytes_mut(&mut self) -> &mut [u8] {
+		&mut self.buffer[self.offset..self.offset + self.len]
+	}
+
+	/// Creates an immutable view of the `.npz` file.
+	///
+	/// # Errors
+	///
+	/// Fails like [`NpzView::new`].
+	pub fn view(&self) -> Result<NpzView<'_>, ViewNpzError> {
+		NpzView::new(self.as_bytes())
+	}
+}
+
+impl NpzWriter<Cursor<Vec<u8>>> {
+	/// Returns a finished copy of the entries written so far without finishing the writer.
+	///
+	/// The copy is a valid `.npz` file for verifying arrays as they are written or for handing
+	/// them over to consumers within the same process, e.g., via [`Snapshot::view`], without
+	/// finishing and reopening the archive. Arrays pending for [`Self::with_packing`] or
+	/// [`Self::with_chunking`] and members added on [`Self::finish`], e.g., the provenance or the
+	/// index, are not part of it. Writing resumes after the central directory of the snapshot,
+	/// which is left superseded within the archive, so frequent snapshots of large archives should
+	/// be followed by [`compact`](crate::compact).
+	///
+	/// # Example
+	///
+	/// ```
+	/// use ndarray::Array1;
+	/// use ndarray_npz::NpzWriter;
+	/// use std::io::Cursor;
+	///
+	/// let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	/// npz.add_array("x.npy", &Array1::<f64>::ones(3))?;
+	/// let snapshot = npz.snapshot()?;
+	/// let view = snapshot.view()?;
+	/// assert_eq!(view.by_name("x.npy")?.view::<f64, _>()?, Array1::<f64>::ones(3));
+	/// npz.add_array("y.npy", &Array1::<f64>::zeros(3))?;
+	/// assert_eq!(npz.snapshot()?.view()?.len(), 2);
+	/// npz.finish()?;
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Finishing or reopening the archive can fail with [`ZipError`](zip::result::ZipError), in
+	/// which case the entries written so far are lost.
+	pub fn snapshot(&mut self) -> Result<Snapshot, WriteNpzError> {
+		let zip = mem::replace(&mut self.zip, ZipWriter::new(Cursor::new(Vec::new())));
+		let bytes = zip.finish_into_readable()?.into_inner().into_inner();
+		let snapshot = Snapshot::new(&bytes);
+		self.zip = ZipWriter::new_append(Cursor::new(bytes))?;
+		Ok(snapshot)
+	}
+}
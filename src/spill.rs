@@ -0,0 +1,78 @@
+use super::{container::TempFile, header::MAGIC, NpzReader, ReadNpzError};
+use std::{
+	fs::File,
+	io::{self, Read, Seek, SeekFrom, Write},
+};
+use zip::result::ZipError;
+
+/// Handle of an `.npy` file spilled to an anonymous temporary file, see [`NpzReader::spill`].
+///
+/// The temporary file is deleted when the handle is dropped.
+#[derive(Debug)]
+pub struct SpilledNpy {
+	temp: TempFile,
+	len: u64,
+}
+
+impl SpilledNpy {
+	/// Returns the temporary file to be memory-mapped, e.g., read-only or copy-on-write.
+	///
+	/// The `.npy` file starts at offset zero, so its data is as aligned as by its header.
+	#[must_use]
+	pub fn file(&self) -> &File {
+		&self.temp.file
+	}
+	/// Length of the `.npy` file in bytes.
+	#[must_use]
+	#[allow(clippy::len_without_is_empty)]
+	pub fn len(&self) -> u64 {
+		self.len
+	}
+}
+
+impl<R: Read + Seek> NpzReader<R> {
+	/// Decompresses the `.npy` file `name` to an anonymous temporary file rather than into memory.
+	///
+	/// The file is streamed to the temporary file in constant memory and its checksum is verified
+	/// on the way. Memory-mapping the temporary file, see [`SpilledNpy::file`], provides a view of
+	/// the array backed by the page cache, e.g., for processing members larger than the available
+	/// memory. Like for [`NpzView`](crate::NpzView), bring your memory-mapping crate of choice.
+	///
+	/// # Example
+	///
+	/// ```no_run
+	/// use memmap2::Mmap;
+	/// use ndarray::{ArrayView2, Axis};
+	/// use ndarray_npy::ViewNpyExt;
+	/// use ndarray_npz::NpzReader;
+	/// use std::fs::File;
+	///
+	/// let mut npz = NpzReader::new(File::open("huge_compressed.npz")?)?;
+	/// let spilled = npz.spill("frames.npy")?;
+	/// let mmap = unsafe { Mmap::map(spilled.file())? };
+	/// let frames = ArrayView2::<f32>::view_npy(&mmap)?;
+	/// println!("{}", frames.mean_axis(Axis(0)).unwrap());
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Fails with [`ZipError::InvalidArchive`] if the file is not an `.npy` file. Reading the file
+	/// or writing the temporary file can fail with [`ZipError`].
+	pub fn spill(&mut self, name: &str) -> Result<SpilledNpy, ReadNpzError> {
+		let mut file = self.zip.by_name(name)?;
+		let mut magic = Vec::with_capacity(MAGIC.len());
+		(&mut file)
+			.take(MAGIC.len() as u64)
+			.read_to_end(&mut magic)
+			.map_err(ZipError::from)?;
+		if magic != MAGIC {
+			return Err(ZipError::InvalidArchive("Not an npy file").into());
+		}
+		let mut temp = TempFile::new().map_err(ZipError::from)?;
+		temp.file.write_all(&magic).map_err(ZipError::from)?;
+		let len = io::copy(&mut file, &mut temp.file).map_err(ZipError::from)? + magic.len() as u64;
+		temp.file.seek(SeekFrom::Start(0)).map_err(ZipError::from)?;
+		Ok(SpilledNpy { temp, len })
+	}
+}
@@ -0,0 +1,69 @@
+use super::{
+	convert::{swap, Dtype},
+	header::NpyHeader,
+	NpyViewMut, ViewNpzError,
+};
+use ndarray::{ArrayViewMut, Dimension};
+use ndarray_npy::{ViewMutElement, ViewNpyError};
+use zip::result::ZipError;
+
+impl NpyViewMut<'_> {
+	/// Passes a mutable native-endian view of a memory-mapped `.npy` file of foreign byte order
+	/// to `f` and returns its result.
+	///
+	/// Unlike [`Self::view_mut`], which fails with [`ViewNpyError::NonNativeEndian`], this swaps
+	/// the bytes of every element to native byte order in place, invokes `f`, and swaps them back,
+	/// so the `.npy` file keeps its byte order. This is **not** zero-cost as the whole array is
+	/// read and written twice. Files of native byte order are passed without swapping. The element
+	/// type must be one of [`Dtype`]. If `f` panics, the `.npy` file is left in
+	/// native byte order.
+	///
+	/// Changes checksum [`status`](`Self::status()`) to
+	/// [`Outdated`](`crate::ChecksumStatus::Outdated`).
+	///
+	/// # Example
+	///
+	/// ```no_run
+	/// use memmap2::MmapOptions;
+	/// use ndarray::Ix1;
+	/// use ndarray_npz::NpzViewMut;
+	/// use std::fs::OpenOptions;
+	///
+	/// // Archive of `.npy` files in big endian.
+	/// let file = OpenOptions::new().read(true).write(true).open("big_endian.npz")?;
+	/// let mut mmap = unsafe { MmapOptions::new().map_mut(&file)? };
+	/// let mut npz = NpzViewMut::new(&mut mmap)?;
+	/// let mut npy = npz.by_name("x.npy")?;
+	/// npy.with_native_endian::<f64, Ix1, _, _>(|mut view| view.mapv_inplace(|x| x * 2.0))?;
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Fails with [`ViewNpyError::WrongDescriptor`] if the element type is not one of
+	/// [`Dtype`], with [`ZipError::InvalidArchive`] if the `.npy` header is invalid,
+	/// or with [`ViewNpyError`] if viewing the swapped `.npy` file fails.
+	pub fn with_native_endian<A, D, F, T>(&mut self, f: F) -> Result<T, ViewNpzError>
+	where
+		A: ViewMutElement,
+		D: Dimension,
+		F: FnOnce(ArrayViewMut<'_, A, D>) -> T,
+	{
+		let invalid = || ZipError::InvalidArchive("Invalid npy header");
+		let mut header = NpyHeader::parse(self.data).ok_or_else(invalid)?;
+		let (dtype, big_endian) = Dtype::parse(&header.descr)
+			.ok_or_else(|| ViewNpyError::WrongDescriptor(header.descr.clone()))?;
+		if dtype.size() == 1 || big_endian == cfg!(target_endian = "big") {
+			return Ok(f(self.view_mut()?));
+		}
+		let original = self.data.get(..header.len).ok_or_else(invalid)?.to_vec();
+		header.descr = dtype.descr(!big_endian);
+		let bytes = header.to_bytes_with_len(header.len).ok_or_else(invalid)?;
+		self.data[..header.len].copy_from_slice(&bytes);
+		swap(&mut self.data[header.len..], dtype.size());
+		let result = self.view_mut().map(f);
+		swap(&mut self.data[header.len..], dtype.size());
+		self.data[..header.len].copy_from_slice(&original);
+		result
+	}
+}
@@ -0,0 +1,212 @@
+use super::{portable::long_path, BufSeekWriter, NpzWriter, WriteNpzError};
+use std::{
+	ffi::OsString,
+	fs::{self, File, OpenOptions},
+	io::{self, BufWriter, Read, Seek, SeekFrom, Write},
+	path::{Path, PathBuf},
+	process,
+};
+use zip::result::ZipError;
+
+/// Writers whose written data can be synchronized with the storage device.
+pub trait SyncData {
+	/// Flushes and synchronizes the written data with the storage device.
+	///
+	/// # Errors
+	///
+	/// Fails with [`io::Error`] if flushing or synchronizing fails.
+	fn sync_data(&mut self) -> io::Result<()>;
+	/// Makes the synchronized data durable at its final location, does nothing by default.
+	///
+	/// # Errors
+	///
+	/// Fails with [`io::Error`] if persisting fails.
+	fn persist(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+impl SyncData for File {
+	fn sync_data(&mut self) -> io::Result<()> {
+		File::sync_data(self)
+	}
+}
+
+impl<W: SyncData + Write> SyncData for BufWriter<W> {
+	fn sync_data(&mut self) -> io::Result<()> {
+		self.flush()?;
+		self.get_mut().sync_data()
+	}
+	fn persist(&mut self) -> io::Result<()> {
+		self.get_mut().persist()
+	}
+}
+
+impl<W: SyncData + Write + Seek> SyncData for BufSeekWriter<W> {
+	fn sync_data(&mut self) -> io::Result<()> {
+		self.flush()?;
+		self.get_mut().sync_data()
+	}
+	fn persist(&mut self) -> io::Result<()> {
+		self.get_mut().persist()
+	}
+}
+
+#[cfg(feature = "lock")]
+impl SyncData for crate::LockedFile {
+	fn sync_data(&mut self) -> io::Result<()> {
+		self.file().sync_data()
+	}
+}
+
+/// File written under a temporary name and atomically renamed to its path once persisted.
+///
+/// Readers of the path either see the previous file or the complete new one, never a partially
+/// written one. The temporary file is next to the path, hence on the same file system, and named
+/// after the path with the process ID and `.tmp` appended. It is removed if dropped before being
+/// [persisted](SyncData::persist), e.g., if writing a checkpoint fails, so the previous file is
+/// kept untouched.
+///
+/// Implements [`Read`], [`Write`], and [`Seek`] by forwarding to the temporary [`File`], so it
+/// can be passed to [`NpzWriter`], see [`NpzWriter::create_atomic`].
+#[derive(Debug)]
+pub struct AtomicFile {
+	file: File,
+	path: PathBuf,
+	temp: Option<PathBuf>,
+}
+
+impl AtomicFile {
+	/// Creates the temporary file of `path` for reading and writing.
+	///
+	/// # Errors
+	///
+	/// Fails with [`io::Error`] if the temporary file cannot be created.
+	pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+		let path = long_path(path.as_ref()).into_owned();
+		let mut temp: OsString = path.as_os_str().to_owned();
+		temp.push(format!(".{}.tmp", process::id()));
+		let temp = PathBuf::from(temp);
+		let file = OpenOptions::new()
+			.read(true)
+			.write(true)
+			.create(true)
+			.truncate(true)
+			.open(&temp)?;
+		Ok(Self {
+			file,
+			path,
+			temp: Some(temp),
+		})
+	}
+	/// Returns the final path.
+	#[must_use]
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+}
+
+impl Read for AtomicFile {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		self.file.read(buf)
+	}
+}
+
+impl Write for AtomicFile {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.file.write(buf)
+	}
+	fn flush(&mut self) -> io::Result<()> {
+		self.file.flush()
+	}
+}
+
+impl Seek for AtomicFile {
+	fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+		self.file.seek(pos)
+	}
+}
+
+impl SyncData for AtomicFile {
+	fn sync_data(&mut self) -> io::Result<()> {
+		self.file.sync_data()
+	}
+	/// Renames the temporary file to the final path and synchronizes the parent directory, so the
+	/// rename itself survives a crash.
+	fn persist(&mut self) -> io::Result<()> {
+		let Some(temp) = &self.temp else {
+			return Ok(());
+		};
+		fs::rename(temp, &self.path)?;
+		self.temp = None;
+		sync_parent(&self.path)
+	}
+}
+
+impl Drop for AtomicFile {
+	fn drop(&mut self) {
+		if let Some(temp) = &self.temp {
+			let _ = fs::remove_file(temp);
+		}
+	}
+}
+
+/// Synchronizes the parent directory of `path` on Unix, where directory entries are only durable
+/// once their directory has been synchronized.
+#[cfg(unix)]
+fn sync_parent(path: &Path) -> io::Result<()> {
+	let parent = match path.parent() {
+		Some(parent) if !parent.as_os_str().is_empty() => parent,
+		_ => Path::new("."),
+	};
+	File::open(parent)?.sync_all()
+}
+
+/// Directories cannot be opened as files on other platforms than Unix.
+#[cfg(not(unix))]
+fn sync_parent(_path: &Path) -> io::Result<()> {
+	Ok(())
+}
+
+impl NpzWriter<AtomicFile> {
+	/// Creates a new `.npz` file without compression which atomically replaces the file at `path`
+	/// once finished via [`Self::finish_and_sync`]. See [`AtomicFile`] and [`NpzWriter::new`].
+	///
+	/// # Errors
+	///
+	/// Fails with [`io::Error`] if the temporary file cannot be created.
+	pub fn create_atomic<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+		Ok(Self::new(AtomicFile::create(path)?))
+	}
+}
+
+impl<W: Write + Seek + SyncData> NpzWriter<W> {
+	/// Like [`Self::finish`] but [synchronizes](SyncData::sync_data) the written data with the
+	/// storage device and [persists](SyncData::persist) it before returning the writer.
+	///
+	/// Once returned, the `.npz` file survives a crash of the process or the operating system,
+	/// e.g., before a training loop proceeds after writing a checkpoint. For an [`AtomicFile`],
+	/// the temporary file is renamed to its final path and the parent directory is synchronized.
+	///
+	/// # Example
+	///
+	/// ```no_run
+	/// use ndarray::Array2;
+	/// use ndarray_npz::NpzWriter;
+	///
+	/// let mut npz = NpzWriter::create_atomic("checkpoint.npz")?;
+	/// npz.add_array("weights.npy", &Array2::<f32>::zeros((64, 64)))?;
+	/// npz.finish_and_sync()?;
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Fails like [`Self::finish`] or with [`ZipError::Io`] if synchronizing or persisting fails.
+	pub fn finish_and_sync(self) -> Result<W, WriteNpzError> {
+		let mut writer = self.finish()?;
+		writer.sync_data().map_err(ZipError::from)?;
+		writer.persist().map_err(ZipError::from)?;
+		Ok(writer)
+	}
+}
@@ -0,0 +1,90 @@
+use super::{NpzReader, ReadNpzError};
+use ndarray_npy::ReadNpyError;
+use std::{
+	io::{self, Read, Seek},
+	time::{Duration, Instant},
+};
+use zip::result::ZipError;
+
+impl<R: Read + Seek> NpzReader<R> {
+	/// Bounds the time of reading a single array to `timeout`.
+	///
+	/// The deadline is checked before each read of the underlying reader, so reading an array
+	/// stalling on a slow link, e.g., of a range-reading reader or a [`RetryReader`] backing off,
+	/// fails with [`ReadNpzError::TimedOut`] instead of blocking indefinitely. A single blocking
+	/// read cannot be interrupted and should be bounded by the underlying reader itself, e.g., via
+	/// a socket timeout. Chunked arrays are bounded as a whole, whereas arrays packed via
+	/// [`NpzWriter::with_packing`] are read on construction.
+	///
+	/// [`RetryReader`]: crate::RetryReader
+	/// [`NpzWriter::with_packing`]: crate::NpzWriter::with_packing
+	///
+	/// # Example
+	///
+	/// ```no_run
+	/// use ndarray::Array2;
+	/// use ndarray_npz::{NpzReader, ReadNpzError};
+	/// use std::{fs::File, time::Duration};
+	///
+	/// let npz = NpzReader::new(File::open("/mnt/remote/arrays.npz")?)?;
+	/// let mut npz = npz.with_entry_timeout(Duration::from_secs(5));
+	/// let huge: Result<Array2<f64>, _> = npz.by_name("huge");
+	/// match huge {
+	/// 	Ok(huge) => println!("{huge}"),
+	/// 	Err(ReadNpzError::TimedOut { name }) => eprintln!("{name} is not available yet"),
+	/// 	Err(err) => return Err(err.into()),
+	/// }
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	#[must_use]
+	pub fn with_entry_timeout(mut self, timeout: Duration) -> Self {
+		self.timeout = Some(timeout);
+		self
+	}
+
+	/// Returns the deadline of reading an array starting now if a timeout is set.
+	pub(crate) fn deadline(&self) -> Option<Instant> {
+		self.timeout
+			.and_then(|timeout| Instant::now().checked_add(timeout))
+	}
+}
+
+/// Reader failing with [`io::ErrorKind::TimedOut`] once its deadline has passed.
+pub(crate) struct Deadline<R: Read> {
+	inner: R,
+	deadline: Option<Instant>,
+}
+
+impl<R: Read> Deadline<R> {
+	/// Wraps `inner` bounded by `deadline` if any.
+	pub(crate) fn new(inner: R, deadline: Option<Instant>) -> Self {
+		Self { inner, deadline }
+	}
+}
+
+impl<R: Read> Read for Deadline<R> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		if self
+			.deadline
+			.is_some_and(|deadline| Instant::now() >= deadline)
+		{
+			return Err(io::Error::new(io::ErrorKind::TimedOut, "deadline exceeded"));
+		}
+		self.inner.read(buf)
+	}
+}
+
+/// Surfaces an I/O timeout while reading the file `name` as [`ReadNpzError::TimedOut`].
+pub(crate) fn timed_out(err: ReadNpzError, name: &str) -> ReadNpzError {
+	let (ReadNpzError::Zip(ZipError::Io(io)) | ReadNpzError::Npy(ReadNpyError::Io(io))) = &err
+	else {
+		return err;
+	};
+	if io.kind() == io::ErrorKind::TimedOut {
+		ReadNpzError::TimedOut {
+			name: name.to_owned(),
+		}
+	} else {
+		err
+	}
+}
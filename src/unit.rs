@@ -0,0 +1,215 @@
+use super::{ErrorCategory, ErrorCode, NpzReader, NpzWriter, ReadNpzError, WriteNpzError};
+use ndarray::{prelude::*, Data, DataOwned};
+use ndarray_npy::{ReadableElement, WritableElement};
+use std::{
+	error::Error,
+	fmt,
+	io::{Read, Seek, Write},
+};
+use zip::result::ZipError;
+
+/// Physical unit checked by [`NpzReader::read_with_unit`].
+///
+/// # Example
+///
+/// ```
+/// use ndarray_npz::Unit;
+///
+/// /// Speed in kilometres per hour.
+/// struct KilometrePerHour;
+///
+/// impl Unit for KilometrePerHour {
+/// 	const SYMBOL: &'static str = "km/h";
+/// }
+/// ```
+pub trait Unit {
+	/// Symbol of the unit as stored in the archive, e.g., `m/s`.
+	const SYMBOL: &'static str;
+}
+
+macro_rules! si_base_units {
+	($($(#[$attr:meta])* $unit:ident => $symbol:literal,)*) => {$(
+		$(#[$attr])*
+		#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+		pub struct $unit;
+
+		impl Unit for $unit {
+			const SYMBOL: &'static str = $symbol;
+		}
+	)*};
+}
+
+si_base_units! {
+	/// SI base unit of length `m`.
+	Metre => "m",
+	/// SI base unit of mass `kg`.
+	Kilogram => "kg",
+	/// SI base unit of time `s`.
+	Second => "s",
+	/// SI base unit of electric current `A`.
+	Ampere => "A",
+	/// SI base unit of thermodynamic temperature `K`.
+	Kelvin => "K",
+	/// SI base unit of amount of substance `mol`.
+	Mole => "mol",
+	/// SI base unit of luminous intensity `cd`.
+	Candela => "cd",
+}
+
+/// An error reading an array with a unit.
+#[derive(Debug)]
+pub enum ReadUnitError {
+	/// An error reading the array or its unit.
+	Npz(ReadNpzError),
+	/// The array has no unit.
+	MissingUnit,
+	/// The unit of the array differs from the expected one.
+	UnitMismatch {
+		/// Symbol of the expected unit.
+		expected: &'static str,
+		/// Symbol of the stored unit.
+		found: String,
+	},
+}
+
+impl Error for ReadUnitError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			ReadUnitError::Npz(err) => Some(err),
+			ReadUnitError::MissingUnit | ReadUnitError::UnitMismatch { .. } => None,
+		}
+	}
+}
+
+impl fmt::Display for ReadUnitError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			ReadUnitError::Npz(err) => write!(f, "{err}"),
+			ReadUnitError::MissingUnit => write!(f, "missing unit"),
+			ReadUnitError::UnitMismatch { expected, found } => {
+				write!(f, "expected unit `{expected}` but found `{found}`")
+			}
+		}
+	}
+}
+
+impl From<ReadNpzError> for ReadUnitError {
+	fn from(err: ReadNpzError) -> ReadUnitError {
+		ReadUnitError::Npz(err)
+	}
+}
+
+impl ReadUnitError {
+	/// Returns the stable code of the error, see [`ErrorCode`].
+	#[must_use]
+	pub fn code(&self) -> ErrorCode {
+		match self {
+			ReadUnitError::Npz(err) => err.code(),
+			ReadUnitError::MissingUnit => ErrorCode::MissingUnit,
+			ReadUnitError::UnitMismatch { .. } => ErrorCode::UnitMismatch,
+		}
+	}
+
+	/// Returns the stable category of the error, see [`ErrorCategory`].
+	#[must_use]
+	pub fn category(&self) -> ErrorCategory {
+		self.code().category()
+	}
+}
+
+/// Returns the name of the member storing the unit of the member `name`.
+fn unit_name(name: &str) -> String {
+	let stem = name.strip_suffix(".npy").unwrap_or(name);
+	format!("{stem}.unit.txt")
+}
+
+impl<W: Write + Seek> NpzWriter<W> {
+	/// Adds the `unit` of the array `name`.
+	///
+	/// The unit is stored as UTF-8 text in the member `x.unit.txt` next to the array `x.npy`.
+	///
+	/// # Errors
+	///
+	/// Adding the unit can fail with [`ZipError`].
+	pub fn add_unit(&mut self, name: &str, unit: &str) -> Result<(), WriteNpzError> {
+		self.zip.start_file(unit_name(name), self.options)?;
+		self.zip
+			.write_all(unit.as_bytes())
+			.map_err(ZipError::from)?;
+		Ok(())
+	}
+
+	/// Adds an array with the specified `name` and `unit`, see [`Self::add_unit`].
+	///
+	/// # Errors
+	///
+	/// Adding an array can fail with [`WriteNpyError`](ndarray_npy::WriteNpyError) or
+	/// [`ZipError`].
+	pub fn add_array_with_unit<S, D>(
+		&mut self,
+		name: &str,
+		array: &ArrayBase<S, D>,
+		unit: &str,
+	) -> Result<(), WriteNpzError>
+	where
+		S::Elem: WritableElement,
+		S: Data,
+		D: Dimension,
+	{
+		self.add_array(name, array)?;
+		self.add_unit(name, unit)
+	}
+}
+
+impl<R: Read + Seek> NpzReader<R> {
+	/// Reads the unit of the array `name` if any.
+	///
+	/// # Errors
+	///
+	/// Reading the unit can fail with [`ZipError`].
+	pub fn unit(&mut self, name: &str) -> Result<Option<String>, ReadNpzError> {
+		let mut file = match self.zip.by_name(&unit_name(name)) {
+			Err(ZipError::FileNotFound) => return Ok(None),
+			Err(err) => return Err(err.into()),
+			Ok(file) => file,
+		};
+		let mut unit = String::new();
+		file.read_to_string(&mut unit).map_err(ZipError::from)?;
+		Ok(Some(unit))
+	}
+
+	/// Reads an array by name after checking that its unit is `U`.
+	///
+	/// # Example
+	///
+	/// ```no_run
+	/// use ndarray_npz::{ndarray::Array1, Metre, NpzReader};
+	/// use std::fs::File;
+	///
+	/// let mut npz = NpzReader::new(File::open("arrays.npz")?)?;
+	/// let x: Array1<f64> = npz.read_with_unit::<Metre, _, _>("x.npy")?;
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Fails with [`ReadUnitError::MissingUnit`] or [`ReadUnitError::UnitMismatch`] if the array
+	/// has no or another unit. Reading an array from an archive can fail with
+	/// [`ReadNpyError`](ndarray_npy::ReadNpyError) or [`ZipError`].
+	pub fn read_with_unit<U, S, D>(&mut self, name: &str) -> Result<ArrayBase<S, D>, ReadUnitError>
+	where
+		U: Unit,
+		S::Elem: ReadableElement,
+		S: DataOwned,
+		D: Dimension,
+	{
+		match self.unit(name)? {
+			None => Err(ReadUnitError::MissingUnit),
+			Some(unit) if unit != U::SYMBOL => Err(ReadUnitError::UnitMismatch {
+				expected: U::SYMBOL,
+				found: unit,
+			}),
+			Some(_) => Ok(self.by_name(name)?),
+		}
+	}
+}
@@ -0,0 +1,274 @@
+use super::{ChecksumStatus, NpzReader, NpzView, ReadNpzError, ViewNpzError};
+use std::{
+	convert::Infallible,
+	io::{self, Read, Seek, Write},
+};
+use zip::result::ZipError;
+
+/// Sample of entries to verify via [`NpzView::verify_sampled`] or [`NpzReader::verify_sampled`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sample {
+	/// Verifies the given fraction in `0.0..=1.0` of the entries rounded up.
+	Fraction(f64),
+	/// Verifies entries as long as their total uncompressed size stays within the given budget in
+	/// bytes.
+	Bytes(u64),
+}
+
+/// Report of a sampled CRC-32 verification.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SampleReport {
+	/// Names of the sampled entries with correct checksums.
+	pub verified: Vec<String>,
+	/// Names of the sampled entries with invalid checksums.
+	pub failed: Vec<String>,
+	/// Total number of entries eligible for verification.
+	pub total_entries: usize,
+	/// Total uncompressed size in bytes of the sampled entries.
+	pub sampled_bytes: u64,
+	/// Total uncompressed size in bytes of the entries eligible for verification.
+	pub total_bytes: u64,
+}
+
+impl SampleReport {
+	/// Returns `true` iff all sampled entries have correct checksums.
+	#[must_use]
+	pub fn is_ok(&self) -> bool {
+		self.failed.is_empty()
+	}
+	/// Returns the number of sampled entries.
+	#[must_use]
+	pub fn sampled_entries(&self) -> usize {
+		self.verified.len() + self.failed.len()
+	}
+	/// Returns the coverage as fraction of sampled bytes in `0.0..=1.0`.
+	///
+	/// Returns `1.0` if there are no bytes to verify.
+	#[must_use]
+	#[allow(clippy::cast_precision_loss)]
+	pub fn coverage(&self) -> f64 {
+		if self.total_bytes == 0 {
+			1.0
+		} else {
+			self.sampled_bytes as f64 / self.total_bytes as f64
+		}
+	}
+}
+
+/// Report of the CRC-32 checksums of all viewable entries, see [`NpzView::checksum_report`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChecksumReport {
+	/// Sorted names of the entries with correct checksums.
+	pub correct: Vec<String>,
+	/// Sorted names of the entries with invalid checksums.
+	pub invalid: Vec<String>,
+	/// Sorted names of the entries with [poisoned](ChecksumStatus::Poisoned) checksums, which are
+	/// not verified.
+	pub poisoned: Vec<String>,
+}
+
+impl ChecksumReport {
+	/// Returns `true` iff all entries have correct checksums.
+	#[must_use]
+	pub fn is_ok(&self) -> bool {
+		self.invalid.is_empty() && self.poisoned.is_empty()
+	}
+	/// Returns the checksum status of the entry `name` as after [verifying](crate::NpyView::verify)
+	/// it, `None` if the entry is not viewable.
+	///
+	/// Invalid checksums are [`Outdated`](ChecksumStatus::Outdated).
+	#[must_use]
+	pub fn status(&self, name: &str) -> Option<ChecksumStatus> {
+		let contains = |names: &[String]| names.binary_search_by(|n| n.as_str().cmp(name)).is_ok();
+		if contains(&self.correct) {
+			Some(ChecksumStatus::Correct)
+		} else if contains(&self.invalid) {
+			Some(ChecksumStatus::Outdated)
+		} else if contains(&self.poisoned) {
+			Some(ChecksumStatus::Poisoned)
+		} else {
+			None
+		}
+	}
+}
+
+impl NpzView<'_> {
+	/// Returns the report of verifying the CRC-32 checksums of all viewable entries.
+	///
+	/// The checksums are verified on the first call only and the report is cached within the
+	/// view, so read-only integrity checks neither need [`NpyView`](crate::NpyView)s nor verify
+	/// the entries repeatedly, even when the view is shared across threads.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use aligned_vec::AVec;
+	/// use ndarray::Array1;
+	/// use ndarray_npz::{ChecksumStatus, NpzView, NpzWriter};
+	/// use std::io::Cursor;
+	///
+	/// let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	/// npz.add_array("x", &Array1::<f64>::zeros(3))?;
+	/// let buffer = AVec::<u8>::from_slice(64, &npz.finish()?.into_inner());
+	/// let npz = NpzView::new(&buffer)?;
+	/// let report = npz.checksum_report();
+	/// assert!(report.is_ok());
+	/// assert_eq!(report.status("x"), Some(ChecksumStatus::Correct));
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	#[must_use]
+	pub fn checksum_report(&self) -> &ChecksumReport {
+		self.checksums.get_or_init(|| {
+			let mut names = self.names.iter().collect::<Vec<_>>();
+			names.sort_unstable();
+			let mut report = ChecksumReport::default();
+			for (name, index) in names {
+				let Some(mut view) = self.files.get(index).copied() else {
+					continue;
+				};
+				match view.verify() {
+					Ok(_crc32) => report.correct.push(name.clone()),
+					Err(ViewNpzError::Poisoned) => report.poisoned.push(name.clone()),
+					Err(_) => report.invalid.push(name.clone()),
+				}
+			}
+			report
+		})
+	}
+
+	/// Verifies the CRC-32 checksums of a random sample of viewable entries.
+	///
+	/// The sample is drawn reproducibly from `seed`. As checksums cover whole entries, the sample
+	/// consists of whole entries. This is a cheaper integrity screening than verifying every entry
+	/// of enormous archives, see [`SampleReport::coverage`].
+	#[must_use]
+	pub fn verify_sampled(&self, sample: Sample, seed: u64) -> SampleReport {
+		let entries = self
+			.names
+			.iter()
+			.filter_map(|(name, &index)| {
+				let view = self.files.get(&index)?;
+				Some((name.clone(), view.data.len() as u64))
+			})
+			.collect();
+		// Viewable entries can only fail with an invalid checksum.
+		let report = sample_entries(entries, sample, seed, |name| {
+			Ok::<_, Infallible>(
+				self.by_name(name)
+					.and_then(|mut view| view.verify())
+					.is_ok(),
+			)
+		});
+		match report {
+			Ok(report) => report,
+			Err(never) => match never {},
+		}
+	}
+}
+
+impl<R: Read + Seek> NpzReader<R> {
+	/// Verifies the CRC-32 checksums of a random sample of entries by reading them.
+	///
+	/// Directories and encrypted entries are not eligible. The sample is drawn reproducibly from
+	/// `seed`. As checksums cover whole entries, the sample consists of whole entries.
+	///
+	/// # Errors
+	///
+	/// Reading an entry can fail with [`ZipError`] other than an invalid checksum.
+	pub fn verify_sampled(
+		&mut self,
+		sample: Sample,
+		seed: u64,
+	) -> Result<SampleReport, ReadNpzError> {
+		let mut entries = Vec::new();
+		for index in 0..self.zip.len() {
+			let file = self.zip.by_index_raw(index)?;
+			if !file.is_dir() && !file.encrypted() {
+				entries.push((file.name().to_string(), file.size()));
+			}
+		}
+		sample_entries(entries, sample, seed, |name| {
+			let mut file = self.zip.by_name(name)?;
+			let (crc32, size) = (file.crc32(), file.size());
+			let mut sink = Crc32Sink::default();
+			match io::copy(&mut file, &mut sink) {
+				Ok(_) => Ok(sink.hasher.finalize() == crc32),
+				// Reading fails at the end of an entry with an invalid checksum.
+				Err(_) if sink.len == size && sink.hasher.finalize() != crc32 => Ok(false),
+				Err(err) => Err(ZipError::from(err).into()),
+			}
+		})
+	}
+}
+
+/// Writer discarding the written bytes while computing their CRC-32 checksum.
+#[derive(Default)]
+struct Crc32Sink {
+	hasher: crc32fast::Hasher,
+	len: u64,
+}
+
+impl Write for Crc32Sink {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.hasher.update(buf);
+		self.len += buf.len() as u64;
+		Ok(buf.len())
+	}
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+fn sample_entries<E>(
+	mut entries: Vec<(String, u64)>,
+	sample: Sample,
+	seed: u64,
+	mut verify: impl FnMut(&str) -> Result<bool, E>,
+) -> Result<SampleReport, E> {
+	// Sort for reproducibility before shuffling.
+	entries.sort();
+	let mut report = SampleReport {
+		total_entries: entries.len(),
+		total_bytes: entries.iter().map(|(_, size)| size).sum(),
+		..SampleReport::default()
+	};
+	let mut state = seed;
+	for index in (1..entries.len()).rev() {
+		#[allow(clippy::cast_possible_truncation)]
+		let other = (splitmix64(&mut state) % (index as u64 + 1)) as usize;
+		entries.swap(index, other);
+	}
+	#[allow(
+		clippy::cast_precision_loss,
+		clippy::cast_possible_truncation,
+		clippy::cast_sign_loss
+	)]
+	let max_entries = match sample {
+		Sample::Fraction(fraction) => {
+			(fraction.clamp(0.0, 1.0) * entries.len() as f64).ceil() as usize
+		}
+		Sample::Bytes(_) => entries.len(),
+	};
+	for (name, size) in entries.into_iter().take(max_entries) {
+		if let Sample::Bytes(budget) = sample {
+			if report.sampled_bytes + size > budget {
+				continue;
+			}
+		}
+		report.sampled_bytes += size;
+		if verify(&name)? {
+			report.verified.push(name);
+		} else {
+			report.failed.push(name);
+		}
+	}
+	Ok(report)
+}
+
+fn splitmix64(state: &mut u64) -> u64 {
+	*state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+	let mut z = *state;
+	z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+	z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+	z ^ (z >> 31)
+}
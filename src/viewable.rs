@@ -0,0 +1,51 @@
+/// Type descriptors of element types viewable in native byte order.
+#[cfg(target_endian = "little")]
+const NATIVE: [&str; 13] = [
+	"|b1", "|i1", "|u1", "<i2", "<u2", "<i4", "<u4", "<i8", "<u8", "<f4", "<f8", "<c8", "<c16",
+];
+/// Type descriptors of element types viewable in native byte order.
+#[cfg(target_endian = "big")]
+const NATIVE: [&str; 13] = [
+	"|b1", "|i1", "|u1", ">i2", ">u2", ">i4", ">u4", ">i8", ">u8", ">f4", ">f8", ">c8", ">c16",
+];
+
+/// Returns the type descriptors of the `.npy` files viewable on this platform.
+///
+/// Files of these element types can be viewed in place via [`NpyView::view`] and
+/// [`NpyViewMut::view_mut`], e.g., memory-mapped, whereas files of other element types, e.g.,
+/// strings, objects, or multi-byte types in foreign byte order, must be read via
+/// [`NpzReader::by_name`]. The descriptors are in NumPy's canonical form, e.g., `<f8` or `|b1`,
+/// and in the native byte order of the platform. Complex types `c8` and `c16` are listed with the
+/// `num-complex-0_4` feature only. Files in foreign byte order can be viewed mutably via
+/// [`NpyViewMut::with_native_endian`] with the `swap-endian` feature.
+///
+/// # Example
+///
+/// ```
+/// use ndarray::Array1;
+/// use ndarray_npz::{supported_view_dtypes, NpzReader, NpzWriter};
+/// use std::io::Cursor;
+///
+/// let mut npz = NpzWriter::new(Cursor::new(Vec::new())).with_index();
+/// npz.add_array("x.npy", &Array1::<f64>::zeros(3))?;
+/// let mut npz = NpzReader::new(npz.finish()?)?;
+/// for entry in npz.index()?.unwrap() {
+/// 	let npy = entry.npy.unwrap();
+/// 	let descr = npy.descr.trim_matches('\'');
+/// 	assert!(supported_view_dtypes().contains(&descr));
+/// }
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// [`NpyView::view`]: crate::NpyView::view
+/// [`NpyViewMut::view_mut`]: crate::NpyViewMut::view_mut
+/// [`NpzReader::by_name`]: crate::NpzReader::by_name
+/// [`NpyViewMut::with_native_endian`]: crate::NpyViewMut::with_native_endian
+#[must_use]
+pub fn supported_view_dtypes() -> &'static [&'static str] {
+	if cfg!(feature = "num-complex-0_4") {
+		&NATIVE
+	} else {
+		&NATIVE[..NATIVE.len() - 2]
+	}
+}
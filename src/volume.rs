@@ -0,0 +1,371 @@
+use super::{
+	capability::{
+		u16_at, u32_at, u64_at, CENTRAL_SIGNATURE, EOCD64_LOCATOR_SIGNATURE, EOCD64_SIGNATURE,
+		EOCD_SIGNATURE,
+	},
+	portable::long_path,
+	NpzReader, ReadNpzError,
+};
+use std::{
+	fs::File,
+	io::{self, Read, Seek, SeekFrom},
+	path::Path,
+};
+use zip::result::ZipError;
+
+/// Reader of a multi-volume zip archive presenting its volumes as one single-disk archive.
+///
+/// Split archives, e.g., `arrays.z01`, `arrays.z02`, and `arrays.zip`, store the offsets of their
+/// files relative to the volume they start in, whereas [`NpzReader`] reads single-disk archives
+/// only. This reader concatenates the volumes on the fly and replaces the central directory with
+/// an equivalent one of absolute offsets held in memory, so the volumes are neither joined on
+/// disk nor read as a whole.
+///
+/// Plain chunks of an archive, e.g., as split by the `split` utility, are supported as well.
+///
+/// Since the volumes are not contiguous in memory, [`NpzView`](crate::NpzView) and
+/// [`NpzViewMut`](crate::NpzViewMut) are not supported.
+#[derive(Debug)]
+pub struct VolumeReader<R: Read + Seek> {
+	volumes: Vec<R>,
+	/// Offset of each volume within the concatenation.
+	starts: Vec<u64>,
+	/// Length of the concatenation.
+	len: u64,
+	/// Replacing central directory and end of central directory records.
+	tail: Vec<u8>,
+	pos: u64,
+}
+
+impl VolumeReader<File> {
+	/// Opens the volumes at `paths` in order, i.e., with the `.zip` volume last.
+	///
+	/// # Errors
+	///
+	/// Opening the volumes can fail with [`ZipError::Io`], see [`Self::new`] otherwise.
+	pub fn open<P: AsRef<Path>>(paths: &[P]) -> Result<Self, ZipError> {
+		let volumes = paths
+			.iter()
+			.map(|path| File::open(long_path(path.as_ref())))
+			.collect::<io::Result<Vec<File>>>()?;
+		Self::new(volumes)
+	}
+}
+
+impl<R: Read + Seek> VolumeReader<R> {
+	/// Creates a reader of the `volumes` in order, i.e., with the volume holding the end of
+	/// central directory record last.
+	///
+	/// # Errors
+	///
+	/// Fails with [`ZipError::InvalidArchive`] if the number of volumes does not match the
+	/// archive or if the zip structures are malformed. Reading the volumes can fail with
+	/// [`ZipError::Io`].
+	pub fn new(mut volumes: Vec<R>) -> Result<Self, ZipError> {
+		let invalid = ZipError::InvalidArchive;
+		let truncated = || invalid("Truncated zip structure");
+		let mut starts = Vec::with_capacity(volumes.len());
+		let mut len = 0u64;
+		for volume in &mut volumes {
+			starts.push(len);
+			len = len
+				.checked_add(volume.seek(SeekFrom::End(0))?)
+				.ok_or_else(|| invalid("Volumes too large"))?;
+		}
+		let mut reader = Self {
+			volumes,
+			starts,
+			len,
+			tail: Vec::new(),
+			pos: 0,
+		};
+		let last = reader
+			.starts
+			.last()
+			.copied()
+			.ok_or_else(|| invalid("No volumes"))?;
+		// Find end of central directory record within last volume.
+		let tail_start = last.max(len.saturating_sub(22 + 0xffff));
+		let mut tail = vec![0; usize::try_from(len - tail_start).unwrap_or_default()];
+		reader.read_at(tail_start, &mut tail)?;
+		let eocd = (0..tail.len().saturating_sub(21))
+			.rev()
+			.find(|&pos| u32_at(&tail, pos) == Some(EOCD_SIGNATURE))
+			.ok_or_else(|| invalid("No end of central directory record"))?;
+		let eocd_start = tail_start + eocd as u64;
+		let eocd = &tail[eocd..];
+		let disk = u32::from(u16_at(eocd, 4).ok_or_else(truncated)?);
+		let mut cd_disk = u32::from(u16_at(eocd, 6).ok_or_else(truncated)?);
+		let mut entries = u64::from(u16_at(eocd, 10).ok_or_else(truncated)?);
+		let mut cd_size = u64::from(u32_at(eocd, 12).ok_or_else(truncated)?);
+		let mut cd_offset = u64::from(u32_at(eocd, 16).ok_or_else(truncated)?);
+		let mut locator = [0; 20];
+		if eocd_start >= last + 20 {
+			reader.read_at(eocd_start - 20, &mut locator)?;
+		}
+		let disks = if u32_at(&locator, 0) == Some(EOCD64_LOCATOR_SIGNATURE) {
+			let eocd64_start = reader.absolute(
+				u32_at(&locator, 4).ok_or_else(truncated)?,
+				u64_at(&locator, 8).ok_or_else(truncated)?,
+			)?;
+			let mut eocd64 = [0; 56];
+			reader.read_at(eocd64_start, &mut eocd64)?;
+			if u32_at(&eocd64, 0) != Some(EOCD64_SIGNATURE) {
+				return Err(invalid("Invalid Zip64 end of central directory record"));
+			}
+			cd_disk = u32_at(&eocd64, 20).ok_or_else(truncated)?;
+			entries = u64_at(&eocd64, 32).ok_or_else(truncated)?;
+			cd_size = u64_at(&eocd64, 40).ok_or_else(truncated)?;
+			cd_offset = u64_at(&eocd64, 48).ok_or_else(truncated)?;
+			u32_at(&locator, 16).ok_or_else(truncated)?
+		} else {
+			disk + 1
+		};
+		if usize::try_from(disks).ok() != Some(reader.volumes.len()) {
+			return Err(invalid("Number of volumes does not match"));
+		}
+		let cd_start = reader.absolute(cd_disk, cd_offset)?;
+		if !cd_start
+			.checked_add(cd_size)
+			.is_some_and(|cd_end| cd_end <= len)
+		{
+			return Err(invalid("Invalid central directory size or offset"));
+		}
+		let mut cd = vec![0; usize::try_from(cd_size).unwrap_or_default()];
+		reader.read_at(cd_start, &mut cd)?;
+		let mut tail = Vec::with_capacity(cd.len() + 98);
+		let mut pos = 0;
+		let invalid_header = || invalid("Invalid central header");
+		for _entry in 0..entries {
+			if u32_at(&cd, pos) != Some(CENTRAL_SIGNATURE) {
+				return Err(invalid_header());
+			}
+			let end = pos
+				+ 46 + usize::from(u16_at(&cd, pos + 28).ok_or_else(invalid_header)?)
+				+ usize::from(u16_at(&cd, pos + 30).ok_or_else(invalid_header)?)
+				+ usize::from(u16_at(&cd, pos + 32).ok_or_else(invalid_header)?);
+			let header = cd.get(pos..end).ok_or_else(invalid_header)?;
+			relocate_central_header(header, &mut tail, |disk, offset| {
+				reader.absolute(disk, offset)
+			})?;
+			pos = end;
+		}
+		let cd_size = tail.len() as u64;
+		let comment = eocd
+			.get(22..22 + usize::from(u16_at(eocd, 20).unwrap_or_default()))
+			.unwrap_or_default();
+		write_eocd(&mut tail, entries, cd_size, len, comment);
+		reader.tail = tail;
+		Ok(reader)
+	}
+
+	/// Returns the volumes in order.
+	#[must_use]
+	pub fn into_inner(self) -> Vec<R> {
+		self.volumes
+	}
+
+	/// Converts the `offset` relative to the volume `disk` into an absolute offset.
+	fn absolute(&self, disk: u32, offset: u64) -> Result<u64, ZipError> {
+		usize::try_from(disk)
+			.ok()
+			.and_then(|disk| self.starts.get(disk))
+			.and_then(|start| start.checked_add(offset))
+			.ok_or(ZipError::InvalidArchive("Invalid disk number"))
+	}
+
+	/// Reads exactly `buf.len()` bytes of the concatenation at the absolute `pos`.
+	fn read_at(&mut self, mut pos: u64, mut buf: &mut [u8]) -> io::Result<()> {
+		while !buf.is_empty() {
+			let read = self.read_volumes(pos, buf)?;
+			if read == 0 {
+				return Err(io::ErrorKind::UnexpectedEof.into());
+			}
+			pos += read as u64;
+			buf = &mut buf[read..];
+		}
+		Ok(())
+	}
+
+	/// Reads from the volume containing the absolute `pos` up to its end.
+	fn read_volumes(&mut self, pos: u64, buf: &mut [u8]) -> io::Result<usize> {
+		let volume = self.starts.partition_point(|&start| start <= pos) - 1;
+		let end = self.starts.get(volume + 1).copied().unwrap_or(self.len);
+		if pos >= end {
+			return Ok(0);
+		}
+		let len = usize::try_from(end - pos).map_or(buf.len(), |len| len.min(buf.len()));
+		let volume_pos = pos - self.starts[volume];
+		let volume = &mut self.volumes[volume];
+		volume.seek(SeekFrom::Start(volume_pos))?;
+		volume.read(&mut buf[..len])
+	}
+}
+
+impl<R: Read + Seek> Read for VolumeReader<R> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		let read = if self.pos < self.len {
+			self.read_volumes(self.pos, buf)?
+		} else {
+			let start = usize::try_from(self.pos - self.len).unwrap_or(usize::MAX);
+			let tail = self.tail.get(start..).unwrap_or_default();
+			let len = tail.len().min(buf.len());
+			buf[..len].copy_from_slice(&tail[..len]);
+			len
+		};
+		self.pos += read as u64;
+		Ok(read)
+	}
+}
+
+impl<R: Read + Seek> Seek for VolumeReader<R> {
+	fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+		let (base, offset) = match pos {
+			SeekFrom::Start(offset) => {
+				self.pos = offset;
+				return Ok(offset);
+			}
+			SeekFrom::End(offset) => (self.len + self.tail.len() as u64, offset),
+			SeekFrom::Current(offset) => (self.pos, offset),
+		};
+		self.pos = base.checked_add_signed(offset).ok_or_else(|| {
+			io::Error::new(
+				io::ErrorKind::InvalidInput,
+				"invalid seek to a negative position",
+			)
+		})?;
+		Ok(self.pos)
+	}
+}
+
+impl NpzReader<VolumeReader<File>> {
+	/// Opens a multi-volume `.npz` file for reading given the `paths` of its volumes in order,
+	/// see [`VolumeReader`].
+	///
+	/// # Example
+	///
+	/// ```no_run
+	/// use ndarray_npz::{ndarray::Array2, NpzReader};
+	///
+	/// let mut npz = NpzReader::open_volumes(&["arrays.z01", "arrays.z02", "arrays.zip"])?;
+	/// let a: Array2<f64> = npz.by_name("a.npy")?;
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Opening the volumes can fail with [`ZipError`].
+	pub fn open_volumes<P: AsRef<Path>>(paths: &[P]) -> Result<Self, ReadNpzError> {
+		Self::new(VolumeReader::open(paths)?)
+	}
+}
+
+/// Appends the central `header` to `tail` with the disk number zeroed and the local header offset
+/// relocated by `relocate` given the original disk number and offset.
+pub(crate) fn relocate_central_header<F>(
+	header: &[u8],
+	tail: &mut Vec<u8>,
+	relocate: F,
+) -> Result<(), ZipError>
+where
+	F: FnOnce(u32, u64) -> Result<u64, ZipError>,
+{
+	let invalid_header = || ZipError::InvalidArchive("Invalid central header");
+	let invalid = || ZipError::InvalidArchive("Invalid extra field");
+	let compressed_size = u32_at(header, 20).ok_or_else(invalid_header)?;
+	let size = u32_at(header, 24).ok_or_else(invalid_header)?;
+	let name_len = usize::from(u16_at(header, 28).ok_or_else(invalid_header)?);
+	let extra_len = usize::from(u16_at(header, 30).ok_or_else(invalid_header)?);
+	let mut disk = u32::from(u16_at(header, 34).ok_or_else(invalid_header)?);
+	let mut offset = u64::from(u32_at(header, 42).ok_or_else(invalid_header)?);
+	let extra = header
+		.get(46 + name_len..46 + name_len + extra_len)
+		.ok_or_else(invalid_header)?;
+	let mut zip64 = Vec::new();
+	let mut fields = Vec::with_capacity(extra.len());
+	let mut pos = 0;
+	while let (Some(id), Some(len)) = (u16_at(extra, pos), u16_at(extra, pos + 2)) {
+		let field_end = pos + 4 + usize::from(len);
+		let field = extra.get(pos + 4..field_end).ok_or_else(invalid)?;
+		if id == 0x0001 {
+			// Only values whose fixed fields are set to their maximum are present in order.
+			let sizes_len =
+				8 * (usize::from(size == u32::MAX) + usize::from(compressed_size == u32::MAX));
+			zip64.extend(field.get(..sizes_len).ok_or_else(invalid)?);
+			let mut value = sizes_len;
+			if offset == u64::from(u32::MAX) {
+				offset = u64_at(field, value).ok_or_else(invalid)?;
+				value += 8;
+			}
+			if disk == u32::from(u16::MAX) {
+				disk = u32_at(field, value).ok_or_else(invalid)?;
+			}
+		} else {
+			fields.extend(&extra[pos..field_end]);
+		}
+		pos = field_end;
+	}
+	let offset = relocate(disk, offset)?;
+	if offset >= u64::from(u32::MAX) {
+		zip64.extend(offset.to_le_bytes());
+	}
+	if !zip64.is_empty() {
+		fields.extend(0x0001u16.to_le_bytes());
+		fields.extend(u16::try_from(zip64.len()).unwrap_or_default().to_le_bytes());
+		fields.extend(zip64);
+	}
+	let fields_len = u16::try_from(fields.len()).map_err(|_| invalid())?;
+	let start = tail.len();
+	tail.extend(&header[..46 + name_len]);
+	tail[start + 30..start + 32].copy_from_slice(&fields_len.to_le_bytes());
+	tail[start + 34..start + 36].copy_from_slice(&0u16.to_le_bytes());
+	let offset = u32::try_from(offset).unwrap_or(u32::MAX);
+	tail[start + 42..start + 46].copy_from_slice(&offset.to_le_bytes());
+	tail.extend(fields);
+	tail.extend(&header[46 + name_len + extra_len..]);
+	Ok(())
+}
+
+/// Appends the end of central directory records of a single-disk archive, including the Zip64
+/// ones if required, followed by the archive `comment`.
+pub(crate) fn write_eocd(
+	tail: &mut Vec<u8>,
+	entries: u64,
+	cd_size: u64,
+	cd_offset: u64,
+	comment: &[u8],
+) {
+	let zip64 = entries >= u64::from(u16::MAX)
+		|| cd_size >= u64::from(u32::MAX)
+		|| cd_offset >= u64::from(u32::MAX);
+	if zip64 {
+		let eocd64_offset = cd_offset + cd_size;
+		tail.extend(EOCD64_SIGNATURE.to_le_bytes());
+		tail.extend(44u64.to_le_bytes());
+		tail.extend(45u16.to_le_bytes());
+		tail.extend(45u16.to_le_bytes());
+		tail.extend([0; 8]);
+		tail.extend(entries.to_le_bytes());
+		tail.extend(entries.to_le_bytes());
+		tail.extend(cd_size.to_le_bytes());
+		tail.extend(cd_offset.to_le_bytes());
+		tail.extend(EOCD64_LOCATOR_SIGNATURE.to_le_bytes());
+		tail.extend(0u32.to_le_bytes());
+		tail.extend(eocd64_offset.to_le_bytes());
+		tail.extend(1u32.to_le_bytes());
+	}
+	let entries = u16::try_from(entries).unwrap_or(u16::MAX);
+	tail.extend(EOCD_SIGNATURE.to_le_bytes());
+	tail.extend([0; 4]);
+	tail.extend(entries.to_le_bytes());
+	tail.extend(entries.to_le_bytes());
+	tail.extend(u32::try_from(cd_size).unwrap_or(u32::MAX).to_le_bytes());
+	tail.extend(u32::try_from(cd_offset).unwrap_or(u32::MAX).to_le_bytes());
+	let comment = &comment[..comment.len().min(usize::from(u16::MAX))];
+	tail.extend(
+		u16::try_from(comment.len())
+			.unwrap_or_default()
+			.to_le_bytes(),
+	);
+	tail.extend(comment);
+}
@@ -0,0 +1,175 @@
+use super::{lenient, NpyView, ViewNpzError};
+use ndarray::{ArrayView, Axis, Dimension, IxDyn, ShapeBuilder, SliceArg, SliceInfoElem};
+use ndarray_npy::{ViewElement, ViewNpyError};
+use std::mem::size_of;
+use zip::result::ZipError;
+
+impl NpyView<'_> {
+	/// Returns an immutable view of the `info` slice of a memory-mapped `.npy` file.
+	///
+	/// Unlike slicing the view returned by [`Self::view`], the strided sub-view is computed
+	/// directly over the mapped bytes, so only the bytes spanned by the slice are accessed, e.g.,
+	/// only the elements within this span are iterated for `bool` arrays to ensure `0x00`/`0x01`
+	/// values. This makes reading small windows of giant arrays cheap and explicit.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use aligned_vec::AVec;
+	/// use ndarray::{array, s, Array2};
+	/// use ndarray_npz::{NpzView, NpzWriter};
+	/// use std::io::Cursor;
+	///
+	/// let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	/// let x = Array2::from_shape_fn((1000, 1000), |(i, j)| (i * 1000 + j) as u64);
+	/// npz.add_array("x", &x)?;
+	/// let buffer = AVec::<u8>::from_slice(64, &npz.finish()?.into_inner());
+	/// let npz = NpzView::new(&buffer)?;
+	/// let x = npz.by_name("x")?;
+	/// let window = x.view_slice::<u64, _>(s![1..3, -2..;-1])?;
+	/// assert_eq!(window, array![[1999, 1998], [2999, 2998]]);
+	/// # Ok::<_, Box<dyn std::error::Error>>(())
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Viewing an `.npy` file can fail with [`ViewNpyError`], e.g., if the number of sliced axes
+	/// differs from the number of dimensions. Fails with [`ViewNpzError::InvalidSlice`] if the
+	/// slice is out of bounds or has a zero step.
+	pub fn view_slice<A, I>(&self, info: I) -> Result<ArrayView<'_, A, I::OutDim>, ViewNpzError>
+	where
+		A: ViewElement,
+		I: SliceArg<IxDyn>,
+	{
+		let header = lenient::header(self.data, self.lenient)
+			.ok_or(ZipError::InvalidArchive("Invalid npy header"))?;
+		let shape = header
+			.shape
+			.iter()
+			.map(|&axis| usize::try_from(axis).ok())
+			.collect::<Option<Vec<usize>>>()
+			.ok_or(ViewNpyError::LengthOverflow)?;
+		if info.in_ndim() != shape.len() {
+			return Err(ViewNpyError::WrongNdim(Some(info.in_ndim()), shape.len()).into());
+		}
+		let len = shape
+			.iter()
+			.try_fold(1usize, |len, &axis| len.checked_mul(axis))
+			.filter(|len| {
+				len.checked_mul(size_of::<A>())
+					.is_some_and(|size| isize::try_from(size).is_ok())
+			})
+			.ok_or(ViewNpyError::LengthOverflow)?;
+		let data = &self.data[header.len..];
+		let size = len * size_of::<A>();
+		if data.len() < size {
+			return Err(ViewNpyError::MissingBytes(size - data.len()).into());
+		}
+		if data.len() > size {
+			return Err(ViewNpyError::ExtraBytes(data.len() - size).into());
+		}
+		// Checks the type descriptor and the alignment without accessing the data.
+		let empty = A::bytes_as_slice(&data[..0], &header.descr, 0).map_err(ViewNpyError::from)?;
+		let (axes, offset) = slice_axes(&shape, header.fortran_order, info.as_ref())?;
+		let (shape, strides) = axes
+			.iter()
+			.map(|&(axis, stride)| (axis, stride.unsigned_abs()))
+			.unzip::<_, _, Vec<_>, Vec<_>>();
+		let array = if shape.contains(&0) {
+			ArrayView::from_shape(IxDyn(&shape), empty)
+		} else {
+			// Lowest and highest element of the slice.
+			let (first, last) =
+				axes.iter()
+					.fold((offset, offset), |(first, last), &(axis, stride)| {
+						#[allow(clippy::cast_possible_wrap)]
+						let span = stride * (axis as isize - 1);
+						(first + span.min(0), last + span.max(0))
+					});
+			#[allow(clippy::cast_sign_loss)]
+			let (first, last) = (first as usize, last as usize);
+			let window = &data[first * size_of::<A>()..(last + 1) * size_of::<A>()];
+			let window = A::bytes_as_slice(window, &header.descr, last + 1 - first)
+				.map_err(ViewNpyError::from)?;
+			ArrayView::from_shape(IxDyn(&shape).strides(IxDyn(&strides)), window)
+		};
+		let mut array = array.map_err(|_| ViewNpzError::InvalidSlice)?;
+		for (index, &(_axis, stride)) in axes.iter().enumerate() {
+			if stride < 0 {
+				array.invert_axis(Axis(index));
+			}
+		}
+		let ndim = array.ndim();
+		Ok(array
+			.into_dimensionality()
+			.map_err(|_| ViewNpyError::WrongNdim(I::OutDim::NDIM, ndim))?)
+	}
+}
+
+/// Slices the axes of `shape` like [`ArrayBase::slice`](ndarray::ArrayBase::slice).
+///
+/// Returns the lengths and strides in elements of the sliced axes and the offset in elements of
+/// their first element. The number of elements must not overflow `isize`.
+#[allow(clippy::cast_possible_wrap)]
+fn slice_axes(
+	shape: &[usize],
+	fortran_order: bool,
+	info: &[SliceInfoElem],
+) -> Result<(Vec<(usize, isize)>, isize), ViewNpzError> {
+	let mut strides = vec![0; shape.len()];
+	let mut stride = 1;
+	for axis in 0..shape.len() {
+		let axis = if fortran_order {
+			axis
+		} else {
+			shape.len() - 1 - axis
+		};
+		strides[axis] = stride;
+		stride *= shape[axis] as isize;
+	}
+	let mut axes = Vec::with_capacity(info.len());
+	let mut offset = 0;
+	let mut index = 0;
+	for elem in info {
+		match *elem {
+			SliceInfoElem::Slice { start, end, step } => {
+				let (len, stride) = (shape[index], strides[index]);
+				index += 1;
+				let start = abs_index(len, start)?;
+				let end = end.map_or(Ok(len), |end| abs_index(len, end))?.max(start);
+				if step == 0 {
+					return Err(ViewNpzError::InvalidSlice);
+				}
+				let span = end - start;
+				if span > 0 {
+					offset += (if step < 0 { end - 1 } else { start }) as isize * stride;
+				}
+				let len = span.div_ceil(step.unsigned_abs());
+				axes.push((len, if len > 1 { stride * step } else { 0 }));
+			}
+			SliceInfoElem::Index(position) => {
+				let len = shape[index];
+				let position = abs_index(len, position)?;
+				if position == len {
+					return Err(ViewNpzError::InvalidSlice);
+				}
+				offset += position as isize * strides[index];
+				index += 1;
+			}
+			SliceInfoElem::NewAxis => axes.push((1, 0)),
+		}
+	}
+	Ok((axes, offset))
+}
+
+/// Returns the absolute `index` of an axis of length `len` if within `0..=len`.
+fn abs_index(len: usize, index: isize) -> Result<usize, ViewNpzError> {
+	let index = if index < 0 {
+		len.checked_sub(index.unsigned_abs())
+	} else {
+		index.try_into().ok()
+	};
+	index
+		.filter(|&index| index <= len)
+		.ok_or(ViewNpzError::InvalidSlice)
+}
@@ -163,3 +163,4716 @@ where
 	}
 	positions
 }
+
+#[test]
+fn mutation_log() {
+	use ndarray_npz::{MutationLog, NpzEditor, NpzReader, NpzViewMut, NpzWriter};
+	use std::io::Cursor;
+
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+		npz.add_array("x.npy", &Array1::<f64>::zeros(5)).unwrap();
+		npz.add_array("y.npy", &Array1::<f64>::zeros(7)).unwrap();
+		npz.finish().unwrap();
+	}
+	let mut buffer = aligned_vec::AVec::<u8>::from_slice(64, &buffer);
+	let log = MutationLog::new();
+	let x_crc32 = {
+		let mut npz = NpzViewMut::new(&mut buffer)
+			.unwrap()
+			.with_mutation_log(&log);
+		let mut x_npy_view_mut = npz.by_name("x.npy").unwrap();
+		x_npy_view_mut.view_mut::<f64, Ix1>().unwrap()[0] = 1.0;
+		let x_crc32 = x_npy_view_mut.update();
+		// Untouched views are not recorded.
+		let _y_npy_view_mut = npz.by_name("y.npy").unwrap();
+		x_crc32
+	};
+	let mutations = log.mutations();
+	assert_eq!(mutations.len(), 1);
+	assert_eq!(mutations[0].name, "x.npy");
+	assert_eq!(mutations[0].crc32, x_crc32);
+	// Persist and read back the log.
+	let mut output = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut output));
+		npz.add_mutation_log(&log).unwrap();
+		npz.finish().unwrap();
+	}
+	let mut npz = NpzReader::new(Cursor::new(&output)).unwrap();
+	let read_log = npz.mutation_log().unwrap().unwrap();
+	assert_eq!(read_log.mutations(), mutations);
+	let mut npz = NpzReader::new(Cursor::new(&buffer[..])).unwrap();
+	assert!(npz.mutation_log().unwrap().is_none());
+	// Edits are recorded as well.
+	let mut npz = NpzEditor::new(Cursor::new(&mut buffer[..]))
+		.unwrap()
+		.with_mutation_log(&log);
+	let y_crc32 = npz.reshape_entry("y.npy", &[7, 1]).unwrap();
+	assert!(npz.reshape_entry("y.npy", &[2]).is_err());
+	let mutations = log.mutations();
+	assert_eq!(mutations.len(), 2);
+	assert_eq!(mutations[1].name, "y.npy");
+	assert_eq!(mutations[1].crc32, y_crc32);
+}
+
+#[cfg(feature = "lock")]
+#[test]
+fn locked_file() {
+	use ndarray_npz::{LockedFile, NpzReader, NpzWriter};
+
+	let path = std::env::temp_dir().join("ndarray_npz_locked_file.npz");
+	{
+		let mut npz = NpzWriter::create_exclusive(&path).unwrap();
+		// Exclusively locked while writing.
+		assert!(LockedFile::try_open_shared(&path).unwrap().is_none());
+		npz.add_array("x.npy", &Array1::<f64>::zeros(5)).unwrap();
+		npz.finish().unwrap();
+	}
+	{
+		let mut x_npz = NpzReader::open_shared(&path).unwrap();
+		let mut y_npz = NpzReader::open_shared(&path).unwrap();
+		// Shared locks exclude exclusive ones.
+		assert!(LockedFile::try_open_exclusive(&path).unwrap().is_none());
+		let x: Array1<f64> = x_npz.by_name("x.npy").unwrap();
+		let y: Array1<f64> = y_npz.by_name("x.npy").unwrap();
+		assert_eq!(x, y);
+	}
+	assert!(LockedFile::try_open_exclusive(&path).unwrap().is_some());
+	std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(feature = "lock")]
+#[test]
+fn locked_npz() {
+	use ndarray_npz::{LockedFile, LockedNpz, NpzReader, NpzWriter};
+
+	let path = std::env::temp_dir().join("ndarray_npz_locked_npz.npz");
+	{
+		let mut npz = NpzWriter::create_exclusive(&path).unwrap();
+		npz.add_array("x.npy", &Array1::<f64>::zeros(5)).unwrap();
+		npz.finish().unwrap();
+	}
+	{
+		let mut npz = LockedNpz::open_exclusive(&path).unwrap();
+		// Exclusively locked while editing.
+		assert!(LockedFile::try_open_shared(&path).unwrap().is_none());
+		npz.view_mut()
+			.unwrap()
+			.by_name("x.npy")
+			.unwrap()
+			.view_mut::<f64, Ix1>()
+			.unwrap()
+			.fill(1.0);
+		let view = npz.view().unwrap();
+		let mut x = view.by_name("x.npy").unwrap();
+		x.verify().unwrap();
+		assert_eq!(x.view::<f64, Ix1>().unwrap(), Array1::<f64>::ones(5));
+		npz.commit().unwrap();
+	}
+	let mut npz = NpzReader::open_shared(&path).unwrap();
+	let x: Array1<f64> = npz.by_name("x.npy").unwrap();
+	assert_eq!(x, Array1::<f64>::ones(5));
+	drop(npz);
+	std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn open_cow() {
+	use ndarray_npz::{ChecksumStatus, NpzMmap, NpzWriter};
+	use std::{fs, io::Cursor};
+	use zip::ZipArchive;
+
+	let x = Array1::<f64>::linspace(1.0, 2.0, 1000);
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	npz.add_array("x", &x).unwrap();
+	npz.add_array("y", &x).unwrap();
+	let bytes = npz.finish().unwrap().into_inner();
+	let path = std::env::temp_dir().join("ndarray_npz_open_cow.npz");
+	fs::write(&path, &bytes).unwrap();
+	// Safety: The file is private to this test and not modified while mapped.
+	let mut mmap = unsafe { NpzMmap::open_cow(&path).unwrap() };
+	{
+		let mut npz = mmap.view_mut().unwrap();
+		let mut npy = npz.by_name("x").unwrap();
+		npy.view_mut::<f64, Ix1>().unwrap().fill(0.0);
+		npy.update();
+		assert_eq!(npy.status(), ChecksumStatus::Outdated);
+		let mut npy = npz.by_name("y").unwrap();
+		npy.verify().unwrap();
+		assert_eq!(npy.view::<f64, Ix1>().unwrap(), x);
+	}
+	// Only the data of the edited array differs, whereas its checksums are untouched.
+	let mut zip = ZipArchive::new(Cursor::new(&bytes)).unwrap();
+	let file = zip.by_name("x").unwrap();
+	let data = file.data_start()..file.data_start() + file.size();
+	let mut changed = (0..bytes.len())
+		.filter(|&index| mmap.as_bytes()[index] != bytes[index])
+		.map(|index| index as u64)
+		.peekable();
+	assert!(changed.peek().is_some());
+	assert!(changed.all(|index| data.contains(&index)));
+	let npz = mmap.view().unwrap();
+	let mut npy = npz.by_name("x").unwrap();
+	assert_eq!(npy.view::<f64, Ix1>().unwrap(), Array1::<f64>::zeros(1000));
+	assert!(npy.verify().is_err());
+	assert_eq!(fs::read(&path).unwrap(), bytes);
+	fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn finish_and_sync() {
+	use ndarray_npz::{NpzReader, NpzWriter};
+	use std::fs::{self, File};
+
+	let path = std::env::temp_dir().join("ndarray_npz_finish_and_sync.npz");
+	let _ = fs::remove_file(&path);
+	{
+		let mut npz = NpzWriter::create_atomic(&path).unwrap();
+		npz.add_array("x.npy", &Array1::<f64>::ones(5)).unwrap();
+		// Not visible before being persisted.
+		assert!(!path.exists());
+		npz.finish_and_sync().unwrap();
+	}
+	{
+		// Dropped before being finished, keeps the previous file.
+		let mut npz = NpzWriter::create_atomic(&path).unwrap();
+		npz.add_array("x.npy", &Array1::<f64>::zeros(5)).unwrap();
+	}
+	let mut npz = NpzReader::new(File::open(&path).unwrap()).unwrap();
+	let x: Array1<f64> = npz.by_name("x.npy").unwrap();
+	assert_eq!(x, Array1::<f64>::ones(5));
+	let temps = fs::read_dir(path.parent().unwrap())
+		.unwrap()
+		.filter_map(Result::ok)
+		.filter(|entry| {
+			let path = entry.path();
+			path.extension().is_some_and(|extension| extension == "tmp")
+				&& entry
+					.file_name()
+					.to_string_lossy()
+					.starts_with("ndarray_npz_finish_and_sync.npz.")
+		})
+		.count();
+	assert_eq!(temps, 0);
+	let mut npz = NpzWriter::new(File::create(&path).unwrap());
+	npz.add_array("x.npy", &Array1::<f64>::zeros(5)).unwrap();
+	npz.finish_and_sync().unwrap();
+	fs::remove_file(&path).unwrap();
+}
+
+#[test]
+#[allow(clippy::cast_precision_loss)]
+fn extract() {
+	use ndarray_npy::ReadNpyExt;
+	use ndarray_npz::{portable_path, NpzReader, NpzWriter, ReadNpzError};
+	use std::{fs, io::Cursor, path::PathBuf};
+	use zip::result::ZipError;
+
+	assert_eq!(portable_path("NUL"), Some(PathBuf::from("NUL_")));
+	assert_eq!(
+		portable_path("com1.x.npy"),
+		Some(PathBuf::from("com1_.x.npy"))
+	);
+	assert_eq!(
+		portable_path("console.npy"),
+		Some(PathBuf::from("console.npy"))
+	);
+	assert_eq!(portable_path("x. "), Some(PathBuf::from("x__")));
+	assert_eq!(portable_path("a\\b\t.npy"), Some(PathBuf::from("a_b_.npy")));
+	assert_eq!(
+		portable_path("./a//b.npy"),
+		Some(["a", "b.npy"].iter().collect())
+	);
+	assert_eq!(portable_path("/etc/passwd"), None);
+	assert_eq!(portable_path("a/../../b"), None);
+	assert_eq!(portable_path("/"), None);
+
+	let write = |names: &[&str]| {
+		let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+		for (index, name) in names.iter().enumerate() {
+			npz.add_array(*name, &Array1::<f64>::from_elem(3, index as f64))
+				.unwrap();
+		}
+		NpzReader::new(npz.finish().unwrap()).unwrap()
+	};
+	let dir = std::env::temp_dir().join("ndarray_npz_extract");
+	let _ = fs::remove_dir_all(&dir);
+	let mut npz = write(&["aux.npy", "lpt1/con.npy", "a:b.npy"]);
+	let paths = npz.extract(&dir).unwrap();
+	assert_eq!(
+		paths,
+		[
+			dir.join("aux_.npy"),
+			dir.join("lpt1_").join("con_.npy"),
+			dir.join("a_b.npy"),
+		]
+	);
+	for (index, path) in paths.iter().enumerate() {
+		let bytes = fs::read(path).unwrap();
+		let x = <Array1<f64> as ReadNpyExt>::read_npy(&bytes[..]).unwrap();
+		assert_eq!(x, Array1::from_elem(3, index as f64));
+	}
+	fs::remove_dir_all(&dir).unwrap();
+	for names in [&["x.npy", "X.npy"][..], &["../x.npy"]] {
+		assert!(matches!(
+			write(names).extract(&dir),
+			Err(ReadNpzError::Zip(ZipError::InvalidArchive(_)))
+		));
+	}
+	assert!(!dir.exists());
+}
+
+#[cfg(feature = "unicode")]
+#[test]
+fn unicode_names() {
+	use ndarray_npz::{portable_path, NpzReader, NpzWriter};
+	use std::{io::Cursor, path::PathBuf};
+
+	// Decomposed as written on macOS.
+	let decomposed = "cafe\u{301}.npy";
+	let composed = "caf\u{e9}.npy";
+	assert_eq!(portable_path(decomposed), Some(PathBuf::from(composed)));
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	npz.add_array(decomposed, &Array1::<f64>::ones(3)).unwrap();
+	let mut npz = NpzReader::new(npz.finish().unwrap()).unwrap();
+	let x: Array1<f64> = npz.by_name(composed).unwrap();
+	assert_eq!(x, Array1::<f64>::ones(3));
+}
+
+#[cfg(windows)]
+#[test]
+fn long_paths() {
+	use ndarray_npz::{NpzReader, NpzWriter};
+	use std::fs::{self, File};
+
+	let root = std::env::temp_dir().join("ndarray_npz_long_paths");
+	let dir = (0..8).fold(root.clone(), |dir, index| {
+		dir.join(format!("{index}{}", "x".repeat(40)))
+	});
+	let path = dir.join("arrays.npz");
+	assert!(path.as_os_str().len() > 260);
+	let _ = fs::remove_dir_all(&root);
+	fs::create_dir_all(&dir).unwrap();
+	let mut npz = NpzWriter::create_atomic(&path).unwrap();
+	npz.add_array("x.npy", &Array1::<f64>::ones(3)).unwrap();
+	npz.finish_and_sync().unwrap();
+	let mut npz = NpzReader::new(File::open(&path).unwrap()).unwrap();
+	let x: Array1<f64> = npz.by_name("x.npy").unwrap();
+	assert_eq!(x, Array1::ones(3));
+	fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+#[allow(clippy::cast_precision_loss)]
+fn retry_reader() {
+	use ndarray_npz::{NpzReader, NpzWriter, RetryPolicy, RetryReader};
+	use std::{
+		io::{self, Cursor, Read, Seek, SeekFrom},
+		time::Duration,
+	};
+
+	// Reader failing every third read after partially advancing its position.
+	struct Flaky {
+		inner: Cursor<Vec<u8>>,
+		reads: usize,
+	}
+	impl Read for Flaky {
+		fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+			self.reads += 1;
+			if self.reads % 3 == 0 {
+				self.inner.seek(SeekFrom::Current(1))?;
+				Err(io::ErrorKind::TimedOut.into())
+			} else {
+				self.inner.read(buf)
+			}
+		}
+	}
+	impl Seek for Flaky {
+		fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+			self.inner.seek(pos)
+		}
+	}
+
+	let mut buffer = Vec::<u8>::new();
+	let x = Array2::<f64>::from_shape_fn((30, 20), |(i, j)| {
+		f64::from(u32::try_from(i * 20 + j).unwrap())
+	});
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+		npz.add_array("x.npy", &x).unwrap();
+		npz.finish().unwrap();
+	}
+	let policy = RetryPolicy::default().with_initial_backoff(Duration::ZERO);
+	let flaky = Flaky {
+		inner: Cursor::new(buffer.clone()),
+		reads: 0,
+	};
+	let mut npz = NpzReader::new(RetryReader::new(flaky, policy).unwrap()).unwrap();
+	let y: Array2<f64> = npz.by_name("x.npy").unwrap();
+	assert_eq!(x, y);
+
+	// Reader interrupted before each read.
+	struct Interrupted {
+		inner: Cursor<Vec<u8>>,
+		interrupted: bool,
+	}
+	impl Read for Interrupted {
+		fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+			self.interrupted = !self.interrupted;
+			if self.interrupted {
+				Err(io::ErrorKind::Interrupted.into())
+			} else {
+				self.inner.read(buf)
+			}
+		}
+	}
+	impl Seek for Interrupted {
+		fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+			self.inner.seek(pos)
+		}
+	}
+
+	// Interruptions do not count against the maximum number of retries.
+	let policy = RetryPolicy::default().with_max_retries(0);
+	let interrupted = Interrupted {
+		inner: Cursor::new(buffer),
+		interrupted: false,
+	};
+	let mut reader = RetryReader::new(interrupted, policy).unwrap();
+	let mut bytes = Vec::new();
+	reader.read_to_end(&mut bytes).unwrap();
+	assert!(reader.retries() > 1);
+	let mut npz = NpzReader::new(Cursor::new(bytes)).unwrap();
+	let y: Array2<f64> = npz.by_name("x.npy").unwrap();
+	assert_eq!(x, y);
+}
+
+#[test]
+fn entry_timeout() {
+	use ndarray::OwnedRepr;
+	use ndarray_npz::{ErrorCode, NpzReader, NpzWriter, ReadNpzError, RetryPolicy, RetryReader};
+	use std::{
+		cell::Cell,
+		io::{self, Cursor, Read, Seek, SeekFrom},
+		rc::Rc,
+		thread,
+		time::Duration,
+	};
+
+	// Reader stalling or failing once the archive has been opened.
+	struct Stalling {
+		inner: Cursor<Vec<u8>>,
+		delay: Rc<Cell<Option<Duration>>>,
+		fail: Rc<Cell<bool>>,
+	}
+	impl Read for Stalling {
+		fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+			if self.fail.get() {
+				return Err(io::ErrorKind::ConnectionReset.into());
+			}
+			if let Some(delay) = self.delay.get() {
+				thread::sleep(delay);
+			}
+			self.inner.read(buf)
+		}
+	}
+	impl Seek for Stalling {
+		fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+			self.inner.seek(pos)
+		}
+	}
+
+	let x = Array1::<f64>::linspace(0.0, 1.0, 100_000);
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	npz.add_array("x", &x).unwrap();
+	let buffer = npz.finish().unwrap().into_inner();
+	let delay = Rc::new(Cell::new(None));
+	let fail = Rc::new(Cell::new(false));
+	let stalling = Stalling {
+		inner: Cursor::new(buffer.clone()),
+		delay: delay.clone(),
+		fail: fail.clone(),
+	};
+	let npz = NpzReader::new(stalling).unwrap();
+	let mut npz = npz.with_entry_timeout(Duration::from_millis(10));
+	delay.set(Some(Duration::from_millis(20)));
+	let err = npz.by_name::<OwnedRepr<f64>, Ix1>("x").unwrap_err();
+	assert!(matches!(&err, ReadNpzError::TimedOut { name } if name == "x"));
+	assert_eq!(err.code(), ErrorCode::TimedOut);
+	let err = npz.by_index::<OwnedRepr<f64>, Ix1>(0).unwrap_err();
+	assert!(matches!(&err, ReadNpzError::TimedOut { name } if name == "x"));
+	delay.set(None);
+	let y: Array1<f64> = npz.by_name("x").unwrap();
+	assert_eq!(x, y);
+
+	// Give up retrying once the deadline of the policy has passed.
+	let policy = RetryPolicy::default()
+		.with_max_retries(u32::MAX)
+		.with_initial_backoff(Duration::from_millis(5))
+		.with_deadline(Duration::from_millis(50));
+	let stalling = Stalling {
+		inner: Cursor::new(buffer),
+		delay,
+		fail: fail.clone(),
+	};
+	let mut npz = NpzReader::new(RetryReader::new(stalling, policy).unwrap()).unwrap();
+	fail.set(true);
+	let err = npz.by_name::<OwnedRepr<f64>, Ix1>("x").unwrap_err();
+	assert!(matches!(&err, ReadNpzError::TimedOut { name } if name == "x"));
+	fail.set(false);
+	let y: Array1<f64> = npz.by_name("x").unwrap();
+	assert_eq!(x, y);
+}
+
+#[test]
+#[allow(
+	clippy::cast_possible_truncation,
+	clippy::cast_precision_loss,
+	clippy::cast_sign_loss
+)]
+fn view_dtypes() {
+	use aligned_vec::AVec;
+	use ndarray_npz::{supported_view_dtypes, NpzView, NpzViewMut, NpzWriter};
+	use std::{fs::read, io::Cursor};
+
+	macro_rules! view {
+		($buffer:expr, $($name:literal: $ty:ty = $x:expr),* $(,)?) => {{
+			let npz = NpzView::new(&$buffer).unwrap();
+			$(
+				let mut view = npz.by_name($name).unwrap();
+				view.verify().unwrap();
+				assert_eq!(view.view::<$ty, Ix1>().unwrap(), $x, "{}", $name);
+			)*
+			let mut npz = NpzViewMut::new(&mut $buffer).unwrap();
+			$(
+				let mut view = npz.by_name($name).unwrap();
+				view.verify().unwrap();
+				let mut array = view.view_mut::<$ty, Ix1>().unwrap();
+				array.swap(0, 1);
+				array.swap(0, 1);
+				assert_eq!(array, $x, "{}", $name);
+				view.update();
+				view.verify().unwrap();
+			)*
+		}};
+	}
+	let aligned = read("tests/examples_64_byte_aligned.npz").unwrap();
+	let mut buffer = AVec::<u8>::from_slice(64, &aligned);
+	view!(
+		buffer,
+		"b8.npy": bool = array![true, false],
+		"i8.npy": i8 = Array1::from_iter((0..10).map(|x| x as i8)),
+		"u8.npy": u8 = Array1::from_iter((0..10).map(|x| x as u8)),
+	);
+	let le = read("tests/examples_little_endian_64_byte_aligned.npz").unwrap();
+	let be = read("tests/examples_big_endian_64_byte_aligned.npz").unwrap();
+	let (native, foreign) = if cfg!(target_endian = "little") {
+		(le, be)
+	} else {
+		(be, le)
+	};
+	let mut buffer = AVec::<u8>::from_slice(64, &native);
+	view!(
+		buffer,
+		"i16.npy": i16 = Array1::from_iter((0..10).map(|x| x as i16)),
+		"u16.npy": u16 = Array1::from_iter((0..10).map(|x| x as u16)),
+		"i32.npy": i32 = Array1::from_iter(0..10),
+		"u32.npy": u32 = Array1::from_iter((0..10).map(|x| x as u32)),
+		"i64.npy": i64 = Array1::from_iter((0..10).map(i64::from)),
+		"u64.npy": u64 = Array1::from_iter((0..10).map(|x| x as u64)),
+		"f32.npy": f32 = Array1::from_iter((0..10).map(|x| x as f32)),
+		"f64.npy": f64 = Array1::from_iter((0..10).map(f64::from)),
+	);
+	// Multi-byte element types in foreign byte order must be read instead.
+	let buffer = AVec::<u8>::from_slice(64, &foreign);
+	let npz = NpzView::new(&buffer).unwrap();
+	assert_eq!(npz.len(), 8);
+	let by_name = |name| npz.by_name(name).unwrap();
+	assert!(by_name("i16.npy").view::<i16, Ix1>().is_err());
+	assert!(by_name("u16.npy").view::<u16, Ix1>().is_err());
+	assert!(by_name("i32.npy").view::<i32, Ix1>().is_err());
+	assert!(by_name("u32.npy").view::<u32, Ix1>().is_err());
+	assert!(by_name("i64.npy").view::<i64, Ix1>().is_err());
+	assert!(by_name("u64.npy").view::<u64, Ix1>().is_err());
+	assert!(by_name("f32.npy").view::<f32, Ix1>().is_err());
+	assert!(by_name("f64.npy").view::<f64, Ix1>().is_err());
+	// Arrays written by this crate.
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	npz.add_array("b1", &array![false, true, true]).unwrap();
+	npz.add_array("i2", &array![-1i16, 2, i16::MIN]).unwrap();
+	npz.add_array("u2", &array![1u16, 2, u16::MAX]).unwrap();
+	let mut buffer = AVec::<u8>::from_slice(64, &npz.finish().unwrap().into_inner());
+	view!(
+		buffer,
+		"b1": bool = array![false, true, true],
+		"i2": i16 = array![-1i16, 2, i16::MIN],
+		"u2": u16 = array![1u16, 2, u16::MAX],
+	);
+	let dtypes = supported_view_dtypes();
+	assert_eq!(
+		dtypes.len(),
+		if cfg!(feature = "num-complex-0_4") {
+			13
+		} else {
+			11
+		}
+	);
+	assert!(dtypes.contains(&"|b1"));
+	#[cfg(target_endian = "little")]
+	assert!(dtypes.contains(&"<u2") && !dtypes.contains(&">u2"));
+}
+
+#[test]
+#[cfg(feature = "compressed")]
+#[allow(clippy::cast_possible_truncation)]
+fn get_flexible() {
+	use aligned_vec::AVec;
+	use ndarray_npz::{Access, Dtype, Fallbacks, NpzView, NpzWriter, ViewNpzError};
+	use std::{fs::read, io::Cursor};
+
+	let x = Array1::from_iter((0..10).map(f64::from));
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	npz.add_array("x.npy", &x).unwrap();
+	let buffer = AVec::<u8>::from_slice(64, &npz.finish().unwrap().into_inner());
+	let npz = NpzView::new(&buffer).unwrap();
+	let (view, access) = npz
+		.get_flexible::<f64, Ix1>("x.npy", Fallbacks::NONE)
+		.unwrap();
+	assert_eq!((view.is_view(), access), (true, Access::View));
+	assert_eq!(view, x);
+	let (cast, access) = npz
+		.get_flexible::<f32, Ix1>("x.npy", Fallbacks::ALL)
+		.unwrap();
+	assert_eq!((cast.is_view(), access), (false, Access::Cast(Dtype::F64)));
+	assert_eq!(cast, x.mapv(|x| x as f32));
+	assert!(npz
+		.get_flexible::<f32, Ix1>("x.npy", Fallbacks::default())
+		.is_err());
+	assert!(npz
+		.get_flexible::<f64, Ix2>("x.npy", Fallbacks::ALL)
+		.is_err());
+	assert!(matches!(
+		npz.get_flexible::<f64, Ix1>("y.npy", Fallbacks::ALL),
+		Err(ViewNpzError::Zip(_))
+	));
+	// Compressed arrays must be read.
+	let mut npz = NpzWriter::new_compressed(Cursor::new(Vec::new()));
+	npz.add_array("x.npy", &x).unwrap();
+	let buffer = AVec::<u8>::from_slice(64, &npz.finish().unwrap().into_inner());
+	let npz = NpzView::new(&buffer).unwrap();
+	assert!(matches!(
+		npz.get_flexible::<f64, Ix1>("x.npy", Fallbacks::NONE),
+		Err(ViewNpzError::CompressedFile)
+	));
+	let (array, access) = npz
+		.get_flexible::<f64, Ix1>("x.npy", Fallbacks::default())
+		.unwrap();
+	assert_eq!((array, access), (x.clone().into(), Access::Read));
+	// Arrays in foreign byte order must be read.
+	let foreign = if cfg!(target_endian = "little") {
+		read("tests/examples_big_endian_64_byte_aligned.npz").unwrap()
+	} else {
+		read("tests/examples_little_endian_64_byte_aligned.npz").unwrap()
+	};
+	let buffer = AVec::<u8>::from_slice(64, &foreign);
+	let npz = NpzView::new(&buffer).unwrap();
+	assert!(npz
+		.get_flexible::<i32, Ix1>("i32.npy", Fallbacks::NONE)
+		.is_err());
+	let (array, access) = npz
+		.get_flexible::<i32, Ix1>("i32.npy", Fallbacks::default())
+		.unwrap();
+	assert_eq!(access, Access::Read);
+	assert_eq!(array, Array1::from_iter(0..10));
+	let (cast, access) = npz
+		.get_flexible::<f64, Ix1>("i16.npy", Fallbacks::ALL)
+		.unwrap();
+	assert_eq!(access, Access::Cast(Dtype::I16));
+	assert_eq!(cast, x);
+}
+
+#[test]
+#[allow(clippy::cast_precision_loss)]
+fn snapshot() {
+	use aligned_vec::AVec;
+	use ndarray_npz::{NpzReader, NpzView, NpzWriter};
+	use std::io::Cursor;
+
+	let x = Array2::from_shape_fn((10, 10), |(i, j)| (i * 10 + j) as f64);
+	let y = Array1::from_iter(0..100u16);
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new())).with_index();
+	let snapshot = npz.snapshot().unwrap();
+	assert!(snapshot.view().unwrap().is_empty());
+	npz.add_array("x.npy", &x).unwrap();
+	let snapshot = npz.snapshot().unwrap();
+	assert_eq!(snapshot.as_bytes().as_ptr() as usize % 64, 0);
+	let view = snapshot.view().unwrap();
+	assert_eq!(view.names().collect::<Vec<_>>(), ["x.npy"]);
+	assert_eq!(
+		view.by_name("x.npy").unwrap().view::<f64, Ix2>().unwrap(),
+		x
+	);
+	npz.add_array("y.npy", &y).unwrap();
+	let snapshot = npz.snapshot().unwrap();
+	let view = snapshot.view().unwrap();
+	assert_eq!(view.len(), 2);
+	assert_eq!(
+		view.by_name("x.npy").unwrap().view::<f64, Ix2>().unwrap(),
+		x
+	);
+	assert_eq!(
+		view.by_name("y.npy").unwrap().view::<u16, Ix1>().unwrap(),
+		y
+	);
+	// Writing resumes after the snapshot.
+	let buffer = npz.finish().unwrap().into_inner();
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	assert_eq!(npz.index().unwrap().unwrap().len(), 2);
+	let (x_read, y_read): (Array2<f64>, Array1<u16>) =
+		(npz.by_name("x.npy").unwrap(), npz.by_name("y.npy").unwrap());
+	assert_eq!((x_read, y_read), (x, y.clone()));
+	let buffer = AVec::<u8>::from_slice(64, &buffer);
+	let view = NpzView::new(&buffer).unwrap();
+	assert_eq!(
+		view.by_name("y.npy").unwrap().view::<u16, Ix1>().unwrap(),
+		y
+	);
+}
+
+#[test]
+fn verify_sampled() {
+	use aligned_vec::AVec;
+	use ndarray_npz::{NpzReader, NpzView, NpzWriter, Sample};
+	use std::{fs::read, io::Cursor};
+
+	let buffer = read("tests/examples_64_byte_aligned.npz").unwrap();
+	let buffer = AVec::<u8>::from_slice(64, &buffer);
+	let npz = NpzView::new(&buffer).unwrap();
+	let report = npz.verify_sampled(Sample::Fraction(1.0), 0);
+	assert!(report.is_ok());
+	assert_eq!(report.sampled_entries(), npz.len());
+	assert!((report.coverage() - 1.0).abs() < f64::EPSILON);
+	let report = npz.verify_sampled(Sample::Bytes(0), 0);
+	assert_eq!(report.sampled_entries(), 0);
+	// Same seed, same sample.
+	let x_report = npz.verify_sampled(Sample::Fraction(0.5), 7);
+	let y_report = npz.verify_sampled(Sample::Fraction(0.5), 7);
+	assert_eq!(x_report, y_report);
+	let mut npz = NpzReader::new(Cursor::new(&buffer[..])).unwrap();
+	let report = npz.verify_sampled(Sample::Fraction(1.0), 0).unwrap();
+	assert!(report.is_ok());
+	assert_eq!(report.sampled_entries(), npz.len());
+	// Invalid checksums fail the entry but not the verification.
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	npz.add_array("x", &Array1::from_elem(4, 1.5f64)).unwrap();
+	npz.add_array("y", &Array1::<f64>::zeros(4)).unwrap();
+	let mut buffer = npz.finish().unwrap().into_inner();
+	let data = 1.5f64.to_le_bytes().repeat(4);
+	let pos = buffer.windows(data.len()).position(|x| x == data).unwrap();
+	buffer[pos] ^= 1;
+	let mut npz = NpzReader::new(Cursor::new(&buffer[..])).unwrap();
+	let report = npz.verify_sampled(Sample::Fraction(1.0), 0).unwrap();
+	assert_eq!(report.verified, ["y"]);
+	assert_eq!(report.failed, ["x"]);
+}
+
+#[cfg(feature = "test-util")]
+#[test]
+fn generate_example_npz() {
+	use aligned_vec::AVec;
+	use ndarray_npz::{
+		example::{generate_example_npz, ExampleSpec},
+		NpzReader, NpzView, NpzViewMut,
+	};
+	use std::io::Cursor;
+
+	for data_descriptor in [false, true] {
+		for zip64 in [false, true] {
+			let spec = ExampleSpec::default()
+				.with_data_descriptor(data_descriptor)
+				.with_zip64(zip64);
+			let buffer = generate_example_npz(&spec);
+			let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+			assert_eq!(npz.len(), 11);
+			let x: Array1<i64> = npz.by_name("i64.npy").unwrap();
+			assert_eq!(x, Array1::from_iter(0..10));
+			let mut buffer = AVec::<u8>::from_slice(64, &buffer);
+			let npz = NpzView::new(&buffer).unwrap();
+			let mut x_npy_view = npz.by_name("f64.npy").unwrap();
+			x_npy_view.verify().unwrap();
+			let x_array_view = x_npy_view.view::<f64, Ix1>().unwrap();
+			assert_eq!(
+				x_array_view,
+				ArrayView1::from(&[0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0])
+			);
+			let mut npz = NpzViewMut::new(&mut buffer).unwrap();
+			let mut x_npy_view_mut = npz.by_name("u16.npy").unwrap();
+			x_npy_view_mut.verify().unwrap();
+			x_npy_view_mut.view_mut::<u16, Ix1>().unwrap()[0] = 7;
+		}
+	}
+	// Fortran order.
+	let spec = ExampleSpec::default().with_fortran_order(true);
+	let mut npz = NpzReader::new(Cursor::new(generate_example_npz(&spec))).unwrap();
+	let x: Array2<u8> = npz.by_name("u8.npy").unwrap();
+	assert_eq!(
+		x,
+		Array1::from_iter(0..10)
+			.into_shape_with_order((2, 5))
+			.unwrap()
+	);
+	// Big endian.
+	let spec = ExampleSpec::default().with_big_endian(true);
+	let mut npz = NpzReader::new(Cursor::new(generate_example_npz(&spec))).unwrap();
+	let x: Array1<u32> = npz.by_name("u32.npy").unwrap();
+	assert_eq!(x, Array1::from_iter(0..10));
+	// Misaligned.
+	let spec = ExampleSpec::default().with_misaligned(true);
+	let buffer = AVec::<u8>::from_slice(64, &generate_example_npz(&spec));
+	let npz = NpzView::new(&buffer).unwrap();
+	npz.by_name("f32.npy")
+		.unwrap()
+		.view::<f32, Ix1>()
+		.unwrap_err();
+}
+
+#[cfg(feature = "test-util")]
+#[test]
+fn generate_fixture() {
+	use ndarray_npz::example::{generate_fixture, write_fixtures, Fixture};
+	use std::{
+		fs::{create_dir_all, read, remove_dir_all},
+		io::{Cursor, Read},
+	};
+	use zip::{CompressionMethod, ZipArchive};
+
+	// Unique per process, so concurrent test runs do not interfere.
+	let dir = std::env::temp_dir().join(format!(
+		"ndarray_npz_generate_fixture_{}",
+		std::process::id()
+	));
+	create_dir_all(&dir).unwrap();
+	write_fixtures(&dir).unwrap();
+	for fixture in Fixture::ALL {
+		let buffer = generate_fixture(fixture);
+		assert_eq!(read(dir.join(fixture.file_name())).unwrap(), buffer);
+		// Same files of same data and zip features as the binary fixture.
+		let original = read(format!("tests/{}", fixture.file_name())).unwrap();
+		let mut zip = ZipArchive::new(Cursor::new(&buffer)).unwrap();
+		let mut original_zip = ZipArchive::new(Cursor::new(&original)).unwrap();
+		assert_eq!(zip.len(), original_zip.len());
+		for index in 0..zip.len() {
+			let mut file = zip.by_index(index).unwrap();
+			let mut original_file = original_zip.by_index(index).unwrap();
+			assert_eq!(file.name(), original_file.name());
+			assert_eq!(file.compression(), CompressionMethod::Stored);
+			if fixture != Fixture::DataDescriptor {
+				assert_eq!(file.data_start() % 64, 0);
+				assert_eq!(original_file.data_start() % 64, 0);
+			}
+			let flags = |buffer: &[u8], start: u64| buffer[start as usize + 6] & 1 << 3;
+			assert_eq!(
+				flags(&buffer, file.header_start()),
+				flags(&original, original_file.header_start())
+			);
+			let (mut data, mut original_data) = (Vec::new(), Vec::new());
+			file.read_to_end(&mut data).unwrap();
+			original_file.read_to_end(&mut original_data).unwrap();
+			assert_eq!(data, original_data, "{}", file.name());
+		}
+	}
+	remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn little_endian_structures() {
+	use ndarray_npz::{NpzReader, NpzWriter, INDEX_NAME};
+	use std::io::Cursor;
+
+	// Decodes explicitly in little endian independent of the target.
+	let le = |bytes: &[u8]| {
+		let value = bytes
+			.iter()
+			.rev()
+			.fold(0, |value, &byte| (value << 8) | u64::from(byte));
+		(value, usize::try_from(value).unwrap())
+	};
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new())).with_index();
+	npz.add_array("x.npy", &array![[1.0f64, 2.0], [3.0, 4.0]])
+		.unwrap();
+	npz.add_array("y.npy", &array![1u16, 2, 3]).unwrap();
+	let buffer = npz.finish().unwrap().into_inner();
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	let index = npz.index().unwrap().unwrap();
+	assert_eq!(index.len(), 2);
+	// End of central directory record.
+	let eocd = buffer.len() - 22;
+	assert_eq!(buffer[eocd..eocd + 4], *b"PK\x05\x06");
+	assert_eq!(le(&buffer[eocd + 10..eocd + 12]).0, 3);
+	let mut pos = le(&buffer[eocd + 16..eocd + 20]).1;
+	for entry in &index {
+		// Central header.
+		assert_eq!(buffer[pos..pos + 4], *b"PK\x01\x02");
+		assert_eq!(le(&buffer[pos + 16..pos + 20]).0, u64::from(entry.crc32));
+		assert_eq!(le(&buffer[pos + 20..pos + 24]).0, entry.compressed_size);
+		assert_eq!(le(&buffer[pos + 24..pos + 28]).0, entry.size);
+		assert_eq!(le(&buffer[pos + 42..pos + 46]).0, entry.header_start);
+		let name_len = le(&buffer[pos + 28..pos + 30]).1;
+		assert_eq!(
+			&buffer[pos + 46..pos + 46 + name_len],
+			entry.name.as_bytes()
+		);
+		let extra_len = le(&buffer[pos + 30..pos + 32]).1;
+		let comment_len = le(&buffer[pos + 32..pos + 34]).1;
+		pos += 46 + name_len + extra_len + comment_len;
+		// Local header.
+		let local = usize::try_from(entry.header_start).unwrap();
+		assert_eq!(buffer[local..local + 4], *b"PK\x03\x04");
+		let name_len = le(&buffer[local + 26..local + 28]).1;
+		let extra_len = le(&buffer[local + 28..local + 30]).1;
+		let data = usize::try_from(entry.data_start).unwrap();
+		assert_eq!(local + 30 + name_len + extra_len, data);
+		// The `.npy` header length is little endian as well.
+		let npy = entry.npy.as_ref().unwrap();
+		assert_eq!(10 + le(&buffer[data + 8..data + 10]).0, npy.header_len);
+	}
+	// Index member.
+	let bytes = npz.read_bytes(INDEX_NAME).unwrap();
+	assert_eq!(bytes[..8], *b"NPZIDX\x01\x00");
+	assert_eq!(le(&bytes[8..16]).0, 2);
+	assert_eq!(le(&bytes[16..18]).0, 5);
+	assert_eq!(&bytes[18..23], b"x.npy");
+	assert_eq!(le(&bytes[23..31]).0, index[0].header_start);
+	assert_eq!(le(&bytes[31..39]).0, index[0].data_start);
+	assert_eq!(le(&bytes[39..47]).0, index[0].compressed_size);
+	assert_eq!(le(&bytes[47..55]).0, index[0].size);
+	assert_eq!(le(&bytes[55..59]).0, u64::from(index[0].crc32));
+}
+
+#[cfg(feature = "test-util")]
+#[test]
+fn native_endian_examples() {
+	use aligned_vec::AVec;
+	use ndarray_npz::{
+		example::{generate_example_npz, ExampleSpec},
+		NpzReader, NpzView,
+	};
+	use std::io::Cursor;
+
+	// Big-endian examples are native on big-endian targets, e.g., `s390x-unknown-linux-gnu`.
+	let native = cfg!(target_endian = "big");
+	for big_endian in [native, !native] {
+		let spec = ExampleSpec::default().with_big_endian(big_endian);
+		let buffer = AVec::<u8>::from_slice(64, &generate_example_npz(&spec));
+		let mut npz = NpzReader::new(Cursor::new(buffer.as_slice())).unwrap();
+		let x: Array1<u32> = npz.by_name("u32.npy").unwrap();
+		assert_eq!(x, Array1::from_iter(0..10));
+		let y: Array1<f64> = npz.by_name("f64.npy").unwrap();
+		assert_eq!(y, Array1::from_iter((0..10).map(f64::from)));
+		let npz = NpzView::new(&buffer).unwrap();
+		let x = npz.by_name("u32.npy").unwrap();
+		let y = npz.by_name("f64.npy").unwrap();
+		if big_endian == native {
+			assert_eq!(x.view::<u32, Ix1>().unwrap(), Array1::from_iter(0..10));
+			assert_eq!(
+				y.view::<f64, Ix1>().unwrap(),
+				Array1::from_iter((0..10).map(f64::from))
+			);
+		} else {
+			x.view::<u32, Ix1>().unwrap_err();
+			y.view::<f64, Ix1>().unwrap_err();
+		}
+		// Single-byte elements are viewable either way.
+		let z = npz.by_name("u8.npy").unwrap();
+		assert_eq!(z.view::<u8, Ix1>().unwrap(), Array1::from_iter(0..10));
+	}
+}
+
+#[test]
+fn reshape_entry() {
+	use ndarray_npz::{EditNpzError, NpzEditor, NpzReader, NpzWriter, Sample};
+	use std::io::Cursor;
+
+	let mut buffer = Vec::<u8>::new();
+	let x = Array1::<f64>::from_iter((0..12).map(f64::from));
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+		npz.add_array("x.npy", &x).unwrap();
+		npz.add_array("y.npy", &x).unwrap();
+		npz.finish().unwrap();
+	}
+	{
+		let mut npz = NpzEditor::new(Cursor::new(&mut buffer)).unwrap();
+		npz.reshape_entry("x.npy", &[3, 4]).unwrap();
+		assert!(matches!(
+			npz.reshape_entry("y.npy", &[5, 2]),
+			Err(EditNpzError::ElementCountMismatch)
+		));
+	}
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	assert!(npz
+		.verify_sampled(Sample::Fraction(1.0), 0)
+		.unwrap()
+		.is_ok());
+	let y: Array2<f64> = npz.by_name("x.npy").unwrap();
+	assert_eq!(y, x.into_shape_with_order((3, 4)).unwrap());
+}
+
+#[test]
+fn patch_scalar() {
+	use ndarray_npz::{EditNpzError, NpzReader, NpzWriter, Sample};
+	use std::io::Cursor;
+
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+		npz.add_array("version.npy", &arr0(1u32)).unwrap();
+		npz.add_array("x.npy", &Array1::<f64>::zeros(4)).unwrap();
+		npz.finish().unwrap();
+	}
+	let size = zip::ZipArchive::new(Cursor::new(&buffer))
+		.unwrap()
+		.by_name("x.npy")
+		.unwrap()
+		.size();
+	{
+		let npz = NpzReader::new(Cursor::new(&mut buffer)).unwrap();
+		let npz = npz.patch_scalar("version.npy", 2u32).unwrap();
+		let mut npz = npz
+			.patch_bytes("x.npy", size - 8, &3.0f64.to_le_bytes())
+			.unwrap();
+		let version: Array0<u32> = npz.by_name("version.npy").unwrap();
+		assert_eq!(version, arr0(2));
+		let x: Array1<f64> = npz.by_name("x.npy").unwrap();
+		assert_eq!(x, Array1::from_vec(vec![0.0, 0.0, 0.0, 3.0]));
+		assert!(matches!(
+			npz.patch_scalar("version.npy", 2i64),
+			Err(EditNpzError::DtypeMismatch)
+		));
+	}
+	let npz = NpzReader::new(Cursor::new(&mut buffer)).unwrap();
+	assert!(matches!(
+		npz.patch_scalar("x.npy", 1.0f64),
+		Err(EditNpzError::ElementCountMismatch)
+	));
+	let npz = NpzReader::new(Cursor::new(&mut buffer)).unwrap();
+	assert!(matches!(
+		npz.patch_bytes("x.npy", size - 4, &[0; 8]),
+		Err(EditNpzError::LengthOverflow)
+	));
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	assert!(npz
+		.verify_sampled(Sample::Fraction(1.0), 0)
+		.unwrap()
+		.is_ok());
+}
+
+#[test]
+#[allow(clippy::cast_precision_loss)]
+fn truncate_entry() {
+	use ndarray::s;
+	use ndarray_npz::{EditNpzError, NpzEditor, NpzReader, NpzWriter, Sample};
+	use std::io::Cursor;
+
+	let mut buffer = Vec::<u8>::new();
+	let x = Array2::<f64>::from_shape_fn((5, 3), |(i, j)| (i * 3 + j) as f64);
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+		npz.add_array("x.npy", &x).unwrap();
+		npz.add_array("y.npy", &x).unwrap();
+		npz.finish().unwrap();
+	}
+	{
+		let mut npz = NpzEditor::new(Cursor::new(&mut buffer)).unwrap();
+		npz.truncate_entry("x.npy", 2).unwrap();
+		assert!(matches!(
+			npz.truncate_entry("y.npy", 6),
+			Err(EditNpzError::LengthOverflow)
+		));
+	}
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	assert!(npz
+		.verify_sampled(Sample::Fraction(1.0), 0)
+		.unwrap()
+		.is_ok());
+	let y: Array2<f64> = npz.by_name("x.npy").unwrap();
+	assert_eq!(y, x.slice(s![..2, ..]));
+	let y: Array2<f64> = npz.by_name("y.npy").unwrap();
+	assert_eq!(y, x);
+}
+
+#[test]
+#[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+fn convert_entry_dtype() {
+	use ndarray_npz::{
+		convert_entry_dtype, Conversion, Dtype, EditNpzError, NpzEditor, NpzReader, NpzWriter,
+	};
+	use std::io::Cursor;
+
+	let mut buffer = Vec::<u8>::new();
+	let x = Array2::<f64>::from_shape_fn((3, 4), |(i, j)| (i * 4 + j) as f64 + 0.5);
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+		npz.add_array("x.npy", &x).unwrap();
+		npz.finish().unwrap();
+	}
+	// Downcast into a new archive.
+	let mut converted = Vec::<u8>::new();
+	{
+		let mut reader = NpzReader::new(Cursor::new(&buffer)).unwrap();
+		let mut writer = NpzWriter::new(Cursor::new(&mut converted));
+		assert!(matches!(
+			convert_entry_dtype(
+				&mut reader,
+				&mut writer,
+				"x.npy",
+				Conversion::Reinterpret(Dtype::F32)
+			),
+			Err(EditNpzError::ItemSizeMismatch)
+		));
+		convert_entry_dtype(
+			&mut reader,
+			&mut writer,
+			"x.npy",
+			Conversion::Cast(Dtype::F32),
+		)
+		.unwrap();
+		writer.finish().unwrap();
+	}
+	let mut npz = NpzReader::new(Cursor::new(&converted)).unwrap();
+	let y: Array2<f32> = npz.by_name("x.npy").unwrap();
+	assert_eq!(y, x.mapv(|x| x as f32));
+	// Reinterpret and cast in place.
+	{
+		let mut npz = NpzEditor::new(Cursor::new(&mut converted)).unwrap();
+		npz.convert_entry_dtype("x.npy", Conversion::Reinterpret(Dtype::U32))
+			.unwrap();
+	}
+	let mut npz = NpzReader::new(Cursor::new(&converted)).unwrap();
+	let z: Array2<u32> = npz.by_name("x.npy").unwrap();
+	assert_eq!(z, y.mapv(f32::to_bits));
+	{
+		let mut npz = NpzEditor::new(Cursor::new(&mut converted)).unwrap();
+		npz.convert_entry_dtype("x.npy", Conversion::Reinterpret(Dtype::F32))
+			.unwrap();
+		npz.convert_entry_dtype("x.npy", Conversion::Cast(Dtype::I32))
+			.unwrap();
+	}
+	let mut npz = NpzReader::new(Cursor::new(&converted)).unwrap();
+	let z: Array2<i32> = npz.by_name("x.npy").unwrap();
+	assert_eq!(z, x.mapv(|x| x as i32));
+}
+
+#[test]
+#[allow(
+	clippy::cast_possible_truncation,
+	clippy::cast_precision_loss,
+	clippy::float_cmp
+)]
+fn add_array_quantized() {
+	use ndarray_npz::{NpzReader, NpzWriter};
+	use std::io::Cursor;
+
+	let mut buffer = Vec::<u8>::new();
+	let x = Array2::<f32>::from_shape_fn((4, 8), |(i, j)| (i as f32 - 2.0) * 1.5 + j as f32 * 0.1);
+	let quantization = {
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+		let quantization = npz.add_array_quantized::<u8, _, _, _>("x.npy", &x).unwrap();
+		npz.finish().unwrap();
+		quantization
+	};
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	let mut names = npz.names().unwrap();
+	names.sort();
+	assert_eq!(names, ["x.npy", "x.scale.npy", "x.zero_point.npy"]);
+	assert_eq!(npz.quantization("x.npy").unwrap(), quantization);
+	let y: Array2<f32> = npz.read_dequantized::<u8, _, _>("x.npy").unwrap();
+	for (x, y) in x.iter().zip(&y) {
+		assert!((x - y).abs() <= quantization.scale as f32 / 2.0 + f32::EPSILON * 8.0);
+	}
+	assert_eq!(
+		quantization.dequantize(quantization.quantize::<u8>(0.0)),
+		0.0
+	);
+}
+
+#[test]
+#[allow(clippy::cast_precision_loss)]
+fn add_array_delta() {
+	use ndarray_npz::{DeltaEncoding, NpzReader, NpzWriter};
+	use std::io::Cursor;
+
+	let x0 = Array2::<f64>::from_shape_fn((3, 4), |(i, j)| (i * 4 + j) as f64 / 3.0);
+	let x1 = x0.mapv(|x| x + 1e-9);
+	let y0 = Array1::<i32>::from_iter(0..10);
+	let y1 = y0.mapv(|y| y.wrapping_mul(i32::MAX));
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+		npz.add_array_delta("x.npy", &x1, &x0, DeltaEncoding::Xor)
+			.unwrap();
+		npz.add_array_delta("y.npy", &y1, &y0, DeltaEncoding::Difference)
+			.unwrap();
+		npz.add_array("z.npy", &y0).unwrap();
+		npz.finish().unwrap();
+	}
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	assert_eq!(npz.by_name_delta("x.npy", &x0).unwrap(), x1);
+	assert_eq!(npz.by_name_delta("y.npy", &y0).unwrap(), y1);
+	assert_eq!(npz.by_name_delta("z.npy", &y1).unwrap(), y0);
+	let encoding: Array0<u8> = npz.by_name("x.delta.npy").unwrap();
+	assert_eq!(encoding.into_scalar(), DeltaEncoding::Xor as u8);
+}
+
+#[test]
+fn by_name_labeled() {
+	use ndarray_npz::{NpzReader, NpzWriter};
+	use std::io::Cursor;
+
+	let x = Array2::<f32>::zeros((3, 2));
+	let time = Array1::<f64>::from_iter([0.0, 0.5, 1.0]);
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+		npz.add_array("x.npy", &x).unwrap();
+		npz.add_axis_names("x.npy", &["time", "\"sensor\"\n"])
+			.unwrap();
+		npz.add_axis_coords("x.npy", Axis(0), &time).unwrap();
+		npz.add_array("y.npy", &x).unwrap();
+		npz.finish().unwrap();
+	}
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	let x = npz.by_name_labeled::<f32, f64, Ix2>("x.npy").unwrap();
+	assert_eq!(x.axes, ["time", "\"sensor\"\n"]);
+	assert_eq!(x.axis("\"sensor\"\n"), Some(Axis(1)));
+	assert_eq!(x.coords("time"), Some(&time));
+	assert_eq!(x.coords[1], None);
+	let y = npz.by_name_labeled::<f32, f64, Ix2>("y.npy").unwrap();
+	assert_eq!(y.axes, ["dim_0", "dim_1"]);
+	assert_eq!(y.coords, [None, None]);
+}
+
+#[cfg(feature = "units")]
+#[test]
+fn read_with_unit() {
+	use ndarray::OwnedRepr;
+	use ndarray_npz::{Metre, NpzReader, NpzWriter, ReadUnitError, Second};
+	use std::io::Cursor;
+
+	let x = Array1::<f64>::linspace(0.0, 1.0, 5);
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+		npz.add_array_with_unit("x.npy", &x, "m").unwrap();
+		npz.add_array("y.npy", &x).unwrap();
+		npz.finish().unwrap();
+	}
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	assert_eq!(npz.unit("x.npy").unwrap().as_deref(), Some("m"));
+	assert_eq!(npz.unit("y.npy").unwrap(), None);
+	let y: Array1<f64> = npz.read_with_unit::<Metre, _, _>("x.npy").unwrap();
+	assert_eq!(y, x);
+	assert!(matches!(
+		npz.read_with_unit::<Second, OwnedRepr<f64>, Ix1>("x.npy"),
+		Err(ReadUnitError::UnitMismatch { expected: "s", found }) if found == "m"
+	));
+	assert!(matches!(
+		npz.read_with_unit::<Metre, OwnedRepr<f64>, Ix1>("y.npy"),
+		Err(ReadUnitError::MissingUnit)
+	));
+}
+
+#[test]
+fn add_categorical() {
+	use ndarray_npz::{Categorical, NpzReader, NpzWriter};
+	use std::io::Cursor;
+
+	let labels = array![["red", "green"], ["green", "blue"], ["red", "red"]];
+	let x = Categorical::from_labels(&labels);
+	assert_eq!(x.categories, ["red", "green", "blue"]);
+	assert_eq!(x.codes, array![[0, 1], [1, 2], [0, 0]]);
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+		npz.add_categorical("x.npy", &x).unwrap();
+		npz.finish().unwrap();
+	}
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	let y = npz.by_name_categorical::<Ix2>("x.npy").unwrap();
+	assert_eq!(y, x);
+	assert_eq!(y.labels(), labels);
+	let codes: Array2<u32> = npz.by_name("x.npy").unwrap();
+	assert_eq!(codes, x.codes);
+}
+
+#[test]
+fn add_bool_mask_rle() {
+	use ndarray_npz::{NpzReader, NpzWriter};
+	use std::io::Cursor;
+
+	let x = Array2::from_shape_fn((6, 7), |(i, j)| (1..4).contains(&i) && j >= 2);
+	let y = Array1::from_elem(5, true);
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+		npz.add_bool_mask_rle("x.npy", &x, false).unwrap();
+		npz.add_bool_mask_rle("y.npy", &y, true).unwrap();
+		npz.add_array("z.npy", &y).unwrap();
+		npz.finish().unwrap();
+	}
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	assert_eq!(npz.read_bool_mask::<Ix2>("x.npy").unwrap(), x);
+	assert_eq!(npz.read_bool_mask::<Ix1>("y.npy").unwrap(), y);
+	assert_eq!(npz.read_bool_mask::<Ix1>("z.npy").unwrap(), y);
+	let runs: Array1<u64> = npz.by_name("y.rle.npy").unwrap();
+	assert_eq!(runs, array![0, 5]);
+	let fallback: Array1<bool> = npz.by_name("y.npy").unwrap();
+	assert_eq!(fallback, y);
+	assert!(npz.read_bool_mask::<Ix1>("x.npy").is_err());
+}
+
+#[test]
+#[allow(clippy::cast_possible_truncation)]
+fn add_image_stack() {
+	use ndarray_npz::{NpzReader, NpzWriter};
+	use std::io::Cursor;
+
+	let x = Array4::<u8>::from_shape_fn((3, 4, 5, 3), |(n, h, w, c)| {
+		(n * 60 + h * 15 + w * 3 + c) as u8
+	});
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+		npz.add_image_stack("x.npy", &x, Some(&["a.png", "b.png", "c.png"][..]))
+			.unwrap();
+		npz.add_image_stack::<_, &str>("y.npy", &x, None).unwrap();
+		npz.finish().unwrap();
+	}
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	assert_eq!(npz.by_name_image_stack::<u8>("x.npy").unwrap(), x);
+	assert!(npz.by_name_image_stack::<u16>("x.npy").is_err());
+	assert_eq!(
+		npz.frame_attrs("x.npy").unwrap().unwrap(),
+		["a.png", "b.png", "c.png"]
+	);
+	assert_eq!(npz.frame_attrs("y.npy").unwrap(), None);
+	let frames = npz.image_frames::<u8>("y.npy").unwrap();
+	assert_eq!(frames.len(), 3);
+	for (frame, x) in frames.zip(x.outer_iter()) {
+		assert_eq!(frame.unwrap(), x);
+	}
+}
+
+#[test]
+#[allow(clippy::cast_precision_loss)]
+fn recorder_writer() {
+	use ndarray_npz::{NpzReader, RecorderWriter, Sample};
+	use std::io::Cursor;
+
+	let x = Array2::<f32>::from_shape_fn((7, 4), |(i, j)| (i * 4 + j) as f32);
+	let mut recorder = RecorderWriter::<_, f32>::new(Cursor::new(Vec::new()), "x", &[4], 3);
+	assert!(recorder.is_empty());
+	for frame in x.outer_iter() {
+		recorder.append(&frame).unwrap();
+	}
+	assert_eq!(recorder.len(), 7);
+	let buffer = recorder.finish().unwrap().into_inner();
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	assert!(npz
+		.verify_sampled(Sample::Fraction(1.0), 0)
+		.unwrap()
+		.is_ok());
+	let last: Array2<f32> = npz.by_name("x.2.npy").unwrap();
+	assert_eq!(last, x.slice(s![6.., ..]));
+	let y = npz.read_recording::<f32>("x").unwrap();
+	assert_eq!(y, x.into_dyn());
+}
+
+#[test]
+#[allow(clippy::cast_precision_loss)]
+fn compact() {
+	use aligned_vec::AVec;
+	use ndarray_npz::{compact, NpzEditor, NpzView, NpzWriter};
+	use std::io::Cursor;
+	use zip::ZipArchive;
+
+	let x = Array2::<f64>::from_shape_fn((100, 3), |(i, j)| (i * 3 + j) as f64);
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+		npz.add_array("x.npy", &x).unwrap();
+		npz.add_array("y.npy", &x).unwrap();
+		npz.finish().unwrap();
+	}
+	{
+		let mut npz = NpzEditor::new(Cursor::new(&mut buffer)).unwrap();
+		npz.truncate_entry("x.npy", 10).unwrap();
+	}
+	let mut compacted = Vec::<u8>::new();
+	let compaction = compact(Cursor::new(&buffer), Cursor::new(&mut compacted)).unwrap();
+	assert_eq!(compaction.original_size, buffer.len() as u64);
+	assert_eq!(compaction.compacted_size, compacted.len() as u64);
+	assert!(compaction.reclaimed() >= 90 * 3 * 8);
+	let compacted = AVec::<u8>::from_slice(64, &compacted);
+	let npz = NpzView::new(&compacted).unwrap();
+	let x_view = npz.by_name("x.npy").unwrap();
+	assert_eq!(x_view.view::<f64, Ix2>().unwrap(), x.slice(s![..10, ..]));
+	let y_view = npz.by_name("y.npy").unwrap();
+	assert_eq!(y_view.view::<f64, Ix2>().unwrap(), x);
+	// The alignment of the archive is kept.
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new())).with_alignment(4096);
+	npz.add_array("x.npy", &x).unwrap();
+	npz.add_array("y.npy", &x).unwrap();
+	let buffer = npz.finish().unwrap().into_inner();
+	let mut compacted = Vec::<u8>::new();
+	compact(Cursor::new(&buffer), Cursor::new(&mut compacted)).unwrap();
+	let mut zip = ZipArchive::new(Cursor::new(&compacted)).unwrap();
+	for index in 0..zip.len() {
+		assert_eq!(zip.by_index_raw(index).unwrap().data_start() % 4096, 0);
+	}
+}
+
+#[test]
+fn strip_metadata() {
+	use ndarray_npz::{compact, ndarray_npy::WriteNpyExt, strip_metadata, NpzReader};
+	use std::io::{Cursor, Write};
+	use zip::{write::FullFileOptions, CompressionMethod, DateTime, ZipArchive, ZipWriter};
+
+	let time = DateTime::from_date_and_time(2024, 6, 1, 12, 0, 0).unwrap();
+	let mut options = FullFileOptions::default()
+		.compression_method(CompressionMethod::Stored)
+		.last_modified_time(time)
+		.unix_permissions(0o600);
+	options
+		.add_extra_data(0xcafe, vec![1, 2, 3].into(), false)
+		.unwrap();
+	let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+	zip.start_file("x.npy", options).unwrap();
+	let mut npy = Vec::new();
+	Array1::<f64>::ones(3).write_npy(&mut npy).unwrap();
+	zip.write_all(&npy).unwrap();
+	let buffer = zip.finish().unwrap().into_inner();
+	let rewrite = |strip: bool| {
+		let mut rewritten = Cursor::new(Vec::new());
+		if strip {
+			strip_metadata(Cursor::new(&buffer), &mut rewritten).unwrap();
+		} else {
+			compact(Cursor::new(&buffer), &mut rewritten).unwrap();
+		}
+		rewritten.into_inner()
+	};
+	let compacted = rewrite(false);
+	let mut zip = ZipArchive::new(Cursor::new(&compacted)).unwrap();
+	let file = zip.by_name("x.npy").unwrap();
+	assert_eq!(file.last_modified(), Some(time));
+	assert_eq!(file.unix_mode().map(|mode| mode & 0o777), Some(0o600));
+	assert!(file
+		.extra_data()
+		.unwrap()
+		.windows(7)
+		.any(|field| field == [0xfe, 0xca, 3, 0, 1, 2, 3]));
+	drop(file);
+	let stripped = rewrite(true);
+	let mut zip = ZipArchive::new(Cursor::new(&stripped)).unwrap();
+	let file = zip.by_name("x.npy").unwrap();
+	assert_eq!(file.last_modified(), Some(DateTime::default()));
+	assert_eq!(file.unix_mode().map(|mode| mode & 0o777), Some(0o644));
+	assert!(!file
+		.extra_data()
+		.unwrap_or_default()
+		.windows(2)
+		.any(|id| id == [0xfe, 0xca]));
+	drop(file);
+	let mut npz = NpzReader::new(Cursor::new(&stripped)).unwrap();
+	let x: Array1<f64> = npz.by_name("x.npy").unwrap();
+	assert_eq!(x, Array1::<f64>::ones(3));
+}
+
+#[test]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn prune() {
+	use aligned_vec::AVec;
+	use ndarray_npz::{ndarray_npy::WriteNpyExt, prune, NpzView};
+	use std::io::{Cursor, Write};
+	use zip::{write::SimpleFileOptions, CompressionMethod, DateTime, ZipWriter};
+
+	let day = |day| DateTime::from_date_and_time(2024, 6, day, 12, 0, 0).unwrap();
+	let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+	for index in 0..3 {
+		let mut npy = Vec::new();
+		Array1::<f64>::from_elem(100, f64::from(index))
+			.write_npy(&mut npy)
+			.unwrap();
+		let options = SimpleFileOptions::default()
+			.compression_method(CompressionMethod::Stored)
+			.last_modified_time(day(index as u8 + 1));
+		zip.start_file(format!("x.{index}.npy"), options).unwrap();
+		zip.write_all(&npy).unwrap();
+	}
+	let buffer = zip.finish().unwrap().into_inner();
+	let mut pruned = Vec::<u8>::new();
+	let pruning = prune(Cursor::new(&buffer), Cursor::new(&mut pruned), |entry| {
+		assert_eq!(entry.size, entry.compressed_size);
+		!entry.is_older_than(day(2))
+	})
+	.unwrap();
+	assert_eq!(pruning.dropped, ["x.0.npy"]);
+	assert_eq!(pruning.original_size, buffer.len() as u64);
+	assert_eq!(pruning.pruned_size, pruned.len() as u64);
+	assert!(pruning.reclaimed() >= 100 * 8);
+	let pruned = AVec::<u8>::from_slice(64, &pruned);
+	let npz = NpzView::new(&pruned).unwrap();
+	assert_eq!(npz.len(), 2);
+	let x = npz.by_name("x.2.npy").unwrap();
+	assert_eq!(x.view::<f64, Ix1>().unwrap(), Array1::from_elem(100, 2.0));
+}
+
+#[cfg(feature = "compressed")]
+#[test]
+fn quick_compare() {
+	use ndarray_npz::{quick_compare, ComparisonSummary, NpzReader, NpzWriter};
+	use std::io::Cursor;
+
+	let write = |arrays: &[(&str, f64)], compressed: bool| {
+		let mut npz = if compressed {
+			NpzWriter::new_compressed(Cursor::new(Vec::new()))
+		} else {
+			NpzWriter::new(Cursor::new(Vec::new()))
+		};
+		for &(name, value) in arrays {
+			npz.add_array(name, &Array1::from_elem(10, value)).unwrap();
+		}
+		NpzReader::new(npz.finish().unwrap()).unwrap()
+	};
+	let mut a = write(&[("x.npy", 1.0), ("y.npy", 2.0), ("z.npy", 3.0)], false);
+	let mut b = write(&[("w.npy", 0.0), ("y.npy", 5.0), ("x.npy", 1.0)], true);
+	let summary = quick_compare(&mut a, &mut b).unwrap();
+	assert_eq!(
+		summary,
+		ComparisonSummary {
+			identical: vec!["x.npy".into()],
+			different: vec!["y.npy".into()],
+			missing_in_b: vec!["z.npy".into()],
+			missing_in_a: vec!["w.npy".into()],
+		}
+	);
+	assert!(!summary.is_identical());
+	let mut b = write(&[("z.npy", 3.0), ("y.npy", 2.0), ("x.npy", 1.0)], true);
+	assert!(quick_compare(&mut a, &mut b).unwrap().is_identical());
+}
+
+#[test]
+fn read_prefixed() {
+	use ndarray::ArrayD;
+	use ndarray_npz::{NpzReader, NpzWriter};
+	use std::io::Cursor;
+
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new())).with_packing(64);
+	for index in 0..10 {
+		npz.add_array(
+			format!("model/layer{index}/weights"),
+			&Array2::from_elem((50, 50), f64::from(index)),
+		)
+		.unwrap();
+		npz.add_array(format!("model/layer{index}/bias"), &arr0(f64::from(index)))
+			.unwrap();
+	}
+	npz.add_array("optimizer/step", &arr0(1.0)).unwrap();
+	npz.add_bytes("model/config.json", b"{}").unwrap();
+	let buffer = npz.finish().unwrap().into_inner();
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	let model = npz.read_prefixed::<f64>("model/").unwrap();
+	assert_eq!(model.len(), 20);
+	assert!(!model.contains_key("config.json"));
+	for index in 0..10 {
+		let weights = &model[&format!("layer{index}/weights")];
+		assert_eq!(weights.shape(), [50, 50]);
+		assert_eq!(weights.sum(), 2500.0 * f64::from(index));
+		assert_eq!(model[&format!("layer{index}/bias")].ndim(), 0);
+	}
+	assert_eq!(npz.read_prefixed_parallel::<f64>("model/").unwrap(), model);
+	let optimizer = npz.read_prefixed::<f64>("optimizer/").unwrap();
+	assert_eq!(optimizer["step"], ArrayD::from_elem(vec![], 1.0));
+	assert!(npz.read_prefixed::<f64>("none/").unwrap().is_empty());
+	assert!(npz.read_prefixed_parallel::<f32>("model/").is_err());
+}
+
+#[test]
+fn require() {
+	use aligned_vec::AVec;
+	use ndarray_npz::{MissingReport, NpzReader, NpzView, NpzViewMut, NpzWriter};
+	use std::io::Cursor;
+
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new())).with_packing(64);
+	npz.add_array("model/w", &Array1::<f64>::zeros(100))
+		.unwrap();
+	npz.add_array("model/b", &Array1::<f64>::zeros(1)).unwrap();
+	npz.add_bytes("info.txt", b"blob").unwrap();
+	let buffer = npz.finish().unwrap().into_inner();
+	let npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	assert!(npz.contains("model/w"));
+	assert!(npz.contains("model/b"));
+	assert!(npz.contains("info.txt"));
+	assert!(!npz.contains("model/"));
+	assert!(!npz.contains(ndarray_npz::PACKED_NAME));
+	npz.require(&["model/w", "model/b", "info.txt"]).unwrap();
+	let report = npz.contains_all(&["model/w", "optimizer/m", "optimizer/v"]);
+	assert!(!report.is_complete());
+	assert_eq!(report.missing, ["optimizer/m", "optimizer/v"]);
+	let err = npz.require(&["optimizer/m"]).unwrap_err();
+	assert_eq!(
+		err,
+		MissingReport {
+			missing: vec!["optimizer/m".into()]
+		}
+	);
+	assert!(err.to_string().contains("optimizer/m"));
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	npz.add_array("x", &Array1::<f64>::zeros(3)).unwrap();
+	npz.add_bytes("info.txt", b"blob").unwrap();
+	let mut buffer = AVec::<u8>::from_slice(64, &npz.finish().unwrap().into_inner());
+	let npz = NpzView::new(&buffer).unwrap();
+	assert!(npz.contains("x"));
+	assert!(!npz.contains("info.txt"));
+	assert!(npz.contains_all(&["x"]).is_complete());
+	assert_eq!(npz.require(&["x", "y"]).unwrap_err().missing, ["y"]);
+	let mut npz = NpzViewMut::new(&mut buffer).unwrap();
+	drop(npz.by_name("x").unwrap());
+	assert!(npz.contains("x"));
+	assert_eq!(npz.contains_all(&["y", "x", "z"]).missing, ["y", "z"]);
+	assert!(npz.require(&["x"]).is_ok());
+}
+
+#[cfg(feature = "compressed")]
+#[test]
+fn block_diff() {
+	use ndarray_npz::{block_diff, NpzReader, NpzWriter};
+	use std::io::Cursor;
+
+	let write = |x: &Array1<u8>, compressed: bool| {
+		let mut npz = if compressed {
+			NpzWriter::new_compressed(Cursor::new(Vec::new()))
+		} else {
+			NpzWriter::new(Cursor::new(Vec::new()))
+		};
+		npz.add_array("x.npy", x).unwrap();
+		NpzReader::new(npz.finish().unwrap()).unwrap()
+	};
+	let x = Array1::from_iter((0..1000).map(|index| (index % 251) as u8));
+	let mut y = x.clone();
+	y[100] = 0;
+	y[101] = 0;
+	y[500] = 0;
+	y[560] = 0;
+	y[600] = 0;
+	let mut a = write(&x, false);
+	let mut b = write(&y, true);
+	// The data follows the 128-byte header.
+	let diff = block_diff(&mut a, &mut b, "x.npy", 64, None).unwrap();
+	assert_eq!(diff.ranges, [192..256, 576..768]);
+	assert!(!diff.truncated);
+	let diff = block_diff(&mut a, &mut b, "x.npy", 64, Some(1)).unwrap();
+	assert_eq!(diff.ranges, vec![192..256]);
+	assert!(diff.truncated);
+	assert!(
+		block_diff(&mut a, &mut write(&x, true), "x.npy", 0, Some(0))
+			.unwrap()
+			.is_identical()
+	);
+	let mut c = write(&x.slice(ndarray::s![..900]).to_owned(), false);
+	let diff = block_diff(&mut a, &mut c, "x.npy", 4096, None).unwrap();
+	assert_eq!(diff.ranges, vec![0..1128]);
+	assert!(block_diff(&mut a, &mut c, "y.npy", 64, None).is_err());
+}
+
+#[test]
+fn coercion_report() {
+	use ndarray_npz::{coercion_report, Coercion, Dtype, Endianness, NpzReader, NpzWriterBuilder};
+	use std::io::Cursor;
+
+	let foreign = if cfg!(target_endian = "little") {
+		Endianness::Big
+	} else {
+		Endianness::Little
+	};
+	let coercion = |name: &str, dtype, byte_swap, cast, layout| Coercion {
+		name: name.into(),
+		dtype: Some(dtype),
+		byte_swap,
+		cast,
+		layout,
+	};
+	for (endianness, byte_swap) in [(Endianness::Native, false), (foreign, true)] {
+		let mut npz = NpzWriterBuilder::new()
+			.endianness(endianness)
+			.build(Cursor::new(Vec::new()));
+		npz.add_array("x", &Array1::<f64>::zeros(3)).unwrap();
+		npz.add_array("y", &Array1::<i16>::zeros(3)).unwrap();
+		npz.add_array("z", &Array2::<f32>::zeros((2, 3).f()))
+			.unwrap();
+		npz.add_array("w", &Array1::<u8>::zeros(3)).unwrap();
+		npz.add_bytes("meta.json", b"{}").unwrap();
+		let mut npz = NpzReader::new(npz.finish().unwrap()).unwrap();
+		let report = coercion_report(&mut npz).unwrap();
+		assert_eq!(
+			report,
+			[
+				coercion("x", Dtype::F64, byte_swap, false, false),
+				coercion("y", Dtype::I16, byte_swap, true, false),
+				coercion("z", Dtype::F32, byte_swap, false, true),
+				coercion("w", Dtype::U8, false, true, false),
+			]
+		);
+		assert_eq!(report[0].is_native(), !byte_swap);
+	}
+}
+
+#[test]
+fn list_entries_jsonl() {
+	use ndarray_npz::{NpzReader, NpzView, NpzWriter};
+	use std::io::Cursor;
+	use zip::ZipArchive;
+
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	npz.add_array("x", &Array2::<f64>::zeros((2, 3))).unwrap();
+	npz.add_bytes("meta \"1\".json", b"{}").unwrap();
+	let bytes = npz.finish().unwrap().into_inner();
+	let mut zip = ZipArchive::new(Cursor::new(&bytes)).unwrap();
+	let file = zip.by_index(0).unwrap();
+	let dtype = if cfg!(target_endian = "little") {
+		"<f8"
+	} else {
+		">f8"
+	};
+	let x = format!(
+		concat!(
+			r#"{{"name": "x", "dtype": "{}", "fortran_order": false, "shape": [2, 3], "#,
+			r#""size": {}, "compressed_size": {}, "method": 0, "header_offset": {}, "#,
+			r#""data_offset": {}, "crc32": {}}}"#,
+		),
+		dtype,
+		file.size(),
+		file.size(),
+		file.header_start(),
+		file.data_start(),
+		file.crc32(),
+	);
+	drop(file);
+	let mut listing = Vec::new();
+	NpzReader::new(Cursor::new(&bytes))
+		.unwrap()
+		.list_entries_jsonl(&mut listing)
+		.unwrap();
+	let listing = String::from_utf8(listing).unwrap();
+	let lines = listing.lines().collect::<Vec<_>>();
+	assert_eq!(lines.len(), 2);
+	assert_eq!(lines[0], x);
+	assert!(lines[1].starts_with(
+		r#"{"name": "meta \"1\".json", "dtype": null, "fortran_order": null, "shape": null, "size": 2, "#
+	));
+	let bytes = aligned_vec::AVec::<u8>::from_slice(64, &bytes);
+	let mut view_listing = Vec::new();
+	NpzView::new(&bytes)
+		.unwrap()
+		.list_entries_jsonl(&mut view_listing)
+		.unwrap();
+	assert_eq!(String::from_utf8(view_listing).unwrap(), listing);
+}
+
+#[test]
+fn zip64() {
+	use ndarray_npz::{NpzReader, NpzWriter};
+	use std::{
+		collections::HashMap,
+		io::{self, Read, Seek, SeekFrom, Write},
+	};
+
+	/// Sparse in-memory file keeping blocks of nonzero bytes only.
+	#[derive(Default)]
+	struct Sparse {
+		blocks: HashMap<u64, Vec<u8>>,
+		pos: u64,
+		len: u64,
+	}
+	const BLOCK: u64 = 1 << 16;
+	impl Write for Sparse {
+		fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+			let offset = (self.pos % BLOCK) as usize;
+			let len = buf.len().min(BLOCK as usize - offset);
+			let buf = &buf[..len];
+			let block = self.blocks.get_mut(&(self.pos / BLOCK));
+			match block {
+				Some(block) => block[offset..offset + len].copy_from_slice(buf),
+				None if buf.iter().all(|&byte| byte == 0) => {}
+				None => {
+					let mut block = vec![0; BLOCK as usize];
+					block[offset..offset + len].copy_from_slice(buf);
+					self.blocks.insert(self.pos / BLOCK, block);
+				}
+			}
+			self.pos += len as u64;
+			self.len = self.len.max(self.pos);
+			Ok(len)
+		}
+		fn flush(&mut self) -> io::Result<()> {
+			Ok(())
+		}
+	}
+	impl Read for Sparse {
+		fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+			let offset = (self.pos % BLOCK) as usize;
+			let len = buf
+				.len()
+				.min(BLOCK as usize - offset)
+				.min(self.len.saturating_sub(self.pos) as usize);
+			match self.blocks.get(&(self.pos / BLOCK)) {
+				Some(block) => buf[..len].copy_from_slice(&block[offset..offset + len]),
+				None => buf[..len].fill(0),
+			}
+			self.pos += len as u64;
+			Ok(len)
+		}
+	}
+	impl Seek for Sparse {
+		fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+			self.pos = match pos {
+				SeekFrom::Start(pos) => pos,
+				SeekFrom::End(pos) => self.len.checked_add_signed(pos).unwrap(),
+				SeekFrom::Current(pos) => self.pos.checked_add_signed(pos).unwrap(),
+			};
+			Ok(self.pos)
+		}
+	}
+
+	// Broadcasting keeps the array of more than 4 GiB of zeros out of memory.
+	let len = 537_000_000;
+	let zero = arr0(0.0f64);
+	let x = zero.broadcast(len).unwrap();
+	let mut npz = NpzWriter::new(Sparse::default());
+	npz.add_array("x", &x).unwrap();
+	npz.add_array("y", &Array1::from_elem(3, 1.0)).unwrap();
+	let mut zip = zip::ZipArchive::new(npz.finish().unwrap()).unwrap();
+	let x = zip.by_name("x").unwrap();
+	assert!(x.size() > len as u64 * 8);
+	assert!(x.size() > u64::from(u32::MAX));
+	drop(x);
+	let mut npz = NpzReader::new(zip.into_inner()).unwrap();
+	assert_eq!(npz.names().unwrap(), ["x", "y"]);
+	let y: Array1<f64> = npz.by_name("y").unwrap();
+	assert_eq!(y, Array1::from_elem(3, 1.0));
+}
+
+#[test]
+fn with_alignment() {
+	use ndarray_npz::NpzWriter;
+	use std::io::Cursor;
+	use zip::ZipArchive;
+
+	let write = |alignment| {
+		let mut npz = NpzWriter::new(Cursor::new(Vec::new())).with_alignment(alignment);
+		npz.add_array("x", &Array1::<f64>::zeros(10)).unwrap();
+		npz.add_array("y", &Array1::<u8>::zeros(3)).unwrap();
+		npz.add_array("z", &Array1::<f64>::zeros(10)).unwrap();
+		ZipArchive::new(npz.finish().unwrap()).unwrap()
+	};
+	let mut zip = write(4096);
+	for index in 0..zip.len() {
+		assert_eq!(zip.by_index(index).unwrap().data_start() % 4096, 0);
+	}
+	let mut zip = write(1);
+	let x = zip.by_index(0).unwrap();
+	let end = x.data_start() + x.size();
+	drop(x);
+	assert_eq!(zip.by_index(1).unwrap().header_start(), end);
+}
+
+#[test]
+fn add_array_f() {
+	use ndarray_npz::{coercion_report, NpzReader, NpzWriter};
+	use std::io::Cursor;
+
+	let x = Array3::from_shape_fn((2, 3, 4), |(i, j, k)| (i * 100 + j * 10 + k) as i64);
+	let mut f = Array3::zeros((2, 3, 4).f());
+	f.assign(&x);
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	npz.add_array_f("c", &x).unwrap();
+	npz.add_array_f("f", &f).unwrap();
+	npz.add_array_f("s", &x.slice(s![.., 1.., ..;2])).unwrap();
+	let mut npz = NpzReader::new(npz.finish().unwrap()).unwrap();
+	let report = coercion_report(&mut npz).unwrap();
+	assert!(report.iter().all(|coercion| coercion.layout));
+	for name in ["c", "f"] {
+		let y: Array3<i64> = npz.by_name(name).unwrap();
+		assert_eq!(y, x);
+		assert!(y.t().is_standard_layout());
+	}
+	let y: Array3<i64> = npz.by_name("s").unwrap();
+	assert_eq!(y, x.slice(s![.., 1.., ..;2]));
+}
+
+#[cfg(feature = "compressed")]
+#[test]
+fn diagnostics() {
+	use aligned_vec::AVec;
+	use ndarray_npz::{ndarray_npy::WriteNpyExt, Diagnostic, Fix, Issue, NpzView, NpzViewMut};
+	use std::io::{Cursor, Write};
+	use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+	let mut npy = Vec::new();
+	Array1::<f64>::ones(10).write_npy(&mut npy).unwrap();
+	let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+	let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+	zip.start_file("a.npy", stored).unwrap();
+	zip.write_all(&npy).unwrap();
+	zip.start_file("c.npy", stored.with_alignment(64)).unwrap();
+	zip.write_all(&npy).unwrap();
+	let deflated = stored.compression_method(CompressionMethod::Deflated);
+	zip.start_file("b.npy", deflated).unwrap();
+	zip.write_all(&npy).unwrap();
+	let buffer = zip.finish().unwrap().into_inner();
+	let mut buffer = AVec::<u8>::from_slice(64, &buffer);
+	let expected = [
+		Diagnostic {
+			name: "a.npy".into(),
+			issue: Issue::Misaligned { offset: 35 },
+			fix: Fix::Realign,
+		},
+		Diagnostic {
+			name: "b.npy".into(),
+			issue: Issue::Compressed,
+			fix: Fix::Store,
+		},
+	];
+	let npz = NpzView::new(&buffer).unwrap();
+	assert_eq!(npz.diagnostics(), expected);
+	assert_eq!(
+		expected[0].to_string(),
+		"a.npy: misaligned by 35 bytes, rewrite the archive 64-byte aligned"
+	);
+	let npz = NpzViewMut::new(&mut buffer).unwrap();
+	assert_eq!(npz.diagnostics(), expected);
+}
+
+#[test]
+fn verify_deterministic() {
+	use ndarray_npz::{verify_deterministic, NpzWriter, Region};
+	use std::io::{self, Cursor, Read, Seek, SeekFrom, Write};
+
+	// Flips a bit of the array data as a faulty writer would.
+	struct Faulty(Cursor<Vec<u8>>);
+	impl Read for Faulty {
+		fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+			self.0.read(buf)
+		}
+	}
+	impl Write for Faulty {
+		fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+			let position = self.0.position();
+			let len = self.0.write(buf)?;
+			if (position..position + len as u64).contains(&1000) {
+				self.0.get_mut()[1000] ^= 1;
+			}
+			Ok(len)
+		}
+		fn flush(&mut self) -> io::Result<()> {
+			Ok(())
+		}
+	}
+	impl Seek for Faulty {
+		fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+			self.0.seek(pos)
+		}
+	}
+
+	let write = |name: &str, y: f64| {
+		let mut npz = NpzWriter::new(Cursor::new(Vec::new())).with_self_check();
+		npz.add_array("x.npy", &Array1::<f64>::zeros(100)).unwrap();
+		npz.add_array(name, &Array1::from_elem(100, y)).unwrap();
+		npz.finish().unwrap()
+	};
+	let a = write("y.npy", 1.0);
+	assert_eq!(
+		verify_deterministic(a.clone(), write("y.npy", 1.0)).unwrap(),
+		None
+	);
+	// The checksum of the local header written back after the data differs first.
+	let divergence = verify_deterministic(a.clone(), write("y.npy", 2.0))
+		.unwrap()
+		.unwrap();
+	assert_eq!(divergence.region, Region::LocalHeader("y.npy".into()));
+	let divergence = verify_deterministic(a.clone(), write("z.npy", 1.0))
+		.unwrap()
+		.unwrap();
+	assert_eq!(divergence.region, Region::LocalHeader("y.npy".into()));
+	let b = Cursor::new(a.get_ref()[..a.get_ref().len() - 1].to_vec());
+	let divergence = verify_deterministic(a.clone(), b).unwrap().unwrap();
+	assert_eq!(divergence.offset, a.get_ref().len() as u64 - 1);
+	assert_eq!(divergence.region, Region::CentralDirectory);
+
+	let mut npz = NpzWriter::new(Faulty(Cursor::new(Vec::new()))).with_self_check();
+	npz.add_array("x.npy", &Array1::<f64>::zeros(1000)).unwrap();
+	assert!(npz.finish().is_err());
+}
+
+#[test]
+fn numpy_compat() {
+	use ndarray_npz::{NpzReader, NpzWriter};
+	use std::io::Cursor;
+	use zip::{DateTime, ZipArchive};
+
+	let x = array![[1.0, 2.0], [3.0, 4.0]];
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::numpy_compat(Cursor::new(&mut buffer))
+			.with_last_modified_time(DateTime::default());
+		npz.add_array("x", &x).unwrap();
+		npz.add_array("y.npy", &x).unwrap();
+		npz.finish().unwrap();
+	}
+	let mut zip = ZipArchive::new(Cursor::new(&buffer)).unwrap();
+	for index in 0..zip.len() {
+		let file = zip.by_index_raw(index).unwrap();
+		assert_eq!(file.unix_mode().map(|mode| mode & 0o777), Some(0o600));
+		assert_eq!(file.last_modified(), Some(DateTime::default()));
+	}
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	let mut names = npz.names().unwrap();
+	names.sort();
+	assert_eq!(names, ["x.npy", "y.npy"]);
+	let y: Array2<f64> = npz.by_name("x.npy").unwrap();
+	assert_eq!(y, x);
+}
+
+#[test]
+fn pack_bundle() {
+	use ndarray_npz::{pack_bundle, unpack_bundle, NpzReader, NpzWriter};
+	use std::io::Cursor;
+
+	let a = array![[1.0, 2.0], [3.0, 4.0]];
+	let b = array![1i32, 2, 3];
+	let mut npz = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut npz));
+		npz.add_array("a.npy", &a).unwrap();
+		npz.add_array("b.npy", &b).unwrap();
+		npz.finish().unwrap();
+	}
+	let mut bundle = Vec::<u8>::new();
+	let mut npz = NpzReader::new(Cursor::new(&npz)).unwrap();
+	pack_bundle(&mut npz, &mut bundle).unwrap();
+	let len = 10 + usize::from(u16::from_le_bytes([bundle[8], bundle[9]]));
+	assert_eq!(len % 64, 0);
+	assert_eq!(bundle.len(), len + 4 * 8 + 3 * 4);
+	let header = std::str::from_utf8(&bundle[10..len]).unwrap();
+	assert!(header.starts_with("{'descr': [('a', '<f8', (2, 2)), ('b', '<i4', (3,))]"));
+	let mut npz = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut npz));
+		unpack_bundle(bundle.as_slice(), &mut npz).unwrap();
+		npz.finish().unwrap();
+	}
+	let mut npz = NpzReader::new(Cursor::new(&npz)).unwrap();
+	assert_eq!(npz.names().unwrap(), ["a.npy", "b.npy"]);
+	let c: Array2<f64> = npz.by_name("a.npy").unwrap();
+	let d: Array1<i32> = npz.by_name("b.npy").unwrap();
+	assert_eq!((c, d), (a, b));
+}
+
+#[cfg(feature = "swap-endian")]
+#[test]
+#[allow(clippy::float_cmp)]
+fn with_native_endian() {
+	use aligned_vec::AVec;
+	use ndarray_npz::{NpzReader, NpzViewMut};
+	use std::io::{Cursor, Write};
+	use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+	// Encode `.npy` file in foreign byte order.
+	let x = array![1.0, 2.0, 3.0];
+	let (descr, to_bytes): (_, fn(f64) -> [u8; 8]) = if cfg!(target_endian = "little") {
+		(">f8", f64::to_be_bytes)
+	} else {
+		("<f8", f64::to_le_bytes)
+	};
+	let mut npy = b"\x93NUMPY\x01\x00\x76\x00".to_vec();
+	npy.extend(format!("{{'descr': '{descr}', 'fortran_order': False, 'shape': (3,), }}").bytes());
+	npy.resize(127, b' ');
+	npy.push(b'\n');
+	npy.extend(x.iter().copied().flat_map(to_bytes));
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut zip = ZipWriter::new(Cursor::new(&mut buffer));
+		let options = SimpleFileOptions::default()
+			.with_alignment(64)
+			.compression_method(CompressionMethod::Stored);
+		zip.start_file("x.npy", options).unwrap();
+		zip.write_all(&npy).unwrap();
+		zip.finish().unwrap();
+	}
+	let mut buffer = AVec::<u8>::from_slice(64, &buffer);
+	{
+		let mut npz = NpzViewMut::new(&mut buffer).unwrap();
+		let mut npy = npz.by_name("x.npy").unwrap();
+		npy.view_mut::<f64, Ix1>().unwrap_err();
+		let sum = npy
+			.with_native_endian::<f64, Ix1, _, _>(|mut view| {
+				view += 1.0;
+				view.sum()
+			})
+			.unwrap();
+		assert_eq!(sum, 9.0);
+	}
+	let y: Array1<f64> = NpzReader::new(Cursor::new(&buffer[..]))
+		.unwrap()
+		.by_name("x.npy")
+		.unwrap();
+	assert_eq!(y, x + 1.0);
+}
+
+#[test]
+fn replace_entry_data() {
+	use aligned_vec::AVec;
+	use ndarray_npz::{NpzReader, NpzViewMut, NpzWriter, ViewNpzError};
+	use std::io::Cursor;
+
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+		npz.add_array("x.npy", &Array2::<f64>::zeros((2, 3)))
+			.unwrap();
+		npz.add_array("y.npy", &Array1::<i32>::zeros(4)).unwrap();
+		npz.finish().unwrap();
+	}
+	let len = buffer.len();
+	let mut buffer = AVec::<u8>::from_slice(64, &buffer);
+	let x = array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]];
+	{
+		let mut npz = NpzViewMut::new(&mut buffer).unwrap();
+		// Replace in Fortran order.
+		let crc32 = npz
+			.replace_entry_data("x.npy", &x.t().to_owned().reversed_axes())
+			.unwrap();
+		assert!(matches!(
+			npz.replace_entry_data("x.npy", &Array2::<f32>::zeros((2, 3))),
+			Err(ViewNpzError::DtypeMismatch)
+		));
+		assert!(matches!(
+			npz.replace_entry_data("x.npy", &Array2::<f64>::zeros((3, 2))),
+			Err(ViewNpzError::ShapeMismatch)
+		));
+		let mut npy = npz.by_name("x.npy").unwrap();
+		assert_eq!(npy.verify().unwrap(), crc32);
+		assert!(matches!(
+			npz.replace_entry_data("x.npy", &x),
+			Err(ViewNpzError::MovedNpyViewMut)
+		));
+	}
+	assert_eq!(buffer.len(), len);
+	let mut npz = NpzReader::new(Cursor::new(&buffer[..])).unwrap();
+	let y: Array2<f64> = npz.by_name("x.npy").unwrap();
+	assert_eq!(y, x);
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn apply() {
+	use aligned_vec::AVec;
+	use ndarray_npz::{NpzReader, NpzViewMut, NpzWriter, ViewNpzError};
+	use std::io::Cursor;
+
+	let names = ["a.npy", "b.npy", "c.npy", "d.npy"];
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+		for (len, name) in names.iter().enumerate() {
+			npz.add_array(*name, &Array1::<f64>::ones(len + 1)).unwrap();
+		}
+		npz.add_array("e.npy", &Array1::<i32>::ones(5)).unwrap();
+		npz.finish().unwrap();
+	}
+	let mut buffer = AVec::<u8>::from_slice(64, &buffer);
+	{
+		let mut npz = NpzViewMut::new(&mut buffer).unwrap();
+		npz.apply::<f64, Ix1, _, _>(&names[..2], |mut view| view *= 2.0)
+			.unwrap();
+		npz.apply_parallel::<f64, Ix1, _, _>(&names[1..], |mut view| view += 1.0)
+			.unwrap();
+		assert!(matches!(
+			npz.apply::<f64, Ix1, _, _>(&["a.npy", "e.npy"], |mut view| view *= 0.0),
+			Err(ViewNpzError::Npy(_))
+		));
+		for name in names {
+			npz.by_name(name).unwrap().verify().unwrap();
+		}
+	}
+	let mut npz = NpzReader::new(Cursor::new(&buffer[..])).unwrap();
+	for (name, value) in names.into_iter().zip([2.0, 3.0, 2.0, 2.0]) {
+		let x: Array1<f64> = npz.by_name(name).unwrap();
+		assert!(x.iter().all(|&x| x == value));
+	}
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn scope() {
+	use aligned_vec::AVec;
+	use ndarray_npz::{ChecksumStatus, NpzViewMut, NpzWriter};
+	use std::{
+		io::Cursor,
+		panic::{self, AssertUnwindSafe},
+		thread,
+	};
+
+	let names = ["a.npy", "b.npy", "c.npy"];
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+		for (len, name) in names.iter().enumerate() {
+			npz.add_array(*name, &Array1::<f64>::ones(len + 1)).unwrap();
+		}
+		npz.finish().unwrap();
+	}
+	let mut buffer = AVec::<u8>::from_slice(64, &buffer);
+	let mut npz = NpzViewMut::new(&mut buffer).unwrap();
+	let visited = npz.scope(|views| {
+		thread::scope(|scope| {
+			for (_name, view) in views {
+				scope.spawn(move || view.view_mut::<f64, Ix1>().unwrap().fill(2.0));
+			}
+		});
+		3
+	});
+	assert_eq!(visited, 3);
+	let result = panic::catch_unwind(AssertUnwindSafe(|| {
+		npz.scope(|views| {
+			assert_eq!(
+				views.iter().map(|(name, _view)| *name).collect::<Vec<_>>(),
+				names
+			);
+			for (_name, view) in views {
+				view.view_mut::<f64, Ix1>().unwrap().fill(3.0);
+			}
+			panic!("interrupted");
+		});
+	}));
+	assert!(result.is_err());
+	assert_eq!(npz.poisoned_names().count(), 3);
+	let mut a = npz.by_name("a.npy").unwrap();
+	assert_eq!(a.status(), ChecksumStatus::Poisoned);
+	assert!(a.view::<f64, Ix1>().unwrap().iter().all(|&x| x == 3.0));
+	a.update();
+	a.verify().unwrap();
+	npz.scope(|views| assert_eq!(views.len(), 2));
+	for name in &names[1..] {
+		npz.by_name(name).unwrap().update();
+	}
+}
+
+#[test]
+fn poison() {
+	use aligned_vec::AVec;
+	use ndarray_npz::{ChecksumStatus, NpzView, NpzViewMut, NpzWriter, ViewNpzError};
+	use std::{
+		io::Cursor,
+		panic::{self, AssertUnwindSafe},
+	};
+
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+		npz.add_array("a.npy", &Array1::<f64>::ones(4)).unwrap();
+		npz.add_array("b.npy", &Array1::<f64>::ones(4)).unwrap();
+		npz.finish().unwrap();
+	}
+	let mut buffer = AVec::<u8>::from_slice(64, &buffer);
+	{
+		let mut npz = NpzViewMut::new(&mut buffer).unwrap();
+		let mut a = npz.by_name("a.npy").unwrap();
+		let mut b = npz.by_name("b.npy").unwrap();
+		let result = panic::catch_unwind(AssertUnwindSafe(move || {
+			a.view_mut::<f64, Ix1>().unwrap().fill(2.0);
+			panic!("interrupted");
+		}));
+		assert!(result.is_err());
+		b.view_mut::<f64, Ix1>().unwrap().fill(3.0);
+	}
+	{
+		let npz = NpzView::new(&buffer).unwrap();
+		assert_eq!(npz.poisoned_names().collect::<Vec<_>>(), ["a.npy"]);
+		let mut a = npz.by_name("a.npy").unwrap();
+		assert_eq!(a.status(), ChecksumStatus::Poisoned);
+		assert!(matches!(a.verify(), Err(ViewNpzError::Poisoned)));
+		npz.by_name("b.npy").unwrap().verify().unwrap();
+	}
+	{
+		let mut npz = NpzViewMut::new(&mut buffer).unwrap();
+		let mut a = npz.by_name("a.npy").unwrap();
+		assert!(matches!(a.verify(), Err(ViewNpzError::Poisoned)));
+		a.update();
+	}
+	let npz = NpzView::new(&buffer).unwrap();
+	assert_eq!(npz.poisoned_names().count(), 0);
+	npz.by_name("a.npy").unwrap().verify().unwrap();
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn reduce() {
+	use ndarray_npz::{NpzReader, NpzWriter, Reduction};
+	use std::io::Cursor;
+
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+		npz.add_array("x.npy", &array![[3.0, -1.0], [f64::NAN, 6.0]])
+			.unwrap();
+		npz.add_array("y.npy", &Array1::from_iter(0..100_000i32))
+			.unwrap();
+		npz.add_array("z.npy", &Array1::<f32>::zeros(0)).unwrap();
+		npz.finish().unwrap();
+	}
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	assert!(npz.reduce("x.npy", Reduction::Min).unwrap().is_nan());
+	assert!(npz.reduce("x.npy", Reduction::Sum).unwrap().is_nan());
+	assert_eq!(npz.reduce("x.npy", Reduction::NanCount).unwrap(), 1.0);
+	assert_eq!(npz.reduce("y.npy", Reduction::Min).unwrap(), 0.0);
+	assert_eq!(npz.reduce("y.npy", Reduction::Max).unwrap(), 99_999.0);
+	assert_eq!(
+		npz.reduce("y.npy", Reduction::Sum).unwrap(),
+		4_999_950_000.0
+	);
+	assert_eq!(npz.reduce("y.npy", Reduction::Mean).unwrap(), 49_999.5);
+	assert_eq!(npz.reduce("y.npy", Reduction::NanCount).unwrap(), 0.0);
+	assert!(npz.reduce("z.npy", Reduction::Max).unwrap().is_nan());
+	assert!(npz.reduce("z.npy", Reduction::Mean).unwrap().is_nan());
+	assert_eq!(npz.reduce("z.npy", Reduction::Sum).unwrap(), 0.0);
+}
+
+#[test]
+fn stats() {
+	use ndarray_npz::{NpzReader, NpzWriter};
+	use std::io::Cursor;
+
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+		npz.add_array(
+			"x.npy",
+			&array![2.0, 4.0, f64::NAN, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0],
+		)
+		.unwrap();
+		npz.add_array("y.npy", &array![1u8, 2, 3]).unwrap();
+		npz.add_array("z.npy", &Array1::<f32>::zeros(0)).unwrap();
+		npz.finish().unwrap();
+	}
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	let bins = [0.0, 4.0, 8.0, 9.0];
+	let x = npz.stats("x.npy", &bins).unwrap();
+	assert_eq!((x.len, x.nan_count), (9, 1));
+	assert_eq!((x.min, x.max), (2.0, 9.0));
+	assert!((x.mean - 5.0).abs() < 1e-12 && (x.std - 2.0).abs() < 1e-12);
+	assert_eq!(x.histogram, [1, 6, 1]);
+	let y = npz.stats("y.npy", &[]).unwrap();
+	assert_eq!((y.min, y.max, y.mean), (1.0, 3.0, 2.0));
+	assert!(y.histogram.is_empty());
+	let all = npz.stats_all(&bins).unwrap();
+	assert_eq!(all.len(), 2);
+	assert_eq!(all[0], ("x.npy".to_string(), x));
+	assert_eq!(all[1].0, "z.npy");
+	assert_eq!(
+		(all[1].1.len, all[1].1.histogram.as_slice()),
+		(0, &[0, 0, 0][..])
+	);
+	assert!(all[1].1.mean.is_nan());
+}
+
+#[test]
+fn find_nonfinite() {
+	use ndarray_npz::{NpzReader, NpzWriter};
+	use std::io::Cursor;
+
+	let mut x = Array3::<f32>::zeros((2, 3, 4));
+	x[[0, 1, 2]] = f32::NAN;
+	x[[1, 0, 3]] = f32::INFINITY;
+	x[[1, 2, 0]] = f32::NEG_INFINITY;
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+		npz.add_array("x.npy", &x).unwrap();
+		npz.add_array("y.npy", &x.t()).unwrap();
+		npz.add_array("z.npy", &Array1::<f64>::zeros(5)).unwrap();
+		npz.add_array("i.npy", &Array1::<i32>::zeros(5)).unwrap();
+		npz.finish().unwrap();
+	}
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	let names = ["x.npy", "y.npy", "z.npy", "i.npy"];
+	let nonfinite = npz.find_nonfinite(&names, 2).unwrap();
+	assert_eq!(nonfinite.len(), 2);
+	assert_eq!(nonfinite[0].0, "x.npy");
+	assert_eq!(nonfinite[0].1, [IxDyn(&[0, 1, 2]), IxDyn(&[1, 0, 3])]);
+	assert_eq!(nonfinite[1].0, "y.npy");
+	assert_eq!(nonfinite[1].1, [IxDyn(&[2, 1, 0]), IxDyn(&[3, 0, 1])]);
+}
+
+#[test]
+fn npy_names() {
+	use aligned_vec::AVec;
+	use ndarray_npz::{ndarray_npy::WriteNpyExt, NpzReader, NpzView, NpzViewMut, ViewNpzError};
+	use std::io::{Cursor, Write};
+	use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+	let x = array![1.0, 2.0, 3.0];
+	let mut npy = Vec::new();
+	x.write_npy(&mut npy).unwrap();
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut zip = ZipWriter::new(Cursor::new(&mut buffer));
+		let options = SimpleFileOptions::default()
+			.with_alignment(64)
+			.compression_method(CompressionMethod::Stored);
+		zip.start_file("weights.bin", options).unwrap();
+		zip.write_all(&npy).unwrap();
+		zip.start_file("info.json", options).unwrap();
+		zip.write_all(b"{}").unwrap();
+		zip.finish().unwrap();
+	}
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	assert_eq!(npz.npy_names().unwrap(), ["weights.bin"]);
+	assert_eq!(npz.blob_names().unwrap(), ["info.json"]);
+	assert_eq!(npz.read_bytes("info.json").unwrap(), b"{}");
+	let y: Array1<f64> = npz.by_name("weights.bin").unwrap();
+	assert_eq!(y, x);
+	let mut buffer = AVec::<u8>::from_slice(64, &buffer);
+	{
+		let npz = NpzView::new(&buffer).unwrap();
+		assert_eq!(npz.names().collect::<Vec<_>>(), ["weights.bin"]);
+		assert_eq!(npz.blob_names().collect::<Vec<_>>(), ["info.json"]);
+		assert_eq!(npz.raw("info.json").unwrap(), b"{}");
+		assert_eq!(npz.raw("weights.bin").unwrap(), npy);
+		assert!(matches!(npz.by_name("info.json"), Err(ViewNpzError::Blob)));
+		let y = npz.by_name("weights.bin").unwrap();
+		assert_eq!(y.view::<f64, Ix1>().unwrap(), x);
+	}
+	let mut npz = NpzViewMut::new(&mut buffer).unwrap();
+	assert_eq!(npz.len(), 1);
+	assert_eq!(npz.blob_names().collect::<Vec<_>>(), ["info.json"]);
+	assert!(matches!(npz.by_name("info.json"), Err(ViewNpzError::Blob)));
+	npz.by_name("weights.bin").unwrap().verify().unwrap();
+}
+
+#[test]
+fn add_bytes() {
+	use ndarray_npy::WriteNpyExt;
+	use ndarray_npz::{NpzReader, NpzWriter, WriteNpzError};
+	use std::io::Cursor;
+	use zip::result::ZipError;
+
+	let config = br#"{"learning_rate": 0.001}"#;
+	let mut npy = Vec::new();
+	array![1u8, 2, 3].write_npy(&mut npy).unwrap();
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+		npz.add_array("x.npy", &array![1u8, 2, 3]).unwrap();
+		npz.add_bytes("config.json", config).unwrap();
+		assert!(matches!(
+			npz.add_bytes("y.bin", &npy),
+			Err(WriteNpzError::Zip(ZipError::InvalidArchive(_)))
+		));
+		npz.add_bytes("empty", &[]).unwrap();
+		npz.finish().unwrap();
+	}
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	assert_eq!(npz.names().unwrap(), ["x.npy", "config.json", "empty"]);
+	assert_eq!(npz.npy_names().unwrap(), ["x.npy"]);
+	assert_eq!(npz.blob_names().unwrap(), ["config.json", "empty"]);
+	assert_eq!(npz.read_bytes("config.json").unwrap(), config);
+	assert!(npz.read_bytes("empty").unwrap().is_empty());
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn add_json() {
+	use ndarray_npz::{JsonNpzError, NpzReader, NpzWriter};
+	use serde_json::{json, Value};
+	use std::io::Cursor;
+
+	let config = json!({"epochs": 10, "layers": [64, 32], "name": "mlp"});
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+		npz.add_array("x.npy", &array![1.0, 2.0]).unwrap();
+		npz.add_json("config", &config).unwrap();
+		npz.add_json("labels.json", &["cat", "dog"]).unwrap();
+		npz.finish().unwrap();
+	}
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	assert_eq!(npz.blob_names().unwrap(), ["config.json", "labels.json"]);
+	assert_eq!(npz.read_json::<Value>("config.json").unwrap(), config);
+	assert_eq!(
+		npz.read_json::<Vec<String>>("labels").unwrap(),
+		["cat", "dog"]
+	);
+	assert!(matches!(
+		npz.read_json::<Vec<u32>>("labels"),
+		Err(JsonNpzError::Json(_))
+	));
+	assert!(matches!(
+		npz.read_json::<Value>("missing"),
+		Err(JsonNpzError::Zip(_))
+	));
+}
+
+#[test]
+fn provenance() {
+	use ndarray_npz::{NpzReader, NpzWriter, Provenance, PROVENANCE_NAME};
+	use std::{
+		io::Cursor,
+		time::{Duration, UNIX_EPOCH},
+	};
+
+	let provenance = Provenance::new("train \"mlp\" 1.2.0")
+		.with_field("commit", "8f2e1c0")
+		.with_field("seed", "42");
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer)).with_provenance(provenance.clone());
+		npz.add_array("x.npy", &array![1.0, 2.0]).unwrap();
+		npz.finish().unwrap();
+	}
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	assert_eq!(npz.blob_names().unwrap(), [PROVENANCE_NAME]);
+	assert_eq!(npz.provenance().unwrap(), Some(provenance));
+	// Written by another tool.
+	let json = r#"{"created": 1735689600.5, "crate_version": "0.4.1", "creator": "numpy",
+		"host": "node-7", "fields": {}}"#;
+	let provenance = Provenance::parse(json).unwrap();
+	assert_eq!(provenance.creator, "numpy");
+	assert_eq!(
+		provenance.created,
+		UNIX_EPOCH + Duration::from_millis(1_735_689_600_500)
+	);
+	assert!(provenance.fields.is_empty());
+	assert!(Provenance::parse(r#"{"creator": "numpy"}"#).is_err());
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+		npz.add_array("x.npy", &array![1.0, 2.0]).unwrap();
+		npz.finish().unwrap();
+	}
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	assert_eq!(npz.provenance().unwrap(), None);
+}
+
+#[test]
+fn add_entry() {
+	use ndarray_npz::{EntrySink, EntrySource, NpzReader, NpzWriter};
+	use std::io::{self, Cursor, Read, Write};
+
+	/// Unsigned 4-bit integers packed two per byte.
+	#[derive(Debug, PartialEq)]
+	struct Nibbles(Vec<u8>);
+
+	impl EntrySink for Nibbles {
+		fn write_entry<W: Write>(&self, mut writer: W) -> io::Result<()> {
+			for pair in self.0.chunks(2) {
+				writer.write_all(&[pair[0] | pair.get(1).map_or(0, |high| high << 4)])?;
+			}
+			Ok(())
+		}
+	}
+
+	impl EntrySource for Nibbles {
+		fn read_entry<R: Read>(mut reader: R) -> io::Result<Self> {
+			let mut bytes = Vec::new();
+			reader.read_to_end(&mut bytes)?;
+			Ok(Nibbles(
+				bytes
+					.iter()
+					.flat_map(|byte| [byte & 15, byte >> 4])
+					.collect(),
+			))
+		}
+	}
+
+	/// Reads the first byte only.
+	struct First(u8);
+
+	impl EntrySource for First {
+		fn read_entry<R: Read>(mut reader: R) -> io::Result<Self> {
+			let mut byte = [0];
+			reader.read_exact(&mut byte)?;
+			Ok(First(byte[0]))
+		}
+	}
+
+	let nibbles = Nibbles(vec![1, 2, 3, 4, 15, 0, 7, 9]);
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+		npz.add_array("x.npy", &array![1.0, 2.0]).unwrap();
+		npz.add_entry("x.nibbles", &nibbles).unwrap();
+		npz.finish().unwrap();
+	}
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	assert_eq!(npz.blob_names().unwrap(), ["x.nibbles"]);
+	assert_eq!(npz.read_entry::<Nibbles>("x.nibbles").unwrap(), nibbles);
+	assert_eq!(npz.read_entry::<First>("x.nibbles").unwrap().0, 0x21);
+	// Corrupt the last byte of the stored data, which the partial decoder does not read.
+	let data = [0x21, 0x43, 0x0f, 0x97];
+	let offset = buffer
+		.windows(data.len())
+		.position(|window| window == data)
+		.unwrap();
+	buffer[offset + 3] ^= 0xff;
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	assert!(npz.read_entry::<First>("x.nibbles").is_err());
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn open_gzip() {
+	use flate2::{write::GzEncoder, Compression};
+	use ndarray_npz::{Buffering, Container, ContainerReader, NpzReader, NpzWriter};
+	use std::io::{Cursor, Write};
+
+	let x = array![[1.0, 2.0], [3.0, 4.0]];
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+		npz.add_array("x.npy", &x).unwrap();
+		npz.finish().unwrap();
+	}
+	let mut gzip = GzEncoder::new(Vec::new(), Compression::default());
+	gzip.write_all(&buffer).unwrap();
+	let gzip = gzip.finish().unwrap();
+	let plain_path = std::env::temp_dir().join("ndarray_npz_open_gzip.npz");
+	let gzip_path = std::env::temp_dir().join("ndarray_npz_open_gzip.npz.gz");
+	std::fs::write(&plain_path, &buffer).unwrap();
+	std::fs::write(&gzip_path, &gzip).unwrap();
+	for buffering in [Buffering::Memory, Buffering::TempFile] {
+		let mut npz = NpzReader::open_with(&gzip_path, buffering).unwrap();
+		let y: Array2<f64> = npz.by_name("x.npy").unwrap();
+		assert_eq!(x, y);
+	}
+	assert!(NpzReader::open_with(&gzip_path, Buffering::Reject).is_err());
+	let mut npz = NpzReader::open_with(&plain_path, Buffering::Reject).unwrap();
+	let y: Array2<f64> = npz.by_name("x.npy").unwrap();
+	assert_eq!(x, y);
+	let reader = ContainerReader::new(gzip.as_slice(), Buffering::Memory).unwrap();
+	assert_eq!(reader.container(), Container::Gzip);
+	let mut npz = NpzReader::new(reader).unwrap();
+	let y: Array2<f64> = npz.by_name("x.npy").unwrap();
+	assert_eq!(x, y);
+	std::fs::remove_file(&plain_path).unwrap();
+	std::fs::remove_file(&gzip_path).unwrap();
+}
+
+#[cfg(feature = "compressed")]
+#[test]
+#[allow(clippy::cast_precision_loss)]
+fn spill() {
+	use memmap2::Mmap;
+	use ndarray_npy::ViewNpyExt;
+	use ndarray_npz::{NpzReader, NpzWriter};
+	use std::io::{Cursor, Read};
+
+	let x = Array2::<f64>::from_shape_fn((64, 32), |(i, j)| (i * 32 + j) as f64);
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new_compressed(Cursor::new(&mut buffer));
+		npz.add_array("x.npy", &x).unwrap();
+		npz.add_bytes("LICENSE", b"CC0-1.0").unwrap();
+		npz.finish().unwrap();
+	}
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	let spilled = npz.spill("x.npy").unwrap();
+	let mut bytes = Vec::new();
+	spilled.file().read_to_end(&mut bytes).unwrap();
+	assert_eq!(spilled.len(), bytes.len() as u64);
+	let mmap = unsafe { Mmap::map(spilled.file()).unwrap() };
+	assert_eq!(ArrayView2::<f64>::view_npy(&mmap).unwrap(), x);
+	assert!(npz.spill("LICENSE").is_err());
+}
+
+#[cfg(feature = "compressed")]
+#[test]
+#[allow(clippy::float_cmp)]
+fn with_cache() {
+	use ndarray_npz::{NpzReader, NpzWriter};
+	use std::io::Cursor;
+
+	let x = Array1::<f64>::linspace(0.0, 1.0, 100);
+	let y = Array1::<f64>::linspace(1.0, 2.0, 100);
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new_compressed(Cursor::new(&mut buffer));
+		npz.add_array("x.npy", &x).unwrap();
+		npz.add_array("y.npy", &y).unwrap();
+		npz.finish().unwrap();
+	}
+	// Budget for one array of 100 `f64` plus header.
+	let npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	let mut npz = npz.with_cache(1024);
+	for _ in 0..3 {
+		let z: Array1<f64> = npz.by_name("x.npy").unwrap();
+		assert_eq!(z, x);
+	}
+	let stats = npz.cache_stats().unwrap();
+	assert_eq!((stats.hits, stats.misses, stats.evictions), (2, 1, 0));
+	assert_eq!(stats.bytes, 128 + 800);
+	let z: Array1<f64> = npz.by_index(1).unwrap();
+	assert_eq!(z, y);
+	let z: Array1<f64> = npz.by_name("x.npy").unwrap();
+	assert_eq!(z, x);
+	let stats = npz.cache_stats().unwrap();
+	assert_eq!((stats.hits, stats.misses, stats.evictions), (2, 3, 2));
+	assert_eq!(stats.hit_rate(), 0.4);
+	let npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	assert_eq!(npz.cache_stats(), None);
+}
+
+#[test]
+fn capabilities() {
+	use ndarray_npz::{Capabilities, NpzReader, NpzWriter};
+	use std::io::Cursor;
+
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+		npz.add_array("x.npy", &array![1.0, 2.0]).unwrap();
+		npz.finish().unwrap();
+	}
+	let capabilities = NpzReader::capabilities(&mut Cursor::new(&buffer)).unwrap();
+	assert_eq!(capabilities, Capabilities::default());
+	assert!(capabilities.check().is_ok());
+	let mut numpy = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::numpy_compat(Cursor::new(&mut numpy));
+		npz.add_array("x", &array![1.0, 2.0]).unwrap();
+		npz.finish().unwrap();
+	}
+	let capabilities = NpzReader::capabilities(&mut Cursor::new(&numpy)).unwrap();
+	assert!(capabilities.zip64);
+	assert!(capabilities.check().is_ok());
+	// Set the traditional encryption flag and an unsupported compression method.
+	let mut patched = buffer.clone();
+	let central = patched
+		.windows(4)
+		.position(|window| window == [0x50, 0x4b, 0x01, 0x02])
+		.unwrap();
+	patched[central + 8] |= 1;
+	patched[central + 10..central + 12].copy_from_slice(&98u16.to_le_bytes());
+	let capabilities = NpzReader::capabilities(&mut Cursor::new(&patched)).unwrap();
+	assert_eq!(capabilities.encrypted, ["x.npy"]);
+	assert_eq!(
+		capabilities.unsupported_compression,
+		[("x.npy".to_string(), 98)]
+	);
+	assert!(capabilities.check().is_err());
+	// Mark as second disk.
+	let mut patched = buffer.clone();
+	let eocd = patched.len() - 22;
+	patched[eocd + 4] = 1;
+	let capabilities = NpzReader::capabilities(&mut Cursor::new(&patched)).unwrap();
+	assert!(capabilities.multi_disk);
+	assert!(capabilities.check().is_err());
+	assert!(NpzReader::new(Cursor::new(&patched)).is_err());
+}
+
+#[test]
+fn empty() {
+	use aligned_vec::AVec;
+	use ndarray_npz::{NpzReader, NpzView, NpzViewMut, NpzWriter};
+	use std::io::Cursor;
+	use zip::{write::SimpleFileOptions, ZipWriter};
+
+	// Like `numpy.savez()` without arrays.
+	let mut numpy = b"PK\x05\x06".to_vec();
+	numpy.resize(22, 0);
+	let written = NpzWriter::new(Cursor::new(Vec::new()))
+		.finish()
+		.unwrap()
+		.into_inner();
+	assert_eq!(written, numpy);
+	let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+	zip.add_directory("a/", SimpleFileOptions::default())
+		.unwrap();
+	zip.add_directory("a/b/", SimpleFileOptions::default())
+		.unwrap();
+	let directories = zip.finish().unwrap().into_inner();
+	for buffer in [numpy, directories] {
+		let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+		assert!(npz.is_empty());
+		assert_eq!(npz.len(), 0);
+		assert!(npz.names().unwrap().is_empty());
+		assert!(npz.npy_names().unwrap().is_empty());
+		assert!(npz.blob_names().unwrap().is_empty());
+		let x: Result<Array1<f64>, _> = npz.by_index(0);
+		assert!(x.is_err());
+		let mut buffer = AVec::<u8>::from_slice(64, &buffer);
+		let npz = NpzView::new(&buffer).unwrap();
+		assert!(npz.is_empty());
+		assert_eq!(npz.names().count(), 0);
+		let npz = NpzViewMut::new(&mut buffer).unwrap();
+		assert!(npz.is_empty());
+	}
+}
+
+#[test]
+fn unsupported_names() {
+	use aligned_vec::AVec;
+	use ndarray_npz::{NpzReader, NpzView, NpzWriter, ReadNpzError, ViewNpzError};
+	use std::io::Cursor;
+	use zip::result::ZipError;
+
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+		npz.add_array("x.npy", &array![1.0, 2.0]).unwrap();
+		npz.add_array("y.npy", &array![3.0, 4.0]).unwrap();
+		npz.finish().unwrap();
+	}
+	// Mark `y.npy` as compressed via PPMd in its local and central header.
+	let central = buffer
+		.windows(4)
+		.rposition(|window| window == [0x50, 0x4b, 0x01, 0x02])
+		.unwrap();
+	let local = u32::from_le_bytes(buffer[central + 42..central + 46].try_into().unwrap());
+	let local = usize::try_from(local).unwrap();
+	buffer[local + 8..local + 10].copy_from_slice(&98u16.to_le_bytes());
+	buffer[central + 10..central + 12].copy_from_slice(&98u16.to_le_bytes());
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	assert_eq!(npz.unsupported_names(), [("y.npy".to_string(), 98)]);
+	assert_eq!(npz.names().unwrap(), ["x.npy", "y.npy"]);
+	assert_eq!(npz.npy_names().unwrap(), ["x.npy"]);
+	let x: Array1<f64> = npz.by_name("x.npy").unwrap();
+	assert_eq!(x, array![1.0, 2.0]);
+	let y: Result<Array1<f64>, _> = npz.by_name("y.npy");
+	assert!(matches!(
+		y,
+		Err(ReadNpzError::Zip(ZipError::UnsupportedArchive(_)))
+	));
+	let buffer = AVec::<u8>::from_slice(64, &buffer);
+	let npz = NpzView::new(&buffer).unwrap();
+	assert_eq!(npz.len(), 1);
+	assert!(matches!(
+		npz.by_name("y.npy"),
+		Err(ViewNpzError::CompressedFile)
+	));
+}
+
+#[test]
+#[allow(clippy::cast_possible_truncation)]
+fn volume_reader() {
+	use ndarray_npz::{NpzReader, NpzWriter, VolumeReader};
+	use std::io::Cursor;
+
+	let u16_at = |bytes: &[u8], pos: usize| u16::from_le_bytes([bytes[pos], bytes[pos + 1]]);
+	let u32_at = |bytes: &[u8], pos: usize| {
+		u32::from_le_bytes([bytes[pos], bytes[pos + 1], bytes[pos + 2], bytes[pos + 3]])
+	};
+	let x = Array1::<f64>::linspace(0.0, 1.0, 100);
+	let y = array![[1i32, 2], [3, 4]];
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+		npz.add_array("x.npy", &x).unwrap();
+		npz.add_array("y.npy", &y).unwrap();
+		npz.finish().unwrap();
+	}
+	// Split in the middle of `x.npy` into two volumes like `zip -s`.
+	let eocd = buffer.len() - 22;
+	let cd_offset = u32_at(&buffer, eocd + 16) as usize;
+	let split = 300;
+	let mut first = vec![0x50, 0x4b, 0x07, 0x08];
+	first.extend(&buffer[..split]);
+	let mut second = buffer[split..].to_vec();
+	let mut pos = cd_offset - split;
+	for _entry in 0..2 {
+		let offset = u32_at(&second, pos + 42) as usize;
+		let (disk, offset) = if offset < split {
+			(0u16, offset + 4)
+		} else {
+			(1, offset - split)
+		};
+		second[pos + 34..pos + 36].copy_from_slice(&disk.to_le_bytes());
+		second[pos + 42..pos + 46].copy_from_slice(&(offset as u32).to_le_bytes());
+		pos += 46
+			+ usize::from(u16_at(&second, pos + 28))
+			+ usize::from(u16_at(&second, pos + 30))
+			+ usize::from(u16_at(&second, pos + 32));
+	}
+	let eocd = second.len() - 22;
+	second[eocd + 4..eocd + 6].copy_from_slice(&1u16.to_le_bytes());
+	second[eocd + 6..eocd + 8].copy_from_slice(&1u16.to_le_bytes());
+	second[eocd + 16..eocd + 20].copy_from_slice(&((cd_offset - split) as u32).to_le_bytes());
+	let mut joined = first.clone();
+	joined.extend(&second);
+	assert!(NpzReader::new(Cursor::new(&joined)).is_err());
+	let volumes = vec![Cursor::new(first.clone()), Cursor::new(second.clone())];
+	let mut npz = NpzReader::new(VolumeReader::new(volumes).unwrap()).unwrap();
+	let z: Array1<f64> = npz.by_name("x.npy").unwrap();
+	assert_eq!(z, x);
+	let z: Array2<i32> = npz.by_name("y.npy").unwrap();
+	assert_eq!(z, y);
+	assert!(VolumeReader::new(vec![Cursor::new(second)]).is_err());
+}
+
+#[test]
+fn pipelined_writer() {
+	use ndarray_npz::{
+		Endianness, ErrorCode, NpzReader, NpzWriter, PipelineMetrics, PipelinedWriter,
+		WriteNpzError,
+	};
+	use std::{
+		io::Cursor,
+		sync::{Arc, Mutex},
+	};
+
+	let peak = Arc::new(Mutex::new(PipelineMetrics::default()));
+	let hook = {
+		let peak = peak.clone();
+		move |metrics: &PipelineMetrics| {
+			let mut peak = peak.lock().unwrap();
+			peak.queue_len = peak.queue_len.max(metrics.queue_len);
+			peak.bytes_in_flight = peak.bytes_in_flight.max(metrics.bytes_in_flight);
+		}
+	};
+	let mut npz = PipelinedWriter::new(NpzWriter::new(Cursor::new(Vec::new())))
+		.with_queue_depth(2)
+		.with_max_bytes_in_flight(2000)
+		.with_metrics(hook);
+	for index in 0..10 {
+		let x = Array1::<f64>::from_elem(100, f64::from(index));
+		npz.add_array(format!("x{index}.npy"), &x).unwrap();
+	}
+	let buffer = npz.finish().unwrap().into_inner();
+	let peak = *peak.lock().unwrap();
+	assert!(peak.queue_len <= 2);
+	assert!(peak.bytes_in_flight <= 2000);
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	assert_eq!(npz.len(), 10);
+	for index in 0..10 {
+		let x: Array1<f64> = npz.by_name(&format!("x{index}.npy")).unwrap();
+		assert_eq!(x, Array1::<f64>::from_elem(100, f64::from(index)));
+	}
+	let mut npz =
+		PipelinedWriter::new(NpzWriter::new(Cursor::new(Vec::new()))).with_max_entry_bytes(200);
+	assert!(npz.add_array("x.npy", &Array1::<f64>::zeros(100)).is_err());
+	npz.add_array("y.npy", &Array1::<f64>::zeros(2)).unwrap();
+	assert!(!npz.finish().unwrap().into_inner().is_empty());
+	// Duplicates fail on the background thread, afterwards the writer is finished.
+	let mut npz = PipelinedWriter::new(NpzWriter::new(Cursor::new(Vec::new())));
+	let x = Array1::<f64>::zeros(2);
+	let err = (0..)
+		.find_map(|_index| npz.add_array("x.npy", &x).err())
+		.unwrap();
+	assert!(matches!(err, WriteNpzError::DuplicateName(_)));
+	let err = npz.add_array("y.npy", &x).unwrap_err();
+	assert!(matches!(err, WriteNpzError::Finished));
+	assert_eq!(err.code(), ErrorCode::WriterFinished);
+	assert!(matches!(npz.finish(), Err(WriteNpzError::Finished)));
+	// Arrays are added like by the writer, e.g., packed and in its byte order.
+	let foreign = if cfg!(target_endian = "big") {
+		Endianness::Little
+	} else {
+		Endianness::Big
+	};
+	let writer = || {
+		NpzWriter::new(Cursor::new(Vec::new()))
+			.with_packing(256)
+			.with_endianness(foreign)
+	};
+	let mut npz = writer();
+	let mut pipelined = PipelinedWriter::new(writer());
+	for len in [1, 2, 100] {
+		let x = Array1::<f64>::linspace(0.0, 1.0, len);
+		npz.add_array(format!("x{len}"), &x).unwrap();
+		pipelined.add_array(format!("x{len}"), &x).unwrap();
+	}
+	let buffer = pipelined.finish().unwrap().into_inner();
+	assert_eq!(buffer, npz.finish().unwrap().into_inner());
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	assert_eq!(npz.npy_names().unwrap(), ["x100", "x1", "x2"]);
+	let x: Array1<f64> = npz.by_name("x100").unwrap();
+	assert_eq!(x, Array1::<f64>::linspace(0.0, 1.0, 100));
+}
+
+#[test]
+fn add_arrays() {
+	use ndarray_npz::{NpzReader, NpzWriter, WriteNpzError};
+	use std::io::Cursor;
+
+	let names = ["a", "b", "c"];
+	let arrays = [
+		Array1::<f64>::zeros(1),
+		Array1::<f64>::ones(2),
+		Array1::<f64>::zeros(3),
+	];
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	npz.add_arrays(names.into_iter().zip(&arrays)).unwrap();
+	let err = npz
+		.add_arrays([("d", &arrays[0]), ("a", &arrays[1]), ("e", &arrays[2])])
+		.unwrap_err();
+	assert!(matches!(err, WriteNpzError::DuplicateName(name) if name == "a"));
+	let mut npz = NpzReader::new(npz.finish().unwrap()).unwrap();
+	assert_eq!(npz.names().unwrap(), ["a", "b", "c", "d"]);
+	for (name, array) in names.into_iter().zip(&arrays) {
+		let read: Array1<f64> = npz.by_name(name).unwrap();
+		assert_eq!(&read, array);
+	}
+}
+
+#[test]
+fn set_entry_order() {
+	use aligned_vec::AVec;
+	use ndarray_npz::{NpzReader, NpzView, NpzWriter, Order};
+	use std::{fs, io::Cursor};
+	use zip::ZipArchive;
+
+	let arrays = [
+		("b.npy", Array1::<f64>::zeros(100)),
+		("c.npy", Array1::<f64>::zeros(1)),
+		("a.npy", Array1::<f64>::zeros(10)),
+	];
+	for (order, names) in [
+		(Order::Insertion, ["b.npy", "c.npy", "a.npy"]),
+		(Order::Alphabetical, ["a.npy", "b.npy", "c.npy"]),
+		(Order::SizeDescending, ["b.npy", "a.npy", "c.npy"]),
+	] {
+		let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+		npz.set_entry_order(order);
+		for (name, array) in &arrays {
+			npz.add_array(*name, array).unwrap();
+		}
+		npz.add_bytes("d.txt", b"not an array").unwrap();
+		let buffer = npz.finish().unwrap().into_inner();
+		let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+		assert_eq!(npz.names().unwrap()[..3], names);
+		for (name, array) in &arrays {
+			let read: Array1<f64> = npz.by_name(name).unwrap();
+			assert_eq!(&read, array);
+		}
+		let buffer = AVec::<u8>::from_slice(64, &buffer);
+		let npz = NpzView::new(&buffer).unwrap();
+		for (name, array) in &arrays {
+			assert_eq!(
+				npz.by_name(name).unwrap().view::<f64, Ix1>().unwrap(),
+				array
+			);
+		}
+	}
+	// Stored files keep the configured alignment.
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new())).with_alignment(4096);
+	npz.set_entry_order(Order::SizeDescending);
+	for (name, array) in &arrays {
+		npz.add_array(*name, array).unwrap();
+	}
+	let mut zip = ZipArchive::new(npz.finish().unwrap()).unwrap();
+	for index in 0..zip.len() {
+		assert_eq!(zip.by_index_raw(index).unwrap().data_start() % 4096, 0);
+	}
+	// Data descriptors are kept.
+	let buffer = fs::read("tests/examples_data_descriptor.npz").unwrap();
+	let mut npz = NpzWriter::append(Cursor::new(buffer)).unwrap();
+	npz.set_entry_order(Order::Alphabetical);
+	npz.add_array("a.npy", &arrays[2].1).unwrap();
+	let buffer = npz.finish().unwrap().into_inner();
+	let descriptors = buffer.windows(4).filter(|bytes| bytes == b"PK\x07\x08");
+	assert_eq!(descriptors.count(), 3);
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	assert_eq!(
+		npz.names().unwrap(),
+		["a.npy", "b8.npy", "i8.npy", "u8.npy"]
+	);
+	for name in ["b8.npy", "i8.npy", "u8.npy"] {
+		let x: Array1<bool> = npz.by_name(name).unwrap();
+		assert_eq!(x, array![true, false]);
+	}
+}
+
+#[test]
+fn overwrite_duplicates() {
+	use aligned_vec::AVec;
+	use ndarray_npz::{NpzReader, NpzView, NpzWriter, Order};
+	use std::io::Cursor;
+	use zip::ZipArchive;
+
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	npz.overwrite_duplicates();
+	npz.add_array("b", &Array1::<f64>::zeros(100)).unwrap();
+	npz.add_array("a", &Array1::<f64>::zeros(10)).unwrap();
+	npz.add_array("b", &Array1::<f64>::ones(3)).unwrap();
+	npz.add_bytes("c.txt", b"draft").unwrap();
+	npz.add_array("b", &Array1::<f64>::from_elem(5, 2.0))
+		.unwrap();
+	npz.add_bytes("c.txt", b"final").unwrap();
+	let buffer = npz.finish().unwrap().into_inner();
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	assert_eq!(npz.names().unwrap(), ["b", "a", "c.txt"]);
+	let b: Array1<f64> = npz.by_name("b").unwrap();
+	assert_eq!(b, Array1::from_elem(5, 2.0));
+	assert_eq!(npz.read_bytes("c.txt").unwrap(), b"final");
+	let buffer = AVec::<u8>::from_slice(64, &buffer);
+	let npz = NpzView::new(&buffer).unwrap();
+	let mut view = npz.by_name("b").unwrap();
+	view.verify().unwrap();
+	assert_eq!(view.view::<f64, Ix1>().unwrap().len(), 5);
+	// Overwriting composes with ordering.
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	npz.set_entry_order(Order::Alphabetical);
+	npz.overwrite_duplicates();
+	npz.add_array("b", &Array1::<f64>::zeros(1)).unwrap();
+	npz.add_array("a", &Array1::<f64>::zeros(1)).unwrap();
+	npz.add_array("a", &Array1::<f64>::ones(1)).unwrap();
+	let mut npz = NpzReader::new(npz.finish().unwrap()).unwrap();
+	assert_eq!(npz.names().unwrap(), ["a", "b"]);
+	let a: Array1<f64> = npz.by_name("a").unwrap();
+	assert_eq!(a, Array1::<f64>::ones(1));
+	// Replacing files keeps the configured alignment.
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new())).with_alignment(4096);
+	npz.overwrite_duplicates();
+	npz.add_array("a", &Array1::<f64>::zeros(10)).unwrap();
+	npz.add_array("b", &Array1::<f64>::zeros(10)).unwrap();
+	npz.add_array("a", &Array1::<f64>::ones(10)).unwrap();
+	let mut zip = ZipArchive::new(npz.finish().unwrap()).unwrap();
+	assert_eq!(zip.len(), 2);
+	for index in 0..zip.len() {
+		assert_eq!(zip.by_index_raw(index).unwrap().data_start() % 4096, 0);
+	}
+}
+
+#[test]
+fn with_packing() {
+	use ndarray_npz::{NpzReader, NpzWriter, PACKED_INDEX_NAME, PACKED_NAME};
+	use std::io::{Cursor, Read};
+	use zip::ZipArchive;
+
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new())).with_packing(256);
+	npz.add_array("large.npy", &Array1::<f64>::zeros(100))
+		.unwrap();
+	for index in 0..100 {
+		npz.add_array(format!("x{index}.npy"), &arr0(index))
+			.unwrap();
+	}
+	let buffer = npz.finish().unwrap().into_inner();
+	let mut zip = ZipArchive::new(Cursor::new(&buffer)).unwrap();
+	assert_eq!(zip.len(), 3);
+	assert!(zip.index_for_name(PACKED_NAME).is_some());
+	let mut index = String::new();
+	let mut file = zip.by_name(PACKED_INDEX_NAME).unwrap();
+	file.read_to_string(&mut index).unwrap();
+	assert!(index.starts_with("{\"x0.npy\": [64, "));
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	assert_eq!(npz.len(), 101);
+	let names = npz.names().unwrap();
+	assert_eq!(names[..2], ["large.npy", "x0.npy"]);
+	assert_eq!(npz.npy_names().unwrap(), names);
+	assert!(npz.blob_names().unwrap().is_empty());
+	for index in 0..100 {
+		let x: Array0<i32> = npz.by_name(&format!("x{index}.npy")).unwrap();
+		assert_eq!(x, arr0(index));
+	}
+	let x: Array0<i32> = npz.by_index(42).unwrap();
+	assert_eq!(x, arr0(41));
+	let large: Array1<f64> = npz.by_name("large.npy").unwrap();
+	assert_eq!(large, Array1::<f64>::zeros(100));
+}
+
+#[test]
+#[allow(clippy::cast_precision_loss)]
+fn with_chunking() {
+	use ndarray_npz::{NpzReader, NpzWriter, CHUNKS_NAME, CHUNK_PREFIX};
+	use std::{
+		collections::HashSet,
+		io::{Cursor, Read},
+	};
+	use zip::ZipArchive;
+
+	let mut state = 42u64;
+	let x = Array1::from_shape_simple_fn(60_000, || {
+		state = state
+			.wrapping_mul(6_364_136_223_846_793_005)
+			.wrapping_add(1);
+		(state >> 11) as f64
+	});
+	let write = |x: ArrayView1<f64>| {
+		let mut npz = NpzWriter::new(Cursor::new(Vec::new())).with_chunking(4096);
+		npz.add_array("x.npy", &x).unwrap();
+		npz.add_array("zeros.npy", &Array1::<f64>::zeros(20_000))
+			.unwrap();
+		npz.add_array("small.npy", &Array1::<f64>::ones(10))
+			.unwrap();
+		npz.add_bytes("info.txt", b"metadata").unwrap();
+		npz.finish().unwrap().into_inner()
+	};
+	let chunks = |buffer: &[u8], name: &str| {
+		let mut zip = ZipArchive::new(Cursor::new(buffer)).unwrap();
+		let mut manifest = String::new();
+		let mut file = zip.by_name(CHUNKS_NAME).unwrap();
+		file.read_to_string(&mut manifest).unwrap();
+		let (_, chunks) = manifest.split_once(&format!("\"{name}\": [")).unwrap();
+		let (chunks, _) = chunks.split_once(']').unwrap();
+		chunks
+			.split(", ")
+			.map(|chunk| chunk.trim_matches('"').to_owned())
+			.collect::<Vec<_>>()
+	};
+	let v1 = write(x.view());
+	let x1 = chunks(&v1, "x.npy");
+	assert!(x1.len() > 10);
+	assert!(x1.iter().all(|chunk| chunk.starts_with(CHUNK_PREFIX)));
+	let zeros = chunks(&v1, "zeros.npy");
+	assert!(zeros.len() >= 10);
+	assert!(zeros.iter().collect::<HashSet<_>>().len() <= 3);
+	let mut npz = NpzReader::new(Cursor::new(&v1)).unwrap();
+	assert_eq!(npz.len(), 4);
+	assert_eq!(
+		npz.names().unwrap(),
+		["small.npy", "info.txt", "x.npy", "zeros.npy"]
+	);
+	assert_eq!(
+		npz.npy_names().unwrap(),
+		["small.npy", "x.npy", "zeros.npy"]
+	);
+	assert_eq!(npz.blob_names().unwrap(), ["info.txt"]);
+	let y: Array1<f64> = npz.by_name("x.npy").unwrap();
+	assert_eq!(y, x);
+	let zeros: Array1<f64> = npz.by_index(3).unwrap();
+	assert_eq!(zeros, Array1::<f64>::zeros(20_000));
+	let small: Array1<f64> = npz.by_name("small.npy").unwrap();
+	assert_eq!(small, Array1::<f64>::ones(10));
+
+	// Removing leading elements only changes the chunks at the start.
+	let v2 = write(x.slice(s![100..]));
+	let x2 = chunks(&v2, "x.npy");
+	let x1 = x1.into_iter().collect::<HashSet<_>>();
+	let changed = x2.iter().filter(|chunk| !x1.contains(*chunk)).count();
+	assert!(changed <= 3, "{changed} of {} chunks changed", x2.len());
+	let mut npz = NpzReader::new(Cursor::new(&v2)).unwrap();
+	let y: Array1<f64> = npz.by_name("x.npy").unwrap();
+	assert_eq!(y, x.slice(s![100..]));
+}
+
+#[cfg(feature = "compressed")]
+#[test]
+fn with_compression_guard() {
+	use ndarray_npz::{Inflation, NpzReader, NpzWriter, WriteNpzError};
+	use std::{
+		io::{self, Cursor},
+		sync::{Arc, Mutex},
+	};
+	use zip::{result::ZipError, CompressionMethod, ZipArchive};
+
+	let mut state = 42u64;
+	let noise = Array1::from_shape_simple_fn(10_000, || {
+		state = state
+			.wrapping_mul(6_364_136_223_846_793_005)
+			.wrapping_add(1);
+		f64::from_bits(state >> 2)
+	});
+	let inflations = Arc::new(Mutex::new(Vec::<Inflation>::new()));
+	let mut npz = NpzWriter::new_compressed(Cursor::new(Vec::new())).with_compression_guard({
+		let inflations = inflations.clone();
+		move |inflation| {
+			inflations.lock().unwrap().push(inflation.clone());
+			if inflation.name == "abort.npy" {
+				Err(io::Error::new(io::ErrorKind::InvalidData, "incompressible"))
+			} else {
+				Ok(())
+			}
+		}
+	});
+	npz.add_array("zeros.npy", &Array1::<f64>::zeros(10_000))
+		.unwrap();
+	npz.add_array("noise.npy", &noise).unwrap();
+	assert!(matches!(
+		npz.add_array("abort.npy", &noise),
+		Err(WriteNpzError::Zip(ZipError::Io(_)))
+	));
+	let bytes = noise
+		.iter()
+		.flat_map(|x| x.to_le_bytes())
+		.collect::<Vec<_>>();
+	npz.add_bytes("noise.bin", &bytes).unwrap();
+	let buffer = npz.finish().unwrap().into_inner();
+	let inflations = inflations.lock().unwrap();
+	let names = inflations
+		.iter()
+		.map(|inflation| inflation.name.as_str())
+		.collect::<Vec<_>>();
+	assert_eq!(names, ["noise.npy", "abort.npy", "noise.bin"]);
+	assert!(inflations[0].compressed_size >= inflations[0].size);
+	assert!(inflations[0].ratio() >= 1.0);
+	let mut zip = ZipArchive::new(Cursor::new(&buffer)).unwrap();
+	assert_eq!(zip.len(), 3);
+	let zeros = zip.by_name("zeros.npy").unwrap();
+	assert_eq!(zeros.compression(), CompressionMethod::Deflated);
+	assert!(zeros.compressed_size() < zeros.size());
+	drop(zeros);
+	let file = zip.by_name("noise.npy").unwrap();
+	assert_eq!(file.compression(), CompressionMethod::Stored);
+	assert_eq!(file.data_start() % 64, 0);
+	drop(file);
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	let x: Array1<f64> = npz.by_name("noise.npy").unwrap();
+	assert_eq!(x.mapv(f64::to_bits), noise.mapv(f64::to_bits));
+	let zeros: Array1<f64> = npz.by_name("zeros.npy").unwrap();
+	assert_eq!(zeros, Array1::<f64>::zeros(10_000));
+	// Stored entries keep the configured alignment.
+	let mut npz = NpzWriter::new_compressed(Cursor::new(Vec::new()))
+		.with_alignment(4096)
+		.with_compression_guard(|_inflation| Ok(()));
+	npz.add_array("noise.npy", &noise).unwrap();
+	let mut zip = ZipArchive::new(npz.finish().unwrap()).unwrap();
+	let file = zip.by_name("noise.npy").unwrap();
+	assert_eq!(file.compression(), CompressionMethod::Stored);
+	assert_eq!(file.data_start() % 4096, 0);
+}
+
+#[test]
+fn with_index() {
+	use aligned_vec::AVec;
+	use ndarray_npz::{NpzReader, NpzView, NpzViewMut, NpzWriter, INDEX_NAME};
+	use std::io::Cursor;
+
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new())).with_index();
+	npz.add_array("x.npy", &Array2::<f64>::zeros((2, 3)))
+		.unwrap();
+	npz.add_array("y.npy", &Array1::<i32>::zeros(4)).unwrap();
+	npz.add_bytes("info.txt", b"metadata").unwrap();
+	let buffer = npz.finish().unwrap().into_inner();
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	let index = npz.index().unwrap().unwrap();
+	assert_eq!(index.len(), 3);
+	assert_eq!(index[0].name, "x.npy");
+	let x = index[0].npy.as_ref().unwrap();
+	assert_eq!(
+		(x.descr.as_str(), x.shape.as_slice()),
+		("'<f8'", [2, 3].as_slice())
+	);
+	assert!(!x.fortran_order);
+	assert_eq!(index[1].npy.as_ref().unwrap().shape, [4]);
+	assert!(index[2].npy.is_none());
+	let mut buffer = AVec::<u8>::from_slice(64, &buffer);
+	{
+		let npz = NpzView::new(&buffer).unwrap();
+		assert_eq!(npz.len(), 2);
+		let mut blob_names = npz.blob_names().collect::<Vec<_>>();
+		blob_names.sort_unstable();
+		assert_eq!(blob_names, [INDEX_NAME, "info.txt"]);
+		let mut x = npz.by_name("x.npy").unwrap();
+		x.verify().unwrap();
+		assert_eq!(x.view::<f64, Ix2>().unwrap(), Array2::<f64>::zeros((2, 3)));
+		assert_eq!(npz.raw("info.txt").unwrap(), b"metadata");
+	}
+	// Editing makes the index stale.
+	NpzViewMut::new(&mut buffer)
+		.unwrap()
+		.replace_entry_data("x.npy", &Array2::<f64>::ones((2, 3)))
+		.unwrap();
+	let mut npz = NpzReader::new(Cursor::new(buffer.as_slice())).unwrap();
+	assert!(npz.index().unwrap().is_none());
+	let npz = NpzView::new(&buffer).unwrap();
+	let x = npz.by_name("x.npy").unwrap();
+	assert_eq!(x.view::<f64, Ix2>().unwrap(), Array2::<f64>::ones((2, 3)));
+}
+
+#[test]
+fn cached_index() {
+	use aligned_vec::AVec;
+	use ndarray_npz::{cached_index, CacheLocation, NpzWriter};
+	use std::fs::{self, File};
+
+	let path = std::env::temp_dir().join("ndarray_npz_cached_index.npz");
+	let cache_path = std::env::temp_dir().join("ndarray_npz_cached_index.npz.index");
+	let _ = fs::remove_file(&cache_path);
+	let mut npz = NpzWriter::new(File::create(&path).unwrap());
+	npz.add_array("x.npy", &Array2::<f64>::ones((2, 3)))
+		.unwrap();
+	npz.add_bytes("info.txt", b"metadata").unwrap();
+	npz.finish().unwrap();
+	let index = cached_index(&path, &CacheLocation::NextToArchive).unwrap();
+	assert!(cache_path.exists());
+	assert_eq!(index.entries().len(), 2);
+	assert_eq!(index.entries()[0].npy.as_ref().unwrap().shape, [2, 3]);
+	assert!(index.entries()[1].npy.is_none());
+	assert_eq!(
+		cached_index(&path, &CacheLocation::NextToArchive).unwrap(),
+		index
+	);
+	// Corrupted cache files are ignored and replaced.
+	let mut cache = fs::read(&cache_path).unwrap();
+	cache[20] ^= 0xff;
+	fs::write(&cache_path, &cache).unwrap();
+	assert_eq!(
+		cached_index(&path, &CacheLocation::NextToArchive).unwrap(),
+		index
+	);
+	assert_ne!(fs::read(&cache_path).unwrap(), cache);
+	let buffer = AVec::<u8>::from_slice(64, &fs::read(&path).unwrap());
+	let npz = index.view(&buffer).unwrap();
+	let x = npz.by_name("x.npy").unwrap();
+	assert_eq!(x.view::<f64, Ix2>().unwrap(), Array2::<f64>::ones((2, 3)));
+	assert_eq!(npz.raw("info.txt").unwrap(), b"metadata");
+	assert!(index.view(&buffer[1..]).is_err());
+	fs::remove_file(&path).unwrap();
+	fs::remove_file(&cache_path).unwrap();
+}
+
+#[test]
+fn with_unix_permissions() {
+	use ndarray_npz::{NpzWriter, NpzWriterBuilder};
+	use std::io::Cursor;
+	use zip::{DateTime, ZipArchive};
+
+	let time = DateTime::from_date_and_time(2024, 2, 29, 12, 30, 0).unwrap();
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new()))
+		.with_last_modified_time(time)
+		.with_unix_permissions(0o640);
+	npz.add_array("x", &Array1::<f64>::zeros(3)).unwrap();
+	npz.add_bytes("y.txt", b"blob").unwrap();
+	let mut zip = ZipArchive::new(npz.finish().unwrap()).unwrap();
+	for index in 0..zip.len() {
+		let file = zip.by_index(index).unwrap();
+		assert_eq!(file.unix_mode().map(|mode| mode & 0o777), Some(0o640));
+		assert_eq!(file.last_modified(), Some(time));
+	}
+	let mut npz = NpzWriterBuilder::new()
+		.unix_permissions(0o600)
+		.build(Cursor::new(Vec::new()));
+	npz.add_array("x", &Array1::<f64>::zeros(3)).unwrap();
+	let mut zip = ZipArchive::new(npz.finish().unwrap()).unwrap();
+	let file = zip.by_index(0).unwrap();
+	assert_eq!(file.unix_mode().map(|mode| mode & 0o777), Some(0o600));
+}
+
+#[test]
+fn set_comment() {
+	use ndarray_npz::{compact, strip_metadata, NpzReader, NpzWriter, Order};
+	use std::io::Cursor;
+
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	npz.set_comment("git 1a2b3c4");
+	npz.set_entry_order(Order::Alphabetical);
+	npz.add_array("b", &Array1::<f64>::zeros(3)).unwrap();
+	npz.add_array("a", &Array1::<f64>::ones(3)).unwrap();
+	let buffer = npz.finish().unwrap().into_inner();
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	assert_eq!(npz.comment().unwrap(), "git 1a2b3c4");
+	assert_eq!(npz.names().unwrap(), ["a", "b"]);
+	let mut compacted = Cursor::new(Vec::new());
+	compact(Cursor::new(&buffer), &mut compacted).unwrap();
+	let npz = NpzReader::new(compacted).unwrap();
+	assert_eq!(npz.comment().unwrap(), "git 1a2b3c4");
+	let mut stripped = Cursor::new(Vec::new());
+	strip_metadata(Cursor::new(&buffer), &mut stripped).unwrap();
+	assert_eq!(NpzReader::new(stripped).unwrap().comment().unwrap(), "");
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	npz.set_comment("\u{e9}".repeat(40_000));
+	let npz = NpzReader::new(npz.finish().unwrap()).unwrap();
+	assert_eq!(npz.comment().unwrap().len(), 65534);
+}
+
+#[test]
+fn writer_builder() {
+	use aligned_vec::AVec;
+	use ndarray_npz::{
+		Duplicates, Endianness, ErrorCode, NpzReader, NpzView, NpzWriterBuilder, WriteNpzError,
+	};
+	use std::io::Cursor;
+	use zip::{DateTime, ZipArchive};
+
+	let x = Array1::from_iter((0..10).map(f64::from));
+	let write = |builder: NpzWriterBuilder| {
+		let mut npz = builder.build(Cursor::new(Vec::new()));
+		npz.add_array("x", &x).unwrap();
+		npz.finish().unwrap().into_inner()
+	};
+	// Deterministic archives are identical and dated to the earliest representable time.
+	let builder = NpzWriterBuilder::new()
+		.deterministic(true)
+		.npy_extension(true)
+		.buffer_size(16);
+	let buffer = write(builder.clone());
+	assert_eq!(buffer, write(builder));
+	let mut zip = ZipArchive::new(Cursor::new(&buffer)).unwrap();
+	let file = zip.by_index(0).unwrap();
+	assert_eq!(file.name(), "x.npy");
+	assert_eq!(file.last_modified(), Some(DateTime::default()));
+	assert_eq!(file.data_start() % 64, 0);
+	// Arrays in foreign byte order are read but not viewed.
+	let foreign = if cfg!(target_endian = "little") {
+		Endianness::Big
+	} else {
+		Endianness::Little
+	};
+	let buffer = write(NpzWriterBuilder::new().endianness(foreign));
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	let y: Array1<f64> = npz.by_name("x").unwrap();
+	assert_eq!(y, x);
+	let buffer = AVec::<u8>::from_slice(64, &buffer);
+	let npz = NpzView::new(&buffer).unwrap();
+	assert!(npz.by_name("x").unwrap().view::<f64, Ix1>().is_err());
+	// Duplicates are rejected or skipped.
+	let mut npz = NpzWriterBuilder::new().build(Cursor::new(Vec::new()));
+	npz.add_array("x", &x).unwrap();
+	let err = npz.add_array("x", &x).unwrap_err();
+	assert!(matches!(&err, WriteNpzError::DuplicateName(name) if name == "x"));
+	assert_eq!(err.code(), ErrorCode::DuplicateName);
+	assert!(npz.add_bytes("x", b"blob").is_err());
+	let mut npz = NpzWriterBuilder::new()
+		.duplicates(Duplicates::Skip)
+		.build(Cursor::new(Vec::new()));
+	npz.add_array("x", &x).unwrap();
+	npz.add_array("x", &Array1::<f64>::zeros(10)).unwrap();
+	let mut npz = NpzReader::new(npz.finish().unwrap()).unwrap();
+	assert_eq!(npz.len(), 1);
+	let y: Array1<f64> = npz.by_name("x").unwrap();
+	assert_eq!(y, x);
+	// Compression replaces the options of the preset.
+	#[cfg(feature = "compressed")]
+	{
+		use zip::CompressionMethod;
+
+		let buffer = write(
+			NpzWriterBuilder::new()
+				.compression(CompressionMethod::Deflated)
+				.compression_level(Some(9))
+				.alignment(0),
+		);
+		let mut zip = ZipArchive::new(Cursor::new(&buffer)).unwrap();
+		let file = zip.by_index(0).unwrap();
+		assert_eq!(file.compression(), CompressionMethod::Deflated);
+		assert!(file.compressed_size() < file.size());
+	}
+}
+
+#[test]
+fn reader_builder() {
+	use aligned_vec::AVec;
+	use ndarray_npz::{Limits, NpzReaderBuilder, NpzViewBuilder, NpzWriter, ReadNpzError};
+	use std::io::Cursor;
+	use zip::result::ZipError;
+
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+		npz.add_array("x.npy", &array![1.0, 2.0]).unwrap();
+		npz.add_array("y.npy", &array![3.0, 4.0]).unwrap();
+		npz.finish().unwrap();
+	}
+	let mut npz = NpzReaderBuilder::new()
+		.reject_duplicates(true)
+		.verify_checksums(true)
+		.strict(true)
+		.build(Cursor::new(&buffer))
+		.unwrap();
+	let y: Array1<f64> = npz.by_name("y.npy").unwrap();
+	assert_eq!(y, array![3.0, 4.0]);
+	for limits in [
+		Limits {
+			max_entries: Some(1),
+			..Limits::default()
+		},
+		Limits {
+			max_entry_size: Some(16),
+			..Limits::default()
+		},
+		Limits {
+			max_total_size: Some(256),
+			..Limits::default()
+		},
+	] {
+		assert!(matches!(
+			NpzReaderBuilder::new()
+				.limits(limits)
+				.build(Cursor::new(&buffer)),
+			Err(ReadNpzError::Zip(ZipError::UnsupportedArchive(_)))
+		));
+	}
+	// Rename `y.npy` to `x.npy` in its local and central header.
+	let mut duplicates = buffer.clone();
+	let central = duplicates
+		.windows(4)
+		.rposition(|window| window == [0x50, 0x4b, 0x01, 0x02])
+		.unwrap();
+	let local = u32::from_le_bytes(duplicates[central + 42..central + 46].try_into().unwrap());
+	let local = usize::try_from(local).unwrap();
+	duplicates[local + 30] = b'x';
+	duplicates[central + 46] = b'x';
+	let mut npz = NpzReaderBuilder::new()
+		.build(Cursor::new(&duplicates))
+		.unwrap();
+	let x: Array1<f64> = npz.by_name("x.npy").unwrap();
+	assert_eq!(x, array![3.0, 4.0]);
+	assert!(NpzReaderBuilder::new()
+		.reject_duplicates(true)
+		.build(Cursor::new(&duplicates))
+		.is_err());
+	// Mark `y.npy` as compressed via PPMd in its local and central header.
+	let mut unsupported = buffer.clone();
+	unsupported[local + 8..local + 10].copy_from_slice(&98u16.to_le_bytes());
+	unsupported[central + 10..central + 12].copy_from_slice(&98u16.to_le_bytes());
+	assert!(NpzReaderBuilder::new()
+		.build(Cursor::new(&unsupported))
+		.is_ok());
+	assert!(NpzReaderBuilder::new()
+		.strict(true)
+		.build(Cursor::new(&unsupported))
+		.is_err());
+	// Flip the last data byte of `y.npy`.
+	let mut corrupted = buffer.clone();
+	let offset = usize::from(u16::from_le_bytes([buffer[local + 26], buffer[local + 27]]))
+		+ usize::from(u16::from_le_bytes([buffer[local + 28], buffer[local + 29]]));
+	let size = u32::from_le_bytes(buffer[central + 20..central + 24].try_into().unwrap());
+	corrupted[local + 29 + offset + usize::try_from(size).unwrap()] ^= 1;
+	assert!(NpzReaderBuilder::new()
+		.build(Cursor::new(&corrupted))
+		.is_ok());
+	assert!(NpzReaderBuilder::new()
+		.verify_checksums(true)
+		.build(Cursor::new(&corrupted))
+		.is_err());
+	let builder = NpzViewBuilder::new().verify_checksums(true);
+	let buffer = AVec::<u8>::from_slice(64, &buffer);
+	assert_eq!(builder.view(&buffer).unwrap().len(), 2);
+	let mut corrupted = AVec::<u8>::from_slice(64, &corrupted);
+	assert!(builder.view(&corrupted).is_err());
+	assert!(builder.view_mut(&mut corrupted).is_err());
+	let mut duplicates = AVec::<u8>::from_slice(64, &duplicates);
+	assert!(NpzViewBuilder::new()
+		.reject_duplicates(true)
+		.view_mut(&mut duplicates)
+		.is_err());
+}
+
+#[test]
+fn reader_builder_lying_sizes() {
+	use ndarray_npy::ReadNpyError;
+	use ndarray_npz::{Limits, NpzReaderBuilder, NpzWriter, ReadNpzError};
+	use std::io::Cursor;
+	use zip::result::ZipError;
+
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+		npz.add_array("x.npy", &Array1::<f64>::zeros(1000)).unwrap();
+		npz.finish().unwrap();
+	}
+	// Declare 100 bytes in the central header instead of 8128 bytes.
+	let central = buffer
+		.windows(4)
+		.rposition(|window| window == [0x50, 0x4b, 0x01, 0x02])
+		.unwrap();
+	buffer[central + 24..central + 28].copy_from_slice(&100u32.to_le_bytes());
+	let limits = Limits {
+		max_entry_size: Some(1024),
+		..Limits::default()
+	};
+	let builder = NpzReaderBuilder::new().limits(limits);
+	let exceeded = |result: Result<Array1<f64>, ReadNpzError>| {
+		matches!(
+			result,
+			Err(ReadNpzError::Zip(ZipError::UnsupportedArchive(_)))
+		)
+	};
+	// The `.npy` header declares 8000 bytes of data.
+	let mut npz = builder.clone().build(Cursor::new(&buffer)).unwrap();
+	assert!(exceeded(npz.by_name("x.npy")));
+	assert!(exceeded(npz.by_index(0)));
+	// Declare 80 bytes of data in the `.npy` header as well.
+	let shape = buffer
+		.windows(7)
+		.position(|window| window == b"(1000,)")
+		.unwrap();
+	buffer[shape..shape + 7].copy_from_slice(b"(10,)  ");
+	assert!(builder
+		.clone()
+		.verify_checksums(true)
+		.build(Cursor::new(&buffer))
+		.is_err_and(|err| matches!(err, ReadNpzError::Zip(ZipError::UnsupportedArchive(_)))));
+	let mut npz = builder.build(Cursor::new(&buffer)).unwrap();
+	assert!(exceeded(npz.by_name("x.npy")));
+	let mut npz = NpzReaderBuilder::new()
+		.limits(Limits {
+			max_entry_size: Some(1 << 20),
+			..Limits::default()
+		})
+		.build(Cursor::new(&buffer))
+		.unwrap();
+	// Fails on the checksum after reading all of the data instead.
+	let x: Result<Array1<f64>, _> = npz.by_name("x.npy");
+	assert!(matches!(x, Err(ReadNpzError::Npy(ReadNpyError::Io(_)))));
+}
+
+#[cfg(feature = "compressed")]
+#[test]
+fn with_compression_level() {
+	use ndarray_npz::{NpzReader, NpzWriter, WriteNpzError};
+	use std::io::Cursor;
+
+	let array = Array1::from_iter((0..10_000).map(|index| f64::from(index % 100)));
+	let mut sizes = Vec::new();
+	for level in [1, 6, 9] {
+		let mut npz =
+			NpzWriter::new_compressed(Cursor::new(Vec::new())).with_compression_level(level);
+		npz.add_array("x", &array).unwrap();
+		let buffer = npz.finish().unwrap().into_inner();
+		let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+		let x: Array1<f64> = npz.by_name("x").unwrap();
+		assert_eq!(x, array);
+		sizes.push(buffer.len());
+	}
+	assert!(sizes[0] > sizes[1] && sizes[1] > sizes[2]);
+	let mut npz = NpzWriter::new_compressed(Cursor::new(Vec::new())).with_compression_level(1000);
+	assert!(matches!(
+		npz.add_array("x", &array),
+		Err(WriteNpzError::Zip(_))
+	));
+}
+
+#[test]
+fn error_codes() {
+	use aligned_vec::AVec;
+	use ndarray_npz::{
+		ErrorCategory, ErrorCode, NpzEditor, NpzReader, NpzView, NpzWriter, ReadNpzError,
+	};
+	use std::io::Cursor;
+
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+		npz.add_array("x.npy", &array![1.0, 2.0]).unwrap();
+		npz.finish().unwrap();
+	}
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	let err = npz
+		.by_name::<ndarray::OwnedRepr<f64>, Ix1>("y.npy")
+		.unwrap_err();
+	assert_eq!(err.code(), ErrorCode::FileNotFound);
+	assert_eq!(err.category(), ErrorCategory::NotFound);
+	let err = npz
+		.by_name::<ndarray::OwnedRepr<i32>, Ix1>("x.npy")
+		.unwrap_err();
+	assert_eq!(err.code(), ErrorCode::DtypeMismatch);
+	assert_eq!(err.category(), ErrorCategory::Mismatch);
+	let err = npz
+		.by_name::<ndarray::OwnedRepr<f64>, Ix2>("x.npy")
+		.unwrap_err();
+	assert_eq!(err.code(), ErrorCode::ShapeMismatch);
+	let Err(err) = NpzReader::new(Cursor::new(&buffer[1..])) else {
+		panic!("invalid archive");
+	};
+	assert_eq!(err.code(), ErrorCode::InvalidArchive);
+	assert_eq!(err.category(), ErrorCategory::Format);
+	let err = ReadNpzError::Zip(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+	assert_eq!(err.code(), ErrorCode::Io);
+	assert_eq!(err.category(), ErrorCategory::Io);
+	let aligned = AVec::<u8>::from_slice(64, &buffer);
+	let npz = NpzView::new(&aligned).unwrap();
+	let err = npz.by_name("y.npy").unwrap_err();
+	assert_eq!(err.code(), ErrorCode::FileNotFound);
+	let err = npz
+		.by_name("x.npy")
+		.unwrap()
+		.view::<i32, Ix1>()
+		.unwrap_err();
+	assert_eq!(err.code(), ErrorCode::DtypeMismatch);
+	let mut editor = NpzEditor::new(Cursor::new(buffer)).unwrap();
+	let err = editor.reshape_entry("x.npy", &[3]).unwrap_err();
+	assert_eq!(err.code(), ErrorCode::ElementCountMismatch);
+	assert_eq!(err.category(), ErrorCategory::Mismatch);
+	for (code, value, category) in [
+		(ErrorCode::Io, 100, 1),
+		(ErrorCode::CompressedFile, 301, 3),
+		(ErrorCode::Poisoned, 604, 6),
+		(ErrorCode::Other, 900, 9),
+	] {
+		assert_eq!(code.value(), value);
+		assert_eq!(code.category().value(), category);
+	}
+}
+
+#[cfg(feature = "ffi")]
+#[test]
+#[allow(clippy::float_cmp)]
+fn ffi() {
+	use ndarray_npz::{ffi::*, ErrorCode, NpzReader};
+	use std::{
+		ffi::{CStr, CString},
+		fs::File,
+		ptr,
+	};
+
+	let code = |code: ErrorCode| i32::from(code.value());
+	let path = std::env::temp_dir().join("ndarray_npz_ffi.npz");
+	let c_path = CString::new(path.to_str().unwrap()).unwrap();
+	let (x, y) = (
+		CString::new("x.npy").unwrap(),
+		CString::new("y.npy").unwrap(),
+	);
+	let (x, y) = (x.as_ptr(), y.as_ptr());
+	let shape = [2usize, 3];
+	let data = [1.0f64, 2.0, 3.0, 4.0, 5.0, 6.0];
+	let flags = [true, false, true];
+	unsafe {
+		let mut writer = ptr::null_mut();
+		assert_eq!(
+			npz_writer_create(c_path.as_ptr(), false, &mut writer),
+			NPZ_OK
+		);
+		let shape = shape.as_ptr();
+		let status = npz_writer_add(
+			writer,
+			x,
+			NPZ_DTYPE_F64,
+			true,
+			2,
+			shape,
+			data.as_ptr().cast(),
+		);
+		assert_eq!(status, NPZ_OK);
+		let shape = shape.add(1);
+		let status = npz_writer_add(
+			writer,
+			y,
+			NPZ_DTYPE_BOOL,
+			false,
+			1,
+			shape,
+			flags.as_ptr().cast(),
+		);
+		assert_eq!(status, NPZ_OK);
+		let status = npz_writer_add(writer, y, 42, false, 0, ptr::null(), ptr::null());
+		assert_eq!(status, code(ErrorCode::InvalidArgument));
+		assert_eq!(npz_writer_finish(writer), NPZ_OK);
+	}
+	let mut npz = NpzReader::new(File::open(&path).unwrap()).unwrap();
+	let array: Array2<f64> = npz.by_name("x.npy").unwrap();
+	assert_eq!(array, array![[1.0, 3.0, 5.0], [2.0, 4.0, 6.0]]);
+	unsafe {
+		let mut reader = ptr::null_mut();
+		assert_eq!(npz_reader_open(c_path.as_ptr(), &mut reader), NPZ_OK);
+		let mut len = 0;
+		assert_eq!(npz_reader_len(reader, &mut len), NPZ_OK);
+		assert_eq!(len, 2);
+		let mut name = ptr::null();
+		assert_eq!(npz_reader_name(reader, 1, &mut name), NPZ_OK);
+		assert_eq!(CStr::from_ptr(name).to_str(), Ok("y.npy"));
+		let status = npz_reader_name(reader, 2, &mut name);
+		assert_eq!(status, code(ErrorCode::FileNotFound));
+		let mut info = std::mem::zeroed::<NpzArrayInfo>();
+		assert_eq!(npz_reader_describe(reader, x, &mut info), NPZ_OK);
+		assert_eq!(info.dtype, NPZ_DTYPE_F64);
+		assert_eq!(&info.shape[..info.ndim], shape);
+		assert_eq!(info.len, 6);
+		assert!(info.fortran_order);
+		let mut array = [0.0f64; 6];
+		let buffer = array.as_mut_ptr().cast();
+		assert_eq!(
+			npz_reader_read(reader, x, NPZ_DTYPE_F64, true, buffer, 6),
+			NPZ_OK
+		);
+		assert_eq!(array, data);
+		assert_eq!(
+			npz_reader_read(reader, x, NPZ_DTYPE_F64, false, buffer, 6),
+			NPZ_OK
+		);
+		assert_eq!(array, [1.0, 3.0, 5.0, 2.0, 4.0, 6.0]);
+		let status = npz_reader_read(reader, x, NPZ_DTYPE_F32, false, buffer, 6);
+		assert_eq!(status, code(ErrorCode::DtypeMismatch));
+		let mut array = [false; 3];
+		let buffer = array.as_mut_ptr().cast();
+		let status = npz_reader_read(reader, y, NPZ_DTYPE_BOOL, false, buffer, 2);
+		assert_eq!(status, code(ErrorCode::ElementCountMismatch));
+		assert_eq!(
+			npz_reader_read(reader, y, NPZ_DTYPE_BOOL, false, buffer, 3),
+			NPZ_OK
+		);
+		assert_eq!(array, flags);
+		npz_reader_free(reader);
+	}
+	std::fs::remove_file(path).unwrap();
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn new_zstd() {
+	use ndarray_npz::{NpzReader, NpzWriter};
+	use std::io::Cursor;
+	use zip::{CompressionMethod, ZipArchive};
+
+	let array = Array1::from_iter((0..10_000).map(|index| f64::from(index % 100)));
+	let mut npz = NpzWriter::new_zstd(Cursor::new(Vec::new())).with_compression_level(19);
+	npz.add_array("x", &array).unwrap();
+	let buffer = npz.finish().unwrap().into_inner();
+	let mut zip = ZipArchive::new(Cursor::new(&buffer)).unwrap();
+	let file = zip.by_index(0).unwrap();
+	assert_eq!(file.compression(), CompressionMethod::Zstd);
+	assert!(file.compressed_size() < file.size() / 10);
+	drop(file);
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	assert!(npz.unsupported_names().is_empty());
+	let x: Array1<f64> = npz.by_name("x").unwrap();
+	assert_eq!(x, array);
+}
+
+#[cfg(feature = "bzip2")]
+#[test]
+fn new_bzip2() {
+	use ndarray_npz::{NpzReader, NpzWriter};
+	use std::io::Cursor;
+	use zip::{CompressionMethod, ZipArchive};
+
+	let array = Array1::from_iter((0..10_000).map(|index| f64::from(index % 100)));
+	let mut npz = NpzWriter::new_bzip2(Cursor::new(Vec::new())).with_compression_level(9);
+	npz.add_array("x", &array).unwrap();
+	let buffer = npz.finish().unwrap().into_inner();
+	let mut zip = ZipArchive::new(Cursor::new(&buffer)).unwrap();
+	let file = zip.by_index(0).unwrap();
+	assert_eq!(file.compression(), CompressionMethod::Bzip2);
+	assert!(file.compressed_size() < file.size());
+	drop(file);
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	let x: Array1<f64> = npz.by_name("x").unwrap();
+	assert_eq!(x, array);
+}
+
+#[test]
+#[allow(clippy::cast_precision_loss, clippy::float_cmp)]
+fn views_send_sync() {
+	use aligned_vec::AVec;
+	use ndarray_npz::{NpzView, NpzViewMut, NpzWriter};
+	use std::{io::Cursor, thread};
+
+	let names = ["a.npy", "b.npy", "c.npy"];
+	let mut buffer = Vec::<u8>::new();
+	{
+		let mut npz = NpzWriter::new(Cursor::new(&mut buffer));
+		for (len, name) in names.iter().enumerate() {
+			npz.add_array(*name, &Array1::<f64>::ones(len + 1)).unwrap();
+		}
+		npz.finish().unwrap();
+	}
+	let mut buffer = AVec::<u8>::from_slice(64, &buffer);
+	{
+		let mut npz = NpzViewMut::new(&mut buffer).unwrap();
+		let views = names.map(|name| npz.by_name(name).unwrap());
+		thread::scope(|scope| {
+			for (index, mut view) in views.into_iter().enumerate() {
+				scope.spawn(move || view.view_mut::<f64, Ix1>().unwrap().fill(index as f64));
+			}
+		});
+	}
+	let npz = NpzView::new(&buffer).unwrap();
+	thread::scope(|scope| {
+		for (index, name) in names.iter().enumerate() {
+			let npz = &npz;
+			scope.spawn(move || {
+				let mut view = npz.by_name(name).unwrap();
+				view.verify().unwrap();
+				let array = view.view::<f64, Ix1>().unwrap();
+				assert_eq!(array.len(), index + 1);
+				assert!(array.iter().all(|&x| x == index as f64));
+			});
+		}
+	});
+}
+
+#[cfg(feature = "lzma")]
+#[test]
+fn read_xz() {
+	use ndarray_npy::WriteNpyExt;
+	use ndarray_npz::NpzReader;
+	use std::io::{Cursor, Write};
+	use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+	let array = Array1::from_iter((0..1_000).map(f64::from));
+	let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+	let options = SimpleFileOptions::default().compression_method(CompressionMethod::Xz);
+	zip.start_file("x.npy", options).unwrap();
+	let mut npy = Vec::new();
+	array.write_npy(&mut npy).unwrap();
+	zip.write_all(&npy).unwrap();
+	let buffer = zip.finish().unwrap().into_inner();
+	let mut npz = NpzReader::new(Cursor::new(buffer)).unwrap();
+	assert!(npz.unsupported_names().is_empty());
+	let x: Array1<f64> = npz.by_name("x.npy").unwrap();
+	assert_eq!(x, array);
+}
+
+#[test]
+fn append() {
+	use aligned_vec::AVec;
+	use ndarray_npz::{NpzView, NpzWriter, WriteNpzError};
+	use std::io::Cursor;
+	use zip::ZipArchive;
+
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	npz.add_array("a.npy", &Array1::<f64>::zeros(3)).unwrap();
+	let mut writer = npz.finish().unwrap();
+	for (index, name) in ["b.npy", "c.npy"].into_iter().enumerate() {
+		let mut npz = NpzWriter::append(writer).unwrap();
+		npz.add_array(name, &Array1::<f64>::ones(index + 1))
+			.unwrap();
+		writer = npz.finish().unwrap();
+	}
+	let mut npz = NpzWriter::append(writer).unwrap();
+	assert!(matches!(
+		npz.add_array("a.npy", &Array1::<f64>::ones(1)),
+		Err(WriteNpzError::DuplicateName(name)) if name == "a.npy"
+	));
+	let buffer = AVec::<u8>::from_slice(64, &npz.finish().unwrap().into_inner());
+	let npz = NpzView::new(&buffer).unwrap();
+	let mut names = npz.names().collect::<Vec<_>>();
+	names.sort_unstable();
+	assert_eq!(names, ["a.npy", "b.npy", "c.npy"]);
+	for (name, len) in [("a.npy", 3), ("b.npy", 1), ("c.npy", 2)] {
+		let mut view = npz.by_name(name).unwrap();
+		view.verify().unwrap();
+		assert_eq!(view.view::<f64, Ix1>().unwrap().len(), len);
+	}
+	// Appended files are aligned like the existing ones.
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new())).with_alignment(4096);
+	npz.add_array("a.npy", &Array1::<f64>::zeros(3)).unwrap();
+	let mut npz = NpzWriter::append(npz.finish().unwrap()).unwrap();
+	npz.add_array("b.npy", &Array1::<f64>::zeros(3)).unwrap();
+	let mut zip = ZipArchive::new(npz.finish().unwrap()).unwrap();
+	let file = zip.by_name("b.npy").unwrap();
+	assert_eq!(file.data_start() % 4096, 0);
+}
+
+#[test]
+fn checksum_report() {
+	use aligned_vec::AVec;
+	use ndarray_npz::{ChecksumStatus, NpzView, NpzWriter};
+	use std::io::Cursor;
+
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	npz.add_array("a.npy", &Array1::<f64>::zeros(8)).unwrap();
+	npz.add_array("b.npy", &Array1::<f64>::ones(8)).unwrap();
+	npz.add_bytes("c.bin", b"blob").unwrap();
+	let mut buffer = AVec::<u8>::from_slice(64, &npz.finish().unwrap().into_inner());
+	let one = 1f64.to_le_bytes();
+	let offset = find_subsequence(&buffer, &one)[0];
+	buffer[offset] ^= 1;
+	let npz = NpzView::new(&buffer).unwrap();
+	let report = npz.checksum_report();
+	assert!(!report.is_ok());
+	assert_eq!(report.correct, ["a.npy"]);
+	assert_eq!(report.invalid, ["b.npy"]);
+	assert!(report.poisoned.is_empty());
+	assert_eq!(report.status("a.npy"), Some(ChecksumStatus::Correct));
+	assert_eq!(report.status("b.npy"), Some(ChecksumStatus::Outdated));
+	assert_eq!(report.status("c.bin"), None);
+	assert!(std::ptr::eq(report, npz.checksum_report()));
+}
+
+#[test]
+fn header_strictness() {
+	use aligned_vec::AVec;
+	use ndarray::OwnedRepr;
+	use ndarray_npy::WriteNpyExt;
+	use ndarray_npz::{HeaderStrictness, NpzReaderBuilder, NpzViewBuilder, NpzWriter};
+	use std::io::{Cursor, Write};
+	use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+	let array = Array1::from_iter((0..5).map(f64::from));
+	let mut npy = Vec::new();
+	array.write_npy(&mut npy).unwrap();
+	let header_len = 10 + usize::from(u16::from_le_bytes([npy[8], npy[9]]));
+	let mut unterminated = npy.clone();
+	unterminated[header_len - 1] = b' ';
+	let mut nul_padded = npy.clone();
+	let end = npy.iter().position(|&byte| byte == b'}').unwrap() + 1;
+	nul_padded[end..header_len - 1].fill(0);
+	let options = SimpleFileOptions::default()
+		.compression_method(CompressionMethod::Stored)
+		.with_alignment(64);
+	let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+	zip.start_file("unterminated.npy", options).unwrap();
+	zip.write_all(&unterminated).unwrap();
+	zip.start_file("nul_padded.npy", options).unwrap();
+	zip.write_all(&nul_padded).unwrap();
+	let buffer = AVec::<u8>::from_slice(64, &zip.finish().unwrap().into_inner());
+	let names = ["unterminated.npy", "nul_padded.npy"];
+	let builder = NpzReaderBuilder::new();
+	let mut npz = builder.clone().build(Cursor::new(&buffer[..])).unwrap();
+	for name in names {
+		assert!(npz.by_name::<OwnedRepr<f64>, Ix1>(name).is_err());
+	}
+	let mut npz = builder
+		.clone()
+		.header_strictness(HeaderStrictness::Lenient)
+		.build(Cursor::new(&buffer[..]))
+		.unwrap();
+	for (index, name) in names.into_iter().enumerate() {
+		assert_eq!(npz.by_name::<OwnedRepr<f64>, Ix1>(name).unwrap(), array);
+		assert_eq!(npz.by_index::<OwnedRepr<f64>, Ix1>(index).unwrap(), array);
+	}
+	let strict = builder.header_strictness(HeaderStrictness::Strict);
+	assert!(strict.clone().build(Cursor::new(&buffer[..])).is_err());
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	npz.add_array("x.npy", &array).unwrap();
+	strict.build(npz.finish().unwrap()).unwrap();
+	let builder = NpzViewBuilder::new();
+	let npz = builder.view(&buffer).unwrap();
+	assert!(npz.by_name(names[0]).unwrap().view::<f64, Ix1>().is_err());
+	let npz = builder
+		.clone()
+		.header_strictness(HeaderStrictness::Lenient)
+		.view(&buffer)
+		.unwrap();
+	for name in names {
+		let view = npz.by_name(name).unwrap();
+		assert_eq!(view.view::<f64, Ix1>().unwrap(), array);
+		assert!(view.view::<f64, Ix2>().is_err());
+	}
+	let builder = builder.header_strictness(HeaderStrictness::Strict);
+	assert!(builder.view(&buffer).is_err());
+}
+
+#[cfg(feature = "compressed")]
+#[test]
+fn add_array_with_options() {
+	use aligned_vec::AVec;
+	use ndarray_npz::{NpzView, NpzWriter};
+	use std::io::Cursor;
+	use zip::{write::SimpleFileOptions, CompressionMethod, DateTime, ZipArchive};
+
+	let time = DateTime::from_date_and_time(2020, 1, 2, 3, 4, 6).unwrap();
+	let options = SimpleFileOptions::default()
+		.compression_method(CompressionMethod::Deflated)
+		.compression_level(Some(9))
+		.last_modified_time(time);
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	npz.add_array("meta.npy", &Array1::<f64>::ones(3)).unwrap();
+	npz.add_array_with_options("data.npy", &Array2::<f64>::zeros((100, 100)), options)
+		.unwrap();
+	npz.add_array("tail.npy", &Array1::<f64>::ones(3)).unwrap();
+	let buffer = AVec::<u8>::from_slice(64, &npz.finish().unwrap().into_inner());
+	let mut zip = ZipArchive::new(Cursor::new(&buffer[..])).unwrap();
+	let data = zip.by_name("data.npy").unwrap();
+	assert_eq!(data.compression(), CompressionMethod::Deflated);
+	assert_eq!(data.last_modified(), Some(time));
+	assert!(data.compressed_size() < data.size() / 10);
+	drop(data);
+	for name in ["meta.npy", "tail.npy"] {
+		assert_eq!(
+			zip.by_name(name).unwrap().compression(),
+			CompressionMethod::Stored
+		);
+	}
+	let npz = NpzView::new(&buffer).unwrap();
+	assert_eq!(npz.compressed_names().collect::<Vec<_>>(), ["data.npy"]);
+	let tail = npz.by_name("tail.npy").unwrap();
+	assert_eq!(tail.view::<f64, Ix1>().unwrap(), Array1::<f64>::ones(3));
+}
+
+#[test]
+fn view_slice() {
+	use aligned_vec::AVec;
+	use ndarray::s;
+	use ndarray_npz::{NpzView, NpzWriter, ViewNpzError};
+	use std::io::Cursor;
+
+	let c = Array::from_iter(0..120)
+		.into_shape_with_order((4, 5, 6))
+		.unwrap();
+	let f = c.t().to_owned();
+	let mask = Array2::from_shape_fn((3, 4), |(i, j)| (i + j) % 2 == 0);
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	npz.add_array("c.npy", &c).unwrap();
+	npz.add_array("f.npy", &f).unwrap();
+	npz.add_array("mask.npy", &mask).unwrap();
+	npz.add_array("scalar.npy", &arr0(7i32)).unwrap();
+	let mut buffer = AVec::<u8>::from_slice(64, &npz.finish().unwrap().into_inner());
+	let npz = NpzView::new(&buffer).unwrap();
+	for name in ["c.npy", "f.npy"] {
+		let file = npz.by_name(name).unwrap();
+		let full = file.view::<i32, Ix3>().unwrap();
+		let slice = s![1..3, ..;-2, 3];
+		assert_eq!(file.view_slice::<i32, _>(slice).unwrap(), full.slice(slice));
+		let slice = s![-1, 1..;2, NewAxis, ..2;-1];
+		assert_eq!(file.view_slice::<i32, _>(slice).unwrap(), full.slice(slice));
+		let slice = s![2..2, .., ..];
+		assert_eq!(file.view_slice::<i32, _>(slice).unwrap(), full.slice(slice));
+		let dyn_slice = file.view_slice::<i32, _>(s![.., 0, 0].as_ref()).unwrap();
+		assert_eq!(dyn_slice, full.slice(s![.., 0, 0]).into_dyn());
+		assert!(matches!(
+			file.view_slice::<i32, _>(s![6, .., ..]),
+			Err(ViewNpzError::InvalidSlice)
+		));
+		assert!(matches!(
+			file.view_slice::<i32, _>(s![..7, .., ..]),
+			Err(ViewNpzError::InvalidSlice)
+		));
+		assert!(file.view_slice::<i32, _>(s![.., ..]).is_err());
+		assert!(file.view_slice::<i64, _>(s![.., .., ..]).is_err());
+	}
+	let scalar = npz.by_name("scalar.npy").unwrap();
+	assert_eq!(scalar.view_slice::<i32, _>(s![]).unwrap(), arr0(7));
+	let expected = mask.slice(s![1, 1..3]).to_owned();
+	let offset = find_subsequence(&buffer, &[1, 0, 1, 0, 0, 1, 0, 1])[0];
+	buffer[offset + 11] = 2;
+	let npz = NpzView::new(&buffer).unwrap();
+	let file = npz.by_name("mask.npy").unwrap();
+	assert!(file.view::<bool, Ix2>().is_err());
+	assert_eq!(file.view_slice::<bool, _>(s![1, 1..3]).unwrap(), expected);
+	assert!(file.view_slice::<bool, _>(s![2, 3..]).is_err());
+}
+
+#[test]
+fn add_npy_bytes() {
+	use aligned_vec::AVec;
+	use ndarray::OwnedRepr;
+	use ndarray_npy::WriteNpyExt;
+	use ndarray_npz::{NpzReader, NpzView, NpzWriter, WriteNpzError};
+	use std::io::Cursor;
+	use zip::result::ZipError;
+
+	let x = Array2::from_shape_fn((3, 4), |(i, j)| {
+		f64::from(u32::try_from(i * 4 + j).unwrap())
+	});
+	let mut npy = Vec::new();
+	x.write_npy(&mut npy).unwrap();
+	let mut npz = NpzWriter::numpy_compat(Cursor::new(Vec::new()));
+	npz.add_npy_bytes("x", npy.as_slice()).unwrap();
+	npz.add_npy_bytes("y", Cursor::new(&npy)).unwrap();
+	assert!(matches!(
+		npz.add_npy_bytes("z", b"not an npy file".as_slice()),
+		Err(WriteNpzError::Zip(ZipError::InvalidArchive(_)))
+	));
+	assert!(npz.add_npy_bytes("z", &npy[..20]).is_err());
+	let buffer = npz.finish().unwrap().into_inner();
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	assert_eq!(npz.names().unwrap(), ["x.npy", "y.npy"]);
+	for name in ["x.npy", "y.npy"] {
+		assert_eq!(npz.by_name::<OwnedRepr<f64>, Ix2>(name).unwrap(), x);
+	}
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	npz.add_npy_bytes("x.npy", npy.as_slice()).unwrap();
+	let buffer = AVec::<u8>::from_slice(64, &npz.finish().unwrap().into_inner());
+	let npz = NpzView::new(&buffer).unwrap();
+	assert_eq!(npz.by_name("x.npy").unwrap().view::<f64, Ix2>().unwrap(), x);
+}
+
+#[test]
+fn create_in() {
+	use aligned_vec::AVec;
+	use ndarray::OwnedRepr;
+	use ndarray_npz::{Dtype, EntryLayout, NpzReader, NpzViewMut, ViewNpzError};
+	use std::io::{self, Cursor};
+	use zip::result::ZipError;
+
+	let entries = [
+		EntryLayout::new("x.npy", Dtype::F64, &[3, 4]),
+		EntryLayout::new("y.npy", Dtype::Bool, &[5]).with_fortran_order(true),
+		EntryLayout::new("z.npy", Dtype::I32, &[0, 2]),
+	];
+	let size = NpzViewMut::required_size(&entries).unwrap();
+	let mut buffer = AVec::<u8>::from_iter(64, (0..size + 100).map(|_| 0xff));
+	let err = NpzViewMut::create_in(&mut buffer[..size - 1], &entries).unwrap_err();
+	assert!(
+		matches!(err, ViewNpzError::Zip(ZipError::Io(err)) if err.kind() == io::ErrorKind::WriteZero)
+	);
+	let mut npz = NpzViewMut::create_in(&mut buffer, &entries).unwrap();
+	assert_eq!(npz.len(), 3);
+	let mut x = npz.by_name("x.npy").unwrap();
+	let mut y = npz.by_name("y.npy").unwrap();
+	assert_eq!(x.view::<f64, Ix2>().unwrap(), Array2::<f64>::zeros((3, 4)));
+	x.view_mut::<f64, Ix2>().unwrap().fill(1.5);
+	y.view_mut::<bool, Ix1>().unwrap()[2] = true;
+	drop((x, y));
+	drop(npz);
+	let mut npz = NpzReader::new(Cursor::new(&buffer[..size])).unwrap();
+	assert_eq!(
+		npz.by_name::<OwnedRepr<f64>, Ix2>("x.npy").unwrap(),
+		Array2::from_elem((3, 4), 1.5)
+	);
+	assert_eq!(
+		npz.by_name::<OwnedRepr<bool>, Ix1>("y.npy").unwrap(),
+		array![false, false, true, false, false]
+	);
+	assert_eq!(
+		npz.by_name::<OwnedRepr<i32>, Ix2>("z.npy").unwrap().shape(),
+		[0, 2]
+	);
+	let duplicate = [
+		EntryLayout::new("x.npy", Dtype::U8, &[1]),
+		EntryLayout::new("x.npy", Dtype::U8, &[1]),
+	];
+	assert!(NpzViewMut::required_size(&duplicate).is_err());
+	let huge = [EntryLayout::new("x.npy", Dtype::F64, &[usize::MAX, 2])];
+	assert!(NpzViewMut::required_size(&huge).is_err());
+}
+
+#[test]
+fn high_dimensional() {
+	use aligned_vec::AVec;
+	use ndarray::{s, IxDyn, OwnedRepr};
+	use ndarray_npz::{Dtype, EntryLayout, NpzReader, NpzView, NpzViewMut, NpzWriter};
+	use std::io::Cursor;
+
+	for shape in [&[2, 3, 1, 2, 2, 3, 2][..], &[2, 1, 2, 3, 1, 2, 2, 2, 3]] {
+		let len = shape.iter().product::<usize>();
+		let x = ArrayD::from_shape_vec(shape, (0..len).map(|x| x as f64).collect()).unwrap();
+		let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+		npz.add_array("c.npy", &x).unwrap();
+		npz.add_array_f("f.npy", &x).unwrap();
+		npz.add_array("t.npy", &x.t()).unwrap();
+		let buffer = npz.finish().unwrap().into_inner();
+		let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+		assert_eq!(npz.by_name_dyn::<f64>("c.npy").unwrap(), x);
+		assert_eq!(npz.by_name_dyn::<f64>("f.npy").unwrap(), x);
+		assert_eq!(
+			npz.by_name::<OwnedRepr<f64>, IxDyn>("t.npy").unwrap(),
+			x.t()
+		);
+		let mut buffer = AVec::<u8>::from_slice(64, &buffer);
+		let mut npz = NpzViewMut::new(&mut buffer).unwrap();
+		let mut f = npz.by_name("f.npy").unwrap();
+		f.view_mut_dyn::<f64>().unwrap().map_inplace(|x| *x += 1.0);
+		drop((f, npz));
+		let y = &x + 1.0;
+		let npz = NpzView::new(&buffer).unwrap();
+		let (c, mut f, t) = (
+			npz.by_name("c.npy").unwrap(),
+			npz.by_name("f.npy").unwrap(),
+			npz.by_name("t.npy").unwrap(),
+		);
+		assert_eq!(c.view_dyn::<f64>().unwrap(), x);
+		assert_eq!(f.view_dyn::<f64>().unwrap(), y);
+		assert_eq!(t.view::<f64, IxDyn>().unwrap(), x.t());
+		f.verify().unwrap();
+		if shape.len() == 7 {
+			let slice = s![.., 1.., .., .., .., ..;-1, ..];
+			assert_eq!(c.view_slice::<f64, _>(slice).unwrap(), x.slice(slice));
+			assert_eq!(f.view_slice::<f64, _>(slice).unwrap(), y.slice(slice));
+		}
+		let entries = [EntryLayout::new("z.npy", Dtype::F64, shape).with_fortran_order(true)];
+		let size = NpzViewMut::required_size(&entries).unwrap();
+		let mut buffer = AVec::<u8>::from_iter(64, (0..size).map(|_| 0));
+		let mut npz = NpzViewMut::create_in(&mut buffer, &entries).unwrap();
+		let mut z = npz.by_name("z.npy").unwrap();
+		z.view_mut_dyn::<f64>().unwrap().assign(&x);
+		drop((z, npz));
+		let mut npz = NpzReader::new(Cursor::new(&buffer[..])).unwrap();
+		assert_eq!(npz.by_name_dyn::<f64>("z.npy").unwrap(), x);
+	}
+}
+
+#[test]
+fn buf_seek_writer() {
+	use ndarray::OwnedRepr;
+	use ndarray_npz::{BufSeekWriter, NpzReader, NpzWriter};
+	use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+
+	fn write<W: Write + Seek>(npz: &mut NpzWriter<W>) {
+		for step in 0..200 {
+			npz.add_array(format!("loss/{step}"), &arr0(f64::from(step)))
+				.unwrap();
+		}
+		npz.add_array("large", &Array1::<f64>::linspace(0.0, 1.0, 100))
+			.unwrap();
+		npz.add_array("last", &arr0(1u8)).unwrap();
+	}
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	write(&mut npz);
+	let expected = npz.finish().unwrap().into_inner();
+	for capacity in [0, 1, 100, 700, 64 * 1024] {
+		let writer = BufSeekWriter::with_capacity(capacity, Cursor::new(Vec::new())).unwrap();
+		let mut npz = NpzWriter::new(writer);
+		write(&mut npz);
+		let buffer = npz.finish().unwrap().into_inner().unwrap().into_inner();
+		assert_eq!(buffer, expected, "capacity {capacity}");
+	}
+	let writer = BufSeekWriter::with_capacity(256, Cursor::new(expected.clone())).unwrap();
+	let mut npz = NpzWriter::append(writer).unwrap();
+	npz.add_array("appended", &arr0(2u8)).unwrap();
+	let mut npz = NpzReader::new(npz.finish().unwrap()).unwrap();
+	assert_eq!(npz.len(), 203);
+	assert_eq!(
+		npz.by_name::<OwnedRepr<f64>, Ix0>("loss/123").unwrap()[()],
+		123.0
+	);
+	assert_eq!(
+		npz.by_name::<OwnedRepr<u8>, Ix0>("appended").unwrap()[()],
+		2
+	);
+	let mut writer = BufSeekWriter::with_capacity(8, Cursor::new(Vec::new())).unwrap();
+	writer.write_all(b"0123456789").unwrap();
+	writer.seek(SeekFrom::Start(2)).unwrap();
+	writer.write_all(b"ab").unwrap();
+	writer.seek(SeekFrom::End(-1)).unwrap();
+	writer.write_all(b"cd").unwrap();
+	assert_eq!(writer.stream_position().unwrap(), 11);
+	writer.seek(SeekFrom::Current(-7)).unwrap();
+	let mut bytes = [0; 3];
+	writer.read_exact(&mut bytes).unwrap();
+	assert_eq!(&bytes, b"456");
+	assert!(writer.seek(SeekFrom::Current(-8)).is_err());
+	assert_eq!(writer.into_inner().unwrap().into_inner(), b"01ab45678cd");
+}
+
+#[test]
+fn write_npz() {
+	use ndarray::OwnedRepr;
+	use ndarray_npz::NpzReader;
+	use std::{collections::BTreeMap, fs::File};
+
+	let path = std::env::temp_dir().join("ndarray_npz_write_npz.npz");
+	let x = array![[1.0, 2.0], [3.0, 4.0]];
+	let y = array![[5.0, 6.0], [7.0, 8.0]];
+	ndarray_npz::write_npz(&path, [("x", &x), ("y", &y)]).unwrap();
+	let mut npz = NpzReader::new(File::open(&path).unwrap()).unwrap();
+	assert_eq!(npz.names().unwrap(), ["x", "y"]);
+	assert_eq!(npz.by_name::<OwnedRepr<f64>, Ix2>("y").unwrap(), y);
+	let arrays = BTreeMap::from([("a".to_owned(), arr1(&[3u8]))]);
+	ndarray_npz::write_npz(&path, &arrays).unwrap();
+	let mut npz = NpzReader::new(File::open(&path).unwrap()).unwrap();
+	assert_eq!(npz.names().unwrap(), ["a"]);
+	assert_eq!(npz.by_name::<OwnedRepr<u8>, Ix1>("a").unwrap(), arr1(&[3]));
+	assert!(ndarray_npz::write_npz(&path, [("x", &x), ("x", &y)]).is_err());
+	#[cfg(feature = "compressed")]
+	{
+		let zeros = Array2::<f64>::zeros((64, 64));
+		ndarray_npz::write_npz_compressed(&path, [("zeros", &zeros)]).unwrap();
+		assert!(std::fs::metadata(&path).unwrap().len() < 4096);
+		let mut npz = NpzReader::new(File::open(&path).unwrap()).unwrap();
+		assert_eq!(npz.by_name::<OwnedRepr<f64>, Ix2>("zeros").unwrap(), zeros);
+	}
+	std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn entry_name() {
+	use aligned_vec::AVec;
+	use ndarray::OwnedRepr;
+	use ndarray_npz::{EntryName, EntryNameError, NpzReader, NpzView, NpzWriter};
+	use std::{collections::HashSet, io::Cursor};
+
+	let name = EntryName::new("/model/./layer1//weights.npy/").unwrap();
+	assert_eq!(name, "model/layer1/weights.npy");
+	assert_eq!(
+		name.components().collect::<Vec<_>>(),
+		["model", "layer1", "weights.npy"]
+	);
+	assert_eq!(name.file_name(), "weights.npy");
+	assert_eq!(name.parent().unwrap(), "model/layer1");
+	assert_eq!(name.parent().unwrap().parent().unwrap().parent(), None);
+	assert_eq!(name.clone().without_npy_extension(), "model/layer1/weights");
+	assert_eq!(name.clone().with_npy_extension(), name);
+	let npy = EntryName::new(".npy").unwrap();
+	assert_eq!(npy.clone().without_npy_extension(), npy);
+	let upper = EntryName::new("weights.NPY").unwrap();
+	assert!(upper.has_npy_extension());
+	assert_eq!(upper.clone().with_npy_extension(), upper);
+	assert_eq!(upper.without_npy_extension(), "weights");
+	assert_eq!(EntryName::new(""), Err(EntryNameError::Empty));
+	assert_eq!(EntryName::new("/./"), Err(EntryNameError::Empty));
+	assert_eq!(EntryName::new("a\0b"), Err(EntryNameError::Nul));
+	assert_eq!(EntryName::new(r"a\b"), Err(EntryNameError::Backslash));
+	assert_eq!(EntryName::new("a/../b"), Err(EntryNameError::ParentDir));
+	assert_eq!(
+		EntryName::new("a".repeat(65536)),
+		Err(EntryNameError::TooLong)
+	);
+	assert_eq!(name.join("a/b"), Err(EntryNameError::Separator));
+	assert_eq!(name.join(".."), Err(EntryNameError::ParentDir));
+	assert_eq!(name.join("."), Err(EntryNameError::Empty));
+	assert_eq!("x/y".parse::<EntryName>().unwrap(), "x/y");
+	let names = [EntryName::new("x").unwrap()]
+		.into_iter()
+		.collect::<HashSet<_>>();
+	assert!(names.contains("x"));
+
+	let model = EntryName::new("model").unwrap();
+	let bias = model.join("bias").unwrap();
+	let weights = model.join("weights").unwrap().with_npy_extension();
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+	npz.add_array(bias.clone(), &arr1(&[1.0])).unwrap();
+	npz.add_array(weights.clone(), &arr1(&[2.0])).unwrap();
+	let buffer = npz.finish().unwrap().into_inner();
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	assert_eq!(npz.names().unwrap(), ["model/bias", "model/weights.npy"]);
+	assert_eq!(
+		npz.by_name::<OwnedRepr<f64>, Ix1>(&bias).unwrap(),
+		arr1(&[1.0])
+	);
+	assert!(npz.contains(&weights));
+	let buffer = AVec::<u8>::from_slice(64, &buffer);
+	let npz = NpzView::new(&buffer).unwrap();
+	let weights = npz.by_name(&weights).unwrap();
+	assert_eq!(weights.view::<f64, Ix1>().unwrap(), arr1(&[2.0]));
+}
+
+#[cfg(feature = "parallel-deflate")]
+#[test]
+fn with_parallel_deflate() {
+	use ndarray::OwnedRepr;
+	use ndarray_npz::{NpzReader, NpzWriter};
+	use std::io::Cursor;
+	use zip::{write::SimpleFileOptions, CompressionMethod, ZipArchive};
+
+	let x = Array2::from_shape_fn((1 << 10, 3 << 8), |(i, j)| ((i * 7919 + j) % 1009) as f32);
+	let small = Array1::<f64>::linspace(0.0, 1.0, 100);
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new())).with_parallel_deflate(6);
+	npz.add_array("x", &x).unwrap();
+	npz.add_array("t", &x.t()).unwrap();
+	npz.add_array("small", &small).unwrap();
+	let options = SimpleFileOptions::default().large_file(true);
+	npz.add_array_with_options("large_file", &x, options)
+		.unwrap();
+	let buffer = npz.finish().unwrap().into_inner();
+	let mut zip = ZipArchive::new(Cursor::new(&buffer)).unwrap();
+	for (name, method) in [
+		("x", CompressionMethod::Deflated),
+		("t", CompressionMethod::Deflated),
+		("small", CompressionMethod::Stored),
+		("large_file", CompressionMethod::Deflated),
+	] {
+		let file = zip.by_name(name).unwrap();
+		assert_eq!(file.compression(), method, "{name}");
+		assert!(file.compressed_size() < file.size() / 2 || name == "small");
+	}
+	let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+	assert_eq!(npz.by_name::<OwnedRepr<f32>, Ix2>("x").unwrap(), x);
+	assert_eq!(npz.by_name::<OwnedRepr<f32>, Ix2>("t").unwrap(), x.t());
+	assert_eq!(npz.by_name::<OwnedRepr<f64>, Ix1>("small").unwrap(), small);
+	assert_eq!(npz.by_name::<OwnedRepr<f32>, Ix2>("large_file").unwrap(), x);
+}
+
+#[test]
+fn finish_dry_run() {
+	use ndarray_npz::{DryRunWriter, NpzWriter};
+	use std::io::{Cursor, Seek, Write};
+	use zip::{write::SimpleFileOptions, ZipArchive};
+
+	fn write<W: Write + Seek>(mut npz: NpzWriter<W>) -> W {
+		let signatures = Array1::from_iter(b"PK\x03\x04\xff\xffPK\x05\x06\xff\xff".repeat(1000));
+		npz.add_array("x", &Array2::<f64>::zeros((100, 100)))
+			.unwrap();
+		npz.add_array("signatures", &signatures).unwrap();
+		npz.add_array(
+			"t",
+			&Array2::from_shape_fn((30, 20), |(i, j)| (i * j) as u16).t(),
+		)
+		.unwrap();
+		let options = SimpleFileOptions::default().large_file(true);
+		npz.add_array_with_options("large_file", &arr1(&[1u32, 2, 3]), options)
+			.unwrap();
+		for index in 0..100 {
+			npz.add_array(format!("scalars/{index}"), &arr0(index))
+				.unwrap();
+		}
+		npz.add_bytes("notes.txt", b"PK\x01\x02").unwrap();
+		npz.set_comment("PK\x05\x06");
+		npz.finish().unwrap()
+	}
+
+	for (npz, dry_run) in [
+		(
+			NpzWriter::new(Cursor::new(Vec::new())),
+			NpzWriter::new(DryRunWriter::new()),
+		),
+		#[cfg(feature = "compressed")]
+		(
+			NpzWriter::new_compressed(Cursor::new(Vec::new())),
+			NpzWriter::new_compressed(DryRunWriter::new()),
+		),
+		(
+			NpzWriter::new(Cursor::new(Vec::new())).with_alignment(4096),
+			NpzWriter::new(DryRunWriter::new()).with_alignment(4096),
+		),
+	] {
+		let buffer = write(npz).into_inner();
+		let writer = write(dry_run);
+		assert_eq!(writer.len(), buffer.len() as u64);
+		let report = writer.report().unwrap();
+		assert_eq!(report.size, buffer.len() as u64);
+		let mut zip = ZipArchive::new(Cursor::new(&buffer)).unwrap();
+		assert_eq!(report.entries.len(), zip.len());
+		for (index, entry) in report.entries.iter().enumerate() {
+			let file = zip.by_index_raw(index).unwrap();
+			assert_eq!(entry.name, file.name());
+			assert_eq!(entry.header_start, file.header_start());
+			assert_eq!(entry.data_start, file.data_start());
+			assert_eq!(entry.compressed_size, file.compressed_size());
+			assert_eq!(entry.size, file.size());
+			assert_eq!(entry.crc32, file.crc32());
+			assert!(entry.npy.is_none());
+		}
+	}
+	assert!(DryRunWriter::new().report().is_err());
+	let npz = NpzWriter::new(DryRunWriter::new());
+	assert!(npz.finish_dry_run().unwrap().entries.is_empty());
+}
+
+#[test]
+fn with_endianness() {
+	use ndarray::OwnedRepr;
+	use ndarray_npz::{Endianness, NpzReader, NpzWriter};
+	use std::io::{Cursor, Read};
+	use zip::ZipArchive;
+
+	let x = Array1::from_iter((0..1000).map(f64::from));
+	for (endianness, descr, to_bytes) in [
+		(
+			Endianness::Little,
+			"'<f8'",
+			f64::to_le_bytes as fn(f64) -> [u8; 8],
+		),
+		(Endianness::Big, "'>f8'", f64::to_be_bytes),
+	] {
+		let mut npz = NpzWriter::new(Cursor::new(Vec::new()))
+			.with_endianness(endianness)
+			.with_packing(64)
+			.with_chunking(1024);
+		npz.add_array("x", &x).unwrap();
+		npz.add_array("s", &arr0(0.5)).unwrap();
+		npz.add_array("b", &arr1(&[true, false])).unwrap();
+		let buffer = npz.finish().unwrap().into_inner();
+		let mut npz = NpzReader::new(Cursor::new(&buffer)).unwrap();
+		assert_eq!(npz.by_name::<OwnedRepr<f64>, Ix1>("x").unwrap(), x);
+		assert_eq!(npz.by_name::<OwnedRepr<f64>, Ix0>("s").unwrap(), arr0(0.5));
+		assert_eq!(
+			npz.by_name::<OwnedRepr<bool>, Ix1>("b").unwrap(),
+			arr1(&[true, false])
+		);
+		// Chunked and packed arrays are swapped as well.
+		let mut zip = ZipArchive::new(Cursor::new(&buffer)).unwrap();
+		let mut bytes = Vec::new();
+		for index in 0..zip.len() {
+			zip.by_index(index)
+				.unwrap()
+				.read_to_end(&mut bytes)
+				.unwrap();
+		}
+		let find = |needle: &[u8]| bytes.windows(needle.len()).filter(|w| *w == needle).count();
+		assert_eq!(find(descr.as_bytes()), 2);
+		assert_eq!(find(b"'|b1'"), 1);
+		assert_eq!(find(&to_bytes(0.5)), 1);
+		assert_eq!(find(&to_bytes(999.0)), 1);
+	}
+}
+
+#[test]
+fn on_progress() {
+	use ndarray::OwnedRepr;
+	use ndarray_npz::{Endianness, NpzReader, NpzWriter};
+	use std::{
+		io::Cursor,
+		sync::{Arc, Mutex},
+	};
+	use zip::ZipArchive;
+
+	let reports = Arc::new(Mutex::new(Vec::<(String, u64, u64)>::new()));
+	let reported = reports.clone();
+	let mut npz =
+		NpzWriter::new(Cursor::new(Vec::new())).on_progress(move |name, written, total| {
+			reported
+				.lock()
+				.unwrap()
+				.push((name.to_owned(), written, total));
+		});
+	let big = Array2::<f32>::from_shape_fn((1024, 1024), |(i, j)| {
+		f32::from(u8::try_from(i * j % 7).unwrap())
+	});
+	let tiny = Array1::<u8>::from_vec(vec![1, 2, 3]);
+	let fortran = big.t();
+	npz.add_array("big", &big).unwrap();
+	npz.add_array("tiny", &tiny).unwrap();
+	npz.add_array("fortran", &fortran).unwrap();
+	let strided = big.slice(s![..;2, ..]);
+	npz.add_array("strided", &strided).unwrap();
+	let buffer = npz.finish().unwrap().into_inner();
+	let mut zip = ZipArchive::new(Cursor::new(&buffer)).unwrap();
+	let reports = reports.lock().unwrap();
+	for name in ["big", "tiny", "fortran", "strided"] {
+		let reports = reports
+			.iter()
+			.filter(|(reported, _, _)| reported == name)
+			.collect::<Vec<_>>();
+		let size = zip.by_name(name).unwrap().size();
+		assert!(reports.windows(2).all(|pair| pair[0].1 < pair[1].1));
+		assert!(reports.iter().all(|&&(_, _, total)| total == size));
+		assert_eq!(reports.last().unwrap().1, size);
+	}
+	assert!(reports.iter().filter(|report| report.0 == "big").count() >= 4);
+	assert_eq!(
+		reports.iter().filter(|report| report.0 == "tiny").count(),
+		1
+	);
+	let mut npz = NpzReader::new(Cursor::new(buffer)).unwrap();
+	assert_eq!(
+		npz.by_name::<OwnedRepr<f32>, Ix2>("fortran").unwrap(),
+		fortran
+	);
+	drop(reports);
+
+	// Arrays serialized in memory first are reported once written.
+	let reports = Arc::new(Mutex::new(Vec::new()));
+	let reported = reports.clone();
+	let mut npz = NpzWriter::new(Cursor::new(Vec::new()))
+		.with_endianness(if cfg!(target_endian = "big") {
+			Endianness::Little
+		} else {
+			Endianness::Big
+		})
+		.on_progress(move |name, written, total| {
+			reported
+				.lock()
+				.unwrap()
+				.push((name.to_owned(), written, total));
+		});
+	npz.add_array("big", &big).unwrap();
+	let buffer = npz.finish().unwrap().into_inner();
+	let size = ZipArchive::new(Cursor::new(&buffer))
+		.unwrap()
+		.by_name("big")
+		.unwrap()
+		.size();
+	assert_eq!(*reports.lock().unwrap(), [("big".to_owned(), size, size)]);
+}
+
+#[cfg(feature = "bench")]
+#[test]
+fn bench() {
+	use ndarray_npz::{
+		bench::{run, synthesize, BenchSpec},
+		Dtype, NpzReader,
+	};
+	use std::io::Cursor;
+
+	for dtype in [Dtype::Bool, Dtype::I8, Dtype::U16, Dtype::F64] {
+		let spec = BenchSpec::default()
+			.with_entries(3)
+			.with_elements(10)
+			.with_dtype(dtype)
+			.with_iterations(2);
+		assert_eq!(spec.payload_size(), 30 * dtype.size() as u64);
+		let report = run(&spec).unwrap();
+		assert!(report.archive_size > spec.payload_size());
+		assert_eq!(report.write.bytes, 2 * spec.payload_size());
+		assert_eq!(report.read.bytes, 2 * spec.payload_size());
+		assert_eq!(report.view.unwrap().bytes, 2 * spec.payload_size());
+		let buffer = synthesize(&spec).unwrap();
+		assert_eq!(buffer.len() as u64, report.archive_size);
+		let mut npz = NpzReader::new(Cursor::new(buffer)).unwrap();
+		assert_eq!(npz.names().unwrap(), ["0.npy", "1.npy", "2.npy"]);
+	}
+	#[cfg(feature = "compressed")]
+	{
+		let spec = BenchSpec::default()
+			.with_entries(1)
+			.with_elements(10)
+			.with_compressed(true);
+		assert_eq!(run(&spec).unwrap().view, None);
+	}
+}
@@ -42,14 +42,14 @@ np.savez("examples_little_endian.npz",
     f64=le_f64,
 )
 
-be_i16 = le_i16.byteswap()
-be_u16 = le_u16.byteswap()
-be_i32 = le_i32.byteswap()
-be_u32 = le_u32.byteswap()
-be_i64 = le_i64.byteswap()
-be_u64 = le_u64.byteswap()
-be_f32 = le_f32.byteswap()
-be_f64 = le_f64.byteswap()
+be_i16 = le_i16.astype(le_i16.dtype.newbyteorder('>'))
+be_u16 = le_u16.astype(le_u16.dtype.newbyteorder('>'))
+be_i32 = le_i32.astype(le_i32.dtype.newbyteorder('>'))
+be_u32 = le_u32.astype(le_u32.dtype.newbyteorder('>'))
+be_i64 = le_i64.astype(le_i64.dtype.newbyteorder('>'))
+be_u64 = le_u64.astype(le_u64.dtype.newbyteorder('>'))
+be_f32 = le_f32.astype(le_f32.dtype.newbyteorder('>'))
+be_f64 = le_f64.astype(le_f64.dtype.newbyteorder('>'))
 
 np.savez("examples_big_endian.npz",
     i16=be_i16,